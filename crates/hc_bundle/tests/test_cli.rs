@@ -215,6 +215,7 @@ async fn test_multi_integrity() {
                 .into(),
             ),
         ],
+        rate_limits: Vec::new(),
     };
     assert_eq!(
         dna.dna_def().integrity_zomes[0]
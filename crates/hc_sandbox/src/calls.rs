@@ -14,8 +14,12 @@ use holochain_conductor_api::AdminRequest;
 use holochain_conductor_api::AdminResponse;
 use holochain_conductor_api::AppStatusFilter;
 use holochain_conductor_api::InterfaceDriver;
+use holochain_conductor_api::DEFAULT_UNIX_SOCKET_MODE;
 use holochain_conductor_api::{AdminInterfaceConfig, InstalledAppInfo};
 use holochain_p2p::kitsune_p2p::agent_store::AgentInfoSigned;
+use holochain_p2p::kitsune_p2p::dependencies::kitsune_p2p_types::codec::rmp_decode;
+use holochain_p2p::kitsune_p2p::dependencies::kitsune_p2p_types::codec::rmp_encode;
+use holochain_types::prelude::DnaDefinition;
 use holochain_types::prelude::DnaHash;
 use holochain_types::prelude::InstallAppDnaPayload;
 use holochain_types::prelude::InstallAppPayload;
@@ -25,10 +29,12 @@ use holochain_types::prelude::{AgentPubKey, AppBundleSource};
 use holochain_types::prelude::{CellId, InstallAppBundlePayload};
 use holochain_types::prelude::{DnaSource, NetworkSeed};
 use std::convert::TryFrom;
+use tokio::process::Child;
 
 use crate::cmds::Existing;
 use crate::expect_match;
 use crate::ports::get_admin_ports;
+use crate::ports::get_admin_socket;
 use crate::run::run_async;
 use crate::CmdRunner;
 use structopt::StructOpt;
@@ -41,6 +47,10 @@ pub struct Call {
     /// If this is empty existing sandboxes will be used.
     /// Cannot be combined with existing sandboxes.
     pub running: Vec<u16>,
+    #[structopt(long, conflicts_with_all = &["existing_paths", "indices"], value_delimiter = ",")]
+    /// Paths to the unix sockets of running conductor admin interfaces.
+    /// Can be combined with ports but not with existing sandboxes.
+    pub running_sockets: Vec<PathBuf>,
     #[structopt(flatten)]
     pub existing: Existing,
     #[structopt(subcommand)]
@@ -55,6 +65,8 @@ pub struct Call {
 pub enum AdminRequestCli {
     AddAdminWs(AddAdminWs),
     AddAppWs(AddAppWs),
+    AddAdminSocket(AddAdminSocket),
+    AddAppSocket(AddAppSocket),
     RegisterDna(RegisterDna),
     InstallApp(InstallApp),
     InstallAppBundle(InstallAppBundle),
@@ -64,6 +76,8 @@ pub enum AdminRequestCli {
     ListAppWs,
     /// Calls AdminRequest::ListDnas.
     ListDnas,
+    /// Calls AdminRequest::GetDnaDefinition.
+    DnaDefinition(DnaDefinitionArgs),
     /// Calls AdminRequest::GenerateAgentPubKey.
     NewAgent,
     /// Calls AdminRequest::ListCellIds.
@@ -75,9 +89,7 @@ pub enum AdminRequestCli {
     EnableApp(EnableApp),
     DisableApp(DisableApp),
     DumpState(DumpState),
-    /// Calls AdminRequest::AddAgentInfo.
-    /// _Unimplemented_.
-    AddAgents,
+    AddAgents(AddAgents),
     ListAgents(ListAgents),
 }
 #[derive(Debug, StructOpt, Clone)]
@@ -98,6 +110,30 @@ pub struct AddAppWs {
     pub port: Option<u16>,
 }
 
+#[derive(Debug, StructOpt, Clone)]
+/// Calls AdminRequest::AddAdminInterfaces
+/// and adds another admin interface on a unix domain socket.
+pub struct AddAdminSocket {
+    /// Path of the socket file.
+    pub path: PathBuf,
+    #[structopt(short, long, parse(try_from_str = parse_mode))]
+    /// Permissions of the socket file in octal, e.g. 660.
+    /// Defaults to 600, so only the user running the conductor can connect.
+    pub mode: Option<u32>,
+}
+
+#[derive(Debug, StructOpt, Clone)]
+/// Calls AdminRequest::AttachAppInterfaceUnixSocket
+/// and adds another app interface on a unix domain socket.
+pub struct AddAppSocket {
+    /// Path of the socket file.
+    pub path: PathBuf,
+    #[structopt(short, long, parse(try_from_str = parse_mode))]
+    /// Permissions of the socket file in octal, e.g. 660.
+    /// Defaults to 600, so only the user running the conductor can connect.
+    pub mode: Option<u32>,
+}
+
 #[derive(Debug, StructOpt, Clone)]
 /// Calls AdminRequest::RegisterDna
 /// and registers a Dna. You can only use a path or a hash not both.
@@ -187,6 +223,15 @@ pub struct DisableApp {
     pub app_id: String,
 }
 
+#[derive(Debug, StructOpt, Clone)]
+/// Calls AdminRequest::GetDnaDefinition
+/// and lists the functions, callbacks and types each zome of a Dna exposes.
+pub struct DnaDefinitionArgs {
+    #[structopt(parse(try_from_str = parse_dna_hash))]
+    /// Hash of the registered dna to inspect.
+    pub dna: DnaHash,
+}
+
 #[derive(Debug, StructOpt, Clone)]
 /// Calls AdminRequest::DumpState
 /// and dumps the current cell's state.
@@ -201,6 +246,16 @@ pub struct DumpState {
     pub agent_key: AgentPubKey,
 }
 
+#[derive(Debug, StructOpt, Clone)]
+/// Calls AdminRequest::AddAgentInfo
+/// and adds agent info saved by `list-agents --output`
+/// to this conductor.
+pub struct AddAgents {
+    #[structopt(required = true, min_values = 1)]
+    /// Files of agent info saved by `list-agents --output`.
+    pub paths: Vec<PathBuf>,
+}
+
 #[derive(Debug, StructOpt, Clone)]
 /// Calls AdminRequest::RequestAgentInfo
 /// and pretty prints the agent info on
@@ -212,6 +267,30 @@ pub struct ListAgents {
     #[structopt(short, long, parse(try_from_str = parse_dna_hash), requires = "agent_key")]
     /// Optionally request agent info for a particular cell id.
     pub dna: Option<DnaHash>,
+    #[structopt(short, long)]
+    /// Save the agent info to this file instead of printing it,
+    /// so it can be added to other conductors with `add-agents`.
+    /// The agent info from every conductor called is saved together.
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Debug, StructOpt)]
+/// Introduces every agent in a set of sandboxes to all the others,
+/// by adding the agent info from each conductor to every other conductor.
+/// This lets sandboxes find each other without a bootstrap service.
+pub struct Link {
+    #[structopt(short, long, conflicts_with_all = &["existing_paths", "indices"], value_delimiter = ",")]
+    /// Ports to running conductor admin interfaces.
+    /// If this is empty existing sandboxes will be used.
+    /// Cannot be combined with existing sandboxes.
+    pub running: Vec<u16>,
+    #[structopt(long, conflicts_with_all = &["existing_paths", "indices"], value_delimiter = ",")]
+    /// Paths to the unix sockets of running conductor admin interfaces.
+    /// Can be combined with ports but not with existing sandboxes.
+    pub running_sockets: Vec<PathBuf>,
+    /// (flattened)
+    #[structopt(flatten)]
+    pub existing: Existing,
 }
 
 #[derive(Debug, StructOpt, Clone)]
@@ -229,17 +308,104 @@ pub async fn call(holochain_path: &Path, req: Call) -> anyhow::Result<()> {
     let Call {
         existing,
         running,
+        running_sockets,
         call,
     } = req;
-    let cmds = if running.is_empty() {
+    let mut cmds = connect(holochain_path, running, running_sockets, existing).await?;
+    match call {
+        AdminRequestCli::ListAgents(ListAgents {
+            agent_key,
+            dna,
+            output: Some(path),
+        }) => {
+            let mut agent_infos = Vec::new();
+            for (cmd, _) in cmds.iter_mut() {
+                let args = ListAgents {
+                    agent_key: agent_key.clone(),
+                    dna: dna.clone(),
+                    output: None,
+                };
+                agent_infos.extend(request_agent_info(cmd, args).await?);
+            }
+            save_agent_infos(&path, &agent_infos)?;
+            msg!(
+                "Saved {} agent infos to {}",
+                agent_infos.len(),
+                path.display()
+            );
+        }
+        call => {
+            for (cmd, _) in cmds.iter_mut() {
+                call_inner(cmd, call.clone()).await?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Introduce every agent in the sandboxes to all the others, by adding
+/// the agent info from each conductor to every other conductor.
+pub async fn link(holochain_path: &Path, link: Link) -> anyhow::Result<()> {
+    let Link {
+        running,
+        running_sockets,
+        existing,
+    } = link;
+    let mut cmds = connect(holochain_path, running, running_sockets, existing).await?;
+    let mut agent_infos = Vec::with_capacity(cmds.len());
+    for (cmd, _) in cmds.iter_mut() {
+        let args = ListAgents {
+            agent_key: None,
+            dna: None,
+            output: None,
+        };
+        agent_infos.push(request_agent_info(cmd, args).await?);
+    }
+    for (i, (cmd, _)) in cmds.iter_mut().enumerate() {
+        let others = agent_infos
+            .iter()
+            .enumerate()
+            .filter(|(j, _)| *j != i)
+            .flat_map(|(_, infos)| infos.iter().cloned())
+            .collect();
+        add_agent_info(cmd, others).await?;
+    }
+    msg!(
+        "Linked {} agents across {} conductors",
+        agent_infos.iter().map(Vec::len).sum::<usize>(),
+        cmds.len()
+    );
+    Ok(())
+}
+
+/// Connect to the admin interfaces of running conductors, or else to the
+/// existing sandboxes, running any of them that aren't already running.
+/// The conductors that had to be run are returned with their admin
+/// interfaces and are stopped when dropped.
+async fn connect(
+    holochain_path: &Path,
+    running: Vec<u16>,
+    running_sockets: Vec<PathBuf>,
+    existing: Existing,
+) -> anyhow::Result<Vec<(CmdRunner, Option<Child>)>> {
+    let cmds = if running.is_empty() && running_sockets.is_empty() {
         let paths = if existing.is_empty() {
             crate::save::load(std::env::current_dir()?)?
         } else {
             existing.load()?
         };
-        let ports = get_admin_ports(paths.clone()).await?;
-        let mut cmds = Vec::with_capacity(ports.len());
-        for (port, path) in ports.into_iter().zip(paths.into_iter()) {
+        let mut cmds = Vec::with_capacity(paths.len());
+        for path in paths {
+            // Sandboxes with a unix socket admin interface aren't run by
+            // the sandbox, so they can only be called if already running.
+            if let Some(socket) = get_admin_socket(path.clone())? {
+                cmds.push((connect_unix_socket(&socket).await?, None));
+                continue;
+            }
+            let port = match get_admin_ports(vec![path.clone()]).await?.pop() {
+                Some(port) => port,
+                None => continue,
+            };
             match CmdRunner::try_new(port).await {
                 Ok(cmd) => cmds.push((cmd, None)),
                 Err(e) => {
@@ -261,16 +427,35 @@ pub async fn call(holochain_path: &Path, req: Call) -> anyhow::Result<()> {
         }
         cmds
     } else {
-        let mut cmds = Vec::with_capacity(running.len());
+        let mut cmds = Vec::with_capacity(running.len() + running_sockets.len());
         for port in running {
             cmds.push((CmdRunner::new(port).await, None));
         }
+        for socket in running_sockets {
+            cmds.push((connect_unix_socket(&socket).await?, None));
+        }
         cmds
     };
-    for mut cmd in cmds {
-        call_inner(&mut cmd.0, call.clone()).await?;
-    }
-    Ok(())
+    Ok(cmds)
+}
+
+#[cfg(unix)]
+async fn connect_unix_socket(socket: &Path) -> anyhow::Result<CmdRunner> {
+    CmdRunner::try_new_unix_socket(socket).await.map_err(|e| {
+        anyhow!(
+            "Failed to connect to the admin socket {}: {:?}",
+            socket.display(),
+            e
+        )
+    })
+}
+
+#[cfg(not(unix))]
+async fn connect_unix_socket(socket: &Path) -> anyhow::Result<CmdRunner> {
+    bail!(
+        "Can't connect to the admin socket {}, unix sockets are only supported on unix",
+        socket.display()
+    )
 }
 
 async fn call_inner(cmd: &mut CmdRunner, call: AdminRequestCli) -> anyhow::Result<()> {
@@ -283,6 +468,14 @@ async fn call_inner(cmd: &mut CmdRunner, call: AdminRequestCli) -> anyhow::Resul
             let port = attach_app_interface(cmd, args).await?;
             msg!("Added App port {}", port);
         }
+        AdminRequestCli::AddAdminSocket(args) => {
+            let path = add_admin_socket(cmd, args).await?;
+            msg!("Added Admin socket {}", path.display());
+        }
+        AdminRequestCli::AddAppSocket(args) => {
+            let path = attach_app_socket(cmd, args).await?;
+            msg!("Added App socket {}", path.display());
+        }
         AdminRequestCli::ListAppWs => {
             let ports = list_app_ws(cmd).await?;
             msg!("Attached App Interfaces {:?}", ports);
@@ -309,6 +502,10 @@ async fn call_inner(cmd: &mut CmdRunner, call: AdminRequestCli) -> anyhow::Resul
             let dnas = list_dnas(cmd).await?;
             msg!("Dnas: {:?}", dnas);
         }
+        AdminRequestCli::DnaDefinition(args) => {
+            let dna_definition = get_dna_definition(cmd, args).await?;
+            msg!("Dna definition: {:#?}", dna_definition);
+        }
         AdminRequestCli::NewAgent => {
             let agent = generate_agent_pub_key(cmd).await?;
             msg!("Added agent {}", agent);
@@ -339,7 +536,15 @@ async fn call_inner(cmd: &mut CmdRunner, call: AdminRequestCli) -> anyhow::Resul
             let state = dump_state(cmd, args).await?;
             msg!("DUMP STATE \n{}", state);
         }
-        AdminRequestCli::AddAgents => todo!("Adding agent info via cli is coming soon"),
+        AdminRequestCli::AddAgents(args) => {
+            let mut agent_infos = Vec::new();
+            for path in args.paths {
+                agent_infos.extend(load_agent_infos(&path)?);
+            }
+            let count = agent_infos.len();
+            add_agent_info(cmd, agent_infos).await?;
+            msg!("Added {} agent infos", count);
+        }
         AdminRequestCli::ListAgents(args) => {
             use std::fmt::Write;
             let agent_infos = request_agent_info(cmd, args).await?;
@@ -412,6 +617,31 @@ pub async fn add_admin_interface(cmd: &mut CmdRunner, args: AddAdminWs) -> anyho
     Ok(port)
 }
 
+/// Calls [`AdminRequest::AddAdminInterfaces`] and adds another admin interface
+/// on a unix domain socket.
+pub async fn add_admin_socket(
+    cmd: &mut CmdRunner,
+    args: AddAdminSocket,
+) -> anyhow::Result<PathBuf> {
+    let AddAdminSocket { path, mode } = args;
+    let resp = cmd
+        .command(AdminRequest::AddAdminInterfaces(vec![
+            AdminInterfaceConfig {
+                driver: InterfaceDriver::UnixSocket {
+                    path: path.clone(),
+                    mode: mode.unwrap_or(DEFAULT_UNIX_SOCKET_MODE),
+                },
+            },
+        ]))
+        .await?;
+    ensure!(
+        matches!(resp, AdminResponse::AdminInterfacesAdded),
+        "Failed to add admin interface, got: {:?}",
+        resp
+    );
+    Ok(path)
+}
+
 /// Calls [`AdminRequest::RegisterDna`] and registers dna.
 pub async fn register_dna(cmd: &mut CmdRunner, args: RegisterDna) -> anyhow::Result<DnaHash> {
     let RegisterDna {
@@ -553,6 +783,17 @@ pub async fn list_dnas(cmd: &mut CmdRunner) -> anyhow::Result<Vec<DnaHash>> {
     Ok(expect_match!(resp => AdminResponse::DnasListed, "Failed to list dnas"))
 }
 
+/// Calls [`AdminRequest::GetDnaDefinition`].
+pub async fn get_dna_definition(
+    cmd: &mut CmdRunner,
+    args: DnaDefinitionArgs,
+) -> anyhow::Result<DnaDefinition> {
+    let resp = cmd
+        .command(AdminRequest::GetDnaDefinition(Box::new(args.dna)))
+        .await?;
+    Ok(expect_match!(resp => AdminResponse::DnaDefinitionReturned, "Failed to get dna definition"))
+}
+
 /// Calls [`AdminRequest::GenerateAgentPubKey`].
 pub async fn generate_agent_pub_key(cmd: &mut CmdRunner) -> anyhow::Result<AgentPubKey> {
     let resp = cmd.command(AdminRequest::GenerateAgentPubKey).await?;
@@ -628,6 +869,27 @@ pub async fn attach_app_interface(cmd: &mut CmdRunner, args: AddAppWs) -> anyhow
     }
 }
 
+/// Calls [`AdminRequest::AttachAppInterfaceUnixSocket`] and adds another app interface
+/// on a unix domain socket.
+pub async fn attach_app_socket(cmd: &mut CmdRunner, args: AddAppSocket) -> anyhow::Result<PathBuf> {
+    let AddAppSocket { path, mode } = args;
+    let resp = cmd
+        .command(AdminRequest::AttachAppInterfaceUnixSocket {
+            path: path.clone(),
+            mode,
+        })
+        .await?;
+    tracing::debug!(?resp);
+    match resp {
+        AdminResponse::AppInterfaceAttachedUnixSocket { path } => Ok(path),
+        _ => Err(anyhow!(
+            "Failed to attach app interface {}, got: {:?}",
+            path.display(),
+            resp
+        )),
+    }
+}
+
 /// Calls [`AdminRequest::DumpState`] and dumps the current cell's state.
 // TODO: Add pretty print.
 // TODO: Default to dumping all cell state.
@@ -653,6 +915,22 @@ pub async fn add_agent_info(cmd: &mut CmdRunner, args: Vec<AgentInfoSigned>) ->
     Ok(())
 }
 
+/// Save agent info to a file, which can be loaded with [`load_agent_infos`].
+pub fn save_agent_infos(path: &Path, agent_infos: &[AgentInfoSigned]) -> anyhow::Result<()> {
+    let mut bytes = Vec::new();
+    rmp_encode(&mut bytes, agent_infos)?;
+    std::fs::write(path, bytes)
+        .map_err(|e| anyhow!("Failed to write agent info to {}: {}", path.display(), e))
+}
+
+/// Load agent info from a file saved with [`save_agent_infos`].
+pub fn load_agent_infos(path: &Path) -> anyhow::Result<Vec<AgentInfoSigned>> {
+    let bytes = std::fs::read(path)
+        .map_err(|e| anyhow!("Failed to read agent info from {}: {}", path.display(), e))?;
+    rmp_decode(&mut bytes.as_slice())
+        .map_err(|e| anyhow!("Failed to decode agent info from {}: {}", path.display(), e))
+}
+
 /// Calls [`AdminRequest::RequestAgentInfo`] and pretty prints the agent info on this conductor.
 pub async fn request_agent_info(
     cmd: &mut CmdRunner,
@@ -685,6 +963,11 @@ fn parse_status_filter(arg: &str) -> anyhow::Result<AppStatusFilter> {
     }
 }
 
+fn parse_mode(arg: &str) -> anyhow::Result<u32> {
+    u32::from_str_radix(arg.trim_start_matches("0o"), 8)
+        .map_err(|e| anyhow::anyhow!("Bad socket mode: {}, expected octal e.g. 660: {}", arg, e))
+}
+
 impl From<CellId> for DumpState {
     fn from(cell_id: CellId) -> Self {
         let (dna, agent_key) = cell_id.into_dna_and_agent();
@@ -703,8 +986,72 @@ impl From<ListAgents> for Option<CellId> {
         let ListAgents {
             agent_key: a,
             dna: d,
+            ..
         } = la;
         d.and_then(|d| a.map(|a| (d, a)))
             .map(|(d, a)| CellId::new(d, a))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use holochain_p2p::kitsune_p2p::{KitsuneAgent, KitsuneSpace};
+    use std::sync::Arc;
+
+    async fn agent_info(agent: u8) -> AgentInfoSigned {
+        AgentInfoSigned::sign(
+            Arc::new(KitsuneSpace(vec![0x01; 36])),
+            Arc::new(KitsuneAgent(vec![agent; 36])),
+            42,
+            vec![url2::url2!("kitsune-quic://localhost:5000").into()],
+            42,
+            69,
+            |_| async move { Ok(Arc::new(vec![0x03; 64].into())) },
+        )
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn save_and_load_agent_infos() {
+        let path = std::env::temp_dir().join(format!("agents_{}.bin", nanoid::nanoid!()));
+        let agent_infos = vec![agent_info(0x02).await, agent_info(0x04).await];
+
+        save_agent_infos(&path, &agent_infos).unwrap();
+        let loaded = load_agent_infos(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.unwrap(), agent_infos);
+
+        assert!(load_agent_infos(&path).is_err());
+    }
+
+    #[test]
+    fn parse_agent_commands() {
+        let call = Call::from_iter_safe(&["call", "add-agents", "a.bin", "b.bin"]).unwrap();
+        matches::assert_matches!(
+            call.call,
+            AdminRequestCli::AddAgents(AddAgents { paths })
+            if paths == vec![PathBuf::from("a.bin"), PathBuf::from("b.bin")]
+        );
+        assert!(Call::from_iter_safe(&["call", "add-agents"]).is_err());
+
+        let call =
+            Call::from_iter_safe(&["call", "list-agents", "--output", "agents.bin"]).unwrap();
+        matches::assert_matches!(
+            call.call,
+            AdminRequestCli::ListAgents(ListAgents { output: Some(path), agent_key: None, dna: None })
+            if path == PathBuf::from("agents.bin")
+        );
+
+        let link = Link::from_iter_safe(&["link", "--running", "9000,9001"]).unwrap();
+        assert_eq!(link.running, vec![9000, 9001]);
+        assert!(link.existing.is_empty());
+
+        let link = Link::from_iter_safe(&["link", "1", "3"]).unwrap();
+        assert!(link.running.is_empty());
+        assert_eq!(link.existing.indices, vec![1, 3]);
+
+        assert!(Link::from_iter_safe(&["link", "-e", "/tmp/a", "--running", "9000"]).is_err());
+    }
+}
@@ -68,6 +68,9 @@ pub enum HcSandboxSubcommand {
     /// Make a call to a conductor's admin interface.
     Call(crate::calls::Call),
 
+    /// Introduce every agent in a set of sandboxes to all the others.
+    Link(crate::calls::Link),
+
     /// List sandboxes found in `$(pwd)/.hc`.
     List {
         /// Show more verbose information.
@@ -149,6 +152,9 @@ impl HcSandbox {
             HcSandboxSubcommand::Call(call) => {
                 crate::calls::call(&self.holochain_path, call).await?
             }
+            HcSandboxSubcommand::Link(link) => {
+                crate::calls::link(&self.holochain_path, link).await?
+            }
             // HcSandboxSubcommand::Task => todo!("Running custom tasks is coming soon"),
             HcSandboxSubcommand::List { verbose } => {
                 crate::save::list(std::env::current_dir()?, verbose)?
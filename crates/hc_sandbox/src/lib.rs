@@ -73,6 +73,23 @@
 //! ```shell
 //! hc sandbox call list-cells
 //! ```
+//! Agent info can be saved from one set of sandboxes and added to another,
+//! for example to connect sandboxes on a network without a bootstrap service:
+//! ```shell
+//! hc sandbox call list-agents --output agents.bin
+//! hc sandbox call add-agents agents.bin
+//! ```
+//! Conductors can also be given admin and app interfaces on unix domain sockets,
+//! so that only users with permission on the socket file can connect to them:
+//! ```shell
+//! hc sandbox call add-admin-socket /run/holochain/admin.sock --mode 660
+//! hc sandbox call --running-sockets /run/holochain/admin.sock add-app-socket /run/holochain/app.sock
+//! ```
+//! #### Link
+//! Introduces every agent in a set of sandboxes to all the others.
+//! ```shell
+//! hc sandbox link
+//! ```
 //! #### List and Clean
 //! These commands allow you to list the persisted sandboxes
 //! in the current directory (from the`.hcXXX`) file.
@@ -165,6 +182,14 @@ impl CmdRunner {
         Ok(Self { client })
     }
 
+    /// Create a new connection for calling admin interface commands
+    /// over the unix domain socket at this path.
+    #[cfg(unix)]
+    pub async fn try_new_unix_socket(path: &Path) -> WebsocketResult<Self> {
+        let client = ports::get_admin_api_by_unix_socket(path).await?;
+        Ok(Self { client })
+    }
+
     /// Create a command runner from a sandbox path.
     /// This expects holochain to be on the path.
     pub async fn from_sandbox(
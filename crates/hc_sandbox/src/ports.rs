@@ -43,11 +43,38 @@ pub async fn get_admin_ports(paths: Vec<PathBuf>) -> anyhow::Result<Vec<u16>> {
     Ok(ports)
 }
 
+/// The unix socket of the first admin interface of a sandbox,
+/// if it is a unix socket interface.
+pub(crate) fn get_admin_socket(path: PathBuf) -> anyhow::Result<Option<PathBuf>> {
+    let first = read_config(path)?
+        .and_then(|config| config.admin_interfaces)
+        .and_then(|ai| ai.into_iter().next());
+    Ok(match first {
+        Some(AdminInterfaceConfig {
+            driver: InterfaceDriver::UnixSocket { path, .. },
+        }) => Some(path),
+        _ => None,
+    })
+}
+
 pub(crate) async fn get_admin_api(port: u16) -> WebsocketResult<WebsocketSender> {
     tracing::debug!(port);
     websocket_client_by_port(port).await.map(|p| p.0)
 }
 
+#[cfg(unix)]
+pub(crate) async fn get_admin_api_by_unix_socket(
+    path: &std::path::Path,
+) -> WebsocketResult<WebsocketSender> {
+    tracing::debug!(?path);
+    ws::connect(
+        ws::unix_socket_url(path)?,
+        Arc::new(WebsocketConfig::default()),
+    )
+    .await
+    .map(|p| p.0)
+}
+
 async fn websocket_client_by_port(
     port: u16,
 ) -> WebsocketResult<(WebsocketSender, WebsocketReceiver)> {
@@ -67,6 +94,9 @@ pub(crate) fn random_admin_port(config: &mut ConductorConfig) {
                 *port = 0;
             }
         }
+        Some(AdminInterfaceConfig {
+            driver: InterfaceDriver::UnixSocket { .. },
+        }) => (),
         None => {
             let port = 0;
             config.admin_interfaces = Some(vec![AdminInterfaceConfig {
@@ -10,6 +10,7 @@ use tokio::sync::oneshot;
 use crate::calls::attach_app_interface;
 use crate::calls::AddAppWs;
 use crate::config::*;
+use crate::ports::get_admin_socket;
 use crate::ports::random_admin_port;
 use crate::ports::set_admin_port;
 use crate::CmdRunner;
@@ -59,6 +60,8 @@ pub async fn run(
 /// Can optionally force the admin port used. Otherwise
 /// the port in the config will be used if it's free or
 /// a random free port will be chosen.
+/// A sandbox with a unix socket admin interface can only
+/// be run by forcing an admin port.
 pub async fn run_async(
     holochain_path: &Path,
     sandbox_path: PathBuf,
@@ -68,6 +71,16 @@ pub async fn run_async(
         Some(c) => c,
         None => create_config(sandbox_path.clone()),
     };
+    if force_admin_port.is_none() {
+        if let Some(socket) = get_admin_socket(sandbox_path.clone())? {
+            anyhow::bail!(
+                "The sandbox at {} has its admin interface on the unix socket {}. \
+                Run it with holochain and use --running-sockets to call it.",
+                sandbox_path.display(),
+                socket.display()
+            );
+        }
+    }
     match force_admin_port {
         Some(port) => {
             set_admin_port(&mut config, port);
@@ -106,7 +106,7 @@ pub fn create_cap_claim(cap_claim_entry: CapClaimEntry) -> ExternResult<ActionHa
 /// extern at one time, or may apply in addition to a stricter grant. In this case, revoking a
 /// stricter grant, or failing to revoke all Unrestricted grants will leave the function open.
 ///
-/// @todo administration functions to query active grants
+/// The active grants of the source chain can be listed with [`capability_grants`].
 ///
 /// There is an apparent "chicken or the egg" situation where [`CapGrant`] are required for remote
 /// agents to call externs, so how does an agent request a grant in the first place?
@@ -198,3 +198,35 @@ pub fn update_cap_grant(
         chain_top_ordering: ChainTopOrdering::default(),
     })
 }
+
+/// List the active capability grants on the local source chain.
+///
+/// A grant is active if it has been neither updated nor deleted, so these are
+/// exactly the grants which can currently authorize zome calls. The grants
+/// can be filtered by tag and by the functions they grant access to, see
+/// [`CapGrantQuery`]. Each grant is returned as the [`Record`] which
+/// committed it, so that its [`ActionHash`] can be used to update or delete it.
+pub fn capability_grants(cap_grant_query: CapGrantQuery) -> ExternResult<Vec<Record>> {
+    HDK.with(|h| h.borrow().capability_grants(cap_grant_query))
+}
+
+/// List the active capability claims on the local source chain.
+///
+/// A claim is active if it has been neither updated nor deleted.
+/// The claims can be filtered by tag and by grantor, see [`CapClaimQuery`].
+///
+/// There is no guarantee that an active claim is still usable, as the grantor
+/// may have revoked the corresponding grant.
+pub fn capability_claims(cap_claim_query: CapClaimQuery) -> ExternResult<Vec<Record>> {
+    HDK.with(|h| h.borrow().capability_claims(cap_claim_query))
+}
+
+/// Get the capability grant which authorized the current zome call.
+///
+/// Calls made by the chain author, and callbacks run by the host, are
+/// authorized by the implicit [`CapGrant::ChainAuthor`] grant, which has no
+/// action. For all other calls this is the grant committed to the source
+/// chain, along with the hash of the action which committed it.
+pub fn capability_info() -> ExternResult<CapabilityInfo> {
+    HDK.with(|h| h.borrow().capability_info(()))
+}
@@ -30,3 +30,34 @@ pub fn get_agent_activity(
 pub fn query(filter: ChainQueryFilter) -> ExternResult<Vec<Record>> {
     HDK.with(|h| h.borrow().query(filter))
 }
+
+/// Close the source chain of the current agent, declaring the DNA which the
+/// agent is migrating to.
+///
+/// A [ `CloseChain` ] action must be the last action on a source chain.
+/// Any attempt to commit after it will fail sys validation.
+///
+/// This is normally written by the conductor while upgrading an app, after
+/// the `migrate_agent` callback has passed for [ `MigrateAgent::Close` ].
+pub fn close_chain(new_dna_hash: DnaHash) -> ExternResult<ActionHash> {
+    HDK.with(|h| {
+        h.borrow().close_chain(CloseChainInput::new(
+            new_dna_hash,
+            ChainTopOrdering::default(),
+        ))
+    })
+}
+
+/// Open the source chain of the current agent, declaring the DNA which the
+/// agent has migrated from.
+///
+/// This is normally written by the conductor while upgrading an app, after
+/// the `migrate_agent` callback has passed for [ `MigrateAgent::Open` ].
+pub fn open_chain(prev_dna_hash: DnaHash) -> ExternResult<ActionHash> {
+    HDK.with(|h| {
+        h.borrow().open_chain(OpenChainInput::new(
+            prev_dna_hash,
+            ChainTopOrdering::default(),
+        ))
+    })
+}
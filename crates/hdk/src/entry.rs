@@ -287,6 +287,23 @@ pub fn get_details<H: Into<AnyDhtHash>>(
         .unwrap())
 }
 
+/// Get the metadata on an action or entry hash without fetching any records or entries.
+///
+/// This is a cheap way to check whether something has been updated or deleted, or how many
+/// links there are on a base. The [`MetadataRequest`] selects which parts of the
+/// [`MetadataSet`] are filled in, and the metadata from several authorities is merged.
+///
+/// See [`get_details`].
+pub fn get_meta<H: Into<AnyDhtHash>>(
+    hash: H,
+    metadata_request: MetadataRequest,
+) -> ExternResult<MetadataSet> {
+    HDK.with(|h| {
+        h.borrow()
+            .get_meta(GetMetaInput::new(hash.into(), metadata_request))
+    })
+}
+
 /// Implements a whole lot of sane defaults for a struct or enum that should behave as an entry.
 /// All the entry def fields are available as dedicated methods on the type and matching From impls
 /// are provided for each. This allows for both Foo::entry_def() and EntryDef::from(Foo::new())
@@ -52,6 +52,7 @@ fn root_ensures() {
             base_address: root_hash().unwrap(),
             link_type: LinkTypeFilter::single_type(0.into(), 0.into()),
             tag_prefix: Some(Path::from("foo").make_tag().unwrap()),
+            filter: LinkFilter::default(),
         }]))
         .returning(|_| {
             Ok(vec![vec![Link {
@@ -211,6 +212,7 @@ fn paths_exists() {
             base_address: Path::from("foo").path_entry_hash().unwrap().into(),
             link_type: LinkTypeFilter::single_type(0.into(), 0.into()),
             tag_prefix: Some(Path::from("bar").make_tag().unwrap()),
+            filter: LinkFilter::default(),
         }]))
         .returning(|_| {
             Ok(vec![vec![Link {
@@ -228,6 +230,7 @@ fn paths_exists() {
             base_address: Path::from("foo.bar").path_entry_hash().unwrap().into(),
             link_type: LinkTypeFilter::single_type(0.into(), 0.into()),
             tag_prefix: Some(Path::from("baz").make_tag().unwrap()),
+            filter: LinkFilter::default(),
         }]))
         .returning(|_| {
             Ok(vec![vec![Link {
@@ -316,6 +319,7 @@ fn children() {
             base_address: root_hash().unwrap(),
             link_type: LinkTypeFilter::single_type(0.into(), 0.into()),
             tag_prefix: Some(Path::from("foo").make_tag().unwrap()),
+            filter: LinkFilter::default(),
         }]))
         .returning({
             let foo = foo.clone();
@@ -327,6 +331,7 @@ fn children() {
             base_address: Path::from("foo").path_entry_hash().unwrap().into(),
             link_type: LinkTypeFilter::single_type(0.into(), 0.into()),
             tag_prefix: Some(Path::from("bar").make_tag().unwrap()),
+            filter: LinkFilter::default(),
         }]))
         .returning({
             let foo_bar = foo_bar.clone();
@@ -338,6 +343,7 @@ fn children() {
             base_address: Path::from("foo").path_entry_hash().unwrap().into(),
             link_type: LinkTypeFilter::single_type(0.into(), 0.into()),
             tag_prefix: Some(Path::from("bar2").make_tag().unwrap()),
+            filter: LinkFilter::default(),
         }]))
         .returning({
             let foo_bar2 = foo_bar2.clone();
@@ -349,6 +355,7 @@ fn children() {
             base_address: Path::from("foo.bar").path_entry_hash().unwrap().into(),
             link_type: LinkTypeFilter::single_type(0.into(), 0.into()),
             tag_prefix: Some(Path::from("baz").make_tag().unwrap()),
+            filter: LinkFilter::default(),
         }]))
         .returning({
             let foo_bar_baz = foo_bar_baz.clone();
@@ -360,6 +367,7 @@ fn children() {
             base_address: Path::from("foo.bar2").path_entry_hash().unwrap().into(),
             link_type: LinkTypeFilter::single_type(0.into(), 0.into()),
             tag_prefix: Some(Path::from("baz2").make_tag().unwrap()),
+            filter: LinkFilter::default(),
         }]))
         .returning({
             let foo_bar2_baz2 = foo_bar2_baz2.clone();
@@ -371,6 +379,7 @@ fn children() {
             base_address: Path::from("foo").path_entry_hash().unwrap().into(),
             link_type: LinkTypeFilter::single_type(0.into(), 0.into()),
             tag_prefix: None,
+            filter: LinkFilter::default(),
         }]))
         .returning(move |_| Ok(vec![vec![foo_bar.clone(), foo_bar2.clone()]]));
     // foo.bar -[]-> foo.bar.baz
@@ -379,6 +388,7 @@ fn children() {
             base_address: Path::from("foo.bar").path_entry_hash().unwrap().into(),
             link_type: LinkTypeFilter::single_type(0.into(), 0.into()),
             tag_prefix: None,
+            filter: LinkFilter::default(),
         }]))
         .returning(move |_| Ok(vec![vec![foo_bar_baz.clone()]]));
     // foo.bar2 -[]-> foo.bar2.baz2
@@ -387,6 +397,7 @@ fn children() {
             base_address: Path::from("foo.bar2").path_entry_hash().unwrap().into(),
             link_type: LinkTypeFilter::single_type(0.into(), 0.into()),
             tag_prefix: None,
+            filter: LinkFilter::default(),
         }]))
         .returning(move |_| Ok(vec![vec![foo_bar2_baz2.clone()]]));
     // foo.bar.baz -[]-> ()
@@ -395,6 +406,7 @@ fn children() {
             base_address: Path::from("foo.bar.baz").path_entry_hash().unwrap().into(),
             link_type: LinkTypeFilter::single_type(0.into(), 0.into()),
             tag_prefix: None,
+            filter: LinkFilter::default(),
         }]))
         .returning(|_| Ok(vec![vec![]]));
     // foo.bar2.baz2 -[]-> ()
@@ -406,6 +418,7 @@ fn children() {
                 .into(),
             link_type: LinkTypeFilter::single_type(0.into(), 0.into()),
             tag_prefix: None,
+            filter: LinkFilter::default(),
         }]))
         .returning(|_| Ok(vec![vec![]]));
     set_hdk(mock);
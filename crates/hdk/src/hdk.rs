@@ -27,6 +27,8 @@ pub trait HdkT: HdiT {
         get_agent_activity_input: GetAgentActivityInput,
     ) -> ExternResult<AgentActivity>;
     fn query(&self, filter: ChainQueryFilter) -> ExternResult<Vec<Record>>;
+    fn close_chain(&self, close_chain_input: CloseChainInput) -> ExternResult<ActionHash>;
+    fn open_chain(&self, open_chain_input: OpenChainInput) -> ExternResult<ActionHash>;
     // Ed25519
     fn sign(&self, sign: Sign) -> ExternResult<Signature>;
     fn sign_ephemeral(&self, sign_ephemeral: SignEphemeral) -> ExternResult<EphemeralSignatures>;
@@ -36,6 +38,7 @@ pub trait HdkT: HdiT {
     fn delete(&self, delete_input: DeleteInput) -> ExternResult<ActionHash>;
     fn get(&self, get_input: Vec<GetInput>) -> ExternResult<Vec<Option<Record>>>;
     fn get_details(&self, get_input: Vec<GetInput>) -> ExternResult<Vec<Option<Details>>>;
+    fn get_meta(&self, get_meta_input: GetMetaInput) -> ExternResult<MetadataSet>;
     // CounterSigning
     fn accept_countersigning_preflight_request(
         &self,
@@ -44,6 +47,10 @@ pub trait HdkT: HdiT {
     // Info
     fn agent_info(&self, agent_info_input: ()) -> ExternResult<AgentInfo>;
     fn call_info(&self, call_info_input: ()) -> ExternResult<CallInfo>;
+    // Capability
+    fn capability_grants(&self, cap_grant_query: CapGrantQuery) -> ExternResult<Vec<Record>>;
+    fn capability_claims(&self, cap_claim_query: CapClaimQuery) -> ExternResult<Vec<Record>>;
+    fn capability_info(&self, capability_info_input: ()) -> ExternResult<CapabilityInfo>;
     // Link
     fn create_link(&self, create_link_input: CreateLinkInput) -> ExternResult<ActionHash>;
     fn delete_link(&self, delete_link_input: DeleteLinkInput) -> ExternResult<ActionHash>;
@@ -52,6 +59,7 @@ pub trait HdkT: HdiT {
         &self,
         get_links_input: Vec<GetLinksInput>,
     ) -> ExternResult<Vec<LinkDetails>>;
+    fn count_links(&self, count_links_input: GetLinksInput) -> ExternResult<usize>;
     // P2P
     fn call(&self, call: Vec<Call>) -> ExternResult<Vec<ZomeCallResponse>>;
     fn emit_signal(&self, app_signal: AppSignal) -> ExternResult<()>;
@@ -97,6 +105,8 @@ mockall::mock! {
             get_agent_activity_input: GetAgentActivityInput,
         ) -> ExternResult<AgentActivity>;
         fn query(&self, filter: ChainQueryFilter) -> ExternResult<Vec<Record>>;
+        fn close_chain(&self, close_chain_input: CloseChainInput) -> ExternResult<ActionHash>;
+        fn open_chain(&self, open_chain_input: OpenChainInput) -> ExternResult<ActionHash>;
         // Ed25519
         fn sign(&self, sign: Sign) -> ExternResult<Signature>;
         fn sign_ephemeral(&self, sign_ephemeral: SignEphemeral) -> ExternResult<EphemeralSignatures>;
@@ -106,6 +116,7 @@ mockall::mock! {
         fn delete(&self, delete_input: DeleteInput) -> ExternResult<ActionHash>;
         fn get(&self, get_input: Vec<GetInput>) -> ExternResult<Vec<Option<Record>>>;
         fn get_details(&self, get_input: Vec<GetInput>) -> ExternResult<Vec<Option<Details>>>;
+        fn get_meta(&self, get_meta_input: GetMetaInput) -> ExternResult<MetadataSet>;
         // CounterSigning
         fn accept_countersigning_preflight_request(
             &self,
@@ -114,6 +125,10 @@ mockall::mock! {
         // Info
         fn agent_info(&self, agent_info_input: ()) -> ExternResult<AgentInfo>;
         fn call_info(&self, call_info_input: ()) -> ExternResult<CallInfo>;
+        // Capability
+        fn capability_grants(&self, cap_grant_query: CapGrantQuery) -> ExternResult<Vec<Record>>;
+        fn capability_claims(&self, cap_claim_query: CapClaimQuery) -> ExternResult<Vec<Record>>;
+        fn capability_info(&self, capability_info_input: ()) -> ExternResult<CapabilityInfo>;
         // Link
         fn create_link(&self, create_link_input: CreateLinkInput) -> ExternResult<ActionHash>;
         fn delete_link(&self, delete_link_input: DeleteLinkInput) -> ExternResult<ActionHash>;
@@ -122,6 +137,7 @@ mockall::mock! {
             &self,
             get_links_input: Vec<GetLinksInput>,
         ) -> ExternResult<Vec<LinkDetails>>;
+        fn count_links(&self, count_links_input: GetLinksInput) -> ExternResult<usize>;
         // P2P
         fn call(&self, call: Vec<Call>) -> ExternResult<Vec<ZomeCallResponse>>;
         fn emit_signal(&self, app_signal: AppSignal) -> ExternResult<()>;
@@ -265,6 +281,12 @@ impl HdkT for ErrHdk {
     fn query(&self, _: ChainQueryFilter) -> ExternResult<Vec<Record>> {
         Self::err()
     }
+    fn close_chain(&self, _: CloseChainInput) -> ExternResult<ActionHash> {
+        Self::err()
+    }
+    fn open_chain(&self, _: OpenChainInput) -> ExternResult<ActionHash> {
+        Self::err()
+    }
     fn sign(&self, _: Sign) -> ExternResult<Signature> {
         Self::err()
     }
@@ -286,6 +308,9 @@ impl HdkT for ErrHdk {
     fn get_details(&self, _: Vec<GetInput>) -> ExternResult<Vec<Option<Details>>> {
         Self::err()
     }
+    fn get_meta(&self, _: GetMetaInput) -> ExternResult<MetadataSet> {
+        Self::err()
+    }
     // CounterSigning
     fn accept_countersigning_preflight_request(
         &self,
@@ -299,6 +324,16 @@ impl HdkT for ErrHdk {
     fn call_info(&self, _: ()) -> ExternResult<CallInfo> {
         Self::err()
     }
+    // Capability
+    fn capability_grants(&self, _: CapGrantQuery) -> ExternResult<Vec<Record>> {
+        Self::err()
+    }
+    fn capability_claims(&self, _: CapClaimQuery) -> ExternResult<Vec<Record>> {
+        Self::err()
+    }
+    fn capability_info(&self, _: ()) -> ExternResult<CapabilityInfo> {
+        Self::err()
+    }
     // Link
     fn create_link(&self, _: CreateLinkInput) -> ExternResult<ActionHash> {
         Self::err()
@@ -312,6 +347,9 @@ impl HdkT for ErrHdk {
     fn get_link_details(&self, _: Vec<GetLinksInput>) -> ExternResult<Vec<LinkDetails>> {
         Self::err()
     }
+    fn count_links(&self, _: GetLinksInput) -> ExternResult<usize> {
+        Self::err()
+    }
     // P2P
     fn call(&self, _: Vec<Call>) -> ExternResult<Vec<ZomeCallResponse>> {
         Self::err()
@@ -450,6 +488,12 @@ impl HdkT for HostHdk {
     fn query(&self, filter: ChainQueryFilter) -> ExternResult<Vec<Record>> {
         host_call::<ChainQueryFilter, Vec<Record>>(__query, filter)
     }
+    fn close_chain(&self, close_chain_input: CloseChainInput) -> ExternResult<ActionHash> {
+        host_call::<CloseChainInput, ActionHash>(__close_chain, close_chain_input)
+    }
+    fn open_chain(&self, open_chain_input: OpenChainInput) -> ExternResult<ActionHash> {
+        host_call::<OpenChainInput, ActionHash>(__open_chain, open_chain_input)
+    }
     fn sign(&self, sign: Sign) -> ExternResult<Signature> {
         host_call::<Sign, Signature>(__sign, sign)
     }
@@ -471,6 +515,9 @@ impl HdkT for HostHdk {
     fn get_details(&self, get_inputs: Vec<GetInput>) -> ExternResult<Vec<Option<Details>>> {
         host_call::<Vec<GetInput>, Vec<Option<Details>>>(__get_details, get_inputs)
     }
+    fn get_meta(&self, get_meta_input: GetMetaInput) -> ExternResult<MetadataSet> {
+        host_call::<GetMetaInput, MetadataSet>(__get_meta, get_meta_input)
+    }
     // CounterSigning
     fn accept_countersigning_preflight_request(
         &self,
@@ -487,6 +534,15 @@ impl HdkT for HostHdk {
     fn call_info(&self, _: ()) -> ExternResult<CallInfo> {
         host_call::<(), CallInfo>(__call_info, ())
     }
+    fn capability_grants(&self, cap_grant_query: CapGrantQuery) -> ExternResult<Vec<Record>> {
+        host_call::<CapGrantQuery, Vec<Record>>(__capability_grants, cap_grant_query)
+    }
+    fn capability_claims(&self, cap_claim_query: CapClaimQuery) -> ExternResult<Vec<Record>> {
+        host_call::<CapClaimQuery, Vec<Record>>(__capability_claims, cap_claim_query)
+    }
+    fn capability_info(&self, _: ()) -> ExternResult<CapabilityInfo> {
+        host_call::<(), CapabilityInfo>(__capability_info, ())
+    }
     fn create_link(&self, create_link_input: CreateLinkInput) -> ExternResult<ActionHash> {
         host_call::<CreateLinkInput, ActionHash>(__create_link, create_link_input)
     }
@@ -502,6 +558,9 @@ impl HdkT for HostHdk {
     ) -> ExternResult<Vec<LinkDetails>> {
         host_call::<Vec<GetLinksInput>, Vec<LinkDetails>>(__get_link_details, get_links_input)
     }
+    fn count_links(&self, count_links_input: GetLinksInput) -> ExternResult<usize> {
+        host_call::<GetLinksInput, usize>(__count_links, count_links_input)
+    }
     fn call(&self, call: Vec<Call>) -> ExternResult<Vec<ZomeCallResponse>> {
         host_call::<Vec<Call>, Vec<ZomeCallResponse>>(__call, call)
     }
@@ -155,6 +155,69 @@ pub fn get_links(
         .unwrap())
 }
 
+/// Returns the links that reference a base entry hash and pass the given [`LinkFilter`].
+///
+/// This is the same as [ `get_links` ], but only returns links created within a time range or by
+/// an author, and can return links newest first. Popular bases can have a great many links, so a
+/// limit can be set and the links fetched a page at a time, continuing from the last link of the
+/// previous page:
+///
+/// ```ignore
+/// let mut filter = LinkFilter {
+///     limit: Some(100),
+///     order: LinkOrder::NewestFirst,
+///     ..Default::default()
+/// };
+/// loop {
+///     let page = get_links_filtered(base.clone(), .., None, filter.clone())?;
+///     // ...
+///     match page.last() {
+///         Some(last) if page.len() == 100 => filter = filter.continue_after(last),
+///         _ => break,
+///     }
+/// }
+/// ```
+///
+/// See [ `get_links` ].
+pub fn get_links_filtered(
+    base: impl Into<AnyLinkableHash>,
+    link_type: impl LinkTypeFilterExt,
+    link_tag: Option<LinkTag>,
+    filter: LinkFilter,
+) -> ExternResult<Vec<Link>> {
+    let link_type = link_type.try_into_filter()?;
+    Ok(HDK
+        .with(|h| {
+            h.borrow().get_links(vec![
+                GetLinksInput::new(base.into(), link_type, link_tag).with_filter(filter)
+            ])
+        })?
+        .into_iter()
+        .next()
+        .unwrap())
+}
+
+/// Counts the links that reference a base entry hash and pass the given [`LinkFilter`].
+///
+/// The links themselves are not fetched: authorities count them from their own DHT database and the
+/// counts from several authorities are merged, so this is much cheaper than [ `get_links_filtered` ]
+/// on a popular base. If the filter has a limit the count stops there, which is enough to answer
+/// questions such as "does this base have at least 10 links?".
+///
+/// See [ `get_links_filtered` ].
+pub fn count_links(
+    base: impl Into<AnyLinkableHash>,
+    link_type: impl LinkTypeFilterExt,
+    link_tag: Option<LinkTag>,
+    filter: LinkFilter,
+) -> ExternResult<usize> {
+    let link_type = link_type.try_into_filter()?;
+    HDK.with(|h| {
+        h.borrow()
+            .count_links(GetLinksInput::new(base.into(), link_type, link_tag).with_filter(filter))
+    })
+}
+
 /// Get all link creates and deletes that reference a base entry hash, optionally filtered by type or tag.
 ///
 /// Type can be filtered by providing a variant of the link types, or a range of them. To get links of
@@ -1,9 +1,14 @@
+pub use crate::capability::capability_claims;
+pub use crate::capability::capability_grants;
+pub use crate::capability::capability_info;
 pub use crate::capability::create_cap_claim;
 pub use crate::capability::create_cap_grant;
 pub use crate::capability::delete_cap_grant;
 pub use crate::capability::generate_cap_secret;
 pub use crate::capability::update_cap_grant;
+pub use crate::chain::close_chain;
 pub use crate::chain::get_agent_activity;
+pub use crate::chain::open_chain;
 pub use crate::chain::query;
 pub use crate::countersigning::accept_countersigning_preflight_request;
 pub use crate::countersigning::session_times_from_millis;
@@ -19,6 +24,7 @@ pub use crate::entry::delete;
 pub use crate::entry::delete_entry;
 pub use crate::entry::get;
 pub use crate::entry::get_details;
+pub use crate::entry::get_meta;
 pub use crate::entry::must_get_action;
 pub use crate::entry::must_get_entry;
 pub use crate::entry::must_get_valid_record;
@@ -38,10 +44,12 @@ pub use crate::info::agent_info;
 pub use crate::info::call_info;
 pub use crate::info::dna_info;
 pub use crate::info::zome_info;
+pub use crate::link::count_links;
 pub use crate::link::create_link;
 pub use crate::link::delete_link;
 pub use crate::link::get_link_details;
 pub use crate::link::get_links;
+pub use crate::link::get_links_filtered;
 pub use crate::link::LinkTypeFilterExt;
 pub use crate::map_extern;
 pub use crate::map_extern::ExternResult;
@@ -125,14 +133,18 @@ macro_rules! holochain_externs {
             __capability_info,
             __get,
             __get_details,
+            __get_meta,
             __get_links,
             __get_link_details,
+            __count_links,
             __get_agent_activity,
             __must_get_entry,
             __must_get_valid_record,
             __must_get_action,
             __accept_countersigning_preflight_request,
             __query,
+            __close_chain,
+            __open_chain,
             __call_remote,
             __call,
             __create,
@@ -61,6 +61,13 @@ struct Opt {
         help = "Display version information such as git revision and HDK version"
     )]
     build_info: bool,
+
+    #[structopt(
+        long,
+        help = "Upgrade the schemas of all databases to the current version, then exit
+    without starting the conductor. Each upgraded database is backed up first"
+    )]
+    migrate_only: bool,
 }
 
 fn main() {
@@ -240,6 +247,11 @@ async fn conductor_handle_from_config_path(opt: &Opt) -> ConductorHandle {
         }
     }
 
+    if opt.migrate_only {
+        migrate_databases(config, passphrase).await;
+        std::process::exit(0);
+    }
+
     // Initialize the Conductor
     Conductor::builder()
         .config(config)
@@ -249,6 +261,37 @@ async fn conductor_handle_from_config_path(opt: &Opt) -> ConductorHandle {
         .expect("Could not initialize Conductor from configuration")
 }
 
+/// Run all pending schema migrations and report on the databases which
+/// were upgraded.
+async fn migrate_databases(config: ConductorConfig, passphrase: Option<sodoken::BufRead>) {
+    let migrations = match Conductor::builder()
+        .config(config)
+        .passphrase(passphrase)
+        .migrate_only()
+        .await
+    {
+        Ok(migrations) => migrations,
+        Err(e) => {
+            println!("Couldn't migrate databases: {}", e);
+            std::process::exit(ERROR_CODE);
+        }
+    };
+    if migrations.is_empty() {
+        println!("All databases are up to date.");
+    }
+    for (path, migration) in migrations {
+        println!(
+            "Migrated {} from version {} to {}.",
+            path.display(),
+            migration.from_version,
+            migration.to_version
+        );
+        if let Some(backup) = migration.backup {
+            println!("    Backup: {}", backup.display());
+        }
+    }
+}
+
 /// Load config, throw friendly error on failure
 fn load_config(config_path: &ConfigFilePath, config_path_default: bool) -> ConductorConfig {
     match ConductorConfig::load_yaml(config_path.as_ref()) {
@@ -130,13 +130,34 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
                 Ok(AdminResponse::DnaRegistered(hash))
             }
             CreateCloneCell(payload) => {
-                let cell_id = payload.cell_id();
-                self.conductor_handle
+                let cell_id = self
+                    .conductor_handle
                     .clone()
                     .create_clone_cell(*payload)
                     .await?;
                 Ok(AdminResponse::CloneCellCreated(cell_id))
             }
+            ArchiveCloneCell(payload) => {
+                self.conductor_handle
+                    .clone()
+                    .archive_clone_cell(*payload)
+                    .await?;
+                Ok(AdminResponse::CloneCellArchived)
+            }
+            RestoreArchivedCloneCell(payload) => {
+                self.conductor_handle
+                    .clone()
+                    .restore_archived_clone_cell(*payload)
+                    .await?;
+                Ok(AdminResponse::ArchivedCloneCellRestored)
+            }
+            DeleteCloneCell(payload) => {
+                self.conductor_handle
+                    .clone()
+                    .delete_clone_cell(*payload)
+                    .await?;
+                Ok(AdminResponse::CloneCellDeleted)
+            }
             InstallApp(payload) => {
                 trace!(?payload.dnas);
                 let InstallAppPayload {
@@ -199,6 +220,40 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
                     InstalledAppInfo::from_installed_app(&app),
                 ))
             }
+            UpgradeApp(payload) => {
+                let UpgradeAppPayload {
+                    installed_app_id,
+                    role_id,
+                    source,
+                    membrane_proof,
+                } = *payload;
+                let dna = match source {
+                    DnaSource::Hash(ref hash) => {
+                        self.conductor_handle.get_dna_file(hash).ok_or_else(|| {
+                            ConductorApiError::DnaReadError(format!(
+                                "Unable to upgrade to Dna: {} not registered",
+                                hash
+                            ))
+                        })?
+                    }
+                    DnaSource::Path(ref path) => {
+                        let bundle = Bundle::read_from_file(path).await?;
+                        let bundle: DnaBundle = bundle.into();
+                        let (dna_file, _original_hash) = bundle.into_dna_file(None, None).await?;
+                        dna_file
+                    }
+                    DnaSource::Bundle(bundle) => {
+                        let (dna_file, _original_hash) = bundle.into_dna_file(None, None).await?;
+                        dna_file
+                    }
+                };
+                let cell_id = self
+                    .conductor_handle
+                    .clone()
+                    .upgrade_app(installed_app_id, role_id, dna, membrane_proof)
+                    .await?;
+                Ok(AdminResponse::AppUpgraded(cell_id))
+            }
             UninstallApp { installed_app_id } => {
                 self.conductor_handle
                     .clone()
@@ -210,6 +265,10 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
                 let dna_list = self.conductor_handle.list_dnas();
                 Ok(AdminResponse::DnasListed(dna_list))
             }
+            GetDnaDefinition(dna_hash) => {
+                let dna_definition = self.conductor_handle.get_dna_definition(&dna_hash)?;
+                Ok(AdminResponse::DnaDefinitionReturned(dna_definition))
+            }
             GenerateAgentPubKey => {
                 let agent_pub_key = self
                     .conductor_handle
@@ -283,13 +342,25 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
             }
             AttachAppInterface { port } => {
                 let port = port.unwrap_or(0);
-                let port = self
+                let driver = self
                     .conductor_handle
                     .clone()
-                    .add_app_interface(port)
+                    .add_app_interface(InterfaceDriver::Websocket { port })
                     .await?;
+                let port = driver.port().unwrap_or(port);
                 Ok(AdminResponse::AppInterfaceAttached { port })
             }
+            AttachAppInterfaceUnixSocket { path, mode } => {
+                let driver = InterfaceDriver::UnixSocket {
+                    path: path.clone(),
+                    mode: mode.unwrap_or(DEFAULT_UNIX_SOCKET_MODE),
+                };
+                self.conductor_handle
+                    .clone()
+                    .add_app_interface(driver)
+                    .await?;
+                Ok(AdminResponse::AppInterfaceAttachedUnixSocket { path })
+            }
             ListAppInterfaces => {
                 let interfaces = self.conductor_handle.list_app_interfaces().await?;
                 Ok(AdminResponse::AppInterfacesListed(interfaces))
@@ -348,6 +419,23 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
                     .await?;
                 Ok(AdminResponse::RecordsAdded)
             }
+            RotateDatabaseKey => {
+                self.conductor_handle.rotate_database_key().await?;
+                Ok(AdminResponse::DatabaseKeyRotated)
+            }
+            BlockAgent { target, expires_at } => {
+                self.conductor_handle
+                    .block(Block::new(target, expires_at))
+                    .await?;
+                Ok(AdminResponse::AgentBlocked)
+            }
+            UnblockAgent { target } => {
+                let unblocked = self.conductor_handle.unblock(target).await?;
+                Ok(AdminResponse::AgentUnblocked(unblocked))
+            }
+            ListBlocks => Ok(AdminResponse::BlocksListed(
+                self.conductor_handle.list_blocks().await?,
+            )),
         }
     }
 }
@@ -410,6 +498,7 @@ mod test {
     use holochain_wasm_test_utils::TestWasm;
     use matches::assert_matches;
     use observability;
+    use std::path::PathBuf;
     use uuid::Uuid;
 
     #[tokio::test(flavor = "multi_thread")]
@@ -664,4 +753,130 @@ mod test {
             .await
             .ok();
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn create_clone_cell() {
+        observability::test_run().ok();
+        let db_dir = test_db_dir();
+        let handle = Conductor::builder().test(db_dir.path(), &[]).await.unwrap();
+        let shutdown = handle.take_shutdown_handle().unwrap();
+        let admin_api = RealAdminInterfaceApi::new(handle.clone());
+        let network_seed = Uuid::new_v4();
+        let dna = fake_dna_zomes(
+            &network_seed.to_string(),
+            vec![(TestWasm::Foo.into(), TestWasm::Foo.into())],
+        );
+        let dna_hash = dna.dna_hash().clone();
+        let other_dna_hash = fake_dna_zomes(
+            &Uuid::new_v4().to_string(),
+            vec![(TestWasm::Foo.into(), TestWasm::Foo.into())],
+        )
+        .dna_hash()
+        .clone();
+        let agent_key = fake_agent_pubkey_1();
+
+        // Install an app with a role which can be cloned once
+        let path = PathBuf::from(format!("{}", dna_hash));
+        let manifest = AppManifestV1 {
+            name: "app".to_string(),
+            description: None,
+            roles: vec![AppRoleManifest {
+                id: "role".to_string(),
+                provisioning: Some(CellProvisioning::Create { deferred: false }),
+                dna: AppRoleDnaManifest {
+                    location: Some(mr_bundle::Location::Bundled(path.clone())),
+                    properties: None,
+                    network_seed: None,
+                    version: None,
+                    clone_limit: 1,
+                },
+            }],
+        };
+        let resources = vec![(path, DnaBundle::from_dna_file(dna).await.unwrap())];
+        let bundle = AppBundle::new(manifest.into(), resources, PathBuf::from("."))
+            .await
+            .unwrap();
+        let res = admin_api
+            .handle_admin_request(AdminRequest::InstallAppBundle(Box::new(
+                InstallAppBundlePayload {
+                    source: AppBundleSource::Bundle(bundle),
+                    agent_key: agent_key.clone(),
+                    installed_app_id: Some("app".to_string()),
+                    membrane_proofs: Default::default(),
+                    network_seed: None,
+                },
+            )))
+            .await;
+        assert_matches!(res, AdminResponse::AppBundleInstalled(_));
+        let res = admin_api
+            .handle_admin_request(AdminRequest::EnableApp {
+                installed_app_id: "app".to_string(),
+            })
+            .await;
+        assert_matches!(res, AdminResponse::AppEnabled { .. });
+
+        let payload = CreateCloneCellPayload {
+            properties: None,
+            dna_hash: dna_hash.clone(),
+            agent_key: agent_key.clone(),
+            installed_app_id: "app".to_string(),
+            role_id: "role".to_string(),
+            membrane_proof: None,
+        };
+
+        // The payload must name the cell of the role
+        let res = admin_api
+            .handle_admin_request(AdminRequest::CreateCloneCell(Box::new(
+                CreateCloneCellPayload {
+                    agent_key: fake_agent_pubkey_2(),
+                    ..payload.clone()
+                },
+            )))
+            .await;
+        assert_matches!(
+            res,
+            AdminResponse::Error(ExternalApiWireError::InternalError(_))
+        );
+        let res = admin_api
+            .handle_admin_request(AdminRequest::CreateCloneCell(Box::new(
+                CreateCloneCellPayload {
+                    dna_hash: other_dna_hash,
+                    ..payload.clone()
+                },
+            )))
+            .await;
+        assert_matches!(
+            res,
+            AdminResponse::Error(ExternalApiWireError::InternalError(_))
+        );
+
+        // The clone is a new cell of the same agent, which is running
+        let res = admin_api
+            .handle_admin_request(AdminRequest::CreateCloneCell(Box::new(payload.clone())))
+            .await;
+        let clone_id = match res {
+            AdminResponse::CloneCellCreated(clone_id) => clone_id,
+            other => panic!("unexpected response {:?}", other),
+        };
+        assert_ne!(clone_id.dna_hash(), &dna_hash);
+        assert_eq!(clone_id.agent_pubkey(), &agent_key);
+        let res = admin_api
+            .handle_admin_request(AdminRequest::ListCellIds)
+            .await;
+        assert_matches!(res, AdminResponse::CellIdsListed(v) if v.contains(&clone_id));
+
+        // The clone limit of the role is reached
+        let res = admin_api
+            .handle_admin_request(AdminRequest::CreateCloneCell(Box::new(payload)))
+            .await;
+        assert_matches!(
+            res,
+            AdminResponse::Error(ExternalApiWireError::InternalError(_))
+        );
+
+        handle.shutdown();
+        tokio::time::timeout(std::time::Duration::from_secs(1), shutdown)
+            .await
+            .ok();
+    }
 }
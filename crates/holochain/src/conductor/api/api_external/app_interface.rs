@@ -2,10 +2,13 @@ use super::InterfaceApi;
 use crate::conductor::api::error::ConductorApiResult;
 use crate::conductor::api::error::ExternalApiWireError;
 use crate::conductor::api::error::SerializationError;
+use crate::conductor::error::ConductorError;
 use crate::conductor::interface::error::InterfaceError;
 use crate::conductor::interface::error::InterfaceResult;
+use crate::conductor::interface::SignalSubscriptions;
 use crate::conductor::ConductorHandle;
 
+use holochain_conductor_api::signal_subscription::SignalSubscription;
 use holochain_serialized_bytes::prelude::*;
 
 use holochain_types::prelude::*;
@@ -41,12 +44,77 @@ pub trait AppInterfaceApi: 'static + Send + Sync + Clone {
 #[derive(Clone)]
 pub struct RealAppInterfaceApi {
     conductor_handle: ConductorHandle,
+    /// The signal subscriptions of the connection this api serves.
+    signal_subscriptions: SignalSubscriptions,
 }
 
 impl RealAppInterfaceApi {
     /// Create a new instance from a shared Conductor reference
     pub fn new(conductor_handle: ConductorHandle) -> Self {
-        Self { conductor_handle }
+        Self {
+            conductor_handle,
+            signal_subscriptions: SignalSubscriptions::default(),
+        }
+    }
+
+    /// Create an instance for a new connection to the interface, which
+    /// starts out without any signal subscriptions.
+    pub fn for_connection(&self) -> Self {
+        Self::new(self.conductor_handle.clone())
+    }
+
+    /// The signal subscriptions of the connection this api serves.
+    pub fn signal_subscriptions(&self) -> &SignalSubscriptions {
+        &self.signal_subscriptions
+    }
+
+    /// Whether the signal should be sent over the connection this api
+    /// serves. The cells of the subscribed Apps are looked up again when
+    /// the signal comes from a Cell they aren't known to have, so that
+    /// clones created after subscribing are covered.
+    pub async fn allows_signal(&self, signal: &Signal) -> bool {
+        if let Signal::App(cell_id, _) = signal {
+            if self.signal_subscriptions.is_unknown_cell(cell_id) {
+                let mut cells = Vec::new();
+                for installed_app_id in self.signal_subscriptions.installed_app_ids() {
+                    match self.app_cell_ids(&installed_app_id).await {
+                        Ok(cell_ids) => cells.push((installed_app_id, cell_ids)),
+                        // The app may have been uninstalled since subscribing.
+                        Err(e) => tracing::debug!(?e, "Couldn't look up the cells of an app"),
+                    }
+                }
+                self.signal_subscriptions.update_cells(cells, cell_id);
+            }
+        }
+        self.signal_subscriptions.allows(signal)
+    }
+
+    async fn app_cell_ids(
+        &self,
+        installed_app_id: &InstalledAppId,
+    ) -> ConductorApiResult<Vec<CellId>> {
+        let app_info = self
+            .conductor_handle
+            .get_app_info(installed_app_id)
+            .await?
+            .ok_or_else(|| ConductorError::AppNotInstalled(installed_app_id.clone()))?;
+        Ok(app_info
+            .cell_data
+            .into_iter()
+            .chain(app_info.clone_cell_data)
+            .map(|cell| cell.into_id())
+            .collect())
+    }
+
+    async fn apply_signal_subscription(
+        &self,
+        subscription: SignalSubscription,
+    ) -> ConductorApiResult<()> {
+        let installed_app_id = subscription.installed_app_id().clone();
+        let cell_ids = self.app_cell_ids(&installed_app_id).await?;
+        self.signal_subscriptions
+            .set(installed_app_id, cell_ids, subscription.filters().clone());
+        Ok(())
     }
 }
 
@@ -100,7 +168,24 @@ impl AppInterfaceApi for RealAppInterfaceApi {
                     Err(e) => Ok(AppResponse::Error(e.into())),
                 }
             }
-            AppRequest::SignalSubscription(_) => Ok(AppResponse::Unimplemented(request)),
+            AppRequest::ArchiveCloneCell(payload) => {
+                self.conductor_handle
+                    .clone()
+                    .archive_clone_cell(*payload)
+                    .await?;
+                Ok(AppResponse::CloneCellArchived)
+            }
+            AppRequest::DeleteCloneCell(payload) => {
+                self.conductor_handle
+                    .clone()
+                    .delete_clone_cell(*payload)
+                    .await?;
+                Ok(AppResponse::CloneCellDeleted)
+            }
+            AppRequest::SignalSubscription(subscription) => {
+                self.apply_signal_subscription(subscription).await?;
+                Ok(AppResponse::SignalSubscriptionUpdated)
+            }
             AppRequest::Crypto(_) => Ok(AppResponse::Unimplemented(request)),
         }
     }
@@ -126,3 +211,191 @@ impl InterfaceApi for RealAppInterfaceApi {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::conductor::Conductor;
+    use holochain_conductor_api::signal_subscription::SignalFilterSet;
+    use holochain_state::prelude::*;
+    use holochain_types::test_utils::fake_agent_pubkey_1;
+    use holochain_types::test_utils::fake_agent_pubkey_2;
+    use holochain_types::test_utils::fake_dna_zomes;
+    use holochain_wasm_test_utils::TestWasm;
+    use matches::assert_matches;
+    use std::path::PathBuf;
+    use uuid::Uuid;
+
+    /// Install and enable an app whose only role can be cloned once
+    async fn install_app(
+        handle: &ConductorHandle,
+        dna: &DnaFile,
+        app_id: &str,
+        agent_key: AgentPubKey,
+    ) {
+        let path = PathBuf::from(format!("{}", dna.dna_hash()));
+        let manifest = AppManifestV1 {
+            name: app_id.to_string(),
+            description: None,
+            roles: vec![AppRoleManifest {
+                id: "role".to_string(),
+                provisioning: Some(CellProvisioning::Create { deferred: false }),
+                dna: AppRoleDnaManifest {
+                    location: Some(mr_bundle::Location::Bundled(path.clone())),
+                    properties: None,
+                    network_seed: None,
+                    version: None,
+                    clone_limit: 1,
+                },
+            }],
+        };
+        let resources = vec![(path, DnaBundle::from_dna_file(dna.clone()).await.unwrap())];
+        let bundle = AppBundle::new(manifest.into(), resources, PathBuf::from("."))
+            .await
+            .unwrap();
+        handle
+            .clone()
+            .install_app_bundle(InstallAppBundlePayload {
+                source: AppBundleSource::Bundle(bundle),
+                agent_key: agent_key.clone(),
+                installed_app_id: Some(app_id.to_string()),
+                membrane_proofs: Default::default(),
+                network_seed: None,
+            })
+            .await
+            .unwrap();
+        handle.clone().enable_app(app_id.to_string()).await.unwrap();
+    }
+
+    async fn create_clone(
+        handle: &ConductorHandle,
+        dna: &DnaFile,
+        app_id: &str,
+        agent_key: AgentPubKey,
+    ) -> CellId {
+        handle
+            .clone()
+            .create_clone_cell(CreateCloneCellPayload {
+                properties: None,
+                dna_hash: dna.dna_hash().clone(),
+                agent_key,
+                installed_app_id: app_id.to_string(),
+                role_id: "role".to_string(),
+                membrane_proof: None,
+            })
+            .await
+            .unwrap()
+    }
+
+    /// Install an app whose only role can be cloned once, and clone it
+    async fn install_app_with_clone(
+        handle: &ConductorHandle,
+        dna: &DnaFile,
+        app_id: &str,
+        agent_key: AgentPubKey,
+    ) -> CellId {
+        install_app(handle, dna, app_id, agent_key.clone()).await;
+        create_clone(handle, dna, app_id, agent_key).await
+    }
+
+    async fn clone_cells(handle: &ConductorHandle, app_id: &str) -> Vec<CellId> {
+        handle
+            .get_app_info(&app_id.to_string())
+            .await
+            .unwrap()
+            .unwrap()
+            .clone_cell_data
+            .into_iter()
+            .map(|cell| cell.into_id())
+            .collect()
+    }
+
+    /// A clone can only be deleted through the app which it belongs to
+    #[tokio::test(flavor = "multi_thread")]
+    async fn delete_clone_cell_of_other_app() {
+        observability::test_run().ok();
+        let db_dir = test_db_dir();
+        let handle = Conductor::builder().test(db_dir.path(), &[]).await.unwrap();
+        let shutdown = handle.take_shutdown_handle().unwrap();
+        let app_api = RealAppInterfaceApi::new(handle.clone());
+        let dna = fake_dna_zomes(
+            &Uuid::new_v4().to_string(),
+            vec![(TestWasm::Foo.into(), TestWasm::Foo.into())],
+        );
+        let clone_a = install_app_with_clone(&handle, &dna, "a", fake_agent_pubkey_1()).await;
+        let clone_b = install_app_with_clone(&handle, &dna, "b", fake_agent_pubkey_2()).await;
+
+        let res = app_api
+            .handle_app_request(AppRequest::DeleteCloneCell(Box::new(
+                DeleteCloneCellPayload {
+                    app_id: "a".to_string(),
+                    role_id: "role".to_string(),
+                    cell_id: clone_b.clone(),
+                },
+            )))
+            .await;
+        assert_matches!(
+            res,
+            AppResponse::Error(ExternalApiWireError::InternalError(_))
+        );
+        assert_eq!(clone_cells(&handle, "b").await, vec![clone_b.clone()]);
+
+        let res = app_api
+            .handle_app_request(AppRequest::DeleteCloneCell(Box::new(
+                DeleteCloneCellPayload {
+                    app_id: "a".to_string(),
+                    role_id: "role".to_string(),
+                    cell_id: clone_a,
+                },
+            )))
+            .await;
+        assert_matches!(res, AppResponse::CloneCellDeleted);
+        assert!(clone_cells(&handle, "a").await.is_empty());
+        assert_eq!(clone_cells(&handle, "b").await, vec![clone_b]);
+
+        handle.shutdown();
+        tokio::time::timeout(std::time::Duration::from_secs(1), shutdown)
+            .await
+            .ok();
+    }
+
+    /// Signal subscriptions cover clones created after subscribing, and
+    /// only apply to the connection which subscribed
+    #[tokio::test(flavor = "multi_thread")]
+    async fn signal_subscription_covers_later_clones() {
+        observability::test_run().ok();
+        let db_dir = test_db_dir();
+        let handle = Conductor::builder().test(db_dir.path(), &[]).await.unwrap();
+        let shutdown = handle.take_shutdown_handle().unwrap();
+        let app_api = RealAppInterfaceApi::new(handle.clone());
+        let other_connection = app_api.for_connection();
+        let dna = fake_dna_zomes(
+            &Uuid::new_v4().to_string(),
+            vec![(TestWasm::Foo.into(), TestWasm::Foo.into())],
+        );
+        install_app(&handle, &dna, "a", fake_agent_pubkey_1()).await;
+
+        let res = app_api
+            .handle_app_request(AppRequest::SignalSubscription(SignalSubscription::new(
+                "a".to_string(),
+                SignalFilterSet::block_all(),
+            )))
+            .await;
+        assert_matches!(res, AppResponse::SignalSubscriptionUpdated);
+
+        let clone = create_clone(&handle, &dna, "a", fake_agent_pubkey_1()).await;
+        let signal = Signal::App(clone, AppSignal::new(ExternIO::encode(()).unwrap()));
+        assert!(!app_api.allows_signal(&signal).await);
+        assert!(other_connection.allows_signal(&signal).await);
+
+        // Cells of other apps are still let through.
+        let other_cell = CellId::new(dna.dna_hash().clone(), fake_agent_pubkey_2());
+        let signal = Signal::App(other_cell, AppSignal::new(ExternIO::encode(()).unwrap()));
+        assert!(app_api.allows_signal(&signal).await);
+
+        handle.shutdown();
+        tokio::time::timeout(std::time::Duration::from_secs(1), shutdown)
+            .await
+            .ok();
+    }
+}
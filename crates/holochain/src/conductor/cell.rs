@@ -15,7 +15,9 @@ use crate::conductor::handle::ConductorHandle;
 use crate::core::queue_consumer::spawn_queue_consumer_tasks;
 use crate::core::queue_consumer::InitialQueueTriggers;
 use crate::core::queue_consumer::QueueTriggers;
+use crate::core::ribosome::error::RibosomeError;
 use crate::core::ribosome::guest_callback::init::InitResult;
+use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentResult;
 use crate::core::ribosome::real_ribosome::RealRibosome;
 use crate::core::ribosome::ZomeCallInvocation;
 use crate::core::workflow::call_zome_workflow;
@@ -23,6 +25,9 @@ use crate::core::workflow::countersigning_workflow::countersigning_success;
 use crate::core::workflow::countersigning_workflow::incoming_countersigning;
 use crate::core::workflow::genesis_workflow::genesis_workflow;
 use crate::core::workflow::initialize_zomes_workflow;
+use crate::core::workflow::migrate_agent_workflow::migrate_agent_workflow;
+use crate::core::workflow::migrate_agent_workflow::stage_migrate_agent_workflow;
+use crate::core::workflow::migrate_agent_workflow::MigrateAgentWorkflowArgs;
 use crate::core::workflow::CallZomeWorkflowArgs;
 use crate::core::workflow::GenesisWorkflowArgs;
 use crate::core::workflow::GenesisWorkspace;
@@ -109,6 +114,24 @@ where
     init_mutex: tokio::sync::Mutex<()>,
 }
 
+/// A `CloseChain` or `OpenChain` action which has passed the `migrate_agent`
+/// callback and validation, but hasn't been written to the source chain yet.
+/// Dropping it abandons the migration.
+pub(crate) struct StagedMigration {
+    workspace: SourceChainWorkspace,
+    network: holochain_p2p::HolochainP2pDna,
+}
+
+impl StagedMigration {
+    /// Write the migration action to the source chain.
+    pub(crate) async fn commit(self) -> CellResult<()> {
+        HostFnWorkspace::from(self.workspace)
+            .flush(&self.network)
+            .await?;
+        Ok(())
+    }
+}
+
 impl Cell {
     /// Constructor for a Cell, which ensure the Cell is fully initialized
     /// before returning.
@@ -447,6 +470,23 @@ impl Cell {
                 .instrument(debug_span!("cell_handle_get_links"))
                 .await;
             }
+            CountLinks {
+                span_context: _,
+                respond,
+                link_key,
+                options,
+                ..
+            } => {
+                async {
+                    let res = self
+                        .handle_count_links(link_key, options)
+                        .await
+                        .map_err(holochain_p2p::HolochainP2pError::other);
+                    respond.respond(Ok(async move { res }.boxed().into()));
+                }
+                .instrument(debug_span!("cell_handle_count_links"))
+                .await;
+            }
             GetAgentActivity {
                 span_context: _,
                 respond,
@@ -645,14 +685,18 @@ impl Cell {
             .map_err(Into::into)
     }
 
-    #[instrument(skip(self, _dht_hash, _options))]
+    #[instrument(skip(self, options))]
     /// a remote node is asking us for metadata
     async fn handle_get_meta(
         &self,
-        _dht_hash: holo_hash::AnyDhtHash,
-        _options: holochain_p2p::event::GetMetaOptions,
+        dht_hash: holo_hash::AnyDhtHash,
+        options: holochain_p2p::event::GetMetaOptions,
     ) -> CellResult<MetadataSet> {
-        unimplemented!()
+        debug!(id = ?self.id());
+        let db = self.space.dht_db.clone();
+        authority::handle_get_meta(db.into(), dht_hash, options)
+            .await
+            .map_err(Into::into)
     }
 
     #[instrument(skip(self, options))]
@@ -672,6 +716,20 @@ impl Cell {
             .map_err(Into::into)
     }
 
+    #[instrument(skip(self, options))]
+    /// a remote node is asking us to count links
+    async fn handle_count_links(
+        &self,
+        link_key: WireLinkKey,
+        options: holochain_p2p::event::GetLinksOptions,
+    ) -> CellResult<CountLinksResponse> {
+        debug!(id = ?self.id());
+        let db = self.space.dht_db.clone();
+        authority::handle_count_links(db.into(), link_key, options)
+            .await
+            .map_err(Into::into)
+    }
+
     #[instrument(skip(self, options))]
     async fn handle_get_agent_activity(
         &self,
@@ -800,6 +858,17 @@ impl Cell {
         call: ZomeCall,
         workspace_lock: Option<SourceChainWorkspace>,
     ) -> CellResult<ZomeCallResult> {
+        let ribosome = self.get_ribosome()?;
+
+        // Fail early if there is no such function to call.
+        let zome = ribosome
+            .dna_def()
+            .get_zome(&call.zome_name)
+            .map_err(|_| RibosomeError::ZomeNotExists(call.zome_name.clone()))?;
+        if !ribosome.list_zome_fns(&zome)?.contains(&call.fn_name) {
+            return Err(RibosomeError::ZomeFnNotExists(call.zome_name, call.fn_name).into());
+        }
+
         // Only check if init has run if this call is not coming from
         // an already running init call.
         if workspace_lock
@@ -814,7 +883,6 @@ impl Cell {
 
         let conductor_handle = self.conductor_handle.clone();
         let signal_tx = self.signal_broadcaster().await;
-        let ribosome = self.get_ribosome()?;
         let invocation =
             ZomeCallInvocation::try_from_interface_call(self.conductor_api.clone(), call).await?;
 
@@ -918,6 +986,83 @@ impl Cell {
         Ok(())
     }
 
+    /// Run the `migrate_agent` callback and, if it passes, close or open
+    /// this cell's source chain. `other_dna_hash` is the DNA being migrated
+    /// to when closing, or from when opening.
+    #[tracing::instrument(skip(self))]
+    pub(crate) async fn migrate_agent(
+        &self,
+        migrate_agent: MigrateAgent,
+        other_dna_hash: DnaHash,
+    ) -> CellResult<()> {
+        let (workspace, args) = self
+            .migrate_agent_workspace(migrate_agent, other_dna_hash)
+            .await?;
+        let result = migrate_agent_workflow(workspace, self.holochain_p2p_cell.clone(), args)
+            .await
+            .map_err(Box::new)?;
+        trace!(?result);
+        match result {
+            MigrateAgentResult::Pass => Ok(()),
+            r => Err(CellError::MigrateAgentFailed(r)),
+        }
+    }
+
+    /// Like [`Cell::migrate_agent`], but the `CloseChain` or `OpenChain`
+    /// action is only staged. It isn't written to the source chain until
+    /// the returned [`StagedMigration`] is committed.
+    #[tracing::instrument(skip(self))]
+    pub(crate) async fn stage_migrate_agent(
+        &self,
+        migrate_agent: MigrateAgent,
+        other_dna_hash: DnaHash,
+    ) -> CellResult<StagedMigration> {
+        let (workspace, args) = self
+            .migrate_agent_workspace(migrate_agent, other_dna_hash)
+            .await?;
+        let result =
+            stage_migrate_agent_workflow(workspace.clone(), self.holochain_p2p_cell.clone(), args)
+                .await
+                .map_err(Box::new)?;
+        trace!(?result);
+        match result {
+            MigrateAgentResult::Pass => Ok(StagedMigration {
+                workspace,
+                network: self.holochain_p2p_cell.clone(),
+            }),
+            r => Err(CellError::MigrateAgentFailed(r)),
+        }
+    }
+
+    async fn migrate_agent_workspace(
+        &self,
+        migrate_agent: MigrateAgent,
+        other_dna_hash: DnaHash,
+    ) -> CellResult<(SourceChainWorkspace, MigrateAgentWorkflowArgs<RealRibosome>)> {
+        let keystore = self.conductor_api.keystore().clone();
+        let ribosome = self.get_ribosome()?;
+        let dna_def = ribosome.dna_def().as_content().clone();
+
+        let workspace = SourceChainWorkspace::new(
+            self.authored_db().clone(),
+            self.dht_db().clone(),
+            self.space.dht_query_cache.clone(),
+            self.cache().clone(),
+            keystore,
+            self.id.agent_pubkey().clone(),
+            Arc::new(dna_def),
+        )
+        .await?;
+
+        let args = MigrateAgentWorkflowArgs {
+            ribosome,
+            conductor_handle: self.conductor_handle.clone(),
+            migrate_agent,
+            other_dna_hash,
+        };
+        Ok((workspace, args))
+    }
+
     /// Clean up long-running managed tasks.
     //
     // FIXME: this should ensure that the long-running managed tasks,
@@ -17,6 +17,7 @@ use super::entry_def_store::get_entry_defs;
 use super::error::ConductorError;
 use super::handle::ConductorHandleImpl;
 use super::interface::error::InterfaceResult;
+use super::interface::websocket::bound_driver;
 use super::interface::websocket::spawn_admin_interface_task;
 use super::interface::websocket::spawn_app_interface_task;
 use super::interface::websocket::spawn_websocket_listener;
@@ -60,6 +61,7 @@ use holochain_conductor_api::FullIntegrationStateDump;
 use holochain_conductor_api::InstalledAppInfo;
 use holochain_conductor_api::IntegrationStateDump;
 use holochain_keystore::lair_keystore::spawn_lair_keystore;
+use holochain_keystore::lair_keystore::spawn_lair_keystore_in_proc;
 use holochain_keystore::lair_keystore::spawn_new_lair_keystore;
 use holochain_keystore::test_keystore::spawn_legacy_test_keystore;
 use holochain_keystore::test_keystore::spawn_test_keystore;
@@ -302,19 +304,15 @@ impl Conductor {
             let admin_api = admin_api.clone();
             let stop_tx = stop_tx.clone();
             async move {
-                match driver {
-                    InterfaceDriver::Websocket { port } => {
-                        let (listener_handle, listener) = spawn_websocket_listener(port).await?;
-                        let port = listener_handle.local_addr().port().unwrap_or(port);
-                        let handle: ManagedTaskHandle = spawn_admin_interface_task(
-                            listener_handle,
-                            listener,
-                            admin_api.clone(),
-                            stop_tx.subscribe(),
-                        )?;
-                        InterfaceResult::Ok((port, handle))
-                    }
-                }
+                let (listener_handle, listener) = spawn_websocket_listener(&driver).await?;
+                let driver = bound_driver(driver, &listener_handle)?;
+                let handle: ManagedTaskHandle = spawn_admin_interface_task(
+                    listener_handle,
+                    listener,
+                    admin_api.clone(),
+                    stop_tx.subscribe(),
+                )?;
+                InterfaceResult::Ok((driver, handle))
             }
         };
 
@@ -340,11 +338,11 @@ impl Conductor {
             .await?;
 
             // Now that tasks are spawned, register them with the TaskManager
-            for (port, handle) in handles {
-                ports.push(port);
+            for (driver, handle) in handles {
+                ports.extend(driver.port());
                 self.manage_task(ManagedTaskAdd::ignore(
                     handle,
-                    &format!("admin interface, port {}", port),
+                    &format!("admin interface, {}", driver),
                 ))
                 .await?
             }
@@ -357,15 +355,25 @@ impl Conductor {
 
     pub(super) async fn add_app_interface_via_handle(
         &self,
-        port: either::Either<u16, AppInterfaceId>,
+        interface: either::Either<InterfaceDriver, AppInterfaceId>,
         handle: ConductorHandle,
-    ) -> ConductorResult<u16> {
-        let interface_id = match port {
-            either::Either::Left(port) => AppInterfaceId::new(port),
-            either::Either::Right(id) => id,
+    ) -> ConductorResult<InterfaceDriver> {
+        let (interface_id, driver) = match interface {
+            either::Either::Left(driver) => (AppInterfaceId::from_driver(&driver), driver),
+            either::Either::Right(id) => {
+                // An interface on a port chosen by the OS is given a new port,
+                // but a unix socket interface reopens the same socket.
+                let driver = match self.get_state().await?.interface_by_id(&id) {
+                    Some(AppInterfaceConfig {
+                        driver: driver @ InterfaceDriver::UnixSocket { .. },
+                        ..
+                    }) => driver,
+                    _ => InterfaceDriver::Websocket { port: id.port() },
+                };
+                (id, driver)
+            }
         };
-        let port = interface_id.port();
-        tracing::debug!("Attaching interface {}", port);
+        tracing::debug!("Attaching interface {}", driver);
         let app_api = RealAppInterfaceApi::new(handle);
         // This receiver is thrown away because we can produce infinite new
         // receivers from the Sender
@@ -376,13 +384,13 @@ impl Conductor {
                 .task_stop_broadcaster()
                 .subscribe()
         });
-        let (port, task) = spawn_app_interface_task(port, app_api, signal_tx.clone(), stop_rx)
+        let (driver, task) = spawn_app_interface_task(driver, app_api, signal_tx.clone(), stop_rx)
             .await
             .map_err(Box::new)?;
         // TODO: RELIABILITY: Handle this task by restarting it if it fails and log the error
         self.manage_task(ManagedTaskAdd::ignore(
             task,
-            &format!("app interface, port {}", port),
+            &format!("app interface, {}", driver),
         ))
         .await?;
         let interface = AppInterfaceRuntime::Websocket { signal_tx };
@@ -397,14 +405,14 @@ impl Conductor {
             app_interfaces.insert(interface_id.clone(), interface);
             Ok(())
         })?;
-        let config = AppInterfaceConfig::websocket(port);
+        let config = AppInterfaceConfig::new(driver.clone());
         self.update_state(|mut state| {
             state.app_interfaces.insert(interface_id, config);
             Ok(state)
         })
         .await?;
-        tracing::debug!("App interface added at port: {}", port);
-        Ok(port)
+        tracing::debug!("App interface added at {}", driver);
+        Ok(driver)
     }
 
     pub(super) async fn list_app_interfaces(&self) -> ConductorResult<Vec<u16>> {
@@ -413,7 +421,7 @@ impl Conductor {
             .await?
             .app_interfaces
             .values()
-            .map(|config| config.driver.port())
+            .filter_map(|config| config.driver.port())
             .collect())
     }
 
@@ -678,6 +686,37 @@ impl Conductor {
         Ok(stopped_app)
     }
 
+    /// Add an app whose cells were resolved against the given provisioned
+    /// cells, in the Disabled state.
+    ///
+    /// Since cells are resolved before the app is added, another app may have
+    /// been installed or uninstalled in the meantime. Unless the provisioned
+    /// cells which the app uses are the same as when it was resolved, so that
+    /// none of its roles would have been resolved differently, the app is
+    /// not added.
+    pub(super) async fn add_resolved_app_to_db(
+        &self,
+        app: InstalledAppCommon,
+        resolved_against: HashSet<CellId>,
+    ) -> ConductorResult<StoppedApp> {
+        let (_, stopped_app) = self
+            .update_state_prime(move |mut state| {
+                let provisioned = state.provisioned_cells();
+                if let Some((role_id, _)) = app.provisioned_cells().find(|(_, cell_id)| {
+                    provisioned.contains(cell_id) != resolved_against.contains(cell_id)
+                }) {
+                    return Err(ConductorError::ProvisionedCellsChanged(
+                        app.id().clone(),
+                        role_id.clone(),
+                    ));
+                }
+                let stopped_app = state.add_app(app)?;
+                Ok((state, stopped_app))
+            })
+            .await?;
+        Ok(stopped_app)
+    }
+
     /// Transition an app's status to a new state.
     #[tracing::instrument(skip(self))]
     pub(super) async fn transition_app_status(
@@ -704,6 +743,10 @@ impl Conductor {
             .update_state_prime({
                 let app_id = app_id.clone();
                 move |mut state| {
+                    let dependents = state.dependent_apps(&app_id)?;
+                    if !dependents.is_empty() {
+                        return Err(ConductorError::AppHasDependents(app_id.clone(), dependents));
+                    }
                     let app = state.remove_app(&app_id)?;
                     Ok((state, app))
                 }
@@ -712,6 +755,12 @@ impl Conductor {
         Ok(app)
     }
 
+    /// Get the cells provisioned by all installed apps, which new apps may
+    /// choose to share instead of creating their own.
+    pub(super) async fn provisioned_cells(&self) -> ConductorResult<HashSet<CellId>> {
+        Ok(self.get_state().await?.provisioned_cells())
+    }
+
     /// Add fully constructed cells to the cell map in the Conductor
     pub(super) fn add_and_initialize_cells(&self, cells: Vec<(Cell, InitialQueueTriggers)>) {
         let (new_cells, triggers): (Vec<_>, Vec<_>) = cells.into_iter().unzip();
@@ -750,6 +799,7 @@ impl Conductor {
         &self,
         app_id: InstalledAppId,
         role_id: AppRoleId,
+        base_cell_id: CellId,
         properties: YamlProperties,
     ) -> ConductorResult<CellId> {
         let ribosome_store = &self.ribosome_store;
@@ -763,6 +813,15 @@ impl Conductor {
                             .roles()
                             .get(&role_id)
                             .ok_or_else(|| AppError::AppRoleIdMissing(role_id.to_owned()))?;
+                        if role.dna_hash() != base_cell_id.dna_hash()
+                            || role.agent_key() != base_cell_id.agent_pubkey()
+                        {
+                            return Err(AppError::CloneBaseCellMismatch(
+                                role_id.to_owned(),
+                                base_cell_id.clone(),
+                            )
+                            .into());
+                        }
                         let parent_dna_hash = role.dna_hash().clone();
                         Ok((state, parent_dna_hash))
                     } else {
@@ -794,6 +853,121 @@ impl Conductor {
         Ok(cell_id)
     }
 
+    /// Archive a clone cell of an app, freeing up its slot in the clone limit
+    pub(super) async fn archive_clone_cell_of_app(
+        &self,
+        payload: ArchiveCloneCellPayload,
+    ) -> ConductorResult<()> {
+        let ArchiveCloneCellPayload {
+            app_id,
+            role_id,
+            cell_id,
+        } = payload;
+        self.update_state_prime(move |mut state| {
+            let app = state
+                .installed_apps_mut()
+                .get_mut(&app_id)
+                .ok_or_else(|| ConductorError::AppNotInstalled(app_id.clone()))?;
+            app.archive_clone(&role_id, &cell_id)?;
+            Ok((state, ()))
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// Move an archived clone cell back into the active clones of its app
+    pub(super) async fn restore_archived_clone_cell_of_app(
+        &self,
+        payload: RestoreArchivedCloneCellPayload,
+    ) -> ConductorResult<()> {
+        let ArchiveCloneCellPayload {
+            app_id,
+            role_id,
+            cell_id,
+        } = payload;
+        self.update_state_prime(move |mut state| {
+            let app = state
+                .installed_apps_mut()
+                .get_mut(&app_id)
+                .ok_or_else(|| ConductorError::AppNotInstalled(app_id.clone()))?;
+            app.restore_archived_clone(&role_id, &cell_id)?;
+            Ok((state, ()))
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// Point a role of an app at the cell of its successor DNA, returning
+    /// the id of the cell which was replaced
+    pub(super) async fn upgrade_role_of_app(
+        &self,
+        app_id: InstalledAppId,
+        role_id: AppRoleId,
+        cell_id: CellId,
+    ) -> ConductorResult<CellId> {
+        let (_, old_cell_id) = self
+            .update_state_prime(move |mut state| {
+                let app = state
+                    .installed_apps_mut()
+                    .get_mut(&app_id)
+                    .ok_or_else(|| ConductorError::AppNotInstalled(app_id.clone()))?;
+                let old_cell_id = app.upgrade_role(&role_id, cell_id)?;
+                Ok((state, old_cell_id))
+            })
+            .await?;
+        Ok(old_cell_id)
+    }
+
+    /// Remove a clone cell from its app, whether it is archived or not.
+    /// Returns true if no other installed app refers to the clone's DNA,
+    /// i.e. if it is safe to purge the data of its space.
+    pub(super) async fn delete_clone_cell_from_app(
+        &self,
+        payload: DeleteCloneCellPayload,
+    ) -> ConductorResult<bool> {
+        let ArchiveCloneCellPayload {
+            app_id,
+            role_id,
+            cell_id,
+        } = payload;
+        let (_, is_orphaned) = self
+            .update_state_prime(move |mut state| {
+                let app = state
+                    .installed_apps_mut()
+                    .get_mut(&app_id)
+                    .ok_or_else(|| ConductorError::AppNotInstalled(app_id.clone()))?;
+                app.delete_clone(&role_id, &cell_id)?;
+                let is_orphaned = !state.installed_apps().iter().any(|(_, app)| {
+                    app.all_cells()
+                        .chain(app.archived_clone_cells())
+                        .any(|id| id.dna_hash() == cell_id.dna_hash())
+                });
+                Ok((state, is_orphaned))
+            })
+            .await?;
+        Ok(is_orphaned)
+    }
+
+    /// Remove a space and wipe all the data of its cells
+    pub(super) async fn purge_space(&self, dna_hash: &DnaHash) -> ConductorResult<()> {
+        self.spaces.purge_space(dna_hash).await
+    }
+
+    /// Generate a new database master key and re-key all databases with it.
+    ///
+    /// The new key is persisted before any database is touched, and the old
+    /// keys are only forgotten once every database file has been re-keyed,
+    /// including those of spaces which aren't open. An interrupted rotation
+    /// is completed when the databases are next opened.
+    pub(super) async fn rotate_database_key(&self) -> ConductorResult<()> {
+        let db_root = self.config.environment_path.as_ref();
+        let keyring = super::db_key::rotate_keyring(&self.keystore, db_root).await?;
+        self.spaces.rekey(keyring.clone()).await?;
+        super::db_key::forget_previous_keys(db_root).await?;
+        self.spaces.set_keyring(keyring.without_previous());
+        Ok(())
+    }
+
     pub(super) async fn load_wasms_into_dna_files(
         &self,
     ) -> ConductorResult<(
@@ -862,6 +1036,12 @@ impl Conductor {
         &self.spaces.db_dir
     }
 
+    /// Get the cache of bundles downloaded from URLs,
+    /// which lives alongside the databases.
+    pub fn download_cache(&self) -> DownloadCache {
+        DownloadCache::new(self.spaces.db_dir.as_ref().join("download_cache"))
+    }
+
     /// Get the keystore.
     pub fn keystore(&self) -> &MetaLairClient {
         &self.keystore
@@ -1265,7 +1445,14 @@ fn query_dht_ops_from_statement(
                 }
                 _ => None,
             };
-            Ok(DhtOp::from_type(op_type, action, entry)?)
+            let warrant: Option<Vec<u8>> = row.get("warrant_blob")?;
+            let warrant = match warrant {
+                Some(warrant) => Some(from_blob::<SignedWarrant>(warrant)?),
+                None => None,
+            };
+            Ok(DhtOp::from_type_with_warrant(
+                op_type, action, entry, warrant,
+            )?)
         })?
         .collect::<StateQueryResult<Vec<_>>>()?;
     Ok(r)
@@ -1323,21 +1510,7 @@ impl Conductor {
     }
 
     pub(super) async fn get_state(&self) -> ConductorResult<ConductorState> {
-        self.spaces
-            .conductor_db
-            .async_reader(|txn| {
-                let state = txn
-                    .query_row("SELECT blob FROM ConductorState WHERE id = 1", [], |row| {
-                        row.get("blob")
-                    })
-                    .optional()?;
-                let state = match state {
-                    Some(state) => from_blob(state)?,
-                    None => ConductorState::default(),
-                };
-                Ok(state)
-            })
-            .await
+        self.spaces.get_state().await
     }
 
     /// Update the internal state with a pure function mapping old state to new
@@ -1406,6 +1579,8 @@ mod builder {
     use crate::conductor::kitsune_host_impl::KitsuneHostImpl;
     use crate::conductor::ribosome_store::RibosomeStore;
     use crate::conductor::ConductorHandle;
+    use holochain_sqlite::schema::SchemaMigration;
+    use std::path::PathBuf;
 
     /// A configurable Builder for Conductor and sometimes ConductorHandle
     #[derive(Default)]
@@ -1447,7 +1622,7 @@ mod builder {
         }
 
         /// Initialize a "production" Conductor
-        pub async fn build(self) -> ConductorResult<ConductorHandle> {
+        pub async fn build(mut self) -> ConductorResult<ConductorHandle> {
             cfg_if::cfg_if! {
                 // if mock_handle is specified, return that instead of
                 // a real handle
@@ -1460,41 +1635,7 @@ mod builder {
 
             tracing::info!(?self.config);
 
-            let keystore = if let Some(keystore) = self.keystore {
-                keystore
-            } else {
-                match &self.config.keystore {
-                    KeystoreConfig::DangerTestKeystoreLegacyDeprecated => {
-                        tracing::warn!("Using DEPRECATED legacy lair api.");
-                        spawn_legacy_test_keystore().await?
-                    }
-                    KeystoreConfig::LairServerLegacyDeprecated {
-                        keystore_path,
-                        danger_passphrase_insecure_from_config,
-                    } => {
-                        tracing::warn!("Using DEPRECATED legacy lair api.");
-                        tracing::warn!("USING INSECURE PASSPHRASE FROM CONFIG--This defeats the whole purpose of having a passphrase.");
-                        let passphrase = sodoken::BufRead::new_no_lock(
-                            danger_passphrase_insecure_from_config.as_bytes(),
-                        );
-                        spawn_lair_keystore(keystore_path.as_deref(), passphrase).await?
-                    }
-                    KeystoreConfig::DangerTestKeystore => spawn_test_keystore().await?,
-                    KeystoreConfig::LairServer { connection_url } => {
-                        let passphrase = match self.passphrase {
-                            None => {
-                                return Err(one_err::OneErr::new(
-                                    "passphrase required for new lair keystore api",
-                                )
-                                .into())
-                            }
-                            Some(p) => p,
-                        };
-                        spawn_new_lair_keystore(connection_url.clone(), passphrase).await?
-                    }
-                    oth => unimplemented!("unimplemented keystore config: {:?}", oth),
-                }
-            };
+            let keystore = self.get_or_spawn_keystore().await?;
 
             let Self {
                 ribosome_store,
@@ -1516,7 +1657,10 @@ mod builder {
             let strat =
                 ArqStrat::from_params(network_config.tuning_params.gossip_redundancy_target);
 
-            let spaces = Spaces::new(&config)?;
+            let db_keyring =
+                super::db_key::load_or_create_keyring(&keystore, config.environment_path.as_ref())
+                    .await?;
+            let spaces = Spaces::with_keyring(&config, db_keyring)?;
             let host = KitsuneHostImpl::new(
                 spaces.clone(),
                 ribosome_store.clone(),
@@ -1568,6 +1712,78 @@ mod builder {
             Self::finish(handle, config, p2p_evt, post_commit_receiver).await
         }
 
+        /// Use the keystore given to this builder, or spawn the one
+        /// specified in the config.
+        async fn get_or_spawn_keystore(&mut self) -> ConductorResult<MetaLairClient> {
+            if let Some(keystore) = self.keystore.take() {
+                return Ok(keystore);
+            }
+            let passphrase = self.passphrase.take();
+            let keystore = match &self.config.keystore {
+                KeystoreConfig::DangerTestKeystoreLegacyDeprecated => {
+                    tracing::warn!("Using DEPRECATED legacy lair api.");
+                    spawn_legacy_test_keystore().await?
+                }
+                KeystoreConfig::LairServerLegacyDeprecated {
+                    keystore_path,
+                    danger_passphrase_insecure_from_config,
+                } => {
+                    tracing::warn!("Using DEPRECATED legacy lair api.");
+                    tracing::warn!("USING INSECURE PASSPHRASE FROM CONFIG--This defeats the whole purpose of having a passphrase.");
+                    let passphrase = sodoken::BufRead::new_no_lock(
+                        danger_passphrase_insecure_from_config.as_bytes(),
+                    );
+                    spawn_lair_keystore(keystore_path.as_deref(), passphrase).await?
+                }
+                KeystoreConfig::DangerTestKeystore => spawn_test_keystore().await?,
+                KeystoreConfig::LairServer { connection_url } => {
+                    let passphrase = match passphrase {
+                        None => {
+                            return Err(one_err::OneErr::new(
+                                "passphrase required for new lair keystore api",
+                            )
+                            .into())
+                        }
+                        Some(p) => p,
+                    };
+                    spawn_new_lair_keystore(connection_url.clone(), passphrase).await?
+                }
+                KeystoreConfig::LairServerInProc { lair_root } => {
+                    let passphrase = match passphrase {
+                        None => {
+                            return Err(one_err::OneErr::new(
+                                "passphrase required for new lair keystore api",
+                            )
+                            .into())
+                        }
+                        Some(p) => p,
+                    };
+                    spawn_lair_keystore_in_proc(lair_root.clone(), passphrase).await?
+                }
+                oth => return Err(ConductorError::UnsupportedKeystoreConfig(oth.clone())),
+            };
+            Ok(keystore)
+        }
+
+        /// Open all databases of this conductor, running any pending schema
+        /// migrations, without starting the conductor itself.
+        ///
+        /// Returns the path of each database which was migrated, along with
+        /// the migration which was run on it.
+        pub async fn migrate_only(mut self) -> ConductorResult<Vec<(PathBuf, SchemaMigration)>> {
+            let keystore = self.get_or_spawn_keystore().await?;
+            let db_keyring = super::db_key::load_or_create_keyring(
+                &keystore,
+                self.config.environment_path.as_ref(),
+            )
+            .await?;
+            let spaces = Spaces::with_keyring(&self.config, db_keyring)?;
+            for dna_hash in spaces.get_state().await?.dna_hashes() {
+                spaces.get_or_create_space(&dna_hash)?;
+            }
+            Ok(spaces.migrations())
+        }
+
         fn spawn_post_commit(
             conductor_handle: ConductorHandle,
             receiver: tokio::sync::mpsc::Receiver<PostCommitArgs>,
@@ -1679,7 +1895,8 @@ mod builder {
             let keystore = self.keystore.unwrap_or_else(test_keystore);
             self.config.environment_path = env_path.to_path_buf().into();
 
-            let spaces = Spaces::new(&self.config)?;
+            let db_keyring = super::db_key::load_or_create_keyring(&keystore, env_path).await?;
+            let spaces = Spaces::with_keyring(&self.config, db_keyring)?;
 
             let network_config = self.config.network.clone().unwrap_or_default();
             let tuning_params = network_config.tuning_params.clone();
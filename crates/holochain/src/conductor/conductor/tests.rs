@@ -116,7 +116,7 @@ async fn can_add_clone_cell_to_app() {
         vec![&"role_id".to_string()]
     );
 
-    conductor.register_phenotype(RealRibosome::empty(dna));
+    conductor.register_phenotype(RealRibosome::empty(dna.clone()));
     conductor
         .update_state(move |mut state| {
             state
@@ -132,13 +132,38 @@ async fn can_add_clone_cell_to_app() {
 
     matches::assert_matches!(
         conductor
-            .add_clone_cell_to_app("no clone".to_string(), "role_id".to_string(), ().into())
+            .add_clone_cell_to_app(
+                "no clone".to_string(),
+                "role_id".to_string(),
+                cell_id.clone(),
+                ().into()
+            )
             .await,
         Err(ConductorError::AppError(AppError::CloneLimitExceeded(0, _)))
     );
 
+    // The clone must be made from the role's own cell
+    matches::assert_matches!(
+        conductor
+            .add_clone_cell_to_app(
+                "yes clone".to_string(),
+                "role_id".to_string(),
+                CellId::new(dna.dna_hash().to_owned(), fixt!(AgentPubKey)),
+                ().into()
+            )
+            .await,
+        Err(ConductorError::AppError(AppError::CloneBaseCellMismatch(
+            _,
+            _
+        )))
+    );
     let cloned_cell_id = conductor
-        .add_clone_cell_to_app("yes clone".to_string(), "role_id".to_string(), ().into())
+        .add_clone_cell_to_app(
+            "yes clone".to_string(),
+            "role_id".to_string(),
+            cell_id.clone(),
+            ().into(),
+        )
         .await
         .unwrap();
 
@@ -156,6 +181,253 @@ async fn can_add_clone_cell_to_app() {
     );
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn can_archive_restore_and_delete_clone_cell() {
+    let db_dir = test_db_dir();
+    let holochain_p2p = holochain_p2p::stub_network().await;
+
+    let agent = fixt!(AgentPubKey);
+    let dna = fake_valid_dna_file("");
+    let cell_id = CellId::new(dna.dna_hash().to_owned(), agent.clone());
+
+    let (post_commit_sender, _post_commit_receiver) =
+        tokio::sync::mpsc::channel(POST_COMMIT_CHANNEL_BOUND);
+    let spaces = Spaces::new(&ConductorConfig {
+        environment_path: db_dir.path().to_path_buf().into(),
+        ..Default::default()
+    })
+    .unwrap();
+
+    let conductor = Conductor::new(
+        Default::default(),
+        RibosomeStore::new(),
+        test_keystore(),
+        holochain_p2p,
+        spaces,
+        post_commit_sender,
+    )
+    .await
+    .unwrap();
+
+    let role_id: AppRoleId = "role_id".into();
+    let role = AppRoleAssignment::new(cell_id.clone(), true, 1);
+    let app = InstalledAppCommon::new("app", agent, vec![(role_id.clone(), role)]);
+
+    conductor.register_phenotype(RealRibosome::empty(dna));
+    conductor
+        .update_state(move |mut state| {
+            state
+                .installed_apps_mut()
+                .insert(RunningApp::from(app.clone()).into());
+            Ok(state)
+        })
+        .await
+        .unwrap();
+
+    let clone_id = conductor
+        .add_clone_cell_to_app(
+            "app".to_string(),
+            role_id.clone(),
+            cell_id.clone(),
+            ().into(),
+        )
+        .await
+        .unwrap();
+    let payload = ArchiveCloneCellPayload {
+        app_id: "app".to_string(),
+        role_id: role_id.clone(),
+        cell_id: clone_id.clone(),
+    };
+
+    // Archiving frees up the only slot in the clone limit
+    conductor
+        .archive_clone_cell_of_app(payload.clone())
+        .await
+        .unwrap();
+    let other_clone_id = conductor
+        .add_clone_cell_to_app(
+            "app".to_string(),
+            role_id.clone(),
+            cell_id.clone(),
+            ().into(),
+        )
+        .await
+        .unwrap();
+
+    // The archived clone can't be restored while the limit is reached
+    assert_matches!(
+        conductor
+            .restore_archived_clone_cell_of_app(payload.clone())
+            .await,
+        Err(ConductorError::AppError(AppError::CloneLimitExceeded(1, _)))
+    );
+
+    let is_orphaned = conductor
+        .delete_clone_cell_from_app(ArchiveCloneCellPayload {
+            cell_id: other_clone_id,
+            ..payload.clone()
+        })
+        .await
+        .unwrap();
+    assert!(is_orphaned);
+
+    conductor
+        .restore_archived_clone_cell_of_app(payload.clone())
+        .await
+        .unwrap();
+
+    let state = conductor.get_state().await.unwrap();
+    let app = state.get_app(&"app".to_string()).unwrap();
+    assert_eq!(
+        app.cloned_cells().cloned().collect::<Vec<CellId>>(),
+        vec![clone_id]
+    );
+    assert_eq!(app.archived_clone_cells().count(), 0);
+}
+
+/// An app can't be uninstalled while another app shares one of its cells,
+/// but the app which shares the cell can be uninstalled at any time
+#[tokio::test(flavor = "multi_thread")]
+async fn cannot_uninstall_app_with_dependents() {
+    let db_dir = test_db_dir();
+    let holochain_p2p = holochain_p2p::stub_network().await;
+    let (post_commit_sender, _post_commit_receiver) =
+        tokio::sync::mpsc::channel(POST_COMMIT_CHANNEL_BOUND);
+    let spaces = Spaces::new(&ConductorConfig {
+        environment_path: db_dir.path().to_path_buf().into(),
+        ..Default::default()
+    })
+    .unwrap();
+    let conductor = Conductor::new(
+        Default::default(),
+        RibosomeStore::new(),
+        test_keystore(),
+        holochain_p2p,
+        spaces,
+        post_commit_sender,
+    )
+    .await
+    .unwrap();
+
+    let cell_id = fake_cell_id(1);
+    let agent = cell_id.agent_pubkey().clone();
+    let owner = InstalledAppCommon::new(
+        "owner",
+        agent.clone(),
+        vec![(
+            "profile".to_string(),
+            AppRoleAssignment::new(cell_id.clone(), true, 0),
+        )],
+    );
+    let dependent = InstalledAppCommon::new(
+        "dependent",
+        agent,
+        vec![(
+            "profile".to_string(),
+            AppRoleAssignment::new_existing(cell_id.clone(), 0),
+        )],
+    );
+    conductor.add_disabled_app_to_db(owner).await.unwrap();
+    conductor.add_disabled_app_to_db(dependent).await.unwrap();
+
+    assert_matches!(
+        conductor.remove_app_from_db(&"owner".to_string()).await,
+        Err(ConductorError::AppHasDependents(id, dependents))
+        if id == "owner" && dependents == vec!["dependent".to_string()]
+    );
+
+    conductor
+        .remove_app_from_db(&"dependent".to_string())
+        .await
+        .unwrap();
+    conductor
+        .remove_app_from_db(&"owner".to_string())
+        .await
+        .unwrap();
+}
+
+/// An app isn't added if the cells provisioned by other apps changed since
+/// its cells were resolved
+#[tokio::test(flavor = "multi_thread")]
+async fn resolved_app_not_added_if_provisioned_cells_changed() {
+    let db_dir = test_db_dir();
+    let holochain_p2p = holochain_p2p::stub_network().await;
+    let (post_commit_sender, _post_commit_receiver) =
+        tokio::sync::mpsc::channel(POST_COMMIT_CHANNEL_BOUND);
+    let spaces = Spaces::new(&ConductorConfig {
+        environment_path: db_dir.path().to_path_buf().into(),
+        ..Default::default()
+    })
+    .unwrap();
+    let conductor = Conductor::new(
+        Default::default(),
+        RibosomeStore::new(),
+        test_keystore(),
+        holochain_p2p,
+        spaces,
+        post_commit_sender,
+    )
+    .await
+    .unwrap();
+
+    let cell_id = fake_cell_id(1);
+    let agent = cell_id.agent_pubkey().clone();
+    let owner = InstalledAppCommon::new(
+        "owner",
+        agent.clone(),
+        vec![(
+            "profile".to_string(),
+            AppRoleAssignment::new(cell_id.clone(), true, 0),
+        )],
+    );
+    let dependent = InstalledAppCommon::new(
+        "dependent",
+        agent.clone(),
+        vec![(
+            "profile".to_string(),
+            AppRoleAssignment::new_existing(cell_id.clone(), 0),
+        )],
+    );
+    let concurrent = InstalledAppCommon::new(
+        "concurrent",
+        agent,
+        vec![(
+            "profile".to_string(),
+            AppRoleAssignment::new(cell_id.clone(), true, 0),
+        )],
+    );
+
+    // The owner is installed concurrently with another app which resolved
+    // the cell as not existing yet.
+    let resolved_against = conductor.provisioned_cells().await.unwrap();
+    conductor
+        .add_resolved_app_to_db(owner, resolved_against.clone())
+        .await
+        .unwrap();
+    assert_matches!(
+        conductor
+            .add_resolved_app_to_db(concurrent, resolved_against)
+            .await,
+        Err(ConductorError::ProvisionedCellsChanged(id, role_id))
+        if id == "concurrent" && role_id == "profile"
+    );
+
+    // The owner is uninstalled while an app which shares its cell is being
+    // installed.
+    let resolved_against = conductor.provisioned_cells().await.unwrap();
+    conductor
+        .remove_app_from_db(&"owner".to_string())
+        .await
+        .unwrap();
+    assert_matches!(
+        conductor
+            .add_resolved_app_to_db(dependent, resolved_against)
+            .await,
+        Err(ConductorError::ProvisionedCellsChanged(id, role_id))
+        if id == "dependent" && role_id == "profile"
+    );
+}
+
 /// App can't be installed if another app is already installed under the
 /// same InstalledAppId
 #[tokio::test(flavor = "multi_thread")]
@@ -305,18 +577,11 @@ async fn common_genesis_test_app(
 ) -> ConductorApiResult<SweetApp> {
     let hardcoded_zome = InlineIntegrityZome::new_unique(Vec::new(), 0);
 
-    // Just a strong reminder that we need to be careful once we start using existing Cells:
-    // When a Cell panics or fails validation in general, we want to disable all Apps touching that Cell.
+    // NB: When a Cell panics or fails validation in general, we want to disable all Apps touching that Cell.
     // However, if the panic/failure happens during Genesis, we want to completely
     // destroy the app which is attempting to Create that Cell, but *NOT* any other apps
-    // which might be touching that Cell.
-    //
-    // It probably works out to be the same either way, since if we are creating a Cell,
-    // no other app could be possibly referencing it, but just in case we have some kind of complex
-    // behavior like installing two apps which reference each others' Cells at the same time,
-    // we need to be aware of this distinction.
-    holochain_types::app::we_must_remember_to_rework_cell_panic_handling_after_implementing_use_existing_cell_resolution(
-    );
+    // which might be touching that Cell. Genesis only ever runs on newly created Cells,
+    // and existing Cells shared via `UseExisting` have already been through it.
 
     // Create one DNA which always works, and another from a zome that gets passed in
     let (dna_hardcoded, _, _) = mk_dna(("hardcoded", hardcoded_zome)).await?;
@@ -362,6 +627,205 @@ async fn test_uninstall_app() {
     );
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn can_rotate_database_key() {
+    observability::test_run().ok();
+    let zome = InlineIntegrityZome::new_unique(Vec::new(), 0);
+    let mut conductor = SweetConductor::from_standard_config().await;
+    common_genesis_test_app(&mut conductor, ("custom", zome))
+        .await
+        .unwrap();
+    let state = conductor.get_state_from_handle().await.unwrap();
+
+    conductor
+        .inner_handle()
+        .rotate_database_key()
+        .await
+        .unwrap();
+    conductor
+        .inner_handle()
+        .rotate_database_key()
+        .await
+        .unwrap();
+
+    // All databases are still readable with the new key.
+    assert_eq!(state, conductor.get_state_from_handle().await.unwrap());
+    let cell_id = state
+        .running_apps()
+        .next()
+        .unwrap()
+        .1
+        .all_cells()
+        .next()
+        .unwrap()
+        .clone();
+    let db = conductor
+        .inner_handle()
+        .get_authored_db(cell_id.dna_hash())
+        .unwrap();
+    let count: usize = db
+        .async_reader(|txn| {
+            txn.query_row("SELECT COUNT(*) FROM Action", [], |row| row.get(0))
+                .map_err(DatabaseError::from)
+        })
+        .await
+        .unwrap();
+    assert!(count > 0);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn block_and_unblock_agents_and_nodes() {
+    observability::test_run().ok();
+    let conductor = SweetConductor::from_standard_config().await;
+    let handle = conductor.inner_handle();
+    let spaces = handle.get_spaces();
+
+    let agent = BlockTarget::Agent(fixt!(AgentPubKey));
+    let node = BlockTarget::Node(vec![0xdb; 32].into());
+    let expired = BlockTarget::Agent(fixt!(AgentPubKey));
+    let agent_block = Block::new(
+        agent.clone(),
+        Some((Timestamp::now() + std::time::Duration::from_secs(60 * 60)).unwrap()),
+    );
+
+    handle.block(agent_block.clone()).await.unwrap();
+    handle.block(Block::new(node.clone(), None)).await.unwrap();
+    handle
+        .block(Block::new(expired.clone(), Some(Timestamp::now())))
+        .await
+        .unwrap();
+
+    assert!(spaces.is_blocked(&agent));
+    assert!(spaces.is_blocked(&node));
+    assert!(!spaces.is_blocked(&expired));
+    let mut blocks = handle.list_blocks().await.unwrap();
+    blocks.sort_by(|a, b| a.target.cmp(&b.target));
+    assert_eq!(blocks, vec![agent_block, Block::new(node.clone(), None)]);
+
+    assert!(handle.unblock(node.clone()).await.unwrap());
+    assert!(!handle.unblock(node.clone()).await.unwrap());
+    assert!(!spaces.is_blocked(&node));
+    assert_eq!(handle.list_blocks().await.unwrap().len(), 1);
+}
+
+/// Upgrading an app closes the chain of the old cell, and opens a chain for
+/// the successor DNA which points back at it
+#[tokio::test(flavor = "multi_thread")]
+async fn upgrade_app_migrates_agent() {
+    async fn chain(conductor: &SweetConductor, cell_id: &CellId) -> Vec<Action> {
+        let handle = conductor.inner_handle();
+        let dna_hash = cell_id.dna_hash();
+        SourceChain::new(
+            handle.get_authored_db(dna_hash).unwrap(),
+            handle.get_dht_db(dna_hash).unwrap(),
+            handle.get_dht_db_cache(dna_hash).unwrap(),
+            conductor.keystore(),
+            cell_id.agent_pubkey().clone(),
+        )
+        .await
+        .unwrap()
+        .query(Default::default())
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|record| record.action().clone())
+        .collect()
+    }
+
+    let wasms = vec![TestWasm::Create, TestWasm::MigrateAgentPass];
+    let (dna, _, _) = SweetDnaFile::unique_from_test_wasms(wasms.clone())
+        .await
+        .unwrap();
+    let (new_dna, _, _) = SweetDnaFile::unique_from_test_wasms(wasms).await.unwrap();
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let app = conductor.setup_app("app", &[dna.clone()]).await.unwrap();
+    let (cell,) = app.into_tuple();
+    let old_cell_id = cell.cell_id().clone();
+    let _: ActionHash = conductor
+        .call(&cell.zome("create_entry"), "create_entry", ())
+        .await;
+
+    let new_cell_id = conductor
+        .inner_handle()
+        .upgrade_app(
+            "app".to_string(),
+            dna.dna_hash().to_string(),
+            new_dna.clone(),
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(new_cell_id.dna_hash(), new_dna.dna_hash());
+    assert_eq!(new_cell_id.agent_pubkey(), old_cell_id.agent_pubkey());
+
+    let old_chain = chain(&conductor, &old_cell_id).await;
+    assert_matches!(
+        old_chain.last().unwrap(),
+        Action::CloseChain(CloseChain { new_dna_hash, .. }) if new_dna_hash == new_dna.dna_hash()
+    );
+    let new_chain = chain(&conductor, &new_cell_id).await;
+    assert_matches!(
+        new_chain.last().unwrap(),
+        Action::OpenChain(OpenChain { prev_dna_hash, .. }) if prev_dna_hash == dna.dna_hash()
+    );
+
+    // The role now points at the new cell
+    let state = conductor.get_state_from_handle().await.unwrap();
+    let app = state.get_app(&"app".to_string()).unwrap();
+    assert_eq!(
+        app.role(&dna.dna_hash().to_string()).unwrap().cell_id(),
+        &new_cell_id
+    );
+}
+
+/// If the successor DNA refuses to open a chain, the old chain stays open and
+/// the role keeps pointing at the old cell
+#[tokio::test(flavor = "multi_thread")]
+async fn upgrade_app_keeps_old_cell_when_open_fails() {
+    let (dna, _, _) =
+        SweetDnaFile::unique_from_test_wasms(vec![TestWasm::Create, TestWasm::MigrateAgentPass])
+            .await
+            .unwrap();
+    let (new_dna, _, _) =
+        SweetDnaFile::unique_from_test_wasms(vec![TestWasm::Create, TestWasm::MigrateAgentFail])
+            .await
+            .unwrap();
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let app = conductor.setup_app("app", &[dna.clone()]).await.unwrap();
+    let (cell,) = app.into_tuple();
+    let old_cell_id = cell.cell_id().clone();
+
+    let result = conductor
+        .inner_handle()
+        .upgrade_app(
+            "app".to_string(),
+            dna.dna_hash().to_string(),
+            new_dna.clone(),
+            None,
+        )
+        .await;
+    assert_matches!(
+        result,
+        Err(ConductorError::InternalCellError(
+            CellError::MigrateAgentFailed(_)
+        ))
+    );
+
+    // The role still points at the old cell, which is running
+    let state = conductor.get_state_from_handle().await.unwrap();
+    let app = state.get_app(&"app".to_string()).unwrap();
+    assert_eq!(
+        app.role(&dna.dna_hash().to_string()).unwrap().cell_id(),
+        &old_cell_id
+    );
+    assert!(conductor.list_cell_ids(None).contains(&old_cell_id));
+
+    // and its chain was never closed, so it can still be committed to
+    let _: ActionHash = conductor
+        .call(&cell.zome("create_entry"), "create_entry", ())
+        .await;
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_reconciliation_idempotency() {
     observability::test_run().ok();
@@ -486,7 +950,13 @@ async fn test_signing_error_during_genesis_doesnt_bork_interfaces() {
     let (cell1,) = app1.into_tuple();
     let (cell2,) = app2.into_tuple();
 
-    let app_port = conductor.inner_handle().add_app_interface(0).await.unwrap();
+    let app_port = conductor
+        .inner_handle()
+        .add_app_interface(InterfaceDriver::Websocket { port: 0 })
+        .await
+        .unwrap()
+        .port()
+        .unwrap();
     let (mut app_client, _) = websocket_client_by_port(app_port).await.unwrap();
     let (mut admin_client, _) = conductor.admin_ws_client().await;
 
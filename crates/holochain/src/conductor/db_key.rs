@@ -0,0 +1,183 @@
+//! Management of the master key which the conductor's databases are
+//! encrypted with.
+//!
+//! The master key is stored next to the databases, encrypted with a shared
+//! secret held in the keystore. It can therefore only be recovered while the
+//! keystore is unlocked with the conductor's passphrase. Every rotation
+//! creates a new shared secret in the keystore.
+
+use super::error::{ConductorError, ConductorResult};
+use holochain_keystore::MetaLairClient;
+use holochain_sqlite::db_key::{DbKey, DbKeyring};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// The name of the file in the database root which holds the wrapped keys.
+pub const DB_KEY_FILENAME: &str = "db_key.json";
+
+/// A master key, encrypted with a shared secret held in the keystore.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+struct WrappedDbKey {
+    /// The keystore tag of the shared secret this key is encrypted with.
+    tag: String,
+    nonce: [u8; 24],
+    cipher: Vec<u8>,
+}
+
+/// The contents of the key file.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+struct DbKeyFile {
+    current: WrappedDbKey,
+    /// Keys which databases may still be encrypted with, most recent first.
+    /// These are only present while a rotation is in progress, or when a
+    /// rotation was interrupted.
+    #[serde(default)]
+    previous: Vec<WrappedDbKey>,
+}
+
+/// Load the keyring from the key file in the database root, or create a new
+/// master key if there is no key file yet.
+///
+/// The legacy keystore can't hold shared secrets, so with it the keyring is
+/// empty and databases keep using the legacy key.
+pub async fn load_or_create_keyring(
+    keystore: &MetaLairClient,
+    db_root: &Path,
+) -> ConductorResult<DbKeyring> {
+    if let MetaLairClient::Legacy(_) = keystore {
+        tracing::warn!("The legacy keystore does not support database encryption keys, databases will be encrypted with the legacy key");
+        return Ok(DbKeyring::default());
+    }
+    let path = key_file_path(db_root);
+    if path.exists() {
+        unwrap_keyring(keystore, read_key_file(&path).await?).await
+    } else {
+        tokio::fs::create_dir_all(db_root).await?;
+        let key = DbKey::generate();
+        let file = DbKeyFile {
+            current: wrap_key(keystore, &key).await?,
+            previous: Vec::new(),
+        };
+        write_key_file(&path, &file).await?;
+        Ok(DbKeyring::new(key, Vec::new()))
+    }
+}
+
+/// Generate a new master key and record it as the current key in the key
+/// file, keeping the existing keys as previous keys.
+///
+/// The returned keyring must then be used to re-key all databases, after
+/// which [`forget_previous_keys`] can be called.
+pub async fn rotate_keyring(
+    keystore: &MetaLairClient,
+    db_root: &Path,
+) -> ConductorResult<DbKeyring> {
+    if let MetaLairClient::Legacy(_) = keystore {
+        return Err(ConductorError::DbKeyNotSupported);
+    }
+    let path = key_file_path(db_root);
+    if !path.exists() {
+        load_or_create_keyring(keystore, db_root).await?;
+    }
+    let file = read_key_file(&path).await?;
+    let keyring = unwrap_keyring(keystore, file.clone()).await?;
+
+    let key = DbKey::generate();
+    let mut previous = vec![file.current];
+    previous.extend(file.previous);
+    let file = DbKeyFile {
+        current: wrap_key(keystore, &key).await?,
+        previous,
+    };
+    write_key_file(&path, &file).await?;
+    Ok(keyring.rotate(key))
+}
+
+/// Remove all previous keys from the key file, once every database has
+/// been re-keyed to the current key.
+pub async fn forget_previous_keys(db_root: &Path) -> ConductorResult<()> {
+    let path = key_file_path(db_root);
+    let mut file = read_key_file(&path).await?;
+    file.previous.clear();
+    write_key_file(&path, &file).await
+}
+
+fn key_file_path(db_root: &Path) -> PathBuf {
+    db_root.join(DB_KEY_FILENAME)
+}
+
+async fn read_key_file(path: &Path) -> ConductorResult<DbKeyFile> {
+    serde_json::from_slice(&tokio::fs::read(path).await?).map_err(ConductorError::other)
+}
+
+/// Write the key file atomically, so that an interrupted write can never
+/// lose the keys the databases are encrypted with.
+async fn write_key_file(path: &Path, file: &DbKeyFile) -> ConductorResult<()> {
+    let tmp = path.with_extension("json.tmp");
+    let bytes = serde_json::to_vec_pretty(file).map_err(ConductorError::other)?;
+    tokio::fs::write(&tmp, bytes).await?;
+    tokio::fs::rename(&tmp, path).await?;
+    Ok(())
+}
+
+async fn wrap_key(keystore: &MetaLairClient, key: &DbKey) -> ConductorResult<WrappedDbKey> {
+    let tag = format!("hc_db_key_{}", nanoid::nanoid!());
+    let tag: Arc<str> = tag.into();
+    keystore.new_shared_secret(tag.clone()).await?;
+    let (nonce, cipher) = keystore
+        .shared_secret_encrypt(tag.clone(), key.as_bytes().to_vec().into())
+        .await?;
+    Ok(WrappedDbKey {
+        tag: tag.to_string(),
+        nonce,
+        cipher: cipher.to_vec(),
+    })
+}
+
+async fn unwrap_keyring(keystore: &MetaLairClient, file: DbKeyFile) -> ConductorResult<DbKeyring> {
+    let current = unwrap_key(keystore, file.current).await?;
+    let mut previous = Vec::with_capacity(file.previous.len());
+    for wrapped in file.previous {
+        previous.push(unwrap_key(keystore, wrapped).await?);
+    }
+    Ok(DbKeyring::new(current, previous))
+}
+
+async fn unwrap_key(keystore: &MetaLairClient, wrapped: WrappedDbKey) -> ConductorResult<DbKey> {
+    let data = keystore
+        .shared_secret_decrypt(wrapped.tag.into(), wrapped.nonce, wrapped.cipher.into())
+        .await?;
+    let bytes: [u8; 32] = data[..]
+        .try_into()
+        .map_err(|_| ConductorError::other("Database key has an invalid length"))?;
+    Ok(DbKey::from_bytes(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn keys_survive_reload_and_rotation() {
+        let keystore = holochain_keystore::test_keystore::spawn_test_keystore()
+            .await
+            .unwrap();
+        let dir = tempfile::tempdir().unwrap();
+
+        let keyring = load_or_create_keyring(&keystore, dir.path()).await.unwrap();
+        let reloaded = load_or_create_keyring(&keystore, dir.path()).await.unwrap();
+        assert_eq!(keyring.current(), reloaded.current());
+
+        let rotated = rotate_keyring(&keystore, dir.path()).await.unwrap();
+        assert_ne!(rotated.current(), keyring.current());
+        assert_eq!(rotated.previous(), &[keyring.current().unwrap().clone()]);
+        let reloaded = load_or_create_keyring(&keystore, dir.path()).await.unwrap();
+        assert_eq!(reloaded.current(), rotated.current());
+        assert_eq!(reloaded.previous(), rotated.previous());
+
+        forget_previous_keys(dir.path()).await.unwrap();
+        let reloaded = load_or_create_keyring(&keystore, dir.path()).await.unwrap();
+        assert_eq!(reloaded.current(), rotated.current());
+        assert!(reloaded.previous().is_empty());
+    }
+}
@@ -3,6 +3,7 @@ use super::{entry_def_store::error::EntryDefStoreError, state::AppInterfaceId};
 use crate::conductor::cell::error::CellError;
 use crate::core::workflow::error::WorkflowError;
 use holochain_conductor_api::conductor::ConductorConfigError;
+use holochain_conductor_api::conductor::KeystoreConfig;
 use holochain_sqlite::error::DatabaseError;
 use holochain_types::prelude::*;
 use holochain_wasmer_host::prelude::WasmErrorInner;
@@ -89,6 +90,14 @@ pub enum ConductorError {
     #[error("Tried to perform an operation on an app that was not running: {0}")]
     AppNotRunning(InstalledAppId),
 
+    #[error(
+        "Tried to uninstall app '{0}' whose cells are still used by the following apps: {1:?}"
+    )]
+    AppHasDependents(InstalledAppId, Vec<InstalledAppId>),
+
+    #[error("The cells installed by other apps changed while app '{0}' was being installed, so its role '{1}' may no longer be resolved correctly. Try installing it again.")]
+    ProvisionedCellsChanged(InstalledAppId, AppRoleId),
+
     #[error(transparent)]
     HolochainP2pError(#[from] holochain_p2p::HolochainP2pError),
 
@@ -98,6 +107,12 @@ pub enum ConductorError {
     #[error(transparent)]
     KeystoreError(#[from] holochain_keystore::KeystoreError),
 
+    #[error("The keystore config {0:?} is not supported by this conductor")]
+    UnsupportedKeystoreConfig(KeystoreConfig),
+
+    #[error("The configured keystore does not support database encryption keys")]
+    DbKeyNotSupported,
+
     #[error(transparent)]
     KitsuneP2pError(#[from] kitsune_p2p::KitsuneP2pError),
 
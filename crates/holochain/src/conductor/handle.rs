@@ -39,6 +39,7 @@ use super::api::error::ConductorApiResult;
 use super::api::ZomeCall;
 use super::conductor::CellStatus;
 use super::config::AdminInterfaceConfig;
+use super::config::InterfaceDriver;
 use super::error::ConductorResult;
 use super::interface::SignalBroadcaster;
 use super::manager::spawn_task_manager;
@@ -128,8 +129,12 @@ pub trait ConductorHandleT: Send + Sync {
         configs: Vec<AdminInterfaceConfig>,
     ) -> ConductorResult<()>;
 
-    /// Add an app interface
-    async fn add_app_interface(self: Arc<Self>, port: u16) -> ConductorResult<u16>;
+    /// Add an app interface, returning its driver with the port
+    /// chosen by the OS if it was given port 0.
+    async fn add_app_interface(
+        self: Arc<Self>,
+        driver: InterfaceDriver,
+    ) -> ConductorResult<InterfaceDriver>;
 
     /// List the app interfaces currently installed.
     async fn list_app_interfaces(&self) -> ConductorResult<Vec<u16>>;
@@ -157,6 +162,10 @@ pub trait ConductorHandleT: Send + Sync {
     /// Get an instance of a [`RealRibosome`](crate::core::ribosome::real_ribosome::RealRibosome) for the DnaHash
     fn get_ribosome(&self, dna_hash: &DnaHash) -> ConductorResult<RealRibosome>;
 
+    /// Inspect the zomes of a registered DNA to find the functions,
+    /// callbacks and types it exposes.
+    fn get_dna_definition(&self, dna_hash: &DnaHash) -> ConductorResult<DnaDefinition>;
+
     /// Get an [`EntryDef`](holochain_zome_types::EntryDef) from the [`EntryDefBufferKey`](holochain_types::dna::EntryDefBufferKey)
     fn get_entry_def(&self, key: &EntryDefBufferKey) -> Option<EntryDef>;
 
@@ -214,8 +223,51 @@ pub trait ConductorHandleT: Send + Sync {
         payload: CreateCloneCellPayload,
     ) -> ConductorResult<CellId>;
 
-    /// Destroy a cloned Cell
-    async fn destroy_clone_cell(self: Arc<Self>, cell_id: CellId) -> ConductorResult<()>;
+    /// Archive a cloned Cell. The Cell is stopped and no longer counts
+    /// towards the clone limit of its role, but its data is kept.
+    async fn archive_clone_cell(
+        self: Arc<Self>,
+        payload: ArchiveCloneCellPayload,
+    ) -> ConductorResult<()>;
+
+    /// Bring an archived clone Cell back into its App, with its source chain intact
+    async fn restore_archived_clone_cell(
+        self: Arc<Self>,
+        payload: RestoreArchivedCloneCellPayload,
+    ) -> ConductorResult<()>;
+
+    /// Remove a cloned Cell from its App and purge all of its data
+    async fn delete_clone_cell(
+        self: Arc<Self>,
+        payload: DeleteCloneCellPayload,
+    ) -> ConductorResult<()>;
+
+    /// Upgrade a role of a running App to a successor DNA, closing the
+    /// source chain of the old cell and opening one for a new cell which
+    /// points back at it. Returns the id of the new cell.
+    /// If the new chain can't be opened, the old cell is left in the role
+    /// with its chain still open.
+    async fn upgrade_app(
+        self: Arc<Self>,
+        installed_app_id: InstalledAppId,
+        role_id: AppRoleId,
+        dna: DnaFile,
+        membrane_proof: Option<MembraneProof>,
+    ) -> ConductorResult<CellId>;
+
+    /// Generate a new database master key and re-key all databases with it
+    async fn rotate_database_key(&self) -> ConductorResult<()>;
+
+    /// Refuse to communicate with a remote agent or node
+    /// until the block is lifted or expires
+    async fn block(&self, block: Block) -> ConductorResult<()>;
+
+    /// Lift the block on a remote agent or node.
+    /// Returns false if the target wasn't blocked.
+    async fn unblock(&self, target: BlockTarget) -> ConductorResult<bool>;
+
+    /// List the blocks which haven't expired yet
+    async fn list_blocks(&self) -> ConductorResult<Vec<Block>>;
 
     /// Install Cells into ConductorState based on installation info, and run
     /// genesis on all new source chains
@@ -560,9 +612,12 @@ impl ConductorHandleT for ConductorHandleImpl {
         self.process_app_status_fx(AppStatusFx::SpinUp, None).await
     }
 
-    async fn add_app_interface(self: Arc<Self>, port: u16) -> ConductorResult<u16> {
+    async fn add_app_interface(
+        self: Arc<Self>,
+        driver: InterfaceDriver,
+    ) -> ConductorResult<InterfaceDriver> {
         self.conductor
-            .add_app_interface_via_handle(either::Left(port), self.clone())
+            .add_app_interface_via_handle(either::Left(driver), self.clone())
             .await
     }
 
@@ -645,6 +700,10 @@ impl ConductorHandleT for ConductorHandleImpl {
         self.conductor.get_ribosome(dna_hash)
     }
 
+    fn get_dna_definition(&self, dna_hash: &DnaHash) -> ConductorResult<DnaDefinition> {
+        Ok(self.get_ribosome(dna_hash)?.dna_definition()?)
+    }
+
     fn get_entry_def(&self, key: &EntryDefBufferKey) -> Option<EntryDef> {
         self.conductor
             .ribosome_store()
@@ -775,6 +834,7 @@ impl ConductorHandleT for ConductorHandleImpl {
             | Get { .. }
             | GetMeta { .. }
             | GetLinks { .. }
+            | CountLinks { .. }
             | GetAgentActivity { .. }
             | ValidationReceiptReceived { .. } => {
                 let cell_id = CellId::new(event.dna_hash().clone(), event.target_agents().clone());
@@ -894,30 +954,216 @@ impl ConductorHandleT for ConductorHandleImpl {
         self: Arc<Self>,
         payload: CreateCloneCellPayload,
     ) -> ConductorResult<CellId> {
+        let base_cell_id = payload.cell_id();
         let CreateCloneCellPayload {
             properties,
-            dna_hash,
             installed_app_id,
-            agent_key,
             role_id,
             membrane_proof,
+            ..
         } = payload;
-        let cell_id = CellId::new(dna_hash, agent_key);
-        let cells = vec![(cell_id.clone(), membrane_proof)];
-
-        // Run genesis on cells.
-        crate::conductor::conductor::genesis_cells(&self.conductor, cells, self.clone()).await?;
 
+        // The clone gets a random network seed, so its CellId is only known
+        // once it has been added to the app.
         let properties = properties.unwrap_or_else(|| ().into());
         let cell_id = self
             .conductor
-            .add_clone_cell_to_app(installed_app_id, role_id, properties)
+            .add_clone_cell_to_app(
+                installed_app_id.clone(),
+                role_id.clone(),
+                base_cell_id,
+                properties,
+            )
             .await?;
+
+        // Run genesis on the new clone, and take it back out of the app if
+        // that fails.
+        let cells = vec![(cell_id.clone(), membrane_proof)];
+        if let Err(err) =
+            crate::conductor::conductor::genesis_cells(&self.conductor, cells, self.clone()).await
+        {
+            self.conductor
+                .delete_clone_cell_from_app(DeleteCloneCellPayload {
+                    app_id: installed_app_id,
+                    role_id,
+                    cell_id,
+                })
+                .await?;
+            return Err(err);
+        }
+
+        self.process_app_status_fx(
+            AppStatusFx::SpinUp,
+            Some(vec![installed_app_id].into_iter().collect()),
+        )
+        .await?;
         Ok(cell_id)
     }
 
-    async fn destroy_clone_cell(self: Arc<Self>, _cell_id: CellId) -> ConductorResult<()> {
-        todo!()
+    async fn archive_clone_cell(
+        self: Arc<Self>,
+        payload: ArchiveCloneCellPayload,
+    ) -> ConductorResult<()> {
+        let app_id = payload.app_id.clone();
+        self.conductor.archive_clone_cell_of_app(payload).await?;
+
+        // Stop the archived cell, which is no longer referenced by the app
+        self.process_app_status_fx(
+            AppStatusFx::SpinDown,
+            Some(vec![app_id].into_iter().collect()),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn restore_archived_clone_cell(
+        self: Arc<Self>,
+        payload: RestoreArchivedCloneCellPayload,
+    ) -> ConductorResult<()> {
+        let app_id = payload.app_id.clone();
+        self.conductor
+            .restore_archived_clone_cell_of_app(payload)
+            .await?;
+
+        // Genesis already ran for this cell, so it only needs to be started again
+        self.process_app_status_fx(
+            AppStatusFx::SpinUp,
+            Some(vec![app_id].into_iter().collect()),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn delete_clone_cell(
+        self: Arc<Self>,
+        payload: DeleteCloneCellPayload,
+    ) -> ConductorResult<()> {
+        let app_id = payload.app_id.clone();
+        let dna_hash = payload.cell_id.dna_hash().clone();
+        let is_orphaned = self.conductor.delete_clone_cell_from_app(payload).await?;
+
+        // Stop the cell before touching its databases
+        self.clone()
+            .process_app_status_fx(
+                AppStatusFx::SpinDown,
+                Some(vec![app_id].into_iter().collect()),
+            )
+            .await?;
+
+        if is_orphaned {
+            self.conductor.purge_space(&dna_hash).await?;
+        } else {
+            warn!(
+                ?dna_hash,
+                "Not purging the data of a deleted clone cell whose DNA is still used by another cell"
+            );
+        }
+        Ok(())
+    }
+
+    async fn upgrade_app(
+        self: Arc<Self>,
+        installed_app_id: InstalledAppId,
+        role_id: AppRoleId,
+        dna: DnaFile,
+        membrane_proof: Option<MembraneProof>,
+    ) -> ConductorResult<CellId> {
+        // Check everything we can before the old chain is closed, since that
+        // can't be undone.
+        let old_cell_id = {
+            let state = self.conductor.get_state().await?;
+            let app = state.get_app(&installed_app_id)?;
+            if !app.status().is_running() {
+                return Err(ConductorError::AppNotRunning(installed_app_id));
+            }
+            let role = app.role(&role_id)?;
+            if role.is_existing() || role.provisioned_cell().is_none() {
+                return Err(AppError::RoleNotUpgradable(role_id).into());
+            }
+            role.cell_id().clone()
+        };
+        let old_dna_hash = old_cell_id.dna_hash().clone();
+        let new_dna_hash = dna.dna_hash().clone();
+        let new_cell_id = CellId::new(new_dna_hash.clone(), old_cell_id.agent_pubkey().clone());
+
+        if !self.list_dnas().contains(&new_dna_hash) {
+            self.register_dna(dna).await?;
+        }
+        crate::conductor::conductor::genesis_cells(
+            &self.conductor,
+            vec![(new_cell_id.clone(), membrane_proof)],
+            self.clone(),
+        )
+        .await?;
+
+        // Neither closing the old chain nor opening the new one can be undone,
+        // so the old chain's CloseChain is only staged until the new chain has
+        // been opened.
+        let close = self
+            .conductor
+            .cell_by_id(&old_cell_id)?
+            .stage_migrate_agent(MigrateAgent::Close, new_dna_hash)
+            .await?;
+
+        // Swap the new cell into the role and open its chain
+        let app_ids: HashSet<_> = vec![installed_app_id.clone()].into_iter().collect();
+        self.conductor
+            .upgrade_role_of_app(
+                installed_app_id.clone(),
+                role_id.clone(),
+                new_cell_id.clone(),
+            )
+            .await?;
+        let opened: ConductorResult<()> = async {
+            self.clone()
+                .process_app_status_fx(AppStatusFx::Both, Some(app_ids.clone()))
+                .await?;
+            self.conductor
+                .cell_by_id(&new_cell_id)?
+                .migrate_agent(MigrateAgent::Open, old_dna_hash)
+                .await?;
+            Ok(())
+        }
+        .await;
+
+        // If the new chain couldn't be opened, put the old cell back
+        if let Err(e) = opened {
+            self.conductor
+                .upgrade_role_of_app(installed_app_id, role_id, old_cell_id)
+                .await?;
+            self.clone()
+                .process_app_status_fx(AppStatusFx::Both, Some(app_ids))
+                .await?;
+            return Err(e);
+        }
+
+        // Close the old chain
+        close.commit().await?;
+        Ok(new_cell_id)
+    }
+
+    async fn rotate_database_key(&self) -> ConductorResult<()> {
+        self.conductor.rotate_database_key().await
+    }
+
+    async fn block(&self, block: Block) -> ConductorResult<()> {
+        use holochain_p2p::HolochainP2pSender;
+        let target = block.target.clone();
+        self.conductor.spaces.block(block).await?;
+        // The block is already enforced on every message, so failing to
+        // close the open connections now is not fatal.
+        if let Err(e) = self.holochain_p2p().block(target).await {
+            tracing::warn!(?e, "Failed to close connections to a blocked node");
+        }
+        Ok(())
+    }
+
+    async fn unblock(&self, target: BlockTarget) -> ConductorResult<bool> {
+        self.conductor.spaces.unblock(target).await
+    }
+
+    async fn list_blocks(&self) -> ConductorResult<Vec<Block>> {
+        Ok(self.conductor.spaces.list_blocks())
     }
 
     async fn install_app(
@@ -957,7 +1203,12 @@ impl ConductorHandleT for ConductorHandleImpl {
         } = payload;
 
         let bundle: AppBundle = {
-            let original_bundle = source.resolve().await?;
+            let cache = self.conductor.download_cache();
+            let original_bundle = source
+                .resolve_cached(&cache)
+                .await?
+                .fetch_remote_dnas(&cache)
+                .await?;
             if let Some(network_seed) = network_seed {
                 let mut manifest = original_bundle.manifest().to_owned();
                 manifest.set_network_seed(network_seed);
@@ -969,8 +1220,13 @@ impl ConductorHandleT for ConductorHandleImpl {
 
         let installed_app_id =
             installed_app_id.unwrap_or_else(|| bundle.manifest().app_name().to_owned());
+        let provisioned_cells = self.conductor.provisioned_cells().await?;
         let ops = bundle
-            .resolve_cells(agent_key.clone(), DnaGamut::placeholder(), membrane_proofs)
+            .resolve_cells(
+                agent_key.clone(),
+                DnaGamut::new(provisioned_cells.iter().cloned()),
+                membrane_proofs,
+            )
             .await?;
 
         let cells_to_create = ops.cells_to_create();
@@ -986,7 +1242,10 @@ impl ConductorHandleT for ConductorHandleImpl {
         let app = InstalledAppCommon::new(installed_app_id, agent_key, roles);
 
         // Update the db
-        let stopped_app = self.conductor.add_disabled_app_to_db(app).await?;
+        let stopped_app = self
+            .conductor
+            .add_resolved_app_to_db(app, provisioned_cells)
+            .await?;
 
         Ok(stopped_app)
     }
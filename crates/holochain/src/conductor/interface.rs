@@ -2,14 +2,20 @@
 //! and dispatch them to the appropriate handlers within Holochain.
 //! They also allow emitting responses and one-way Signals.
 //!
-//! Currently the only InterfaceDriver is a Websocket-based one, whose
-//! implementation can be found in the `websocket` module here.
+//! Both InterfaceDrivers are Websocket-based, listening either on a port or
+//! on a unix domain socket. The implementation can be found in the
+//! `websocket` module here.
 
 use crate::conductor::api::*;
 use error::InterfaceError;
 use error::InterfaceResult;
+use holochain_conductor_api::signal_subscription::SignalFilterSet;
+use holochain_types::prelude::*;
 use holochain_types::signal::Signal;
+use std::collections::HashMap;
+use std::collections::HashSet;
 use std::convert::TryInto;
+use std::sync::Arc;
 use tokio::sync::broadcast;
 
 #[allow(missing_docs)]
@@ -98,4 +104,101 @@ impl SignalBroadcaster {
     }
 }
 
+/// The signal filters of a single connection to an App interface,
+/// one [`SignalFilterSet`] per App the connection has subscribed to.
+///
+/// Signals from a Cell are only governed by the filters of the Apps which
+/// the Cell belongs to, and are let through if any of those filters allows
+/// them. System signals are governed by the filters of all Apps.
+/// A signal which no filter governs is always let through.
+#[derive(Clone, Debug, Default)]
+pub struct SignalSubscriptions(Arc<parking_lot::RwLock<SignalSubscriptionsInner>>);
+
+#[derive(Debug, Default)]
+struct SignalSubscriptionsInner {
+    apps: HashMap<InstalledAppId, AppSignalFilter>,
+    /// Cells known not to belong to any of the subscribed Apps.
+    other_cells: HashSet<CellId>,
+}
+
+#[derive(Clone, Debug)]
+struct AppSignalFilter {
+    cell_ids: HashSet<CellId>,
+    filters: SignalFilterSet,
+}
+
+impl SignalSubscriptions {
+    /// Set the filters for the signals of an App, whose cells are given.
+    /// Replaces any previous filters for that App.
+    pub fn set(
+        &self,
+        installed_app_id: InstalledAppId,
+        cell_ids: impl IntoIterator<Item = CellId>,
+        filters: SignalFilterSet,
+    ) {
+        let filter = AppSignalFilter {
+            cell_ids: cell_ids.into_iter().collect(),
+            filters,
+        };
+        let mut inner = self.0.write();
+        inner.apps.insert(installed_app_id, filter);
+        inner.other_cells.clear();
+    }
+
+    /// The Apps this connection has subscribed to.
+    pub fn installed_app_ids(&self) -> Vec<InstalledAppId> {
+        self.0.read().apps.keys().cloned().collect()
+    }
+
+    /// Whether the Cell may belong to a subscribed App without being known
+    /// to, e.g. because it was cloned after subscribing, in which case the
+    /// cells of the subscribed Apps should be updated.
+    pub fn is_unknown_cell(&self, cell_id: &CellId) -> bool {
+        let inner = self.0.read();
+        !inner.apps.is_empty()
+            && !inner.other_cells.contains(cell_id)
+            && !inner
+                .apps
+                .values()
+                .any(|sub| sub.cell_ids.contains(cell_id))
+    }
+
+    /// Update the cells of subscribed Apps, after looking them up because
+    /// of a signal from the given Cell. If the Cell still belongs to none of
+    /// the Apps it is remembered as such.
+    pub fn update_cells(
+        &self,
+        cells: impl IntoIterator<Item = (InstalledAppId, Vec<CellId>)>,
+        cell_id: &CellId,
+    ) {
+        let mut inner = self.0.write();
+        for (installed_app_id, cell_ids) in cells {
+            if let Some(sub) = inner.apps.get_mut(&installed_app_id) {
+                sub.cell_ids = cell_ids.into_iter().collect();
+            }
+        }
+        if !inner
+            .apps
+            .values()
+            .any(|sub| sub.cell_ids.contains(cell_id))
+        {
+            inner.other_cells.insert(cell_id.clone());
+        }
+    }
+
+    /// Whether the signal should be sent over this connection.
+    pub fn allows(&self, signal: &Signal) -> bool {
+        let inner = self.0.read();
+        let mut governing = inner
+            .apps
+            .values()
+            .filter(|sub| match signal {
+                Signal::App(cell_id, _) => sub.cell_ids.contains(cell_id),
+                Signal::System(_) => true,
+            })
+            .peekable();
+        governing.peek().is_none() || governing.any(|sub| sub.filters.allows(signal))
+    }
+}
+
 pub use holochain_conductor_api::config::InterfaceDriver;
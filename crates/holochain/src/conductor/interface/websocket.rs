@@ -1,8 +1,11 @@
 //! Module for establishing Websocket-based Interfaces,
-//! i.e. those configured with `InterfaceDriver::Websocket`
+//! i.e. those configured with `InterfaceDriver::Websocket`,
+//! or with `InterfaceDriver::UnixSocket` which uses the same framing
+//! over a unix domain socket.
 
 use super::error::InterfaceError;
 use super::error::InterfaceResult;
+use crate::conductor::api::RealAppInterfaceApi;
 use crate::conductor::conductor::StopReceiver;
 use crate::conductor::interface::*;
 use crate::conductor::manager::ManagedTaskHandle;
@@ -25,6 +28,7 @@ use tokio::sync::broadcast;
 use tokio_stream::StreamExt;
 use tracing::*;
 use url2::url2;
+use url2::Url2;
 
 // TODO: This is arbitrary, choose reasonable size.
 /// Number of signals in buffer before applying
@@ -34,21 +38,54 @@ const MAX_CONNECTIONS: isize = 400;
 
 /// Create a WebsocketListener to be used in interfaces
 pub async fn spawn_websocket_listener(
-    port: u16,
+    driver: &InterfaceDriver,
 ) -> InterfaceResult<(
     ListenerHandle,
     impl futures::stream::Stream<Item = ListenerItem>,
 )> {
     trace!("Initializing Admin interface");
-    let listener = WebsocketListener::bind_with_handle(
-        url2!("ws://127.0.0.1:{}", port),
-        Arc::new(WebsocketConfig::default()),
-    )
-    .await?;
+    let (url, config) = listener_url_and_config(driver)?;
+    let listener = WebsocketListener::bind_with_handle(url, Arc::new(config)).await?;
     trace!("LISTENING AT: {}", listener.0.local_addr());
     Ok(listener)
 }
 
+/// The url to bind the listener for an interface to, and its config.
+fn listener_url_and_config(driver: &InterfaceDriver) -> InterfaceResult<(Url2, WebsocketConfig)> {
+    match driver {
+        InterfaceDriver::Websocket { port } => {
+            Ok((url2!("ws://127.0.0.1:{}", port), WebsocketConfig::default()))
+        }
+        #[cfg(unix)]
+        InterfaceDriver::UnixSocket { path, mode } => Ok((
+            holochain_websocket::unix_socket_url(path)
+                .map_err(holochain_websocket::WebsocketError::from)?,
+            WebsocketConfig::default().unix_socket_mode(*mode),
+        )),
+        #[cfg(not(unix))]
+        InterfaceDriver::UnixSocket { .. } => Err(InterfaceError::Other(
+            "Unix socket interfaces are only supported on unix".into(),
+        )),
+    }
+}
+
+/// The driver of an interface once its listener is bound,
+/// with the port chosen by the OS if the driver asked for port 0.
+pub(crate) fn bound_driver(
+    driver: InterfaceDriver,
+    handle: &ListenerHandle,
+) -> InterfaceResult<InterfaceDriver> {
+    match driver {
+        InterfaceDriver::Websocket { .. } => Ok(InterfaceDriver::Websocket {
+            port: handle
+                .local_addr()
+                .port()
+                .ok_or(InterfaceError::PortError)?,
+        }),
+        driver @ InterfaceDriver::UnixSocket { .. } => Ok(driver),
+    }
+}
+
 /// Create an Admin Interface, which only receives AdminRequest messages
 /// from the external client
 pub fn spawn_admin_interface_task<A: InterfaceApi>(
@@ -91,23 +128,17 @@ pub fn spawn_admin_interface_task<A: InterfaceApi>(
 
 /// Create an App Interface, which includes the ability to receive signals
 /// from Cells via a broadcast channel
-pub async fn spawn_app_interface_task<A: InterfaceApi>(
-    port: u16,
-    api: A,
+pub async fn spawn_app_interface_task(
+    driver: InterfaceDriver,
+    api: RealAppInterfaceApi,
     signal_broadcaster: broadcast::Sender<Signal>,
     mut stop_rx: StopReceiver,
-) -> InterfaceResult<(u16, ManagedTaskHandle)> {
+) -> InterfaceResult<(InterfaceDriver, ManagedTaskHandle)> {
     trace!("Initializing App interface");
-    let (handle, mut listener) = WebsocketListener::bind_with_handle(
-        url2!("ws://127.0.0.1:{}", port),
-        Arc::new(WebsocketConfig::default()),
-    )
-    .await?;
+    let (url, config) = listener_url_and_config(&driver)?;
+    let (handle, mut listener) = WebsocketListener::bind_with_handle(url, Arc::new(config)).await?;
     trace!("LISTENING AT: {}", handle.local_addr());
-    let port = handle
-        .local_addr()
-        .port()
-        .ok_or(InterfaceError::PortError)?;
+    let driver = bound_driver(driver, &handle)?;
     // Task that will kill the listener and all child connections.
     tokio::task::spawn(
         handle.close_on(async move { stop_rx.recv().await.map(|_| true).unwrap_or(true) }),
@@ -119,7 +150,7 @@ pub async fn spawn_app_interface_task<A: InterfaceApi>(
                 Ok((tx_to_iface, rx_from_iface)) => {
                     let rx_from_cell = signal_broadcaster.subscribe();
                     spawn_recv_incoming_msgs_and_outgoing_signals(
-                        api.clone(),
+                        api.for_connection(),
                         rx_from_iface,
                         rx_from_cell,
                         tx_to_iface,
@@ -133,7 +164,7 @@ pub async fn spawn_app_interface_task<A: InterfaceApi>(
 
         ManagedTaskResult::Ok(())
     });
-    Ok((port, task))
+    Ok((driver, task))
 }
 
 /// Polls for messages coming in from the external client.
@@ -160,9 +191,10 @@ async fn recv_incoming_admin_msgs<A: InterfaceApi>(
 
 /// Polls for messages coming in from the external client while simultaneously
 /// polling for signals being broadcast from the Cells associated with this
-/// App interface.
-fn spawn_recv_incoming_msgs_and_outgoing_signals<A: InterfaceApi>(
-    api: A,
+/// App interface. Only signals allowed by the signal subscriptions of the
+/// connection are sent.
+fn spawn_recv_incoming_msgs_and_outgoing_signals(
+    api: RealAppInterfaceApi,
     rx_from_iface: WebsocketReceiver,
     rx_from_cell: broadcast::Receiver<Signal>,
     tx_to_iface: WebsocketSender,
@@ -179,6 +211,13 @@ fn spawn_recv_incoming_msgs_and_outgoing_signals<A: InterfaceApi>(
         }
     });
 
+    let signal_api = api.clone();
+    let rx_from_cell = rx_from_cell.filter(move |signal| {
+        let api = signal_api.clone();
+        let signal = signal.clone();
+        async move { api.allows_signal(&signal).await }
+    });
+
     tokio::task::spawn(rx_from_cell.for_each_concurrent(4096, move |signal| {
         let mut tx_to_iface = tx_to_iface.clone();
         async move {
@@ -235,6 +274,7 @@ pub mod test {
     use crate::test_utils::conductor_setup::ConductorTestData;
     use ::fixt::prelude::*;
     use futures::future::FutureExt;
+    use holochain_conductor_api::signal_subscription::*;
     use holochain_p2p::{AgentPubKeyExt, DnaHashExt};
     use holochain_serialized_bytes::prelude::*;
     use holochain_sqlite::prelude::*;
@@ -414,6 +454,67 @@ pub mod test {
         shutdown.await.unwrap().unwrap();
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn signal_subscription_filters_signals() {
+        observability::test_run().ok();
+        let uuid = Uuid::new_v4();
+        let dna = fake_dna_zomes(
+            &uuid.to_string(),
+            vec![(TestWasm::Foo.into(), TestWasm::Foo.into())],
+        );
+        let cell_id = CellId::from((dna.dna_hash().clone(), fake_agent_pubkey_1()));
+        let other_cell_id = CellId::from((dna.dna_hash().clone(), fake_agent_pubkey_2()));
+        let installed_cell = InstalledCell::new(cell_id.clone(), "handle".into());
+
+        let (_tmpdir, app_api, handle) = setup_app(vec![dna], vec![(installed_cell, None)]).await;
+        let app_signal = |cell_id: &CellId| {
+            Signal::App(
+                cell_id.clone(),
+                AppSignal::new(ExternIO::encode(()).unwrap()),
+            )
+        };
+        let system_signal = test_signal("test");
+        let subscriptions = app_api.signal_subscriptions().clone();
+        let other_connection = app_api.for_connection();
+        assert!(subscriptions.allows(&app_signal(&cell_id)));
+        assert!(subscriptions.allows(&system_signal));
+
+        let filters = SignalFilterSet::Exclude(
+            [(
+                cell_id.clone(),
+                SignalFilter::signal_types([SignalType::App]),
+            )]
+            .into(),
+        );
+        let msg = AppRequest::SignalSubscription(SignalSubscription::new(
+            "test app".to_string(),
+            filters,
+        ));
+        let msg = msg.try_into().unwrap();
+        let respond = |bytes: SerializedBytes| {
+            let response: AppResponse = bytes.try_into().unwrap();
+            assert_matches!(response, AppResponse::SignalSubscriptionUpdated);
+            async { Ok(()) }.boxed().into()
+        };
+        let respond = Respond::Request(Box::new(respond));
+        handle_incoming_message((msg, respond), app_api)
+            .await
+            .unwrap();
+
+        assert!(!subscriptions.allows(&app_signal(&cell_id)));
+        // Cells of other apps are not affected.
+        assert!(subscriptions.allows(&app_signal(&other_cell_id)));
+        assert!(subscriptions.allows(&system_signal));
+        // Other connections to the interface are not affected.
+        assert!(other_connection
+            .signal_subscriptions()
+            .allows(&app_signal(&cell_id)));
+
+        let shutdown = handle.take_shutdown_handle().unwrap();
+        handle.shutdown();
+        shutdown.await.unwrap().unwrap();
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn enable_disable_app() {
         observability::test_run().ok();
@@ -616,6 +717,7 @@ pub mod test {
                     .map(TestZomes::from)
                     .map(|z| z.coordinator.into_inner())
                     .collect(),
+                rate_limits: Vec::new(),
             },
             zomes.into_iter().flat_map(|t| Vec::<DnaWasm>::from(t)),
         )
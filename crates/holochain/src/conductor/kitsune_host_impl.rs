@@ -7,12 +7,12 @@ use std::sync::Arc;
 
 use super::{ribosome_store::RibosomeStore, space::Spaces};
 use futures::FutureExt;
-use holo_hash::DnaHash;
+use holo_hash::{AgentPubKey, DnaHash};
 use holochain_p2p::{
     dht::{spacetime::Topology, ArqStrat},
-    DnaHashExt,
+    AgentPubKeyExt, DnaHashExt,
 };
-use holochain_types::{db::PermittedConn, prelude::DnaError, share::RwShare};
+use holochain_types::{block::BlockTarget, db::PermittedConn, prelude::DnaError, share::RwShare};
 use kitsune_p2p::{
     agent_store::AgentInfoSigned, event::GetAgentInfoSignedEvt, KitsuneHost, KitsuneHostResult,
 };
@@ -143,4 +143,17 @@ impl KitsuneHost for KitsuneHostImpl {
             .boxed()
             .into()
     }
+
+    fn is_blocked(&self, target: kitsune_p2p::block::BlockTarget) -> KitsuneHostResult<bool> {
+        let target = match target {
+            kitsune_p2p::block::BlockTarget::Agent(agent) => {
+                BlockTarget::Agent(AgentPubKey::from_kitsune(&agent))
+            }
+            kitsune_p2p::block::BlockTarget::Node(cert) => {
+                BlockTarget::Node(cert.as_bytes().to_vec().into())
+            }
+        };
+        let blocked = self.spaces.is_blocked(&target);
+        async move { Ok(blocked) }.boxed().into()
+    }
 }
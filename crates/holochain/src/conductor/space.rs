@@ -1,7 +1,7 @@
 //! This module contains data and functions for running operations
 //! at the level of a [`DnaHash`] space.
 //! Multiple [`Cell`](crate::conductor::Cell)'s could share the same space.
-use std::{collections::HashMap, sync::Arc, time::Duration};
+use std::{collections::HashMap, path::PathBuf, sync::Arc, time::Duration};
 
 use holo_hash::{DhtOpHash, DnaHash};
 use holochain_conductor_api::conductor::{ConductorConfig, DatabaseRootPath};
@@ -21,25 +21,28 @@ use holochain_p2p::{
 use holochain_sqlite::{
     conn::{DbSyncLevel, DbSyncStrategy},
     db::{
-        DbKindAuthored, DbKindCache, DbKindConductor, DbKindDht, DbKindP2pAgents, DbKindP2pMetrics,
-        DbKindWasm, DbWrite, ReadAccess,
+        rekey_database_files, DbKindAuthored, DbKindCache, DbKindConductor, DbKindDht,
+        DbKindP2pAgents, DbKindP2pMetrics, DbKindT, DbKindWasm, DbWrite, ReadAccess, WriteManager,
     },
+    db_key::DbKeyring,
     prelude::{DatabaseError, DatabaseResult},
+    schema::SchemaMigration,
 };
 use holochain_state::{
     prelude::{from_blob, StateQueryResult},
     query::{map_sql_dht_op_common, StateQueryError},
 };
 use holochain_types::{
+    block::{Block, BlockTarget},
     db_cache::DhtDbQueryCache,
     dht_op::{DhtOp, DhtOpType},
 };
-use holochain_zome_types::{Entry, EntryVisibility, SignedAction, Timestamp};
+use holochain_zome_types::{Entry, EntryVisibility, SignedAction, SignedWarrant, Timestamp};
 use kitsune_p2p::{
     event::{TimeWindow, TimeWindowInclusive},
     KitsuneP2pConfig,
 };
-use rusqlite::named_params;
+use rusqlite::{named_params, OptionalExtension};
 use tracing::instrument;
 
 use crate::core::{
@@ -54,8 +57,9 @@ use crate::core::{
 
 use super::{
     conductor::RwShare,
-    error::ConductorResult,
+    error::{ConductorError, ConductorResult},
     p2p_agent_store::{self, P2pBatch},
+    state::ConductorState,
 };
 use std::convert::TryInto;
 
@@ -70,11 +74,16 @@ pub struct Spaces {
     map: RwShare<HashMap<DnaHash, Space>>,
     pub(crate) db_dir: Arc<DatabaseRootPath>,
     pub(crate) db_sync_strategy: DbSyncStrategy,
+    /// The keys the databases are encrypted with.
+    db_keyring: RwShare<DbKeyring>,
     /// The map of running queue consumer workflows.
     pub(crate) queue_consumer_map: QueueConsumerMap,
     pub(crate) conductor_db: DbWrite<DbKindConductor>,
     pub(crate) wasm_db: DbWrite<DbKindWasm>,
     network_config: KitsuneP2pConfig,
+    /// The blocks in the conductor database, kept in memory because
+    /// kitsune checks them for every message and gossip target.
+    blocks: RwShare<HashMap<BlockTarget, Block>>,
 }
 
 #[derive(Clone)]
@@ -133,26 +142,53 @@ pub struct TestSpace {
 }
 
 impl Spaces {
-    /// Create a new empty set of [`DnaHash`] spaces.
+    /// Create a new empty set of [`DnaHash`] spaces, whose databases are
+    /// encrypted with the legacy key.
     pub fn new(config: &ConductorConfig) -> ConductorResult<Self> {
+        Self::with_keyring(config, DbKeyring::default())
+    }
+
+    /// Create a new empty set of [`DnaHash`] spaces, whose databases are
+    /// encrypted with keys derived from the current key of the keyring.
+    pub fn with_keyring(config: &ConductorConfig, db_keyring: DbKeyring) -> ConductorResult<Self> {
         let root_db_dir = config.environment_path.clone();
         let db_sync_strategy = config.db_sync_strategy;
         let db_sync_level = match db_sync_strategy {
             DbSyncStrategy::Fast => DbSyncLevel::Off,
             DbSyncStrategy::Resilient => DbSyncLevel::Normal,
         };
-        let conductor_db =
-            DbWrite::open_with_sync_level(root_db_dir.as_ref(), DbKindConductor, db_sync_level)?;
-        let wasm_db =
-            DbWrite::open_with_sync_level(root_db_dir.as_ref(), DbKindWasm, db_sync_level)?;
+        let conductor_db = DbWrite::open_with_keyring(
+            root_db_dir.as_ref(),
+            DbKindConductor,
+            db_sync_level,
+            &db_keyring,
+        )?;
+        let wasm_db = DbWrite::open_with_keyring(
+            root_db_dir.as_ref(),
+            DbKindWasm,
+            db_sync_level,
+            &db_keyring,
+        )?;
+        let blocks = conductor_db.conn()?.with_commit_sync(|txn| {
+            let now = Timestamp::now();
+            holochain_state::mutations::delete_expired_blocks(txn, now)?;
+            ConductorResult::Ok(holochain_state::block::list_blocks(txn, now)?)
+        })?;
         Ok(Spaces {
             map: RwShare::new(HashMap::new()),
             db_dir: Arc::new(root_db_dir),
             db_sync_strategy,
+            db_keyring: RwShare::new(db_keyring),
             queue_consumer_map: QueueConsumerMap::new(),
             conductor_db,
             wasm_db,
             network_config: config.network.clone().unwrap_or_default(),
+            blocks: RwShare::new(
+                blocks
+                    .into_iter()
+                    .map(|block| (block.target.clone(), block))
+                    .collect(),
+            ),
         })
     }
 
@@ -182,6 +218,7 @@ impl Spaces {
                             Arc::new(dna_hash.clone()),
                             &self.db_dir,
                             self.db_sync_strategy,
+                            &self.db_keyring.share_ref(|k| k.clone()),
                         )?;
 
                         let r = f(&space);
@@ -217,6 +254,156 @@ impl Spaces {
         self.get_or_create_space_ref(dna_hash, |space| space.p2p_metrics_db.clone())
     }
 
+    /// Remove a space and purge all data held in its authored, dht and cache
+    /// databases. The caller must make sure that no running cell is still
+    /// using this space.
+    /// Databases which don't exist are skipped rather than created.
+    pub async fn purge_space(&self, dna_hash: &DnaHash) -> ConductorResult<()> {
+        self.map.share_mut(|spaces| spaces.remove(dna_hash));
+        let dna_hash = Arc::new(dna_hash.clone());
+        self.purge_db(DbKindAuthored(dna_hash.clone())).await?;
+        self.purge_db(DbKindDht(dna_hash.clone())).await?;
+        self.purge_db(DbKindCache(dna_hash)).await?;
+        Ok(())
+    }
+
+    async fn purge_db<Kind: DbKindT>(&self, kind: Kind) -> ConductorResult<()> {
+        let db_dir: &PathBuf = (*self.db_dir).as_ref();
+        if !db_dir.join(kind.filename()).exists() {
+            return Ok(());
+        }
+        let db = DbWrite::open_with_keyring(
+            db_dir,
+            kind,
+            DbSyncLevel::Normal,
+            &self.db_keyring.share_ref(|k| k.clone()),
+        )?;
+        db.async_commit(purge_cell_db).await?;
+        Ok(())
+    }
+
+    /// Replace the keyring used to open the databases of new spaces.
+    pub fn set_keyring(&self, db_keyring: DbKeyring) {
+        self.db_keyring.share_mut(|k| *k = db_keyring);
+    }
+
+    /// Re-key all databases under the database root with the current key of
+    /// the given keyring, whether or not their space is open. Once this
+    /// returns, the previous keys of the keyring are no longer needed.
+    pub async fn rekey(&self, db_keyring: DbKeyring) -> ConductorResult<()> {
+        self.set_keyring(db_keyring.clone());
+        self.conductor_db.rekey(&db_keyring).await?;
+        self.wasm_db.rekey(&db_keyring).await?;
+        let spaces = self.get_from_spaces(Space::clone);
+        for space in spaces {
+            space.cache_db.rekey(&db_keyring).await?;
+            space.authored_db.rekey(&db_keyring).await?;
+            space.dht_db.rekey(&db_keyring).await?;
+            space.p2p_agents_db.rekey(&db_keyring).await?;
+            space.p2p_metrics_db.rekey(&db_keyring).await?;
+        }
+        // The open databases are on the current key now, so this only
+        // re-keys the databases of spaces which aren't open.
+        let db_dir: PathBuf = (*self.db_dir).as_ref().clone();
+        tokio::task::spawn_blocking(move || rekey_database_files(&db_dir, &db_keyring))
+            .await
+            .map_err(ConductorError::other)??;
+        Ok(())
+    }
+
+    /// The schema migrations which were run on the conductor databases and
+    /// the databases of all open spaces when they were opened, along with
+    /// the path of each migrated database.
+    pub fn migrations(&self) -> Vec<(PathBuf, SchemaMigration)> {
+        fn migration<Kind: DbKindT>(db: &DbWrite<Kind>) -> Option<(PathBuf, SchemaMigration)> {
+            db.migration().map(|m| (db.path().clone(), m.clone()))
+        }
+        let mut migrations: Vec<_> = migration(&self.conductor_db)
+            .into_iter()
+            .chain(migration(&self.wasm_db))
+            .collect();
+        for space in self.get_from_spaces(Space::clone) {
+            migrations.extend(
+                migration(&space.cache_db)
+                    .into_iter()
+                    .chain(migration(&space.authored_db))
+                    .chain(migration(&space.dht_db))
+                    .chain(migration(&space.p2p_agents_db))
+                    .chain(migration(&space.p2p_metrics_db)),
+            );
+        }
+        migrations
+    }
+
+    /// Get the persisted state of the conductor.
+    pub async fn get_state(&self) -> ConductorResult<ConductorState> {
+        self.conductor_db
+            .async_reader(|txn| {
+                let state = txn
+                    .query_row("SELECT blob FROM ConductorState WHERE id = 1", [], |row| {
+                        row.get("blob")
+                    })
+                    .optional()?;
+                let state = match state {
+                    Some(state) => from_blob(state)?,
+                    None => ConductorState::default(),
+                };
+                Ok(state)
+            })
+            .await
+    }
+
+    /// Block a remote agent or node, replacing any existing block on it.
+    /// Blocks which have expired are pruned at the same time, so that they
+    /// don't pile up in memory and in the database.
+    pub async fn block(&self, block: Block) -> ConductorResult<()> {
+        let stored = block.clone();
+        let now = Timestamp::now();
+        self.conductor_db
+            .async_commit(move |txn| {
+                holochain_state::mutations::delete_expired_blocks(txn, now)?;
+                holochain_state::mutations::insert_block(txn, &stored)?;
+                ConductorResult::Ok(())
+            })
+            .await?;
+        self.blocks.share_mut(|blocks| {
+            blocks.retain(|_, block| !block.is_expired(now));
+            blocks.insert(block.target.clone(), block);
+        });
+        Ok(())
+    }
+
+    /// Lift the block on a remote agent or node.
+    /// Returns false if the target wasn't blocked.
+    pub async fn unblock(&self, target: BlockTarget) -> ConductorResult<bool> {
+        let deleted = target.clone();
+        let unblocked = self
+            .conductor_db
+            .async_commit(move |txn| Ok(holochain_state::mutations::delete_block(txn, &deleted)?))
+            .await?;
+        self.blocks.share_mut(|blocks| blocks.remove(&target));
+        Ok(unblocked)
+    }
+
+    /// All the blocks which haven't expired yet.
+    pub fn list_blocks(&self) -> Vec<Block> {
+        let now = Timestamp::now();
+        self.blocks.share_ref(|blocks| {
+            blocks
+                .values()
+                .filter(|block| !block.is_expired(now))
+                .cloned()
+                .collect()
+        })
+    }
+
+    /// Is this remote agent or node currently blocked?
+    pub fn is_blocked(&self, target: &BlockTarget) -> bool {
+        self.blocks.share_ref(|blocks| {
+            matches!(blocks.get(target), Some(block) if !block.is_expired(Timestamp::now()))
+        })
+    }
+
     /// Get the batch sender (this will create the space if it doesn't already exist).
     pub fn p2p_batch_sender(
         &self,
@@ -461,10 +648,12 @@ impl Spaces {
                         "
                             SELECT DhtOp.hash, DhtOp.type AS dht_type,
                             Action.blob AS action_blob, Entry.blob AS entry_blob,
+                            Warrant.blob AS warrant_blob,
                             LENGTH(Action.blob) as action_size, LENGTH(Entry.blob) as entry_size
                             FROM DHtOp
                             JOIN Action ON DhtOp.action_hash = Action.hash
                             LEFT JOIN Entry ON Action.entry_hash = Entry.hash
+                            LEFT JOIN Warrant ON DhtOp.hash = Warrant.hash
                             WHERE
                             DhtOp.hash = ?
                             AND
@@ -492,7 +681,13 @@ impl Spaces {
                                     None => None,
                                 };
                             }
-                            let op = DhtOp::from_type(op_type, action, entry)?;
+                            let warrant: Option<Vec<u8>> = row.get("warrant_blob")?;
+                            let warrant = match warrant {
+                                Some(warrant) => Some(from_blob::<SignedWarrant>(warrant)?),
+                                None => None,
+                            };
+                            let op =
+                                DhtOp::from_type_with_warrant(op_type, action, entry, warrant)?;
                             StateQueryResult::Ok(((hash, op), bytes))
                         },
                     );
@@ -575,11 +770,17 @@ impl Spaces {
     }
 }
 
+fn purge_cell_db(txn: &mut rusqlite::Transaction) -> DatabaseResult<()> {
+    txn.execute_batch(holochain_sqlite::sql::sql_cell::DELETE_ALL)?;
+    Ok(())
+}
+
 impl Space {
     fn new(
         dna_hash: Arc<DnaHash>,
         root_db_dir: &DatabaseRootPath,
         db_sync_strategy: DbSyncStrategy,
+        db_keyring: &DbKeyring,
     ) -> ConductorResult<Self> {
         use holochain_p2p::DnaHashExt;
         let space = dna_hash.to_kitsune();
@@ -587,30 +788,35 @@ impl Space {
             DbSyncStrategy::Fast => DbSyncLevel::Off,
             DbSyncStrategy::Resilient => DbSyncLevel::Normal,
         };
-        let cache = DbWrite::open_with_sync_level(
+        let cache = DbWrite::open_with_keyring(
             root_db_dir.as_ref(),
             DbKindCache(dna_hash.clone()),
             db_sync_level,
+            db_keyring,
         )?;
-        let authored_db = DbWrite::open_with_sync_level(
+        let authored_db = DbWrite::open_with_keyring(
             root_db_dir.as_ref(),
             DbKindAuthored(dna_hash.clone()),
             DbSyncLevel::Normal,
+            db_keyring,
         )?;
-        let dht_db = DbWrite::open_with_sync_level(
+        let dht_db = DbWrite::open_with_keyring(
             root_db_dir.as_ref(),
             DbKindDht(dna_hash.clone()),
             db_sync_level,
+            db_keyring,
         )?;
-        let p2p_agents_db = DbWrite::open_with_sync_level(
+        let p2p_agents_db = DbWrite::open_with_keyring(
             root_db_dir.as_ref(),
             DbKindP2pAgents(space.clone()),
             db_sync_level,
+            db_keyring,
         )?;
-        let p2p_metrics_db = DbWrite::open_with_sync_level(
+        let p2p_metrics_db = DbWrite::open_with_keyring(
             root_db_dir.as_ref(),
             DbKindP2pMetrics(space),
             db_sync_level,
+            db_keyring,
         )?;
 
         let (tx, rx) = tokio::sync::mpsc::channel(100);
@@ -693,6 +899,7 @@ impl TestSpace {
                 Arc::new(dna_hash),
                 &temp_dir.path().to_path_buf().into(),
                 Default::default(),
+                &Default::default(),
             )
             .unwrap(),
             _temp_dir: temp_dir,
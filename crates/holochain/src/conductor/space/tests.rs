@@ -138,3 +138,203 @@ async fn test_region_queries() {
     assert_eq!(fetched_ops.len(), NUM_OPS);
     assert_eq!(inserted_ops, fetched_ops);
 }
+
+proptest::proptest! {
+    #![proptest_config(proptest::prelude::ProptestConfig::with_cases(100))]
+
+    /// Test that the region set built in memory from the `OpRegion` impl of
+    /// `DhtOp` matches the region set queried from the database, for ops
+    /// anywhere in the historical and recent time windows.
+    #[test]
+    fn test_region_data_matches_database(
+        seed: u64,
+        times in proptest::collection::vec(0u64..5, 0..40),
+    ) {
+        tokio::runtime::Runtime::new()
+            .unwrap()
+            .block_on(region_data_matches_database(seed, times));
+    }
+}
+
+async fn region_data_matches_database(seed: u64, times: Vec<u64>) {
+    use holochain_p2p::dht::prelude::*;
+    use rand::SeedableRng;
+
+    let noise: Vec<u8> = rand::rngs::StdRng::seed_from_u64(seed)
+        .sample_iter(rand::distributions::Standard)
+        .take(1_000_000)
+        .collect();
+    let mut u = Unstructured::new(&noise);
+    let temp_dir = tempfile::TempDir::new().unwrap();
+
+    let spaces = Spaces::new(&ConductorConfig {
+        environment_path: temp_dir.path().to_path_buf().into(),
+        ..Default::default()
+    })
+    .unwrap();
+    let keystore = test_keystore();
+    let agent = keystore.new_sign_keypair_random().await.unwrap();
+
+    let mut dna_def = DnaDef::arbitrary(&mut u).unwrap();
+    let q_us = Dimension::standard_time().quantum as u64;
+    let five_quanta_ago = (Timestamp::now() - Duration::from_micros(q_us * 5)).unwrap();
+    dna_def.origin_time = five_quanta_ago;
+    let topo = dna_def.topology(Duration::from_micros(q_us * 2));
+    let dna_def = DnaDefHashed::from_content_sync(dna_def);
+    let db = spaces.dht_db(dna_def.as_hash()).unwrap();
+
+    let mut ops = vec![];
+    for quanta in times {
+        let mut op = DhtOp::arbitrary(&mut u).unwrap();
+        *op.author_mut() = agent.clone();
+        let mut fact = valid_dht_op(keystore.clone(), agent.clone(), true);
+        fact.satisfy(&mut op, &mut u);
+        let offset = q_us * quanta + u.int_in_range(0..=q_us - 1).unwrap();
+        *op.timestamp_mut() = (five_quanta_ago + Duration::from_micros(offset)).unwrap();
+        ops.push(op);
+    }
+    for op in ops.iter() {
+        fill_db(&db, DhtOpHashed::from_content_sync(op.clone()));
+    }
+
+    let region_set = spaces
+        .handle_fetch_op_regions(dna_def.as_hash(), topo.clone(), DhtArcSet::Full)
+        .await
+        .unwrap();
+    let expected = region_set
+        .coords
+        .clone()
+        .into_region_set_infallible(|(_, coords)| {
+            ops.iter()
+                .filter(|op| coords.contains(&topo, &op.coords(&topo)))
+                .map(|op| op.region_data())
+                .sum::<RegionData>()
+        });
+    assert_eq!(region_set, expected);
+}
+
+/// Blocks are kept in memory, but they must also be loaded from the
+/// database when the conductor restarts.
+#[tokio::test(flavor = "multi_thread")]
+async fn blocks_survive_restart() {
+    use holochain_types::block::{Block, BlockTarget};
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let config = ConductorConfig {
+        environment_path: temp_dir.path().to_path_buf().into(),
+        ..Default::default()
+    };
+    let node = BlockTarget::Node(vec![0xdb; 32].into());
+    let expired = BlockTarget::Agent(AgentPubKey::from_raw_32(vec![0xa9; 32]));
+
+    let spaces = Spaces::new(&config).unwrap();
+    spaces.block(Block::new(node.clone(), None)).await.unwrap();
+    spaces
+        .block(Block::new(expired.clone(), Some(Timestamp::now())))
+        .await
+        .unwrap();
+    assert!(spaces.is_blocked(&node));
+    assert!(!spaces.is_blocked(&expired));
+    drop(spaces);
+
+    let spaces = Spaces::new(&config).unwrap();
+    assert!(spaces.is_blocked(&node));
+    assert!(!spaces.is_blocked(&expired));
+    assert_eq!(spaces.list_blocks(), vec![Block::new(node.clone(), None)]);
+
+    assert!(spaces.unblock(node.clone()).await.unwrap());
+    assert!(!spaces.is_blocked(&node));
+}
+
+/// Expired blocks are pruned from memory and from the database.
+#[tokio::test(flavor = "multi_thread")]
+async fn expired_blocks_are_pruned() {
+    use holochain_types::block::{Block, BlockTarget};
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let config = ConductorConfig {
+        environment_path: temp_dir.path().to_path_buf().into(),
+        ..Default::default()
+    };
+    let node = BlockTarget::Node(vec![0xdb; 32].into());
+    let expired = BlockTarget::Agent(AgentPubKey::from_raw_32(vec![0xa9; 32]));
+    let stored_blocks = |spaces: &Spaces| {
+        spaces
+            .conductor_db
+            .conn()
+            .unwrap()
+            .with_reader_test(|txn| {
+                txn.query_row("SELECT COUNT(*) FROM BlockedTarget", [], |row| {
+                    row.get::<_, usize>(0)
+                })
+            })
+            .unwrap()
+    };
+
+    let spaces = Spaces::new(&config).unwrap();
+    spaces
+        .block(Block::new(expired.clone(), Some(Timestamp::now())))
+        .await
+        .unwrap();
+    assert_eq!(stored_blocks(&spaces), 1);
+
+    // Blocking another target prunes the expired block.
+    spaces.block(Block::new(node.clone(), None)).await.unwrap();
+    assert!(!spaces
+        .blocks
+        .share_ref(|blocks| blocks.contains_key(&expired)));
+    assert_eq!(stored_blocks(&spaces), 1);
+
+    // Blocks which expire while the conductor is down are pruned on startup.
+    spaces
+        .block(Block::new(expired.clone(), Some(Timestamp::now())))
+        .await
+        .unwrap();
+    assert_eq!(stored_blocks(&spaces), 2);
+    drop(spaces);
+
+    let spaces = Spaces::new(&config).unwrap();
+    assert!(!spaces
+        .blocks
+        .share_ref(|blocks| blocks.contains_key(&expired)));
+    assert_eq!(stored_blocks(&spaces), 1);
+    assert!(spaces.is_blocked(&node));
+}
+
+/// Purging a space empties the databases which exist, without creating
+/// the databases of a space which was never opened.
+#[tokio::test(flavor = "multi_thread")]
+async fn purge_space_skips_missing_dbs() {
+    use holochain_sqlite::db::{DbKindAuthored, DbKindT};
+    use std::sync::Arc;
+
+    let mut u = Unstructured::new(&NOISE);
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let spaces = Spaces::new(&ConductorConfig {
+        environment_path: temp_dir.path().to_path_buf().into(),
+        ..Default::default()
+    })
+    .unwrap();
+
+    let unopened = Arc::new(DnaHash::from_raw_32(vec![0xdb; 32]));
+    spaces.purge_space(&unopened).await.unwrap();
+    assert!(!temp_dir
+        .path()
+        .join(DbKindAuthored(unopened.clone()).filename())
+        .exists());
+    assert!(!spaces.map.share_ref(|m| m.contains_key(&*unopened)));
+
+    let dna_hash = DnaHash::from_raw_32(vec![0xda; 32]);
+    let db = spaces.authored_db(&dna_hash).unwrap();
+    fill_db(
+        &db,
+        DhtOpHashed::from_content_sync(DhtOp::arbitrary(&mut u).unwrap()),
+    );
+    spaces.purge_space(&dna_hash).await.unwrap();
+    let count: i64 = db
+        .conn()
+        .unwrap()
+        .query_row("SELECT COUNT(*) FROM DhtOp", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(count, 0);
+}
@@ -7,6 +7,7 @@ use holochain_types::prelude::*;
 use serde::Deserialize;
 use serde::Serialize;
 use std::collections::HashMap;
+use std::collections::HashSet;
 
 use super::error::{ConductorError, ConductorResult};
 
@@ -52,6 +53,18 @@ impl AppInterfaceId {
         };
         Self { port, id }
     }
+    /// Create an id for an interface with this driver.
+    /// Unix socket interfaces are identified by their path.
+    pub fn from_driver(driver: &InterfaceDriver) -> Self {
+        match driver {
+            InterfaceDriver::Websocket { port } => Self::new(*port),
+            InterfaceDriver::UnixSocket { path, .. } => Self {
+                port: 0,
+                id: Some(path.display().to_string()),
+            },
+        }
+    }
+
     /// Get the port intended for this interface
     pub fn port(&self) -> u16 {
         self.port
@@ -129,6 +142,40 @@ impl ConductorState {
             .ok_or_else(|| ConductorError::AppNotInstalled(id.clone()))
     }
 
+    /// The cells provisioned by all installed apps
+    pub fn provisioned_cells(&self) -> HashSet<CellId> {
+        self.installed_apps
+            .iter()
+            .flat_map(|(_, app)| app.provisioned_cells().map(|(_, cell_id)| cell_id.clone()))
+            .collect()
+    }
+
+    /// List the other installed apps which use any of the cells created by the
+    /// given app, i.e. apps that would be broken if it were uninstalled.
+    pub fn dependent_apps(&self, id: &InstalledAppId) -> ConductorResult<Vec<InstalledAppId>> {
+        let owned_cells: HashSet<&CellId> = self.get_app(id)?.owned_cells().collect();
+        Ok(self
+            .installed_apps
+            .iter()
+            .filter(|(other_id, _)| *other_id != id)
+            .filter(|(_, app)| {
+                app.all_cells()
+                    .any(|cell_id| owned_cells.contains(&cell_id))
+            })
+            .map(|(other_id, _)| other_id.clone())
+            .collect())
+    }
+
+    /// The hashes of all DNAs which have data on this conductor, i.e. of
+    /// the cells of all installed apps, including archived clone cells.
+    pub fn dna_hashes(&self) -> HashSet<DnaHash> {
+        self.installed_apps
+            .values()
+            .flat_map(|app| app.all_cells().chain(app.archived_clone_cells()))
+            .map(|cell_id| cell_id.dna_hash().clone())
+            .collect()
+    }
+
     /// Add an app in the Deactivated state. Returns an error if an app is already
     /// present at the given ID.
     pub fn add_app(&mut self, app: InstalledAppCommon) -> ConductorResult<StoppedApp> {
@@ -174,9 +221,7 @@ impl ConductorState {
 /// GUIs, browser based web UIs, local native UIs, other local applications and scripts.
 /// We currently have:
 /// * websockets
-///
-/// We will also soon develop
-/// * Unix domain sockets
+/// * websockets over Unix domain sockets
 ///
 /// The cells (referenced by ID) that are to be made available via that interface should be listed.
 #[derive(Clone, Deserialize, Serialize, Debug)]
@@ -190,13 +235,18 @@ pub struct AppInterfaceConfig {
 }
 
 impl AppInterfaceConfig {
-    /// Create config for a websocket interface
-    pub fn websocket(port: u16) -> Self {
+    /// Create config for an interface with this driver
+    pub fn new(driver: InterfaceDriver) -> Self {
         Self {
             signal_subscriptions: HashMap::new(),
-            driver: InterfaceDriver::Websocket { port },
+            driver,
         }
     }
+
+    /// Create config for a websocket interface
+    pub fn websocket(port: u16) -> Self {
+        Self::new(InterfaceDriver::Websocket { port })
+    }
 }
 
 // TODO: Tons of consistency check tests were ripped out in the great legacy code cleanup
@@ -27,6 +27,7 @@ use crate::core::ribosome::guest_callback::validate::ValidateInvocation;
 use crate::core::ribosome::guest_callback::validate::ValidateResult;
 use crate::core::ribosome::guest_callback::validation_package::ValidationPackageInvocation;
 use crate::core::ribosome::guest_callback::validation_package::ValidationPackageResult;
+use crate::core::ribosome::guest_callback::weigh::WeighInvocation;
 use crate::core::ribosome::guest_callback::CallIterator;
 use derive_more::Constructor;
 use error::RibosomeResult;
@@ -36,6 +37,7 @@ use guest_callback::migrate_agent::MigrateAgentHostAccess;
 use guest_callback::post_commit::PostCommitHostAccess;
 use guest_callback::validate::ValidateHostAccess;
 use guest_callback::validation_package::ValidationPackageHostAccess;
+use guest_callback::weigh::WeighHostAccess;
 use holo_hash::AgentPubKey;
 use holochain_keystore::MetaLairClient;
 use holochain_p2p::HolochainP2pDna;
@@ -105,6 +107,7 @@ pub enum HostContext {
     PostCommit(PostCommitHostAccess), // MAYBE: add emit_signal access here?
     Validate(ValidateHostAccess),
     ValidationPackage(ValidationPackageHostAccess),
+    Weigh(WeighHostAccess),
     ZomeCall(ZomeCallHostAccess),
 }
 
@@ -119,6 +122,7 @@ impl From<&HostContext> for HostFnAccess {
             HostContext::MigrateAgent(access) => access.into(),
             HostContext::ValidationPackage(access) => access.into(),
             HostContext::PostCommit(access) => access.into(),
+            HostContext::Weigh(access) => access.into(),
         }
     }
 }
@@ -525,19 +529,12 @@ pub trait RibosomeT: Sized + std::fmt::Debug + Send + Sync {
     /// values without needing to make holochain a dependency.
     fn get_const_fn(&self, zome: &Zome, name: &str) -> Result<Option<i32>, RibosomeError>;
 
-    /// @todo list out all the available callbacks and maybe cache them somewhere
-    fn list_callbacks(&self) {
-        unimplemented!()
-        // pseudocode
-        // self.instance().exports().filter(|e| e.is_callback())
-    }
+    /// List the callbacks implemented by a zome, e.g. `init` or `validate`.
+    fn list_callbacks(&self, zome: &Zome) -> RibosomeResult<Vec<FunctionName>>;
 
-    /// @todo list out all the available zome functions and maybe cache them somewhere
-    fn list_zome_fns(&self) {
-        unimplemented!()
-        // pseudocode
-        // self.instance().exports().filter(|e| !e.is_callback())
-    }
+    /// List the zome functions exposed by a zome,
+    /// i.e. every extern which is not a callback.
+    fn list_zome_fns(&self, zome: &Zome) -> RibosomeResult<Vec<FunctionName>>;
 
     fn run_genesis_self_check(
         &self,
@@ -575,6 +572,14 @@ pub trait RibosomeT: Sized + std::fmt::Debug + Send + Sync {
         invocation: PostCommitInvocation,
     ) -> RibosomeResult<()>;
 
+    /// Run the `weigh` callback for an action about to be committed.
+    /// Actions are weightless if no zome implements `weigh`.
+    fn run_weigh(
+        &self,
+        access: WeighHostAccess,
+        invocation: WeighInvocation,
+    ) -> RibosomeResult<EntryRateWeight>;
+
     /// Helper function for running a validation callback. Calls
     /// private fn `do_callback!` under the hood.
     fn run_validate(
@@ -594,7 +599,8 @@ pub trait RibosomeT: Sized + std::fmt::Debug + Send + Sync {
     fn zome_types(&self) -> &Arc<GlobalZomeTypes>;
 }
 
-/// Placeholder for weighing. Currently produces zero weight.
+/// Placeholder for weighing countersigned entries, whose weight must be
+/// agreed on before the session starts. Currently produces zero weight.
 pub fn weigh_placeholder() -> EntryRateWeight {
     EntryRateWeight::default()
 }
@@ -5,6 +5,7 @@ pub mod migrate_agent;
 pub mod post_commit;
 pub mod validate;
 pub mod validation_package;
+pub mod weigh;
 use super::HostContext;
 use crate::core::ribosome::error::RibosomeError;
 use crate::core::ribosome::FnComponents;
@@ -14,6 +15,26 @@ use fallible_iterator::FallibleIterator;
 use holochain_types::prelude::*;
 use holochain_zome_types::ExternIO;
 
+/// The names of every extern which the conductor invokes as a callback.
+/// Any other extern a zome exports is a zome function.
+pub const CALLBACK_NAMES: &[&str] = &[
+    "entry_defs",
+    "genesis_self_check",
+    "init",
+    "migrate_agent",
+    "migrate_agent_close",
+    "migrate_agent_open",
+    "post_commit",
+    "validate",
+    "validation_package",
+    "weigh",
+];
+
+/// Is this extern a callback rather than a zome function?
+pub fn is_callback(fn_name: &FunctionName) -> bool {
+    CALLBACK_NAMES.contains(&fn_name.0.as_str())
+}
+
 pub struct CallIterator<R: RibosomeT, I: Invocation> {
     host_context: HostContext,
     ribosome: R,
@@ -0,0 +1,215 @@
+use crate::core::ribosome::FnComponents;
+use crate::core::ribosome::HostContext;
+use crate::core::ribosome::Invocation;
+use crate::core::ribosome::InvocationAuth;
+use crate::core::ribosome::RibosomeT;
+use crate::core::ribosome::ZomesToInvoke;
+use derive_more::Constructor;
+use holochain_serialized_bytes::prelude::*;
+use holochain_types::prelude::*;
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub struct WeighInvocation {
+    zomes: ZomesToInvoke,
+    input: Arc<WeighInput>,
+}
+
+impl WeighInvocation {
+    /// Weigh an action in the integrity zome which defines its type.
+    /// Actions which have no zome, such as deletes and capability entries,
+    /// are weighed by the first integrity zome which implements `weigh`.
+    pub fn new(ribosome: &impl RibosomeT, input: WeighInput) -> Self {
+        let zome_id = match &input {
+            WeighInput::Link(create_link) => Some(create_link.zome_id),
+            WeighInput::Create(Create { entry_type, .. }, _)
+            | WeighInput::Update(Update { entry_type, .. }, _) => match entry_type {
+                EntryType::App(app_entry_type) => Some(app_entry_type.zome_id()),
+                _ => None,
+            },
+            WeighInput::Delete(_) => None,
+        };
+        let zomes = zome_id
+            .and_then(|zome_id| ribosome.get_integrity_zome(&zome_id))
+            .map(ZomesToInvoke::OneIntegrity)
+            .unwrap_or(ZomesToInvoke::AllIntegrity);
+        Self {
+            zomes,
+            input: Arc::new(input),
+        }
+    }
+}
+
+#[derive(Clone, Constructor)]
+pub struct WeighHostAccess;
+
+impl From<WeighHostAccess> for HostContext {
+    fn from(weigh_host_access: WeighHostAccess) -> Self {
+        Self::Weigh(weigh_host_access)
+    }
+}
+
+impl From<&WeighHostAccess> for HostFnAccess {
+    fn from(_: &WeighHostAccess) -> Self {
+        let mut access = Self::none();
+        access.keystore_deterministic = Permission::Allow;
+        access.bindings_deterministic = Permission::Allow;
+        access
+    }
+}
+
+impl Invocation for WeighInvocation {
+    fn zomes(&self) -> ZomesToInvoke {
+        self.zomes.clone()
+    }
+    fn fn_components(&self) -> FnComponents {
+        vec!["weigh".into()].into()
+    }
+    fn host_input(self) -> Result<ExternIO, SerializedBytesError> {
+        ExternIO::encode(self.input)
+    }
+    fn auth(&self) -> InvocationAuth {
+        InvocationAuth::LocalCallback
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::WeighInvocation;
+    use crate::core::ribosome::Invocation;
+    use crate::core::ribosome::MockRibosomeT;
+    use crate::core::ribosome::ZomesToInvoke;
+    use ::fixt::prelude::*;
+    use holochain_types::prelude::*;
+    use holochain_zome_types::fixt::*;
+    use mockall::predicate::*;
+
+    #[test]
+    fn weigh_invocation_zomes() {
+        let zome = fixt!(IntegrityZome);
+        let mut ribosome = MockRibosomeT::new();
+        ribosome
+            .expect_get_integrity_zome()
+            .with(eq(ZomeId::from(3)))
+            .return_const(Some(zome.clone()));
+
+        let mut create_link = fixt!(CreateLink);
+        create_link.zome_id = 3.into();
+        let invocation = WeighInvocation::new(&ribosome, WeighInput::Link(create_link.unweighed()));
+        assert_eq!(invocation.zomes(), ZomesToInvoke::OneIntegrity(zome));
+
+        let invocation =
+            WeighInvocation::new(&ribosome, WeighInput::Delete(fixt!(Delete).unweighed()));
+        assert_eq!(invocation.zomes(), ZomesToInvoke::AllIntegrity);
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "slow_tests")]
+mod slow_tests {
+    use crate::core::ribosome::wasm_test::RibosomeTestFixture;
+    use crate::core::sys_validate::check_rate_weight;
+    use crate::core::sys_validate::SysValidationError;
+    use crate::core::ValidationOutcome;
+    use holochain_types::prelude::*;
+    use holochain_wasm_test_utils::TestWasm;
+    use matches::assert_matches;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn weigh_actions_and_check_their_weights() {
+        observability::test_run().ok();
+        let RibosomeTestFixture {
+            conductor,
+            alice,
+            alice_cell,
+            ..
+        } = RibosomeTestFixture::new(TestWasm::RateLimits).await;
+
+        let post: ActionHash = conductor.call(&alice, "create_post", ()).await;
+        let like: ActionHash = conductor.call(&alice, "like", post.clone()).await;
+        let delete: ActionHash = conductor.call(&alice, "delete_post", post.clone()).await;
+
+        let post: Option<Record> = conductor.call(&alice, "get_record", post).await;
+        let post = post.unwrap();
+        let like: Option<Record> = conductor.call(&alice, "get_record", like).await;
+        let like = like.unwrap();
+        let delete: Option<Record> = conductor.call(&alice, "get_record", delete).await;
+        let delete = delete.unwrap();
+
+        // The weights the zome's weigh callback gave each action.
+        assert_eq!(
+            post.action().entry_rate_data(),
+            Some(EntryRateWeight {
+                bucket_id: 1,
+                units: 2,
+                rate_bytes: 1,
+            })
+        );
+        assert_eq!(
+            like.action().rate_data(),
+            RateWeight {
+                bucket_id: 0,
+                units: 1,
+            }
+        );
+        assert_eq!(
+            delete.action().rate_data(),
+            RateWeight {
+                bucket_id: 1,
+                units: 1,
+            }
+        );
+
+        // Validators weigh the actions the same way.
+        let handle = conductor.handle();
+        let dna_hash = alice_cell.cell_id().dna_hash();
+        for record in [&post, &like, &delete] {
+            check_rate_weight(
+                record.action(),
+                record.entry().as_option(),
+                dna_hash,
+                handle.as_ref(),
+            )
+            .await
+            .unwrap();
+        }
+
+        // An author can't claim a lighter weight than the zome gives them.
+        let mut light_like = match like.action().clone() {
+            Action::CreateLink(create_link) => create_link,
+            _ => unreachable!(),
+        };
+        light_like.weight.units = 0;
+        assert_matches!(
+            check_rate_weight(
+                &Action::CreateLink(light_like),
+                None,
+                dna_hash,
+                handle.as_ref()
+            )
+            .await,
+            Err(SysValidationError::ValidationOutcome(
+                ValidationOutcome::RateWeight(_)
+            ))
+        );
+
+        // Nor move an action into another bucket.
+        let mut moved_post = match post.action().clone() {
+            Action::Create(create) => create,
+            _ => unreachable!(),
+        };
+        moved_post.weight.bucket_id = 0;
+        assert_matches!(
+            check_rate_weight(
+                &Action::Create(moved_post),
+                post.entry().as_option(),
+                dna_hash,
+                handle.as_ref()
+            )
+            .await,
+            Err(SysValidationError::ValidationOutcome(
+                ValidationOutcome::RateWeight(_)
+            ))
+        );
+    }
+}
@@ -74,14 +74,17 @@ host_fn_api_impls! {
 
     fn call (Vec<zt::call::Call>) -> Vec<zt::ZomeCallResponse>;
 
-    // @todo List all the local capability claims.
-    fn capability_claims (()) -> ();
+    // List the active capability claims on the local source chain.
+    fn capability_claims (zt::capability::CapClaimQuery) -> Vec<Record>;
 
-    // @todo List all the local capability grants.
-    fn capability_grants (()) -> ();
+    // List the active capability grants on the local source chain.
+    fn capability_grants (zt::capability::CapGrantQuery) -> Vec<Record>;
 
-    // @todo Get the capability for the current zome call.
-    fn capability_info (()) -> ();
+    // Get the capability grant which authorized the current zome call.
+    fn capability_info (()) -> zt::capability::CapabilityInfo;
+
+    // Close the source chain, declaring the DNA the agent is migrating to.
+    fn close_chain (zt::migrate_agent::CloseChainInput) -> holo_hash::ActionHash;
 
     // The EntryDefId determines how a create is handled on the host side.
     // CapGrant and CapClaim are handled natively.
@@ -144,8 +147,14 @@ host_fn_api_impls! {
     // Get links by entry hash from the cascade.
     fn get_links (Vec<zt::link::GetLinksInput>) -> Vec<Vec<zt::link::Link>>;
 
+    // Count links by entry hash from the cascade without fetching them.
+    fn count_links (zt::link::GetLinksInput) -> usize;
+
     fn get_link_details (Vec<zt::link::GetLinksInput>) -> Vec<zt::link::LinkDetails>;
 
+    // Get the metadata on a hash from the cascade without fetching any records.
+    fn get_meta (zt::request::GetMetaInput) -> zt::metadata::MetadataSet;
+
     // Hash data on the host.
     fn hash (zt::hash::HashInput) -> zt::hash::HashOutput;
 
@@ -161,6 +170,9 @@ host_fn_api_impls! {
     // Attempt to accept a preflight request.
     fn accept_countersigning_preflight_request(zt::countersigning::PreflightRequest) -> zt::countersigning::PreflightRequestAcceptance;
 
+    // Open the source chain, declaring the DNA the agent migrated from.
+    fn open_chain (zt::migrate_agent::OpenChainInput) -> holo_hash::ActionHash;
+
     // Query the source chain for data.
     fn query (zt::query::ChainQueryFilter) -> Vec<Record>;
 
@@ -1,13 +1,97 @@
 use crate::core::ribosome::CallContext;
+use crate::core::ribosome::HostFnAccess;
+use crate::core::ribosome::RibosomeError;
 use crate::core::ribosome::RibosomeT;
-use std::sync::Arc;
+use holochain_types::prelude::*;
 use holochain_wasmer_host::prelude::*;
+use std::sync::Arc;
 
-/// lists all the local claims filtered by tag
+/// List all the active claims stored locally in the chain, filtered by the
+/// query. This is only the current claims as per local CRUD.
 pub fn capability_claims(
     _ribosome: Arc<impl RibosomeT>,
-    _call_context: Arc<CallContext>,
-    _input: (),
-) -> Result<(), RuntimeError> {
-    unimplemented!();
+    call_context: Arc<CallContext>,
+    input: CapClaimQuery,
+) -> Result<Vec<Record>, RuntimeError> {
+    match HostFnAccess::from(&call_context.host_context()) {
+        HostFnAccess {
+            read_workspace: Permission::Allow,
+            ..
+        } => tokio_helper::block_forever_on(async move {
+            let records: Vec<Record> = call_context
+                .host_context
+                .workspace()
+                .source_chain()
+                .as_ref()
+                .expect("Must have source chain to list capability claims")
+                .cap_claims(input)
+                .await
+                .map_err(|source_chain_error| -> RuntimeError {
+                    wasm_error!(WasmErrorInner::Host(source_chain_error.to_string())).into()
+                })?;
+            Ok(records)
+        }),
+        _ => Err(wasm_error!(WasmErrorInner::Host(
+            RibosomeError::HostFnPermissions(
+                call_context.zome.zome_name().clone(),
+                call_context.function_name().clone(),
+                "capability_claims".into(),
+            )
+            .to_string(),
+        ))
+        .into()),
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "slow_tests")]
+pub mod wasm_test {
+    use crate::core::ribosome::wasm_test::RibosomeTestFixture;
+    use hdk::prelude::*;
+    use holochain_wasm_test_utils::TestWasm;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn ribosome_capability_claims() {
+        observability::test_run().ok();
+        let RibosomeTestFixture {
+            conductor,
+            alice,
+            bob,
+            alice_pubkey,
+            bob_pubkey,
+            ..
+        } = RibosomeTestFixture::new(TestWasm::Capability).await;
+
+        let claims: Vec<Record> = conductor
+            .call(&alice, "capability_claims", CapClaimQuery::new())
+            .await;
+        assert!(claims.is_empty());
+
+        // bob grants alice access and sends her the claim
+        let _: () = conductor
+            .call(&bob, "send_assigned_cap_claim", alice_pubkey)
+            .await;
+
+        let claims: Vec<Record> = conductor
+            .call(
+                &alice,
+                "capability_claims",
+                CapClaimQuery::new().grantor(bob_pubkey.clone()),
+            )
+            .await;
+        assert_eq!(claims.len(), 1);
+        assert!(matches!(
+            claims[0].entry().as_option(),
+            Some(Entry::CapClaim(claim)) if claim.tag() == "has_cap_claim"
+        ));
+
+        let claims: Vec<Record> = conductor
+            .call(
+                &alice,
+                "capability_claims",
+                CapClaimQuery::new().grantor(bob_pubkey).tag("other"),
+            )
+            .await;
+        assert!(claims.is_empty());
+    }
 }
@@ -1,16 +1,46 @@
 use crate::core::ribosome::CallContext;
+use crate::core::ribosome::HostFnAccess;
+use crate::core::ribosome::RibosomeError;
 use crate::core::ribosome::RibosomeT;
+use holochain_types::prelude::*;
 use holochain_wasmer_host::prelude::*;
 use std::sync::Arc;
 
-/// list all the grants stored locally in the chain filtered by tag
-/// this is only the current grants as per local CRUD
+/// List all the active grants stored locally in the chain, filtered by the
+/// query. This is only the current grants as per local CRUD.
 pub fn capability_grants(
     _ribosome: Arc<impl RibosomeT>,
-    _call_context: Arc<CallContext>,
-    _input: (),
-) -> Result<(), RuntimeError> {
-    unimplemented!();
+    call_context: Arc<CallContext>,
+    input: CapGrantQuery,
+) -> Result<Vec<Record>, RuntimeError> {
+    match HostFnAccess::from(&call_context.host_context()) {
+        HostFnAccess {
+            read_workspace: Permission::Allow,
+            ..
+        } => tokio_helper::block_forever_on(async move {
+            let records: Vec<Record> = call_context
+                .host_context
+                .workspace()
+                .source_chain()
+                .as_ref()
+                .expect("Must have source chain to list capability grants")
+                .cap_grants(input)
+                .await
+                .map_err(|source_chain_error| -> RuntimeError {
+                    wasm_error!(WasmErrorInner::Host(source_chain_error.to_string())).into()
+                })?;
+            Ok(records)
+        }),
+        _ => Err(wasm_error!(WasmErrorInner::Host(
+            RibosomeError::HostFnPermissions(
+                call_context.zome.zome_name().clone(),
+                call_context.function_name().clone(),
+                "capability_grants".into(),
+            )
+            .to_string(),
+        ))
+        .into()),
+    }
 }
 
 #[cfg(test)]
@@ -58,6 +88,83 @@ pub mod wasm_test {
         assert_eq!(entry_secret, secret);
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn ribosome_capability_grants() {
+        observability::test_run().ok();
+        let RibosomeTestFixture {
+            conductor, alice, ..
+        } = RibosomeTestFixture::new(TestWasm::Capability).await;
+
+        let needs_cap_claim = |grants: &[Record]| -> Vec<ActionHash> {
+            grants
+                .iter()
+                .filter(|record| {
+                    record.entry().to_grant_option().map_or(false, |grant| {
+                        grant
+                            .functions
+                            .iter()
+                            .any(|(_, function)| function.0 == "needs_cap_claim")
+                    })
+                })
+                .map(|record| record.action_address().clone())
+                .collect()
+        };
+
+        // the unrestricted grant committed in init is active
+        let grants: Vec<Record> = conductor
+            .call(&alice, "capability_grants", CapGrantQuery::new())
+            .await;
+        assert_eq!(grants.len(), 1);
+
+        let secret: CapSecret = conductor.call(&alice, "cap_secret", ()).await;
+        let original_hash: ActionHash = conductor
+            .call(&alice, "transferable_cap_grant", secret)
+            .await;
+        let grants: Vec<Record> = conductor
+            .call(&alice, "capability_grants", CapGrantQuery::new())
+            .await;
+        assert_eq!(grants.len(), 2);
+        assert_eq!(needs_cap_claim(&grants), vec![original_hash.clone()]);
+
+        // rolling the grant replaces it
+        let rolled_hash: ActionHash = conductor
+            .call(&alice, "roll_cap_grant", original_hash)
+            .await;
+        let grants: Vec<Record> = conductor
+            .call(&alice, "capability_grants", CapGrantQuery::new())
+            .await;
+        assert_eq!(grants.len(), 2);
+        assert_eq!(needs_cap_claim(&grants), vec![rolled_hash.clone()]);
+
+        // the query filters by function
+        let zome_name: ZomeName = TestWasm::Capability.into();
+        let functions: GrantedFunctions = [(zome_name, "needs_cap_claim".into())]
+            .into_iter()
+            .collect();
+        let grants: Vec<Record> = conductor
+            .call(
+                &alice,
+                "capability_grants",
+                CapGrantQuery::new().functions(functions.clone()),
+            )
+            .await;
+        assert_eq!(needs_cap_claim(&grants), vec![rolled_hash.clone()]);
+        assert_eq!(grants.len(), 1);
+
+        // deleting the grant removes it
+        let _: ActionHash = conductor
+            .call(&alice, "delete_cap_grant", rolled_hash)
+            .await;
+        let grants: Vec<Record> = conductor
+            .call(
+                &alice,
+                "capability_grants",
+                CapGrantQuery::new().functions(functions),
+            )
+            .await;
+        assert!(grants.is_empty());
+    }
+
     // MAYBE: [ B-03669 ] can move this to an integration test (may need to switch to using a RibosomeStore)
     #[tokio::test(flavor = "multi_thread")]
     async fn ribosome_authorized_call() -> anyhow::Result<()> {
@@ -1,14 +1,150 @@
 use crate::core::ribosome::CallContext;
+use crate::core::ribosome::HostFnAccess;
+use crate::core::ribosome::InvocationAuth;
+use crate::core::ribosome::RibosomeError;
 use crate::core::ribosome::RibosomeT;
-use std::sync::Arc;
+use holochain_state::source_chain::SourceChainError;
+use holochain_types::prelude::*;
 use holochain_wasmer_host::prelude::*;
+use std::sync::Arc;
 
-/// return the access info used for this call
-/// also return who is originated the call (pubkey)
+/// return the capability grant which authorized this call,
+/// along with who originated the call (pubkey)
 pub fn capability_info(
     _ribosome: Arc<impl RibosomeT>,
-    _call_context: Arc<CallContext>,
+    call_context: Arc<CallContext>,
     _input: (),
-) -> Result<(), RuntimeError> {
-    unimplemented!();
+) -> Result<CapabilityInfo, RuntimeError> {
+    match HostFnAccess::from(&call_context.host_context()) {
+        HostFnAccess {
+            bindings: Permission::Allow,
+            ..
+        } => tokio_helper::block_forever_on(async move {
+            let workspace = call_context.host_context.workspace();
+            let source_chain = workspace
+                .source_chain()
+                .as_ref()
+                .expect("Must have source chain if bindings access is given");
+            let host_error = |e: SourceChainError| -> RuntimeError {
+                wasm_error!(WasmErrorInner::Host(e.to_string())).into()
+            };
+            let (provenance, cap_grant) = match call_context.auth() {
+                InvocationAuth::Cap(provenance, cap_secret) => {
+                    let check_function = (
+                        call_context.zome.zome_name().clone(),
+                        call_context.function_name().clone(),
+                    );
+                    let cap_grant = source_chain
+                        .valid_cap_grant(check_function, provenance.clone(), cap_secret)
+                        .await
+                        .map_err(host_error)?
+                        // The host should never allow a call with an unauthorized secret,
+                        // but if it has the call must not learn anything.
+                        .ok_or_else(|| -> RuntimeError {
+                            wasm_error!(WasmErrorInner::Host(
+                                "The call was not authorized by any capability grant".into()
+                            ))
+                            .into()
+                        })?;
+                    (provenance, cap_grant)
+                }
+                InvocationAuth::LocalCallback => {
+                    let author = source_chain.agent_pubkey().clone();
+                    (author.clone(), CapGrant::ChainAuthor(author))
+                }
+            };
+            let action_hash = match &cap_grant {
+                CapGrant::ChainAuthor(_) => None,
+                CapGrant::RemoteAgent(grant) => source_chain
+                    .cap_grants(CapGrantQuery::new().tag(grant.tag.clone()))
+                    .await
+                    .map_err(host_error)?
+                    .into_iter()
+                    .rev()
+                    .find(|record| {
+                        matches!(record.entry().as_option(), Some(Entry::CapGrant(g)) if g == grant)
+                    })
+                    .map(|record| record.action_address().clone()),
+            };
+            Ok(CapabilityInfo {
+                provenance,
+                cap_grant,
+                action_hash,
+            })
+        }),
+        _ => Err(wasm_error!(WasmErrorInner::Host(
+            RibosomeError::HostFnPermissions(
+                call_context.zome.zome_name().clone(),
+                call_context.function_name().clone(),
+                "capability_info".into(),
+            )
+            .to_string(),
+        ))
+        .into()),
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "slow_tests")]
+pub mod wasm_test {
+    use crate::core::ribosome::wasm_test::RibosomeTestFixture;
+    use hdk::prelude::*;
+    use holochain_wasm_test_utils::TestWasm;
+
+    #[derive(serde::Serialize, serde::Deserialize, SerializedBytes, Debug)]
+    pub struct CapFor(CapSecret, AgentPubKey);
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn ribosome_capability_info_author() {
+        observability::test_run().ok();
+        let RibosomeTestFixture {
+            conductor,
+            alice,
+            alice_pubkey,
+            ..
+        } = RibosomeTestFixture::new(TestWasm::Capability).await;
+
+        let info: CapabilityInfo = conductor.call(&alice, "capability_info", ()).await;
+        assert_eq!(
+            info,
+            CapabilityInfo {
+                provenance: alice_pubkey.clone(),
+                cap_grant: CapGrant::ChainAuthor(alice_pubkey),
+                action_hash: None,
+            }
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn ribosome_capability_info_remote() {
+        observability::test_run().ok();
+        let RibosomeTestFixture {
+            conductor,
+            alice,
+            bob,
+            alice_pubkey,
+            bob_pubkey,
+            ..
+        } = RibosomeTestFixture::new(TestWasm::Capability).await;
+
+        let secret: CapSecret = conductor.call(&bob, "cap_secret", ()).await;
+        let grant_hash: ActionHash = conductor.call(&bob, "transferable_cap_grant", secret).await;
+
+        let response: ZomeCallResponse = conductor
+            .call(&alice, "try_capability_info", CapFor(secret, bob_pubkey))
+            .await;
+        let info: CapabilityInfo = match response {
+            ZomeCallResponse::Ok(io) => io.decode().unwrap(),
+            other => panic!("Unexpected response {:?}", other),
+        };
+        assert_eq!(info.provenance, alice_pubkey);
+        assert_eq!(info.action_hash, Some(grant_hash));
+        assert!(matches!(
+            info.cap_grant,
+            CapGrant::RemoteAgent(ZomeCallCapGrant {
+                access: CapAccess::Transferable { secret: s, .. },
+                ..
+            }) if s == secret
+        ));
+    }
 }
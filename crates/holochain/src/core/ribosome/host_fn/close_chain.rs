@@ -0,0 +1,53 @@
+use crate::core::ribosome::CallContext;
+use crate::core::ribosome::HostFnAccess;
+use crate::core::ribosome::RibosomeError;
+use crate::core::ribosome::RibosomeT;
+use holochain_types::prelude::*;
+use holochain_wasmer_host::prelude::*;
+use std::sync::Arc;
+
+/// close the source chain of the calling agent, declaring the dna it is migrating to.
+/// no further actions may be committed to a closed chain
+pub fn close_chain(
+    _ribosome: Arc<impl RibosomeT>,
+    call_context: Arc<CallContext>,
+    input: CloseChainInput,
+) -> Result<ActionHash, RuntimeError> {
+    match HostFnAccess::from(&call_context.host_context()) {
+        HostFnAccess {
+            write_workspace: Permission::Allow,
+            ..
+        } => {
+            let CloseChainInput {
+                new_dna_hash,
+                chain_top_ordering,
+            } = input;
+            tokio_helper::block_forever_on(async move {
+                call_context
+                    .host_context
+                    .workspace_write()
+                    .source_chain()
+                    .as_ref()
+                    .expect("Must have source chain if write_workspace access is given")
+                    .put(
+                        builder::CloseChain { new_dna_hash },
+                        None,
+                        chain_top_ordering,
+                    )
+                    .await
+                    .map_err(|source_chain_error| -> RuntimeError {
+                        wasm_error!(WasmErrorInner::Host(source_chain_error.to_string())).into()
+                    })
+            })
+        }
+        _ => Err(wasm_error!(WasmErrorInner::Host(
+            RibosomeError::HostFnPermissions(
+                call_context.zome.zome_name().clone(),
+                call_context.function_name().clone(),
+                "close_chain".into(),
+            )
+            .to_string(),
+        ))
+        .into()),
+    }
+}
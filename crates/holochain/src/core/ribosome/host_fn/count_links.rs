@@ -0,0 +1,84 @@
+use crate::core::ribosome::CallContext;
+use crate::core::ribosome::HostFnAccess;
+use crate::core::ribosome::RibosomeError;
+use crate::core::ribosome::RibosomeT;
+use holochain_cascade::Cascade;
+use holochain_p2p::actor::GetLinksOptions;
+use holochain_types::prelude::*;
+use holochain_wasmer_host::prelude::*;
+use std::sync::Arc;
+
+/// Count the links matching the input without fetching them.
+/// The filter's limit caps the count.
+#[tracing::instrument(skip(_ribosome, call_context), fields(?call_context.zome, function = ?call_context.function_name))]
+pub fn count_links(
+    _ribosome: Arc<impl RibosomeT>,
+    call_context: Arc<CallContext>,
+    input: GetLinksInput,
+) -> Result<usize, RuntimeError> {
+    match HostFnAccess::from(&call_context.host_context()) {
+        HostFnAccess {
+            read_workspace: Permission::Allow,
+            ..
+        } => {
+            let GetLinksInput {
+                base_address,
+                link_type,
+                tag_prefix,
+                filter,
+            } = input;
+
+            let key = WireLinkKey {
+                base: base_address,
+                type_query: link_type,
+                tag: tag_prefix,
+                filter,
+            };
+            tokio_helper::block_forever_on(async move {
+                Cascade::from_workspace_network(
+                    &call_context.host_context.workspace(),
+                    call_context.host_context.network().to_owned(),
+                )
+                .dht_count_links(key, GetLinksOptions::default())
+                .await
+            })
+            .map_err(|cascade_error| -> RuntimeError {
+                wasm_error!(WasmErrorInner::Host(cascade_error.to_string())).into()
+            })
+        }
+        _ => Err(wasm_error!(WasmErrorInner::Host(
+            RibosomeError::HostFnPermissions(
+                call_context.zome.zome_name().clone(),
+                call_context.function_name().clone(),
+                "count_links".into(),
+            )
+            .to_string(),
+        ))
+        .into()),
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "slow_tests")]
+pub mod slow_tests {
+    use crate::core::ribosome::wasm_test::RibosomeTestFixture;
+    use hdk::prelude::*;
+    use holochain_wasm_test_utils::TestWasm;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn count_links_excludes_deleted_links() {
+        observability::test_run().ok();
+        let RibosomeTestFixture {
+            conductor, alice, ..
+        } = RibosomeTestFixture::new(TestWasm::Link).await;
+
+        let _: ActionHash = conductor.call(&alice, "create_link", ()).await;
+        let action_hash: ActionHash = conductor.call(&alice, "create_link", ()).await;
+        let count: usize = conductor.call(&alice, "count_links", ()).await;
+        assert_eq!(count, 2);
+
+        let _: ActionHash = conductor.call(&alice, "delete_link", action_hash).await;
+        let count: usize = conductor.call(&alice, "count_links", ()).await;
+        assert_eq!(count, 1);
+    }
+}
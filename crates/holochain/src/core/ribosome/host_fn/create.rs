@@ -1,3 +1,5 @@
+use crate::core::ribosome::guest_callback::weigh::WeighHostAccess;
+use crate::core::ribosome::guest_callback::weigh::WeighInvocation;
 use crate::core::ribosome::weigh_placeholder;
 use crate::core::ribosome::CallContext;
 use crate::core::ribosome::HostFnAccess;
@@ -11,7 +13,7 @@ use std::sync::Arc;
 /// create record
 #[allow(clippy::extra_unused_lifetimes)]
 pub fn create<'a>(
-    _ribosome: Arc<impl RibosomeT>,
+    ribosome: Arc<impl RibosomeT>,
     call_context: Arc<CallContext>,
     input: CreateInput,
 ) -> Result<ActionHash, RuntimeError> {
@@ -27,8 +29,6 @@ pub fn create<'a>(
                 chain_top_ordering,
             } = input;
 
-            let weight = weigh_placeholder();
-
             // Countersigned entries have different action handling.
             match entry {
                 Entry::CounterSign(_, _) => tokio_helper::block_forever_on(async move {
@@ -38,7 +38,7 @@ pub fn create<'a>(
                         .source_chain()
                         .as_ref()
                         .expect("Must have source chain if write_workspace access is given")
-                        .put_countersigned(entry, chain_top_ordering, weight)
+                        .put_countersigned(entry, chain_top_ordering, weigh_placeholder())
                         .await
                         .map_err(|source_chain_error| -> RuntimeError {
                             wasm_error!(WasmErrorInner::Host(source_chain_error.to_string())).into()
@@ -67,6 +67,30 @@ pub fn create<'a>(
                         entry_type,
                         entry_hash,
                     };
+                    let source_chain = call_context
+                        .host_context
+                        .workspace_write()
+                        .source_chain()
+                        .as_ref()
+                        .expect("Must have source chain if write_workspace access is given");
+                    let unweighed: Create<()> = source_chain
+                        .build_unweighed(action_builder)
+                        .map_err(|source_chain_error| -> RuntimeError {
+                            wasm_error!(WasmErrorInner::Host(source_chain_error.to_string())).into()
+                        })?;
+
+                    // weigh the action for rate limiting
+                    let weight = ribosome
+                        .run_weigh(
+                            WeighHostAccess,
+                            WeighInvocation::new(
+                                ribosome.as_ref(),
+                                WeighInput::Create(unweighed.clone(), entry.clone()),
+                            ),
+                        )
+                        .map_err(|ribosome_error| -> RuntimeError {
+                            wasm_error!(WasmErrorInner::Host(ribosome_error.to_string())).into()
+                        })?;
 
                     // return the hash of the committed entry
                     // note that validation is handled by the workflow
@@ -74,13 +98,8 @@ pub fn create<'a>(
                     // being atomic
                     tokio_helper::block_forever_on(async move {
                         // push the action and the entry into the source chain
-                        call_context
-                            .host_context
-                            .workspace_write()
-                            .source_chain()
-                            .as_ref()
-                            .expect("Must have source chain if write_workspace access is given")
-                            .put_weightless(action_builder, Some(entry), chain_top_ordering)
+                        source_chain
+                            .put_unweighed(unweighed, Some(entry), chain_top_ordering, weight)
                             .await
                             .map_err(|source_chain_error| -> RuntimeError {
                                 wasm_error!(WasmErrorInner::Host(source_chain_error.to_string()))
@@ -1,3 +1,5 @@
+use crate::core::ribosome::guest_callback::weigh::WeighHostAccess;
+use crate::core::ribosome::guest_callback::weigh::WeighInvocation;
 use crate::core::ribosome::CallContext;
 use crate::core::ribosome::HostFnAccess;
 use crate::core::ribosome::RibosomeError;
@@ -9,7 +11,7 @@ use std::sync::Arc;
 
 #[allow(clippy::extra_unused_lifetimes)]
 pub fn create_link<'a>(
-    _ribosome: Arc<impl RibosomeT>,
+    ribosome: Arc<impl RibosomeT>,
     call_context: Arc<CallContext>,
     input: CreateLinkInput,
 ) -> Result<ActionHash, RuntimeError> {
@@ -30,6 +32,26 @@ pub fn create_link<'a>(
             // Construct the link add
             let action_builder =
                 builder::CreateLink::new(base_address, target_address, zome_id, link_type, tag);
+            let unweighed: CreateLink<()> = call_context
+                .host_context
+                .workspace_write()
+                .source_chain()
+                .as_ref()
+                .expect("Must have source chain if write_workspace access is given")
+                .build_unweighed(action_builder)
+                .map_err(|source_chain_error| -> RuntimeError {
+                    wasm_error!(WasmErrorInner::Host(source_chain_error.to_string())).into()
+                })?;
+
+            // weigh the link for rate limiting
+            let weight = ribosome
+                .run_weigh(
+                    WeighHostAccess,
+                    WeighInvocation::new(ribosome.as_ref(), WeighInput::Link(unweighed.clone())),
+                )
+                .map_err(|ribosome_error| -> RuntimeError {
+                    wasm_error!(WasmErrorInner::Host(ribosome_error.to_string())).into()
+                })?;
 
             let action_hash = tokio_helper::block_forever_on(tokio::task::spawn(async move {
                 // push the action into the source chain
@@ -39,7 +61,7 @@ pub fn create_link<'a>(
                     .source_chain()
                     .as_ref()
                     .expect("Must have source chain if write_workspace access is given")
-                    .put_weightless(action_builder, None, chain_top_ordering)
+                    .put_unweighed(unweighed, None, chain_top_ordering, weight.into())
                     .await?;
                 Ok::<ActionHash, RibosomeError>(action_hash)
             }))
@@ -1,4 +1,6 @@
 use crate::core::ribosome::error::RibosomeError;
+use crate::core::ribosome::guest_callback::weigh::WeighHostAccess;
+use crate::core::ribosome::guest_callback::weigh::WeighInvocation;
 use crate::core::ribosome::CallContext;
 use crate::core::ribosome::RibosomeT;
 use holochain_cascade::error::CascadeError;
@@ -13,7 +15,7 @@ use std::sync::Arc;
 
 #[allow(clippy::extra_unused_lifetimes)]
 pub fn delete<'a>(
-    _ribosome: Arc<impl RibosomeT>,
+    ribosome: Arc<impl RibosomeT>,
     call_context: Arc<CallContext>,
     input: DeleteInput,
 ) -> Result<ActionHash, RuntimeError> {
@@ -30,20 +32,36 @@ pub fn delete<'a>(
                 get_original_entry_data(call_context.clone(), deletes_action_hash.clone())?;
 
             let host_access = call_context.host_context();
+            let source_chain = host_access
+                .workspace_write()
+                .source_chain()
+                .as_ref()
+                .expect("Must have source chain if write_workspace access is given");
+            let action_builder = builder::Delete {
+                deletes_address: deletes_action_hash,
+                deletes_entry_address,
+            };
+            let unweighed: Delete<()> =
+                source_chain
+                    .build_unweighed(action_builder)
+                    .map_err(|source_chain_error| {
+                        wasm_error!(WasmErrorInner::Host(source_chain_error.to_string()))
+                    })?;
+
+            // weigh the action for rate limiting
+            let weight = ribosome
+                .run_weigh(
+                    WeighHostAccess,
+                    WeighInvocation::new(ribosome.as_ref(), WeighInput::Delete(unweighed.clone())),
+                )
+                .map_err(|ribosome_error| {
+                    wasm_error!(WasmErrorInner::Host(ribosome_error.to_string()))
+                })?;
 
             // handle timeouts at the source chain layer
             tokio_helper::block_forever_on(async move {
-                let source_chain = host_access
-                    .workspace_write()
-                    .source_chain()
-                    .as_ref()
-                    .expect("Must have source chain if write_workspace access is given");
-                let action_builder = builder::Delete {
-                    deletes_address: deletes_action_hash,
-                    deletes_entry_address,
-                };
                 let action_hash = source_chain
-                    .put_weightless(action_builder, None, chain_top_ordering)
+                    .put_unweighed(unweighed, None, chain_top_ordering, weight.into())
                     .await
                     .map_err(|source_chain_error| {
                         wasm_error!(WasmErrorInner::Host(source_chain_error.to_string()))
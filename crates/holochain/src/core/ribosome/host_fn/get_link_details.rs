@@ -27,12 +27,14 @@ pub fn get_link_details<'a>(
                             base_address,
                             link_type,
                             tag_prefix,
+                            filter,
                         } = input;
 
                         let key = WireLinkKey {
                             base: base_address,
                             type_query: link_type,
                             tag: tag_prefix,
+                            filter,
                         };
                         Ok(Cascade::from_workspace_network(
                             &call_context.host_context.workspace(),
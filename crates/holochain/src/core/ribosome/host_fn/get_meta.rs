@@ -0,0 +1,53 @@
+use crate::core::ribosome::CallContext;
+use crate::core::ribosome::HostFnAccess;
+use crate::core::ribosome::RibosomeError;
+use crate::core::ribosome::RibosomeT;
+use holochain_cascade::Cascade;
+use holochain_p2p::actor::GetMetaOptions;
+use holochain_types::prelude::*;
+use holochain_wasmer_host::prelude::*;
+use std::sync::Arc;
+
+/// Get the metadata on a hash without fetching any records or entries.
+#[tracing::instrument(skip(_ribosome, call_context), fields(?call_context.zome, function = ?call_context.function_name))]
+pub fn get_meta(
+    _ribosome: Arc<impl RibosomeT>,
+    call_context: Arc<CallContext>,
+    input: GetMetaInput,
+) -> Result<MetadataSet, RuntimeError> {
+    match HostFnAccess::from(&call_context.host_context()) {
+        HostFnAccess {
+            read_workspace: Permission::Allow,
+            ..
+        } => {
+            let GetMetaInput {
+                any_dht_hash,
+                metadata_request,
+            } = input;
+            let options = GetMetaOptions {
+                metadata_request,
+                ..Default::default()
+            };
+            tokio_helper::block_forever_on(async move {
+                Cascade::from_workspace_network(
+                    &call_context.host_context.workspace(),
+                    call_context.host_context.network().to_owned(),
+                )
+                .get_meta(any_dht_hash, options)
+                .await
+            })
+            .map_err(|cascade_error| -> RuntimeError {
+                wasm_error!(WasmErrorInner::Host(cascade_error.to_string())).into()
+            })
+        }
+        _ => Err(wasm_error!(WasmErrorInner::Host(
+            RibosomeError::HostFnPermissions(
+                call_context.zome.zome_name().clone(),
+                call_context.function_name().clone(),
+                "get_meta".into(),
+            )
+            .to_string(),
+        ))
+        .into()),
+    }
+}
@@ -1,4 +1,6 @@
 use super::delete::get_original_entry_data;
+use crate::core::ribosome::guest_callback::weigh::WeighHostAccess;
+use crate::core::ribosome::guest_callback::weigh::WeighInvocation;
 use crate::core::ribosome::weigh_placeholder;
 use crate::core::ribosome::CallContext;
 use crate::core::ribosome::HostFnAccess;
@@ -11,7 +13,7 @@ use std::sync::Arc;
 
 #[allow(clippy::extra_unused_lifetimes)]
 pub fn update<'a>(
-    _ribosome: Arc<impl RibosomeT>,
+    ribosome: Arc<impl RibosomeT>,
     call_context: Arc<CallContext>,
     input: UpdateInput,
 ) -> Result<ActionHash, RuntimeError> {
@@ -30,8 +32,6 @@ pub fn update<'a>(
             let (original_entry_address, entry_type) =
                 get_original_entry_data(call_context.clone(), original_action_address.clone())?;
 
-            let weight = weigh_placeholder();
-
             // Countersigned entries have different action handling.
             match entry {
                 Entry::CounterSign(_, _) => tokio_helper::block_forever_on(async move {
@@ -41,7 +41,7 @@ pub fn update<'a>(
                         .source_chain()
                         .as_ref()
                         .expect("Must have source chain if write_workspace access is given")
-                        .put_countersigned(entry, chain_top_ordering, weight)
+                        .put_countersigned(entry, chain_top_ordering, weigh_placeholder())
                         .await
                         .map_err(|source_chain_error| -> RuntimeError {
                             wasm_error!(WasmErrorInner::Host(source_chain_error.to_string())).into()
@@ -58,20 +58,39 @@ pub fn update<'a>(
                         entry_type,
                         entry_hash,
                     };
-                    let workspace = call_context.host_context.workspace_write();
+                    let source_chain = call_context
+                        .host_context
+                        .workspace_write()
+                        .source_chain()
+                        .as_ref()
+                        .expect("Must have source chain if write_workspace access is given");
+                    let unweighed: Update<()> = source_chain
+                        .build_unweighed(action_builder)
+                        .map_err(|source_chain_error| -> RuntimeError {
+                            wasm_error!(WasmErrorInner::Host(source_chain_error.to_string())).into()
+                        })?;
+
+                    // weigh the action for rate limiting
+                    let weight = ribosome
+                        .run_weigh(
+                            WeighHostAccess,
+                            WeighInvocation::new(
+                                ribosome.as_ref(),
+                                WeighInput::Update(unweighed.clone(), entry.clone()),
+                            ),
+                        )
+                        .map_err(|ribosome_error| -> RuntimeError {
+                            wasm_error!(WasmErrorInner::Host(ribosome_error.to_string())).into()
+                        })?;
 
                     // return the hash of the updated entry
                     // note that validation is handled by the workflow
                     // if the validation fails this update will be rolled back by virtue of the DB transaction
                     // being atomic
                     tokio_helper::block_forever_on(async move {
-                        let source_chain = workspace
-                            .source_chain()
-                            .as_ref()
-                            .expect("Must have source chain if write_workspace access is given");
                         // push the action and the entry into the source chain
                         let action_hash = source_chain
-                            .put_weightless(action_builder, Some(entry), chain_top_ordering)
+                            .put_unweighed(unweighed, Some(entry), chain_top_ordering, weight)
                             .await
                             .map_err(|source_chain_error| -> RuntimeError {
                                 wasm_error!(WasmErrorInner::Host(source_chain_error.to_string()))
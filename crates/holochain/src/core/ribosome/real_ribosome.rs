@@ -17,6 +17,7 @@ use crate::core::ribosome::guest_callback::genesis_self_check::GenesisSelfCheckI
 use crate::core::ribosome::guest_callback::genesis_self_check::GenesisSelfCheckResult;
 use crate::core::ribosome::guest_callback::init::InitInvocation;
 use crate::core::ribosome::guest_callback::init::InitResult;
+use crate::core::ribosome::guest_callback::is_callback;
 use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentInvocation;
 use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentResult;
 use crate::core::ribosome::guest_callback::post_commit::PostCommitInvocation;
@@ -24,6 +25,8 @@ use crate::core::ribosome::guest_callback::validate::ValidateInvocation;
 use crate::core::ribosome::guest_callback::validate::ValidateResult;
 use crate::core::ribosome::guest_callback::validation_package::ValidationPackageInvocation;
 use crate::core::ribosome::guest_callback::validation_package::ValidationPackageResult;
+use crate::core::ribosome::guest_callback::weigh::WeighHostAccess;
+use crate::core::ribosome::guest_callback::weigh::WeighInvocation;
 use crate::core::ribosome::guest_callback::CallIterator;
 use crate::core::ribosome::host_fn::accept_countersigning_preflight_request::accept_countersigning_preflight_request;
 use crate::core::ribosome::host_fn::agent_info::agent_info;
@@ -32,6 +35,8 @@ use crate::core::ribosome::host_fn::call_info::call_info;
 use crate::core::ribosome::host_fn::capability_claims::capability_claims;
 use crate::core::ribosome::host_fn::capability_grants::capability_grants;
 use crate::core::ribosome::host_fn::capability_info::capability_info;
+use crate::core::ribosome::host_fn::close_chain::close_chain;
+use crate::core::ribosome::host_fn::count_links::count_links;
 use crate::core::ribosome::host_fn::create::create;
 use crate::core::ribosome::host_fn::create_link::create_link;
 use crate::core::ribosome::host_fn::create_x25519_keypair::create_x25519_keypair;
@@ -43,10 +48,12 @@ use crate::core::ribosome::host_fn::get::get;
 use crate::core::ribosome::host_fn::get_details::get_details;
 use crate::core::ribosome::host_fn::get_link_details::get_link_details;
 use crate::core::ribosome::host_fn::get_links::get_links;
+use crate::core::ribosome::host_fn::get_meta::get_meta;
 use crate::core::ribosome::host_fn::hash::hash;
 use crate::core::ribosome::host_fn::must_get_action::must_get_action;
 use crate::core::ribosome::host_fn::must_get_entry::must_get_entry;
 use crate::core::ribosome::host_fn::must_get_valid_record::must_get_valid_record;
+use crate::core::ribosome::host_fn::open_chain::open_chain;
 use crate::core::ribosome::host_fn::query::query;
 use crate::core::ribosome::host_fn::random_bytes::random_bytes;
 use crate::core::ribosome::host_fn::remote_signal::remote_signal;
@@ -200,6 +207,11 @@ static CONTEXT_MAP: ContextMap = Lazy::new(Default::default);
 
 static CONTEXT_KEY: AtomicU64 = AtomicU64::new(0);
 
+type ExternFnsCache = Lazy<Mutex<HashMap<WasmHash, Arc<Vec<FunctionName>>>>>;
+/// Map from a wasm to the externs it exports, so modules
+/// only need to be inspected once.
+static EXTERN_FNS_CACHE: ExternFnsCache = Lazy::new(Default::default);
+
 /// Create a key for the instance cache.
 /// It will be [WasmHash..DnaHash..context_key] all as bytes.
 fn instance_cache_key(wasm_hash: &WasmHash, dna_hash: &DnaHash, context_key: u64) -> [u8; 32] {
@@ -559,6 +571,8 @@ impl RealRibosome {
             .with_host_function(&mut ns, "__get_details", get_details)
             .with_host_function(&mut ns, "__get_links", get_links)
             .with_host_function(&mut ns, "__get_link_details", get_link_details)
+            .with_host_function(&mut ns, "__count_links", count_links)
+            .with_host_function(&mut ns, "__get_meta", get_meta)
             .with_host_function(&mut ns, "__get_agent_activity", get_agent_activity)
             .with_host_function(&mut ns, "__must_get_entry", must_get_entry)
             .with_host_function(&mut ns, "__must_get_action", must_get_action)
@@ -577,6 +591,8 @@ impl RealRibosome {
             .with_host_function(&mut ns, "__delete_link", delete_link)
             .with_host_function(&mut ns, "__update", update)
             .with_host_function(&mut ns, "__delete", delete)
+            .with_host_function(&mut ns, "__close_chain", close_chain)
+            .with_host_function(&mut ns, "__open_chain", open_chain)
             .with_host_function(&mut ns, "__schedule", schedule);
 
         imports.register("env", ns);
@@ -584,6 +600,89 @@ impl RealRibosome {
         imports
     }
 
+    /// All the externs a zome exports, sorted by name.
+    /// Externs used internally by the HDK and HDI are not included.
+    /// For wasm zomes this is cached by [`WasmHash`].
+    pub fn extern_fns(&self, zome: &Zome) -> RibosomeResult<Arc<Vec<FunctionName>>> {
+        match zome.zome_def() {
+            ZomeDef::Wasm(WasmZome { wasm_hash, .. }) => {
+                if let Some(extern_fns) = EXTERN_FNS_CACHE.lock().get(wasm_hash) {
+                    return Ok(extern_fns.clone());
+                }
+                let module = self.module(zome.zome_name())?;
+                let mut extern_fns: Vec<FunctionName> = module
+                    .exports()
+                    .functions()
+                    .map(|f| f.name().to_string())
+                    .filter(|name| !name.starts_with("__"))
+                    .map(FunctionName)
+                    .collect();
+                extern_fns.sort();
+                let extern_fns = Arc::new(extern_fns);
+                EXTERN_FNS_CACHE
+                    .lock()
+                    .insert(wasm_hash.clone(), extern_fns.clone());
+                Ok(extern_fns)
+            }
+            ZomeDef::Inline { inline_zome, .. } => Ok(Arc::new(inline_zome.0.callbacks())),
+        }
+    }
+
+    /// Inspect every zome in the DNA to find the functions,
+    /// callbacks and types it exposes.
+    pub fn dna_definition(&self) -> RibosomeResult<DnaDefinition> {
+        let mut entry_defs = match self.run_entry_defs(EntryDefsHostAccess, EntryDefsInvocation)? {
+            EntryDefsResult::Defs(defs) => defs,
+            EntryDefsResult::Err(zome, error_string) => {
+                return Err(RibosomeError::EntryDefs(zome, error_string))
+            }
+        };
+        let zome_definition = |zome: Zome| {
+            RibosomeResult::Ok(ZomeDefinition {
+                zome_fns: self.list_zome_fns(&zome)?,
+                callbacks: self.list_callbacks(&zome)?,
+                entry_defs: Vec::new(),
+                link_types: Vec::new(),
+                name: zome.into_inner().0,
+            })
+        };
+        let dna_def = self.dna_def();
+        let integrity_zomes = dna_def
+            .integrity_zomes
+            .iter()
+            .enumerate()
+            .map(|(i, (name, def))| {
+                let mut definition =
+                    zome_definition(Zome::new(name.clone(), def.clone().erase_type()))?;
+                definition.entry_defs = entry_defs.remove(name).map(|d| d.0).unwrap_or_default();
+                let zome_id = ZomeId(
+                    i.try_into()
+                        .map_err(|_| ZomeTypesError::ZomeIndexOverflow)?,
+                );
+                definition.link_types = self
+                    .zome_types
+                    .in_scope_subset(&[zome_id])
+                    .links
+                    .0
+                    .into_iter()
+                    .flat_map(|(_, link_types)| link_types)
+                    .collect();
+                RibosomeResult::Ok(definition)
+            })
+            .collect::<RibosomeResult<_>>()?;
+        let coordinator_zomes = dna_def
+            .coordinator_zomes
+            .iter()
+            .map(|(name, def)| zome_definition(Zome::new(name.clone(), def.clone().erase_type())))
+            .collect::<RibosomeResult<_>>()?;
+        Ok(DnaDefinition {
+            dna_hash: dna_def.as_hash().clone(),
+            name: dna_def.name.clone(),
+            integrity_zomes,
+            coordinator_zomes,
+        })
+    }
+
     pub fn get_zome_dependencies(&self, zome_name: &ZomeName) -> RibosomeResult<&[ZomeId]> {
         Ok(self
             .zome_dependencies
@@ -699,6 +798,24 @@ impl RibosomeT for RealRibosome {
         })
     }
 
+    fn list_callbacks(&self, zome: &Zome) -> RibosomeResult<Vec<FunctionName>> {
+        Ok(self
+            .extern_fns(zome)?
+            .iter()
+            .filter(|fn_name| is_callback(fn_name))
+            .cloned()
+            .collect())
+    }
+
+    fn list_zome_fns(&self, zome: &Zome) -> RibosomeResult<Vec<FunctionName>> {
+        Ok(self
+            .extern_fns(zome)?
+            .iter()
+            .filter(|fn_name| !is_callback(fn_name))
+            .cloned()
+            .collect())
+    }
+
     /// call a function in a zome for an invocation if it exists
     /// if it does not exist then return Ok(None)
     fn maybe_call<I: Invocation>(
@@ -848,6 +965,19 @@ impl RibosomeT for RealRibosome {
         }
     }
 
+    fn run_weigh(
+        &self,
+        host_access: WeighHostAccess,
+        invocation: WeighInvocation,
+    ) -> RibosomeResult<EntryRateWeight> {
+        // The first zome to weigh the action decides its weight.
+        match self.call_iterator(host_access.into(), invocation).next() {
+            Ok(Some((_zome, extern_io))) => Ok(extern_io.decode()?),
+            Ok(None) => Ok(EntryRateWeight::default()),
+            Err((_zome, ribosome_error)) => Err(ribosome_error),
+        }
+    }
+
     fn run_genesis_self_check(
         &self,
         host_access: GenesisSelfCheckHostAccess,
@@ -926,6 +1056,10 @@ impl RibosomeT for RealRibosome {
 #[cfg(test)]
 #[cfg(feature = "slow_tests")]
 pub mod wasm_test {
+    use super::RealRibosome;
+    use crate::conductor::api::error::ConductorApiError;
+    use crate::conductor::CellError;
+    use crate::core::ribosome::error::RibosomeError;
     use crate::core::ribosome::wasm_test::RibosomeTestFixture;
     use crate::core::ribosome::ZomeCall;
     use crate::sweettest::SweetConductor;
@@ -986,6 +1120,57 @@ pub mod wasm_test {
         }
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    /// The zome functions and callbacks of each zome can be listed without calling them.
+    async fn ribosome_dna_definition_test() {
+        observability::test_run().ok();
+
+        let (dna_file, _, _) = SweetDnaFile::unique_from_test_wasms(vec![TestWasm::Validate])
+            .await
+            .unwrap();
+        let ribosome = RealRibosome::new(dna_file.clone()).unwrap();
+        let dna_definition = ribosome.dna_definition().unwrap();
+        assert_eq!(dna_definition.dna_hash, *dna_file.dna_hash());
+
+        let integrity = &dna_definition.integrity_zomes[0];
+        assert!(integrity.callbacks.contains(&"entry_defs".into()));
+        assert!(integrity.callbacks.contains(&"validate".into()));
+        assert!(!integrity.zome_fns.contains(&"validate".into()));
+        assert!(!integrity.entry_defs.is_empty());
+
+        let coordinator = &dna_definition.coordinator_zomes[0];
+        assert!(coordinator.callbacks.is_empty());
+        for fn_name in [
+            "must_get_valid_record",
+            "always_validates",
+            "never_validates",
+        ] {
+            assert!(coordinator.zome_fns.contains(&fn_name.into()));
+        }
+
+        // Calling a function which doesn't exist fails before the call is made.
+        let mut conductor = SweetConductor::from_standard_config().await;
+        let app = conductor.setup_app("app", &[dna_file]).await.unwrap();
+        let (cell,) = app.into_tuple();
+        let result = conductor
+            .handle()
+            .call_zome(ZomeCall {
+                cell_id: cell.cell_id().clone(),
+                zome_name: coordinator.name.clone(),
+                fn_name: "no_such_function".into(),
+                cap_secret: None,
+                provenance: cell.agent_pubkey().clone(),
+                payload: ExternIO::encode(()).unwrap(),
+            })
+            .await;
+        matches::assert_matches!(
+            result,
+            Err(ConductorApiError::CellError(CellError::RibosomeError(
+                RibosomeError::ZomeFnNotExists(zome_name, fn_name)
+            ))) if zome_name == coordinator.name && fn_name == FunctionName::from("no_such_function")
+        );
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn the_incredible_halt_test() {
         observability::test_run().ok();
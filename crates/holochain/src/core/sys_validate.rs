@@ -2,6 +2,10 @@
 //! This module contains all the checks we run for sys validation
 
 use super::queue_consumer::TriggerSender;
+use super::ribosome::error::RibosomeError;
+use super::ribosome::guest_callback::weigh::WeighHostAccess;
+use super::ribosome::guest_callback::weigh::WeighInvocation;
+use super::ribosome::weigh_placeholder;
 use super::ribosome::RibosomeT;
 use super::workflow::incoming_dht_ops_workflow::incoming_dht_ops_workflow;
 use super::workflow::sys_validation_workflow::SysValidationWorkspace;
@@ -47,6 +51,21 @@ pub async fn verify_action_signature(sig: &Signature, action: &Action) -> SysVal
     }
 }
 
+/// Verify that a warrant was signed by its warrantor and that it
+/// refers to the action it was gossiped with.
+pub async fn check_warrant(action: &Action, warrant: &SignedWarrant) -> SysValidationResult<()> {
+    let SignedWarrant(w, signature) = warrant;
+    if w.action_hash != ActionHash::with_data_sync(action)
+        || w.author != *action.author()
+        || !w.warrantor.verify_signature(signature, w).await
+    {
+        return Err(SysValidationError::ValidationOutcome(
+            ValidationOutcome::InvalidWarrant(Box::new(warrant.clone())),
+        ));
+    }
+    Ok(())
+}
+
 /// Verify the author key was valid at the time
 /// of signing with dpki
 /// TODO: This is just a stub until we have dpki.
@@ -222,12 +241,103 @@ pub async fn check_chain_rollback(
     }
 }
 
-/// Placeholder for future spam check.
-/// Check action timestamps don't exceed MAX_PUBLISH_FREQUENCY
-pub async fn check_spam(_action: &Action) -> SysValidationResult<()> {
+/// Check the action doesn't overflow the rate limiting bucket it was
+/// weighed into, by replaying the author's whole chain up to this action.
+/// Buckets which the DNA doesn't define a limit for are not checked.
+///
+/// The level of a bucket depends on every earlier action in it, so the
+/// check waits until all of the action's prior chain is held rather than
+/// giving an outcome which depends on what happens to be held.
+pub async fn check_rate_limit(
+    action: &Action,
+    workspace: &SysValidationWorkspace,
+) -> SysValidationResult<()> {
+    let weight = action.rate_data();
+    let limit = match workspace.dna_def().rate_limit(weight.bucket_id) {
+        // Weightless actions can only drain the bucket.
+        Some(limit) if weight.units > 0 => limit.clone(),
+        _ => return Ok(()),
+    };
+    let mut prior = workspace.prior_chain_actions(action).await?;
+
+    // Follow the chain back to its root so forks are ignored.
+    let mut chain = Vec::with_capacity(prior.len());
+    let mut prev_hash = action.prev_action().cloned();
+    while let Some(hash) = prev_hash {
+        let prev = prior
+            .remove(&hash)
+            .ok_or_else(|| ValidationOutcome::DepMissingFromDht(hash.into()))?;
+        prev_hash = prev.prev_action().cloned();
+        chain.push(prev);
+    }
+
+    let mut bucket = RateBucket::new(limit);
+    for prev in chain.into_iter().rev() {
+        let prev_weight = prev.rate_data();
+        if prev_weight.bucket_id == weight.bucket_id {
+            // An earlier action which overflowed the bucket is invalid
+            // in its own right and doesn't count towards the level.
+            let _ = bucket.fill(prev.timestamp(), prev_weight.units);
+        }
+    }
+    bucket
+        .fill(action.timestamp(), weight.units)
+        .map_err(|e| ValidationOutcome::RateLimitExceeded(ActionHash::with_data_sync(action), e))?;
     Ok(())
 }
 
+/// Check the action was given the weight its zome's `weigh` callback gives
+/// it, so an author can't dodge a rate limit by claiming a lighter weight or
+/// another bucket.
+/// Creates and updates can only be weighed along with their entry, so their
+/// weight is not checked when the entry isn't available, as is the case for
+/// private entries.
+pub async fn check_rate_weight(
+    action: &Action,
+    maybe_entry: Option<&Entry>,
+    dna_hash: &DnaHash,
+    conductor: &dyn ConductorHandleT,
+) -> SysValidationResult<()> {
+    let weight = match maybe_entry {
+        // Countersigned entries are weighed before the session starts.
+        Some(Entry::CounterSign(_, _)) => weigh_placeholder(),
+        _ => {
+            let input = match (action, maybe_entry) {
+                (Action::CreateLink(create_link), _) => {
+                    WeighInput::Link(create_link.clone().unweighed())
+                }
+                (Action::Delete(delete), _) => WeighInput::Delete(delete.clone().unweighed()),
+                (Action::Create(create), Some(entry)) => {
+                    WeighInput::Create(create.clone().unweighed(), entry.clone())
+                }
+                (Action::Update(update), Some(entry)) => {
+                    WeighInput::Update(update.clone().unweighed(), entry.clone())
+                }
+                // All other actions are weightless.
+                _ => return Ok(()),
+            };
+            let ribosome = conductor
+                .get_ribosome(dna_hash)
+                .map_err(|_| SysValidationError::DnaMissing(dna_hash.clone()))?;
+            tokio::task::spawn_blocking(move || {
+                let invocation = WeighInvocation::new(&ribosome, input);
+                ribosome.run_weigh(WeighHostAccess, invocation)
+            })
+            .await
+            .map_err(RibosomeError::from)??
+        }
+    };
+    let weighed = match action {
+        Action::Create(_) | Action::Update(_) => action.entry_rate_data() == Some(weight),
+        _ => action.rate_data() == weight.into(),
+    };
+    if weighed {
+        Ok(())
+    } else {
+        Err(ValidationOutcome::RateWeight(ActionHash::with_data_sync(action)).into())
+    }
+}
+
 /// Check previous action timestamp is before this action
 pub fn check_prev_timestamp(action: &Action, prev_action: &Action) -> SysValidationResult<()> {
     if action.timestamp() > prev_action.timestamp() {
@@ -249,6 +359,17 @@ pub fn check_prev_seq(action: &Action, prev_action: &Action) -> SysValidationRes
     }
 }
 
+/// Check the previous action did not close the source chain.
+/// A `CloseChain` action must be the last action on a chain.
+pub fn check_prev_not_closed(prev_action: &Action) -> SysValidationResult<()> {
+    match prev_action {
+        Action::CloseChain(_) => {
+            Err(PrevActionError::ChainClosed).map_err(|e| ValidationOutcome::from(e).into())
+        }
+        _ => Ok(()),
+    }
+}
+
 /// Check the entry variant matches the variant in the actions entry type
 pub fn check_entry_type(entry_type: &EntryType, entry: &Entry) -> SysValidationResult<()> {
     match (entry_type, entry) {
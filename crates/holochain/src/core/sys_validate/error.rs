@@ -3,6 +3,7 @@ use std::convert::TryFrom;
 use super::SourceChainError;
 use crate::conductor::api::error::ConductorApiError;
 use crate::conductor::entry_def_store::error::EntryDefStoreError;
+use crate::core::ribosome::error::RibosomeError;
 use crate::core::validation::OutcomeOrError;
 use crate::core::workflow::error::WorkflowError;
 use crate::from_sub_error;
@@ -49,6 +50,8 @@ pub enum SysValidationError {
     ConductorApiError(#[from] Box<ConductorApiError>),
     #[error("Expected Entry-based Action, but got: {0:?}")]
     NonEntryAction(Action),
+    #[error(transparent)]
+    RibosomeError(#[from] RibosomeError),
 }
 
 impl From<CounterSigningError> for SysValidationError {
@@ -115,6 +118,8 @@ pub enum ValidationOutcome {
     EntryType,
     #[error("The app entry type {0:?} visibility didn't match the zome")]
     EntryVisibility(AppEntryType),
+    #[error("The warrant {0:?} doesn't match its action or wasn't signed by its warrantor")]
+    InvalidWarrant(Box<SignedWarrant>),
     #[error("The link tag size {0} was bigger then the MAX_TAG_SIZE {1}")]
     TagTooLarge(usize, usize),
     #[error("The action {0:?} was expected to be a link add action")]
@@ -127,6 +132,10 @@ pub enum ValidationOutcome {
     PreflightResponseSignature(PreflightResponse),
     #[error(transparent)]
     PrevActionError(#[from] PrevActionError),
+    #[error("The action {0:?} overflowed its author's rate limit: {1}")]
+    RateLimitExceeded(ActionHash, RateBucketError),
+    #[error("The action {0:?} doesn't have the weight its zome's weigh callback gives it")]
+    RateWeight(ActionHash),
     #[error("StoreEntry should not be gossiped for private entries")]
     PrivateEntry,
     #[error("Update original EntryType: {0:?} doesn't match new EntryType {1:?}")]
@@ -154,6 +163,8 @@ impl ValidationOutcome {
 
 #[derive(Error, Debug)]
 pub enum PrevActionError {
+    #[error("The previous action closed the source chain, so no more actions may follow it")]
+    ChainClosed,
     #[error("The previous action in the source chain doesn't match the next action")]
     HashMismatch,
     #[error("Root of source chain must be Dna")]
@@ -8,7 +8,9 @@ use error::SysValidationError;
 
 use holochain_keystore::AgentPubKeyExt;
 use holochain_serialized_bytes::SerializedBytes;
+use holochain_sqlite::db::WriteManager;
 use holochain_state::prelude::fresh_reader_test;
+use holochain_state::prelude::insert_op;
 use holochain_state::prelude::test_authored_db;
 use holochain_state::prelude::test_cache_db;
 use holochain_state::prelude::test_dht_db;
@@ -132,6 +134,95 @@ async fn check_valid_if_dna_test() {
     );
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn check_rate_limit_test() {
+    let tmp = test_authored_db();
+    let tmp_dht = test_dht_db();
+    let tmp_cache = test_cache_db();
+
+    let mut dna_def = fixt!(DnaDef);
+    dna_def.rate_limits = vec![RateBucketLimit {
+        bucket_id: 0,
+        capacity: 10,
+        drain_per_second: 1,
+    }];
+    let cache: DhtDbQueryCache = tmp_dht.to_db().into();
+    let workspace = SysValidationWorkspace::new(
+        tmp.to_db().into(),
+        tmp_dht.to_db().into(),
+        cache,
+        tmp_cache.to_db(),
+        Arc::new(dna_def),
+    );
+
+    let author = fake_agent_pubkey_1();
+    let insert = |action: Action| {
+        let hash = ActionHash::with_data_sync(&action);
+        let op =
+            DhtOpHashed::from_content_sync(DhtOp::RegisterAgentActivity(fixt!(Signature), action));
+        tmp_dht
+            .to_db()
+            .conn()
+            .unwrap()
+            .with_commit_test(|txn| insert_op(txn, &op).unwrap())
+            .unwrap();
+        hash
+    };
+    let link =
+        |prev: &ActionHash, seq: u32, secs: i64, bucket_id: RateBucketId, units: RateUnits| {
+            let mut link = fixt!(CreateLink);
+            link.author = author.clone();
+            link.prev_action = prev.clone();
+            link.action_seq = seq;
+            link.timestamp = Timestamp::from_micros(secs * 1_000_000);
+            link.weight = RateWeight { bucket_id, units };
+            Action::CreateLink(link)
+        };
+
+    let mut dna = fixt!(Dna);
+    dna.author = author.clone();
+    dna.timestamp = Timestamp::from_micros(0);
+    let dna_hash = insert(Action::Dna(dna));
+    // Fill the bucket to capacity, then top it up again once half of it
+    // has drained.
+    let full = insert(link(&dna_hash, 1, 0, 0, 10));
+    let topped_up = insert(link(&full, 2, 5, 0, 5));
+
+    // The bucket is still full.
+    assert_matches!(
+        check_rate_limit(&link(&topped_up, 3, 5, 0, 1), &workspace).await,
+        Err(SysValidationError::ValidationOutcome(
+            ValidationOutcome::RateLimitExceeded(_, _)
+        ))
+    );
+    // Other buckets and unlimited buckets are unaffected.
+    assert_matches!(
+        check_rate_limit(&link(&topped_up, 3, 5, 1, 100), &workspace).await,
+        Ok(())
+    );
+    // The bucket has drained enough after a few seconds.
+    assert_matches!(
+        check_rate_limit(&link(&topped_up, 3, 11, 0, 6), &workspace).await,
+        Ok(())
+    );
+    // Actions from longer ago than a full bucket takes to drain still
+    // count, as the bucket was never empty since.
+    assert_matches!(
+        check_rate_limit(&link(&topped_up, 3, 11, 0, 7), &workspace).await,
+        Err(SysValidationError::ValidationOutcome(
+            ValidationOutcome::RateLimitExceeded(_, _)
+        ))
+    );
+    // The outcome waits for the whole prior chain to be held.
+    let missing = fixt!(ActionHash);
+    assert_matches!(
+        check_rate_limit(&link(&missing, 3, 11, 0, 1), &workspace).await,
+        Err(SysValidationError::ValidationOutcome(
+            ValidationOutcome::DepMissingFromDht(_)
+        ))
+    );
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn check_previous_timestamp() {
     let mut action = fixt!(CreateLink);
@@ -192,6 +283,18 @@ async fn check_previous_seq() {
     );
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn check_prev_not_closed_test() {
+    assert_matches!(check_prev_not_closed(&fixt!(OpenChain).into()), Ok(()));
+    assert_matches!(check_prev_not_closed(&fixt!(Create).into()), Ok(()));
+    assert_matches!(
+        check_prev_not_closed(&fixt!(CloseChain).into()),
+        Err(SysValidationError::ValidationOutcome(
+            ValidationOutcome::PrevActionError(PrevActionError::ChainClosed),
+        ))
+    );
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn check_entry_type_test() {
     let entry_fixt = EntryFixturator::new(Predictable);
@@ -335,6 +438,7 @@ async fn check_app_entry_type_test() {
             coordinator_zomes: vec![TestZomes::from(TestWasm::EntryDefs)
                 .coordinator
                 .into_inner()],
+            rate_limits: Vec::new(),
         },
         [integrity, coordinator],
     )
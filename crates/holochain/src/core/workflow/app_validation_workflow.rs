@@ -5,6 +5,7 @@ use std::sync::Arc;
 
 use super::error::WorkflowResult;
 use super::sys_validation_workflow::validation_query;
+use crate::conductor::conductor::CellStatus;
 use crate::conductor::ConductorHandle;
 use crate::core::queue_consumer::TriggerSender;
 use crate::core::queue_consumer::WorkComplete;
@@ -18,6 +19,7 @@ pub use error::*;
 use futures::stream::StreamExt;
 use holo_hash::DhtOpHash;
 use holochain_cascade::Cascade;
+use holochain_keystore::AgentPubKeyExt;
 use holochain_keystore::MetaLairClient;
 use holochain_p2p::actor::GetOptions as NetworkGetOptions;
 use holochain_p2p::HolochainP2pDna;
@@ -29,6 +31,7 @@ use holochain_types::db_cache::DhtDbQueryCache;
 use holochain_types::prelude::*;
 use holochain_zome_types::op::EntryCreationAction;
 use holochain_zome_types::op::Op;
+use rusqlite::named_params;
 use rusqlite::Transaction;
 use std::collections::HashSet;
 use tracing::*;
@@ -97,6 +100,8 @@ async fn app_validation_workflow_inner(
     let iter = sorted_ops.into_iter().map({
         let network = network.clone();
         let workspace = workspace.clone();
+        let conductor_handle = conductor_handle.clone();
+        let dna_hash = dna_hash.clone();
         move |so| {
             let network = network.clone();
             let conductor_handle = conductor_handle.clone();
@@ -108,6 +113,10 @@ async fn app_validation_workflow_inner(
                 let action = op.action();
                 let dependency = get_dependency(op_type, &action);
                 let op_light = op.to_light();
+                // Kept so we can issue a warrant if the op is rejected.
+                // A rejected warrant says nothing about the action it names.
+                let signed_action = (!matches!(op_type, DhtOpType::RegisterWarrant))
+                    .then(|| SignedAction(action.clone(), op.signature().clone()));
 
                 // If this is agent activity, track it for the cache.
                 let activity = matches!(op_type, DhtOpType::RegisterAgentActivity).then(|| {
@@ -120,14 +129,34 @@ async fn app_validation_workflow_inner(
 
                 // Validate this op
                 let mut cascade = workspace.full_cascade(network.clone());
-                let r = match dhtop_to_op(op, &mut cascade).await {
-                    Ok(op) => {
-                        validate_op_outer(dna_hash, &op, &conductor_handle, &(*workspace), &network)
-                            .await
+                let r = match op {
+                    DhtOp::RegisterWarrant(signature, action, _) => {
+                        validate_warranted_action(
+                            signature,
+                            action,
+                            dna_hash,
+                            &conductor_handle,
+                            &workspace,
+                            &network,
+                            &mut cascade,
+                        )
+                        .await
                     }
-                    Err(e) => Err(e),
+                    op => match dhtop_to_op(op, &mut cascade).await {
+                        Ok(op) => {
+                            validate_op_outer(
+                                dna_hash,
+                                &op,
+                                &conductor_handle,
+                                &(*workspace),
+                                &network,
+                            )
+                            .await
+                        }
+                        Err(e) => Err(e),
+                    },
                 };
-                (op_hash, dependency, op_light, r, activity)
+                (op_hash, dependency, op_light, r, activity, signed_action)
             }
         }
     });
@@ -165,15 +194,17 @@ async fn app_validation_workflow_inner(
             "Committing {} ops",
             chunk.iter().map(|c| c.len()).sum::<usize>()
         );
-        let (t, a, r, activity) = workspace
+        let (t, a, r, activity, invalid) = workspace
             .dht_db
             .async_commit(move |txn| {
                 let mut total = 0;
                 let mut awaiting = 0;
                 let mut rejected = 0;
                 let mut agent_activity = Vec::new();
+                let mut invalid = Vec::new();
                 for outcome in chunk.into_iter().flatten() {
-                    let (op_hash, dependency, op_light, outcome, activity) = outcome;
+                    let (op_hash, dependency, op_light, outcome, activity, signed_action) =
+                        outcome;
                     // Get the outcome or return the error
                     let outcome = outcome.or_else(|outcome_or_err| outcome_or_err.try_into())?;
 
@@ -206,20 +237,24 @@ async fn app_validation_workflow_inner(
                             let status = ValidationLimboStatus::AwaitingAppDeps(deps);
                             put_validation_limbo(txn, &op_hash, status)?;
                         }
-                        Outcome::Rejected(_) => {
+                        Outcome::Rejected(reason) => {
                             rejected += 1;
-                            tracing::warn!("Received invalid op! Warrants aren't implemented yet, so we can't do anything about this right now, but be warned that somebody on the network has maliciously hacked their node.\nOp: {:?}", op_light);
+                            tracing::warn!("Received invalid op! A warrant will be issued against its author.\nOp: {:?}", op_light);
                             if let Dependency::Null = dependency {
                                 put_integrated(txn, &op_hash, ValidationStatus::Rejected)?;
                             } else {
                                 put_integration_limbo(txn, &op_hash, ValidationStatus::Rejected)?;
                             }
+                            if let Some(signed_action) = signed_action {
+                                invalid.push((signed_action, reason));
+                            }
                         }
                     }
                 }
-                WorkflowResult::Ok((total, awaiting, rejected, agent_activity))
+                WorkflowResult::Ok((total, awaiting, rejected, agent_activity, invalid))
             })
             .await?;
+        issue_warrants(&dna_hash, &conductor_handle, &workspace, network, invalid).await?;
         // Once the database transaction is committed, add agent activity to the cache
         // that is ready for integration.
         for (author, seq, has_no_dependency) in activity {
@@ -265,6 +300,100 @@ async fn app_validation_workflow_inner(
     })
 }
 
+/// Issue warrants against the authors of actions which failed app validation.
+///
+/// Each warrant is signed by one of our agents in this dna, held as integrated
+/// in the DHT database and published to the warranted agent's authorities.
+/// The warranted agent is then blocked at the network layer.
+async fn issue_warrants(
+    dna_hash: &DnaHash,
+    conductor_handle: &ConductorHandle,
+    workspace: &AppValidationWorkspace,
+    network: &HolochainP2pDna,
+    invalid: Vec<(SignedAction, String)>,
+) -> WorkflowResult<()> {
+    if invalid.is_empty() {
+        return Ok(());
+    }
+    let our_agents: Vec<AgentPubKey> = conductor_handle
+        .list_cell_ids(Some(CellStatus::Joined))
+        .into_iter()
+        .filter(|cell_id| cell_id.dna_hash() == dna_hash)
+        .map(|cell_id| cell_id.agent_pubkey().clone())
+        .collect();
+    let warrantor = match our_agents.first() {
+        Some(agent) => agent.clone(),
+        None => return Ok(()),
+    };
+
+    // An invalid action produces several invalid ops but only needs one warrant.
+    let mut warranted = HashSet::new();
+    for (SignedAction(action, signature), reason) in invalid {
+        let action_hash = ActionHash::with_data_sync(&action);
+        let author = action.author().clone();
+        if our_agents.contains(&author) || !warranted.insert(action_hash.clone()) {
+            continue;
+        }
+        let warrant = Warrant::new(
+            action_hash.clone(),
+            author.clone(),
+            reason,
+            warrantor.clone(),
+            Timestamp::now(),
+        );
+        let warrant_signature = warrantor.sign(&workspace.keystore, &warrant).await?;
+        let op = DhtOpHashed::from_content_sync(DhtOp::RegisterWarrant(
+            signature,
+            action,
+            Box::new(SignedWarrant(warrant, warrant_signature)),
+        ));
+
+        let inserted = workspace
+            .dht_db
+            .async_commit({
+                let op = op.clone();
+                let warrantor = warrantor.clone();
+                move |txn| {
+                    let exists: bool = txn.query_row(
+                        "SELECT EXISTS(
+                            SELECT 1 FROM Warrant
+                            WHERE action_hash = :action_hash AND warrantor = :warrantor
+                        )",
+                        named_params! {
+                            ":action_hash": action_hash,
+                            ":warrantor": warrantor,
+                        },
+                        |row| row.get(0),
+                    )?;
+                    if exists {
+                        return WorkflowResult::Ok(false);
+                    }
+                    insert_op(txn, &op)?;
+                    put_integrated(txn, op.as_hash(), ValidationStatus::Valid)?;
+                    WorkflowResult::Ok(true)
+                }
+            })
+            .await?;
+        if !inserted {
+            continue;
+        }
+
+        // The warrant is held either way, so failing to block
+        // the author must not fail the workflow.
+        let block = Block::new(BlockTarget::Agent(author.clone()), None);
+        if let Err(e) = conductor_handle.block(block).await {
+            warn!(failed_to_block_agent = ?e);
+        }
+        if let Err(e) = network
+            .publish(false, false, author.into(), vec![op.into_content()], None)
+            .await
+        {
+            warn!(failed_to_publish_warrant = ?e);
+        }
+    }
+    Ok(())
+}
+
 pub async fn record_to_op(
     record: Record,
     op_type: DhtOpType,
@@ -422,10 +551,64 @@ async fn dhtop_to_op(op: DhtOp, cascade: &mut Cascade) -> AppValidationOutcome<O
                 create_link,
             })
         }
+        // A warrant is validated by validating the action it was
+        // issued for, see `validate_warranted_action`.
+        DhtOp::RegisterWarrant(..) => {
+            return Outcome::exit_with_rejected("A warrant can't be validated as an op")
+        }
     };
     Ok(op)
 }
 
+/// Validate the action a warrant was issued for.
+///
+/// Anyone can sign a warrant, so a warrant is only accepted if the action
+/// it names fails app validation here too.
+async fn validate_warranted_action(
+    signature: Signature,
+    action: Action,
+    dna_hash: Arc<DnaHash>,
+    conductor_handle: &ConductorHandle,
+    workspace: &AppValidationWorkspace,
+    network: &HolochainP2pDna,
+    cascade: &mut Cascade,
+) -> AppValidationOutcome<Outcome> {
+    let entry = match action.entry_data() {
+        Some((entry_hash, entry_type))
+            if matches!(entry_type.visibility(), EntryVisibility::Public) =>
+        {
+            Some(
+                cascade
+                    .retrieve_entry(entry_hash.clone(), Default::default())
+                    .await?
+                    .map(|e| e.into_content())
+                    .ok_or_else(|| Outcome::awaiting(entry_hash))?,
+            )
+        }
+        _ => None,
+    };
+    let record = Record::new(
+        SignedActionHashed::with_presigned(ActionHashed::from_content_sync(action), signature),
+        entry,
+    );
+    for op_type in action_to_op_types(record.action()) {
+        // A private entry isn't stored on the DHT, so there's no op for it.
+        if matches!(op_type, DhtOpType::StoreEntry) && record.entry().as_option().is_none() {
+            continue;
+        }
+        let (op, _) = record_to_op(record.clone(), op_type, cascade).await?;
+        match validate_op_outer(dna_hash.clone(), &op, conductor_handle, workspace, network).await?
+        {
+            Outcome::Accepted => (),
+            Outcome::Rejected(_) => return Ok(Outcome::Accepted),
+            awaiting @ Outcome::AwaitingDeps(_) => return Ok(awaiting),
+        }
+    }
+    Ok(Outcome::Rejected(
+        "The action this warrant was issued for is valid".to_string(),
+    ))
+}
+
 async fn validate_op_outer(
     dna_hash: Arc<DnaHash>,
     op: &Op,
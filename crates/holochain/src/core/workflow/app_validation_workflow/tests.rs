@@ -9,6 +9,7 @@ use crate::test_utils::wait_for_integration;
 use holo_hash::ActionHash;
 use holo_hash::AnyDhtHash;
 use holo_hash::EntryHash;
+use holochain_keystore::AgentPubKeyExt;
 use holochain_state::prelude::fresh_reader_test;
 use holochain_state::prelude::from_blob;
 use holochain_state::prelude::StateQueryResult;
@@ -23,8 +24,10 @@ use std::convert::TryFrom;
 use std::convert::TryInto;
 use std::time::Duration;
 
-#[tokio::test(flavor = "multi_thread")]
-async fn app_validation_workflow_test() {
+/// Alice validates what bob commits. Once bob commits something invalid
+/// alice warrants and blocks him, dropping anything else he publishes,
+/// so each test ends with bob's invalid commit.
+async fn setup() -> (SweetConductorBatch, CellId, CellId, DnaFile) {
     observability::test_run().ok();
 
     let (dna_file, _, _) = SweetDnaFile::unique_from_test_wasms(vec![
@@ -46,16 +49,58 @@ async fn app_validation_workflow_test() {
 
     conductors.exchange_peer_info().await;
 
-    let expected_count = run_test(
-        alice_cell_id.clone(),
-        bob_cell_id.clone(),
+    (conductors, alice_cell_id, bob_cell_id, dna_file)
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn app_validation_workflow_test() {
+    let (conductors, alice_cell_id, bob_cell_id, dna_file) = setup().await;
+    let expected_count = run_valid_test(&alice_cell_id, &bob_cell_id, &conductors).await;
+    run_invalid_entry_test(
+        &alice_cell_id,
+        &bob_cell_id,
+        &conductors,
+        &dna_file,
+        expected_count,
+    )
+    .await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn app_validation_workflow_link_test() {
+    let (conductors, alice_cell_id, bob_cell_id, dna_file) = setup().await;
+    let expected_count = run_valid_test(&alice_cell_id, &bob_cell_id, &conductors).await;
+    run_invalid_link_test(
+        &alice_cell_id,
+        &bob_cell_id,
         &conductors,
         &dna_file,
+        expected_count,
     )
     .await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn app_validation_workflow_remove_link_test() {
+    let (conductors, alice_cell_id, bob_cell_id, dna_file) = setup().await;
+    let expected_count = run_valid_test(&alice_cell_id, &bob_cell_id, &conductors).await;
+    run_invalid_remove_link_test(
+        &alice_cell_id,
+        &bob_cell_id,
+        &conductors,
+        &dna_file,
+        expected_count,
+    )
+    .await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn app_validation_workflow_entry_def_id_test() {
+    let (conductors, alice_cell_id, bob_cell_id, dna_file) = setup().await;
+    let expected_count = run_valid_test(&alice_cell_id, &bob_cell_id, &conductors).await;
     run_test_entry_def_id(
-        alice_cell_id,
-        bob_cell_id,
+        &alice_cell_id,
+        &bob_cell_id,
         &conductors,
         &dna_file,
         expected_count,
@@ -63,6 +108,87 @@ async fn app_validation_workflow_test() {
     .await;
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn app_validation_workflow_rejects_forged_warrant_test() {
+    let (conductors, alice_cell_id, bob_cell_id, _dna_file) = setup().await;
+    let expected_count = run_valid_test(&alice_cell_id, &bob_cell_id, &conductors).await;
+    let alice_db = conductors[0].get_dht_db(alice_cell_id.dna_hash()).unwrap();
+
+    // Someone who never validated bob's latest action warrants him for it anyway.
+    let SignedAction(action, signature) = fresh_reader_test(alice_db.clone(), |txn| {
+        let blob = txn
+            .query_row(
+                "SELECT blob FROM Action WHERE author = :author ORDER BY seq DESC LIMIT 1",
+                named_params! { ":author": bob_cell_id.agent_pubkey() },
+                |row| row.get(0),
+            )
+            .unwrap();
+        from_blob::<SignedAction>(blob).unwrap()
+    });
+    let action_hash = ActionHash::with_data_sync(&action);
+    let keystore = conductors[0].keystore();
+    let forger = keystore.new_sign_keypair_random().await.unwrap();
+    let warrant = Warrant::new(
+        action_hash.clone(),
+        bob_cell_id.agent_pubkey().clone(),
+        "forged".into(),
+        forger.clone(),
+        Timestamp::now(),
+    );
+    let warrant_signature = forger.sign(&keystore, &warrant).await.unwrap();
+    conductors[0]
+        .spaces
+        .handle_publish(
+            alice_cell_id.dna_hash(),
+            false,
+            false,
+            vec![DhtOp::RegisterWarrant(
+                signature,
+                action,
+                Box::new(SignedWarrant(warrant, warrant_signature)),
+            )],
+        )
+        .await
+        .unwrap();
+
+    let expected_count = expected_count + 1;
+    wait_for_integration(&alice_db, expected_count, NUM_ATTEMPTS, DELAY_PER_ATTEMPT).await;
+
+    fresh_reader_test(alice_db, |txn| {
+        let limbo = show_limbo(&txn);
+        assert!(limbo_is_empty(&txn), "{:?}", limbo);
+
+        // The warrant is rejected and alice doesn't warrant bob either.
+        let rejected: usize = txn
+            .query_row(
+                "
+                SELECT count(DhtOp.hash) FROM DhtOp
+                JOIN Warrant ON DhtOp.hash = Warrant.hash
+                WHERE DhtOp.type = :register_warrant AND DhtOp.validation_status = :rejected
+                    AND Warrant.action_hash = :action_hash AND Warrant.warrantor = :forger
+                ",
+                named_params! {
+                    ":register_warrant": DhtOpType::RegisterWarrant,
+                    ":rejected": ValidationStatus::Rejected,
+                    ":action_hash": action_hash,
+                    ":forger": forger,
+                },
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(rejected, 1);
+        assert!(!expected_warrant(
+            &txn,
+            &action_hash,
+            bob_cell_id.agent_pubkey(),
+            alice_cell_id.agent_pubkey()
+        ));
+        assert_eq!(num_valid(&txn), expected_count - 1);
+    });
+    let bob = BlockTarget::Agent(bob_cell_id.agent_pubkey().clone());
+    assert!(!conductors[0].spaces.is_blocked(&bob));
+}
+
 const SELECT: &'static str = "SELECT count(hash) FROM DhtOp WHERE";
 
 // These are the expected invalid ops
@@ -145,6 +271,35 @@ fn expected_invalid_remove_link(txn: &Transaction, invalid_remove_hash: &ActionH
     count == 1
 }
 
+// The validator has warranted the author of an invalid action
+fn expected_warrant(
+    txn: &Transaction,
+    invalid_action_hash: &ActionHash,
+    author: &AgentPubKey,
+    warrantor: &AgentPubKey,
+) -> bool {
+    let count: usize = txn
+        .query_row(
+            "
+            SELECT count(DhtOp.hash) FROM DhtOp
+            JOIN Warrant ON DhtOp.hash = Warrant.hash
+            WHERE DhtOp.type = :register_warrant AND DhtOp.validation_status = :valid
+                AND Warrant.action_hash = :invalid_action_hash
+                AND Warrant.author = :author AND Warrant.warrantor = :warrantor
+            ",
+            named_params! {
+                ":register_warrant": DhtOpType::RegisterWarrant,
+                ":valid": ValidationStatus::Valid,
+                ":invalid_action_hash": invalid_action_hash,
+                ":author": author,
+                ":warrantor": warrantor,
+            },
+            |row| row.get(0),
+        )
+        .unwrap();
+    count == 1
+}
+
 fn limbo_is_empty(txn: &Transaction) -> bool {
     let not_empty: bool = txn
         .query_row(
@@ -188,30 +343,28 @@ fn num_valid(txn: &Transaction) -> usize {
             .unwrap()
 }
 
-async fn run_test(
-    alice_cell_id: CellId,
-    bob_cell_id: CellId,
+// Check if the correct number of ops are integrated
+// every 100 ms for a maximum of 10 seconds but early exit
+// if they are there.
+const NUM_ATTEMPTS: usize = 100;
+const DELAY_PER_ATTEMPT: Duration = Duration::from_millis(100);
+
+/// Bob commits something valid, returning how many ops alice then has.
+async fn run_valid_test(
+    alice_cell_id: &CellId,
+    bob_cell_id: &CellId,
     conductors: &SweetConductorBatch,
-    dna_file: &DnaFile,
 ) -> usize {
-    // Check if the correct number of ops are integrated
-    // every 100 ms for a maximum of 10 seconds but early exit
-    // if they are there.
-    let num_attempts = 100;
-    let delay_per_attempt = Duration::from_millis(100);
-
     let invocation =
-        new_zome_call(&bob_cell_id, "always_validates", (), TestWasm::Validate).unwrap();
+        new_zome_call(bob_cell_id, "always_validates", (), TestWasm::Validate).unwrap();
     conductors[1].call_zome(invocation).await.unwrap().unwrap();
 
     // Integration should have 3 ops in it
     // Plus another 16 for genesis + init
     // Plus 2 for Cap Grant
     let expected_count = 3 + 16 + 2;
-    let alice_db = conductors[0].get_dht_db(&alice_cell_id.dna_hash()).unwrap();
-    wait_for_integration(&alice_db, expected_count, num_attempts, delay_per_attempt).await;
-
-    let alice_db = conductors[0].get_dht_db(&alice_cell_id.dna_hash()).unwrap();
+    let alice_db = conductors[0].get_dht_db(alice_cell_id.dna_hash()).unwrap();
+    wait_for_integration(&alice_db, expected_count, NUM_ATTEMPTS, DELAY_PER_ATTEMPT).await;
 
     fresh_reader_test(alice_db, |txn| {
         // Validation should be empty
@@ -220,17 +373,27 @@ async fn run_test(
 
         assert_eq!(num_valid(&txn), expected_count);
     });
+    expected_count
+}
 
+async fn run_invalid_entry_test(
+    alice_cell_id: &CellId,
+    bob_cell_id: &CellId,
+    conductors: &SweetConductorBatch,
+    dna_file: &DnaFile,
+    expected_count: usize,
+) {
     let (invalid_action_hash, invalid_entry_hash) =
-        commit_invalid(&bob_cell_id, &conductors[1].handle(), dna_file).await;
+        commit_invalid(bob_cell_id, &conductors[1].handle(), dna_file).await;
     let invalid_entry_hash: AnyDhtHash = invalid_entry_hash.into();
 
     // Integration should have 3 ops in it
     // StoreEntry should be invalid.
     // RegisterAgentActivity will be valid.
-    let expected_count = 3 + expected_count;
-    let alice_db = conductors[0].get_dht_db(&alice_cell_id.dna_hash()).unwrap();
-    wait_for_integration(&alice_db, expected_count, num_attempts, delay_per_attempt).await;
+    // Plus 1 for the warrant alice issues against bob.
+    let expected_count = 3 + 1 + expected_count;
+    let alice_db = conductors[0].get_dht_db(alice_cell_id.dna_hash()).unwrap();
+    wait_for_integration(&alice_db, expected_count, NUM_ATTEMPTS, DELAY_PER_ATTEMPT).await;
 
     fresh_reader_test(alice_db, |txn| {
         // Validation should be empty
@@ -242,129 +405,144 @@ async fn run_test(
             &invalid_action_hash,
             &invalid_entry_hash
         ));
+        assert!(expected_warrant(
+            &txn,
+            &invalid_action_hash,
+            bob_cell_id.agent_pubkey(),
+            alice_cell_id.agent_pubkey()
+        ));
         // Expect having one invalid op for the store entry.
         assert_eq!(num_valid(&txn), expected_count - 1);
     });
+    // Alice's conductor has added bob to its blocklist.
+    let bob = BlockTarget::Agent(bob_cell_id.agent_pubkey().clone());
+    assert!(conductors[0].spaces.is_blocked(&bob));
+}
 
+async fn run_invalid_link_test(
+    alice_cell_id: &CellId,
+    bob_cell_id: &CellId,
+    conductors: &SweetConductorBatch,
+    dna_file: &DnaFile,
+    expected_count: usize,
+) {
     let invocation =
-        new_zome_call(&bob_cell_id, "add_valid_link", (), TestWasm::ValidateLink).unwrap();
+        new_zome_call(bob_cell_id, "add_valid_link", (), TestWasm::ValidateLink).unwrap();
     conductors[1].call_zome(invocation).await.unwrap().unwrap();
 
     // Integration should have 6 ops in it
     let expected_count = 6 + expected_count;
-    let alice_db = conductors[0].get_dht_db(&alice_cell_id.dna_hash()).unwrap();
-    wait_for_integration(&alice_db, expected_count, num_attempts, delay_per_attempt).await;
+    let alice_db = conductors[0].get_dht_db(alice_cell_id.dna_hash()).unwrap();
+    wait_for_integration(&alice_db, expected_count, NUM_ATTEMPTS, DELAY_PER_ATTEMPT).await;
 
     fresh_reader_test(alice_db, |txn| {
         // Validation should be empty
         let limbo = show_limbo(&txn);
         assert!(limbo_is_empty(&txn), "{:?}", limbo);
 
-        assert!(expected_invalid_entry(
-            &txn,
-            &invalid_action_hash,
-            &invalid_entry_hash
-        ));
-        // Expect having one invalid op for the store entry.
-        assert_eq!(num_valid(&txn), expected_count - 1);
+        assert_eq!(num_valid(&txn), expected_count);
     });
 
     let invocation = new_invocation(
-        &bob_cell_id,
+        bob_cell_id,
         "add_invalid_link",
         (),
         TestWasm::ValidateLink.coordinator_zome(),
     )
     .unwrap();
     let invalid_link_hash: ActionHash =
-        call_zome_directly(&bob_cell_id, &conductors[1].handle(), dna_file, invocation)
+        call_zome_directly(bob_cell_id, &conductors[1].handle(), dna_file, invocation)
             .await
             .decode()
             .unwrap();
 
     // Integration should have 9 ops in it
-    let expected_count = 9 + expected_count;
-    let alice_db = conductors[0].get_dht_db(&alice_cell_id.dna_hash()).unwrap();
-    wait_for_integration(&alice_db, expected_count, num_attempts, delay_per_attempt).await;
+    // Plus 1 for the warrant alice issues against bob.
+    let expected_count = 9 + 1 + expected_count;
+    let alice_db = conductors[0].get_dht_db(alice_cell_id.dna_hash()).unwrap();
+    wait_for_integration(&alice_db, expected_count, NUM_ATTEMPTS, DELAY_PER_ATTEMPT).await;
 
     fresh_reader_test(alice_db, |txn| {
         // Validation should be empty
         let limbo = show_limbo(&txn);
         assert!(limbo_is_empty(&txn), "{:?}", limbo);
 
-        assert!(expected_invalid_entry(
+        assert!(expected_invalid_link(&txn, &invalid_link_hash));
+        assert!(expected_warrant(
             &txn,
-            &invalid_action_hash,
-            &invalid_entry_hash
+            &invalid_link_hash,
+            bob_cell_id.agent_pubkey(),
+            alice_cell_id.agent_pubkey()
         ));
-        assert!(expected_invalid_link(&txn, &invalid_link_hash));
-        // Expect having two invalid ops for the two store entries.
-        assert_eq!(num_valid(&txn), expected_count - 2);
+        // Expect having one invalid op for the link.
+        assert_eq!(num_valid(&txn), expected_count - 1);
     });
+}
 
+async fn run_invalid_remove_link_test(
+    alice_cell_id: &CellId,
+    bob_cell_id: &CellId,
+    conductors: &SweetConductorBatch,
+    dna_file: &DnaFile,
+    expected_count: usize,
+) {
     let invocation = new_invocation(
-        &bob_cell_id,
+        bob_cell_id,
         "remove_valid_link",
         (),
         TestWasm::ValidateLink.coordinator_zome(),
     )
     .unwrap();
-    call_zome_directly(&bob_cell_id, &conductors[1].handle(), dna_file, invocation).await;
+    call_zome_directly(bob_cell_id, &conductors[1].handle(), dna_file, invocation).await;
 
     // Integration should have 9 ops in it
     let expected_count = 9 + expected_count;
-    let alice_db = conductors[0].get_dht_db(&alice_cell_id.dna_hash()).unwrap();
-    wait_for_integration(&alice_db, expected_count, num_attempts, delay_per_attempt).await;
+    let alice_db = conductors[0].get_dht_db(alice_cell_id.dna_hash()).unwrap();
+    wait_for_integration(&alice_db, expected_count, NUM_ATTEMPTS, DELAY_PER_ATTEMPT).await;
 
     fresh_reader_test(alice_db, |txn| {
         // Validation should be empty
         let limbo = show_limbo(&txn);
         assert!(limbo_is_empty(&txn), "{:?}", limbo);
 
-        assert!(expected_invalid_entry(
-            &txn,
-            &invalid_action_hash,
-            &invalid_entry_hash
-        ));
-        assert!(expected_invalid_link(&txn, &invalid_link_hash));
-        // Expect having two invalid ops for the two store entries.
-        assert_eq!(num_valid(&txn), expected_count - 2);
+        assert_eq!(num_valid(&txn), expected_count);
     });
 
     let invocation = new_invocation(
-        &bob_cell_id,
+        bob_cell_id,
         "remove_invalid_link",
         (),
         TestWasm::ValidateLink.coordinator_zome(),
     )
     .unwrap();
     let invalid_remove_hash: ActionHash =
-        call_zome_directly(&bob_cell_id, &conductors[1].handle(), dna_file, invocation)
+        call_zome_directly(bob_cell_id, &conductors[1].handle(), dna_file, invocation)
             .await
             .decode()
             .unwrap();
 
     // Integration should have 12 ops in it
-    let expected_count = 12 + expected_count;
-    let alice_db = conductors[0].get_dht_db(&alice_cell_id.dna_hash()).unwrap();
-    wait_for_integration(&alice_db, expected_count, num_attempts, delay_per_attempt).await;
+    // Plus 2 for the warrants alice issues against bob,
+    // one for the invalid link and one for removing it.
+    let expected_count = 12 + 2 + expected_count;
+    let alice_db = conductors[0].get_dht_db(alice_cell_id.dna_hash()).unwrap();
+    wait_for_integration(&alice_db, expected_count, NUM_ATTEMPTS, DELAY_PER_ATTEMPT).await;
 
     fresh_reader_test(alice_db, |txn| {
         // Validation should be empty
         let limbo = show_limbo(&txn);
         assert!(limbo_is_empty(&txn), "{:?}", limbo);
 
-        assert!(expected_invalid_entry(
+        assert!(expected_invalid_remove_link(&txn, &invalid_remove_hash));
+        assert!(expected_warrant(
             &txn,
-            &invalid_action_hash,
-            &invalid_entry_hash
+            &invalid_remove_hash,
+            bob_cell_id.agent_pubkey(),
+            alice_cell_id.agent_pubkey()
         ));
-        assert!(expected_invalid_link(&txn, &invalid_link_hash));
-        assert!(expected_invalid_remove_link(&txn, &invalid_remove_hash));
-        // 3 invalid ops above plus 1 extra invalid ops that `remove_invalid_link` commits.
-        assert_eq!(num_valid(&txn), expected_count - (3 + 1));
+        // The invalid op above plus the invalid link that `remove_invalid_link` commits.
+        assert_eq!(num_valid(&txn), expected_count - 2);
     });
-    expected_count
 }
 
 /// 1. Commits an entry with validate_create_entry_<EntryDefId> callback
@@ -372,27 +550,22 @@ async fn run_test(
 /// 3. Reject only Post with "Banana" as the String to show it doesn't
 ///    affect other entries.
 async fn run_test_entry_def_id(
-    alice_cell_id: CellId,
-    bob_cell_id: CellId,
+    alice_cell_id: &CellId,
+    bob_cell_id: &CellId,
     conductors: &SweetConductorBatch,
     dna_file: &DnaFile,
     expected_count: usize,
 ) {
-    // Check if the correct number of ops are integrated
-    // every 100 ms for a maximum of 10 seconds but early exit
-    // if they are there.
-    let num_attempts = 100;
-    let delay_per_attempt = Duration::from_millis(100);
-
     let (invalid_action_hash, invalid_entry_hash) =
-        commit_invalid_post(&bob_cell_id, &conductors[1].handle(), dna_file).await;
+        commit_invalid_post(bob_cell_id, &conductors[1].handle(), dna_file).await;
     let invalid_entry_hash: AnyDhtHash = invalid_entry_hash.into();
 
     // Integration should have 3 ops in it
     // StoreEntry and StoreRecord should be invalid.
-    let expected_count = 3 + expected_count;
-    let alice_db = conductors[0].get_dht_db(&alice_cell_id.dna_hash()).unwrap();
-    wait_for_integration(&alice_db, expected_count, num_attempts, delay_per_attempt).await;
+    // Plus 1 for the warrant alice issues against bob.
+    let expected_count = 3 + 1 + expected_count;
+    let alice_db = conductors[0].get_dht_db(alice_cell_id.dna_hash()).unwrap();
+    wait_for_integration(&alice_db, expected_count, NUM_ATTEMPTS, DELAY_PER_ATTEMPT).await;
 
     fresh_reader_test(alice_db, |txn| {
         // Validation should be empty
@@ -404,8 +577,14 @@ async fn run_test_entry_def_id(
             &invalid_action_hash,
             &invalid_entry_hash
         ));
-        // Expect having two invalid ops for the two store entries plus the 3 from the previous test.
-        assert_eq!(num_valid(&txn), expected_count - 5);
+        assert!(expected_warrant(
+            &txn,
+            &invalid_action_hash,
+            bob_cell_id.agent_pubkey(),
+            alice_cell_id.agent_pubkey()
+        ));
+        // Expect having two invalid ops for the two store entries.
+        assert_eq!(num_valid(&txn), expected_count - 2);
     });
 }
 
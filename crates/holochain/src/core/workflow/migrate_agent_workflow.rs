@@ -0,0 +1,222 @@
+use super::error::WorkflowResult;
+use crate::conductor::ConductorHandle;
+use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentHostAccess;
+use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentInvocation;
+use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentResult;
+use crate::core::ribosome::RibosomeT;
+use derive_more::Constructor;
+use holochain_p2p::HolochainP2pDna;
+use holochain_state::host_fn_workspace::HostFnWorkspace;
+use holochain_state::host_fn_workspace::SourceChainWorkspace;
+use holochain_types::prelude::*;
+use holochain_zome_types::action::builder;
+use tracing::*;
+
+#[derive(Constructor)]
+pub struct MigrateAgentWorkflowArgs<Ribosome>
+where
+    Ribosome: RibosomeT + 'static,
+{
+    pub ribosome: Ribosome,
+    pub conductor_handle: ConductorHandle,
+    pub migrate_agent: MigrateAgent,
+    /// The DNA on the other side of the migration, i.e. the DNA being
+    /// migrated to when closing a chain, or from when opening one.
+    pub other_dna_hash: DnaHash,
+}
+
+/// Run the `migrate_agent` callback and, if every zome passes, close or open
+/// the source chain with a `CloseChain` or `OpenChain` action.
+#[instrument(skip(network, workspace, args))]
+pub async fn migrate_agent_workflow<Ribosome>(
+    workspace: SourceChainWorkspace,
+    network: HolochainP2pDna,
+    args: MigrateAgentWorkflowArgs<Ribosome>,
+) -> WorkflowResult<MigrateAgentResult>
+where
+    Ribosome: RibosomeT + Clone + 'static,
+{
+    let result = stage_migrate_agent_workflow(workspace.clone(), network.clone(), args).await?;
+
+    // --- END OF WORKFLOW, BEGIN FINISHER BOILERPLATE ---
+
+    // only commit if the result was successful
+    if result == MigrateAgentResult::Pass {
+        HostFnWorkspace::from(workspace).flush(&network).await?;
+    }
+    Ok(result)
+}
+
+/// Run the `migrate_agent` callback and, if every zome passes, validate a
+/// `CloseChain` or `OpenChain` action in the workspace's scratch without
+/// committing it. Dropping the workspace abandons the migration.
+pub async fn stage_migrate_agent_workflow<Ribosome>(
+    workspace: SourceChainWorkspace,
+    network: HolochainP2pDna,
+    args: MigrateAgentWorkflowArgs<Ribosome>,
+) -> WorkflowResult<MigrateAgentResult>
+where
+    Ribosome: RibosomeT + 'static,
+{
+    let MigrateAgentWorkflowArgs {
+        ribosome,
+        conductor_handle,
+        migrate_agent,
+        other_dna_hash,
+    } = args;
+    let dna_def = ribosome.dna_def().as_content().clone();
+
+    // Call the migrate_agent callback
+    let result = {
+        let host_access = MigrateAgentHostAccess::new(workspace.clone().into());
+        let invocation = MigrateAgentInvocation::new(dna_def, migrate_agent.clone());
+        ribosome.run_migrate_agent(host_access, invocation)?
+    };
+    if result != MigrateAgentResult::Pass {
+        return Ok(result);
+    }
+
+    // Declare the migration on the chain
+    let ws = workspace.clone();
+    tokio::task::spawn(async move {
+        let source_chain = ws.source_chain();
+        match migrate_agent {
+            MigrateAgent::Close => {
+                source_chain
+                    .put(
+                        builder::CloseChain {
+                            new_dna_hash: other_dna_hash,
+                        },
+                        None,
+                        ChainTopOrdering::Strict,
+                    )
+                    .await
+            }
+            MigrateAgent::Open => {
+                source_chain
+                    .put(
+                        builder::OpenChain {
+                            prev_dna_hash: other_dna_hash,
+                        },
+                        None,
+                        ChainTopOrdering::Strict,
+                    )
+                    .await
+            }
+        }
+    })
+    .await??;
+
+    super::inline_validation(workspace, network, conductor_handle, ribosome).await?;
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conductor::handle::MockConductorHandleT;
+    use crate::core::ribosome::guest_callback::validate::ValidateResult;
+    use crate::core::ribosome::MockRibosomeT;
+    use crate::fixt::DnaDefFixturator;
+    use crate::test_utils::fake_genesis;
+    use ::fixt::prelude::*;
+    use fixt::Unpredictable;
+    use holochain_p2p::HolochainP2pDnaFixturator;
+    use holochain_state::prelude::test_authored_db;
+    use holochain_state::prelude::test_cache_db;
+    use holochain_state::prelude::test_dht_db;
+    use holochain_types::db_cache::DhtDbQueryCache;
+    use holochain_types::prelude::DnaDefHashed;
+    use holochain_zome_types::fake_agent_pubkey_1;
+    use holochain_zome_types::Action;
+    use matches::assert_matches;
+    use std::sync::Arc;
+
+    async fn test_workspace() -> (SourceChainWorkspace, DnaDefHashed) {
+        let test_db = test_authored_db();
+        let test_cache = test_cache_db();
+        let test_dht = test_dht_db();
+        let keystore = test_keystore();
+        let db = test_db.to_db();
+
+        fake_genesis(db.clone(), test_dht.to_db(), keystore.clone())
+            .await
+            .unwrap();
+
+        let dna_def = DnaDefFixturator::new(Unpredictable).next().unwrap();
+        let dna_def_hashed = DnaDefHashed::from_content_sync(dna_def.clone());
+        let workspace = SourceChainWorkspace::new(
+            db,
+            test_dht.to_db(),
+            DhtDbQueryCache::new(test_dht.to_db().into()),
+            test_cache.to_db(),
+            keystore,
+            fake_agent_pubkey_1(),
+            Arc::new(dna_def),
+        )
+        .await
+        .unwrap();
+        (workspace, dna_def_hashed)
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn closes_chain_when_migration_passes() {
+        let (workspace, dna_def_hashed) = test_workspace().await;
+        let new_dna_hash = fixt!(DnaHash);
+
+        let mut ribosome = MockRibosomeT::new();
+        ribosome
+            .expect_run_migrate_agent()
+            .returning(|_, _| Ok(MigrateAgentResult::Pass));
+        ribosome
+            .expect_run_validate()
+            .returning(|_, _| Ok(ValidateResult::Valid));
+        ribosome.expect_dna_def().return_const(dna_def_hashed);
+
+        let args = MigrateAgentWorkflowArgs {
+            ribosome,
+            conductor_handle: Arc::new(MockConductorHandleT::new()),
+            migrate_agent: MigrateAgent::Close,
+            other_dna_hash: new_dna_hash.clone(),
+        };
+        let result = stage_migrate_agent_workflow(workspace.clone(), fixt!(HolochainP2pDna), args)
+            .await
+            .unwrap();
+        assert_eq!(result, MigrateAgentResult::Pass);
+
+        let scratch = workspace.source_chain().snapshot().unwrap();
+        assert_matches!(
+            scratch.actions().next().unwrap().action(),
+            Action::CloseChain(CloseChain { new_dna_hash: hash, .. }) if *hash == new_dna_hash
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn leaves_chain_open_when_migration_fails() {
+        let (workspace, dna_def_hashed) = test_workspace().await;
+
+        let mut ribosome = MockRibosomeT::new();
+        ribosome.expect_run_migrate_agent().returning(|_, _| {
+            Ok(MigrateAgentResult::Fail(
+                "zome".into(),
+                "not ready to migrate".into(),
+            ))
+        });
+        ribosome.expect_dna_def().return_const(dna_def_hashed);
+
+        let args = MigrateAgentWorkflowArgs {
+            ribosome,
+            conductor_handle: Arc::new(MockConductorHandleT::new()),
+            migrate_agent: MigrateAgent::Close,
+            other_dna_hash: fixt!(DnaHash),
+        };
+        let result = stage_migrate_agent_workflow(workspace.clone(), fixt!(HolochainP2pDna), args)
+            .await
+            .unwrap();
+        assert_matches!(result, MigrateAgentResult::Fail(_, _));
+
+        let scratch = workspace.source_chain().snapshot().unwrap();
+        assert!(scratch.actions().next().is_none());
+    }
+}
@@ -25,6 +25,7 @@ use holochain_types::prelude::*;
 use holochain_zome_types::Entry;
 use holochain_zome_types::ValidationStatus;
 use rusqlite::Transaction;
+use std::collections::HashMap;
 use std::convert::TryInto;
 use std::sync::Arc;
 use tracing::*;
@@ -286,6 +287,7 @@ fn handle_failed(error: ValidationOutcome) -> Outcome {
         ValidationOutcome::EntryTooLarge(_, _) => Rejected,
         ValidationOutcome::EntryType => Rejected,
         ValidationOutcome::EntryVisibility(_) => Rejected,
+        ValidationOutcome::InvalidWarrant(_) => Rejected,
         ValidationOutcome::TagTooLarge(_, _) => Rejected,
         ValidationOutcome::NotCreateLink(_) => Rejected,
         ValidationOutcome::NotNewEntry(_) => Rejected,
@@ -294,6 +296,8 @@ fn handle_failed(error: ValidationOutcome) -> Outcome {
             AwaitingOpDep(dep.into())
         }
         ValidationOutcome::PrevActionError(_) => Rejected,
+        ValidationOutcome::RateLimitExceeded(_, _) => Rejected,
+        ValidationOutcome::RateWeight(_) => Rejected,
         ValidationOutcome::PrivateEntry => Rejected,
         ValidationOutcome::PreflightResponseSignature(_) => Rejected,
         ValidationOutcome::UpdateTypeMismatch(_, _) => Rejected,
@@ -313,6 +317,13 @@ async fn validate_op_inner(
     match op {
         DhtOp::StoreRecord(_, action, entry) => {
             store_record(action, workspace, network.clone()).await?;
+            check_rate_weight(
+                action,
+                entry.as_deref(),
+                workspace.dna_hash(),
+                conductor_handle,
+            )
+            .await?;
             if let Some(entry) = entry {
                 // Retrieve for all other actions on countersigned entry.
                 if let Entry::CounterSign(session_data, _) = &**entry {
@@ -379,9 +390,17 @@ async fn validate_op_inner(
 
             let action = action.clone().into();
             store_record(&action, workspace, network).await?;
+            check_rate_weight(
+                &action,
+                Some(entry.as_ref()),
+                workspace.dna_hash(),
+                conductor_handle,
+            )
+            .await?;
             Ok(())
         }
         DhtOp::RegisterAgentActivity(_, action) => {
+            check_rate_weight(action, None, workspace.dna_hash(), conductor_handle).await?;
             register_agent_activity(action, workspace, network.clone(), incoming_dht_ops_sender)
                 .await?;
             store_record(action, workspace, network).await?;
@@ -390,6 +409,13 @@ async fn validate_op_inner(
         DhtOp::RegisterUpdatedContent(_, action, entry) => {
             register_updated_content(action, workspace, network.clone(), incoming_dht_ops_sender)
                 .await?;
+            check_rate_weight(
+                &Action::Update(action.clone()),
+                entry.as_deref(),
+                workspace.dna_hash(),
+                conductor_handle,
+            )
+            .await?;
             if let Some(entry) = entry {
                 store_entry(
                     NewEntryActionRef::Update(action),
@@ -406,6 +432,13 @@ async fn validate_op_inner(
         DhtOp::RegisterUpdatedRecord(_, action, entry) => {
             register_updated_record(action, workspace, network.clone(), incoming_dht_ops_sender)
                 .await?;
+            check_rate_weight(
+                &Action::Update(action.clone()),
+                entry.as_deref(),
+                workspace.dna_hash(),
+                conductor_handle,
+            )
+            .await?;
             if let Some(entry) = entry {
                 store_entry(
                     NewEntryActionRef::Update(action),
@@ -421,21 +454,46 @@ async fn validate_op_inner(
         }
         DhtOp::RegisterDeletedBy(_, action) => {
             register_deleted_by(action, workspace, network, incoming_dht_ops_sender).await?;
+            check_rate_weight(
+                &Action::Delete(action.clone()),
+                None,
+                workspace.dna_hash(),
+                conductor_handle,
+            )
+            .await?;
             Ok(())
         }
         DhtOp::RegisterDeletedEntryAction(_, action) => {
             register_deleted_entry_action(action, workspace, network, incoming_dht_ops_sender)
                 .await?;
+            check_rate_weight(
+                &Action::Delete(action.clone()),
+                None,
+                workspace.dna_hash(),
+                conductor_handle,
+            )
+            .await?;
             Ok(())
         }
         DhtOp::RegisterAddLink(_, action) => {
             register_add_link(action, workspace, network, incoming_dht_ops_sender).await?;
+            check_rate_weight(
+                &Action::CreateLink(action.clone()),
+                None,
+                workspace.dna_hash(),
+                conductor_handle,
+            )
+            .await?;
             Ok(())
         }
         DhtOp::RegisterRemoveLink(_, action) => {
             register_delete_link(action, workspace, network, incoming_dht_ops_sender).await?;
             Ok(())
         }
+        DhtOp::RegisterWarrant(_, action, warrant) => {
+            check_warrant(action, warrant).await?;
+            Ok(())
+        }
     }
 }
 
@@ -490,6 +548,7 @@ async fn sys_validate_record_inner(
     ) -> SysValidationResult<()> {
         let incoming_dht_ops_sender = None;
         store_record(action, workspace, network.clone()).await?;
+        check_rate_limit(action, workspace).await?;
         if let Some((maybe_entry, EntryVisibility::Public)) =
             &maybe_entry.and_then(|e| action.entry_type().map(|et| (e, et.visibility())))
         {
@@ -574,11 +633,12 @@ async fn register_agent_activity(
             workspace,
             network,
             incoming_dht_ops_sender,
-            |_| Ok(()),
+            |prev_record| check_prev_not_closed(prev_record.action()),
         )
         .await?;
     }
     check_chain_rollback(action, workspace).await?;
+    check_rate_limit(action, workspace).await?;
     Ok(())
 }
 
@@ -600,6 +660,7 @@ async fn store_record(
             .ok_or_else(|| ValidationOutcome::DepMissingFromDht(prev_action_hash.clone().into()))?;
         check_prev_timestamp(action, prev_action.action())?;
         check_prev_seq(action, prev_action.action())?;
+        check_prev_not_closed(prev_action.action())?;
     }
     Ok(())
 }
@@ -901,6 +962,45 @@ impl SysValidationWorkspace {
         };
         Ok(!action_seq_is_not_empty)
     }
+    /// Get the actions held for this action's author which come before it
+    /// on their chain, keyed by hash. Forks are included, so callers should
+    /// follow the `prev_action` links from the action to find its chain.
+    pub async fn prior_chain_actions(
+        &self,
+        action: &Action,
+    ) -> SourceChainResult<HashMap<ActionHash, Action>> {
+        let author = action.author().clone();
+        let seq = action.action_seq();
+        let mut actions = match &self.scratch {
+            // We are the author so our own chain is in the authored database.
+            Some(_) => {
+                let author = author.clone();
+                self.authored_db
+                    .async_reader(move |txn| prior_chain_actions_query(&txn, author, seq))
+                    .await?
+            }
+            None => {
+                let author = author.clone();
+                self.dht_db
+                    .async_reader(move |txn| prior_chain_actions_query(&txn, author, seq))
+                    .await?
+            }
+        };
+        if let Some(scratch) = &self.scratch {
+            scratch.apply(|scratch| {
+                actions.extend(
+                    scratch
+                        .actions()
+                        .filter(|shh| {
+                            *shh.action().author() == author && shh.action().action_seq() < seq
+                        })
+                        .map(|shh| (shh.action_address().clone(), shh.action().clone())),
+                );
+            })?;
+        }
+        Ok(actions)
+    }
+
     /// Create a cascade with local data only
     pub fn local_cascade(&self) -> Cascade {
         let cascade = Cascade::empty().with_dht(self.dht_db.clone());
@@ -936,6 +1036,46 @@ impl SysValidationWorkspace {
     }
 }
 
+fn prior_chain_actions_query(
+    txn: &Transaction,
+    author: AgentPubKey,
+    seq: u32,
+) -> StateQueryResult<HashMap<ActionHash, Action>> {
+    let mut stmt = txn.prepare(
+        "
+        SELECT DISTINCT Action.hash, Action.blob
+        FROM Action
+        JOIN DhtOp ON DhtOp.action_hash = Action.hash
+        WHERE
+        Action.author = :author
+        AND
+        Action.seq < :seq
+        AND
+        DhtOp.type = :activity
+        ",
+    )?;
+    let actions = stmt
+        .query_map(
+            named_params! {
+                ":author": author,
+                ":seq": seq,
+                ":activity": DhtOpType::RegisterAgentActivity,
+            },
+            |row| {
+                Ok((
+                    row.get::<_, ActionHash>("hash")?,
+                    row.get::<_, Vec<u8>>("blob")?,
+                ))
+            },
+        )?
+        .map(|row| {
+            let (hash, blob) = row?;
+            Ok((hash, from_blob::<SignedAction>(blob)?.0))
+        })
+        .collect::<StateQueryResult<HashMap<_, _>>>()?;
+    Ok(actions)
+}
+
 fn put_validation_limbo(
     txn: &mut Transaction<'_>,
     hash: &DhtOpHash,
@@ -7,6 +7,7 @@ use holochain_types::dht_op::DhtOpHashed;
 use holochain_types::dht_op::DhtOpType;
 use holochain_zome_types::Entry;
 use holochain_zome_types::SignedAction;
+use holochain_zome_types::SignedWarrant;
 
 pub use crate::core::validation::DhtOpOrder;
 use crate::core::workflow::error::WorkflowResult;
@@ -33,6 +34,7 @@ async fn get_ops_to_validate(
         SELECT
         Action.blob as action_blob,
         Entry.blob as entry_blob,
+        Warrant.blob as warrant_blob,
         DhtOp.type as dht_type,
         DhtOp.hash as dht_hash
         FROM DhtOp
@@ -40,6 +42,8 @@ async fn get_ops_to_validate(
         Action ON DhtOp.action_hash = Action.hash
         LEFT JOIN
         Entry ON Action.entry_hash = Entry.hash
+        LEFT JOIN
+        Warrant ON DhtOp.hash = Warrant.hash
         "
     .to_string();
     if system {
@@ -90,8 +94,13 @@ async fn get_ops_to_validate(
                 Some(entry) => Some(from_blob::<Entry>(entry)?),
                 None => None,
             };
+            let warrant: Option<Vec<u8>> = row.get("warrant_blob")?;
+            let warrant = match warrant {
+                Some(warrant) => Some(from_blob::<SignedWarrant>(warrant)?),
+                None => None,
+            };
             WorkflowResult::Ok(DhtOpHashed::with_pre_hashed(
-                DhtOp::from_type(op_type, action, entry)?,
+                DhtOp::from_type_with_warrant(op_type, action, entry, warrant)?,
                 hash,
             ))
         })?;
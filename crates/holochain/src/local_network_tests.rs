@@ -471,6 +471,7 @@ async fn setup(
                 .map(TestZomes::from)
                 .map(|z| z.coordinator.into_inner())
                 .collect(),
+            rate_limits: Vec::new(),
         },
         zomes.into_iter().map(Into::into),
     )
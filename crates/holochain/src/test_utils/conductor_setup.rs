@@ -191,6 +191,7 @@ impl ConductorTestData {
                     .map(TestZomes::from)
                     .map(|z| z.coordinator.into_inner())
                     .collect(),
+                rate_limits: Vec::new(),
             },
             zomes.into_iter().flat_map(Vec::<DnaWasm>::from),
         )
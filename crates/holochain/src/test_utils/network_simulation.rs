@@ -237,10 +237,13 @@ fn cache_data(in_memory: bool, data: &MockNetworkData, is_cached: bool) -> Conne
         return conn;
     }
     holochain_sqlite::schema::SCHEMA_CELL
-        .initialize(&mut conn, None)
+        .initialize(&mut conn, None, None)
         .unwrap();
+    // Both schemas share this database, so the version of the cell schema
+    // must be cleared for the p2p schema to be initialized too.
+    conn.pragma_update(None, "user_version", &0).unwrap();
     holochain_sqlite::schema::SCHEMA_P2P_STATE
-        .initialize(&mut conn, None)
+        .initialize(&mut conn, None, None)
         .unwrap();
     let mut txn = conn
         .transaction_with_behavior(rusqlite::TransactionBehavior::Exclusive)
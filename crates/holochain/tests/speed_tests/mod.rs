@@ -130,6 +130,7 @@ async fn speed_test(n: Option<usize>) -> Arc<TempDir> {
             origin_time: Timestamp::HOLOCHAIN_EPOCH,
             integrity_zomes: vec![TestZomes::from(TestWasm::Anchor).integrity.into_inner()],
             coordinator_zomes: vec![TestZomes::from(TestWasm::Anchor).coordinator.into_inner()],
+            rate_limits: Vec::new(),
         },
         vec![TestWasm::Anchor.into()],
     )
@@ -413,6 +413,64 @@ async fn list_app_interfaces_succeeds() -> Result<()> {
     Ok(())
 }
 
+#[cfg(unix)]
+#[tokio::test(flavor = "multi_thread")]
+async fn unix_socket_interfaces() -> Result<()> {
+    use holochain_conductor_api::AdminInterfaceConfig;
+    use holochain_conductor_api::AppRequest;
+    use holochain_conductor_api::AppResponse;
+    use holochain_conductor_api::InterfaceDriver;
+    use std::os::unix::fs::PermissionsExt;
+    observability::test_run().ok();
+
+    let tmp_dir = TempDir::new("conductor_cfg").unwrap();
+    let admin_path = tmp_dir.path().join("admin.sock");
+    let app_path = tmp_dir.path().join("app.sock");
+    let mut config = create_config(0, tmp_dir.path().to_path_buf());
+    config.admin_interfaces = Some(vec![AdminInterfaceConfig {
+        driver: InterfaceDriver::UnixSocket {
+            path: admin_path.clone(),
+            mode: 0o600,
+        },
+    }]);
+    let conductor_handle = Conductor::builder().config(config).build().await?;
+    assert_eq!(conductor_handle.get_arbitrary_admin_websocket_port(), None);
+    let mode = std::fs::metadata(&admin_path)?.permissions().mode();
+    assert_eq!(mode & 0o777, 0o600);
+
+    let (mut admin, _) = holochain_websocket::connect(
+        unix_socket_url(&admin_path)?,
+        Arc::new(WebsocketConfig::default()),
+    )
+    .await?;
+    let request = AdminRequest::AttachAppInterfaceUnixSocket {
+        path: app_path.clone(),
+        mode: Some(0o660),
+    };
+    let response: AdminResponse = admin.request(request).await?;
+    assert_matches!(response, AdminResponse::AppInterfaceAttachedUnixSocket { path } if path == app_path);
+    let mode = std::fs::metadata(&app_path)?.permissions().mode();
+    assert_eq!(mode & 0o777, 0o660);
+
+    // Unix socket interfaces have no port to list.
+    let response: AdminResponse = admin.request(AdminRequest::ListAppInterfaces).await?;
+    assert_matches!(response, AdminResponse::AppInterfacesListed(interfaces) if interfaces.is_empty());
+
+    let (mut app, _) = holochain_websocket::connect(
+        unix_socket_url(&app_path)?,
+        Arc::new(WebsocketConfig::default()),
+    )
+    .await?;
+    let request = AppRequest::AppInfo {
+        installed_app_id: "no such app".to_string(),
+    };
+    let response: AppResponse = app.request(request).await?;
+    assert_matches!(response, AppResponse::AppInfo(None));
+
+    conductor_handle.shutdown();
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn conductor_admin_interface_ends_with_shutdown() -> Result<()> {
     if let Err(e) = conductor_admin_interface_ends_with_shutdown_inner().await {
@@ -22,18 +22,29 @@ fn merge_hashes(
     let mut valid = HashSet::new();
     let mut rejected = HashSet::new();
     let mut merged_highest_observed = None;
+    let mut merged_warranted: Option<ChainHead> = None;
+    let mut merged_warrants = HashSet::new();
     for result in results {
         let AgentActivityResponse {
             agent: the_agent,
             highest_observed,
             valid_activity,
             rejected_activity,
-            ..
+            status,
+            warrants,
         } = result;
         if the_agent != agent {
             continue;
         }
 
+        merged_warrants.extend(warrants);
+        if let ChainStatus::Warranted(c) = status {
+            merged_warranted = match merged_warranted.take() {
+                Some(a) if a.action_seq < c.action_seq => Some(a),
+                _ => Some(c),
+            };
+        }
+
         match (merged_highest_observed.take(), highest_observed) {
             (None, None) => {}
             (Some(h), None) | (None, Some(h)) => {
@@ -68,6 +79,8 @@ fn merge_hashes(
     }
 
     let (status, valid, rejected) = compute_chain_status(valid, rejected);
+    // A warrant against the author overrides anything we can tell from the hashes.
+    let status = merged_warranted.map_or(status, ChainStatus::Warranted);
     let valid_activity = if options.include_valid_activity {
         ChainItems::Hashes(valid)
     } else {
@@ -84,6 +97,7 @@ fn merge_hashes(
         valid_activity,
         rejected_activity,
         highest_observed: merged_highest_observed,
+        warrants: merged_warrants.into_iter().collect(),
     }
 }
 
@@ -147,16 +161,19 @@ fn merge_status_only(
 ) -> AgentActivityResponse<ActionHash> {
     let mut merged_status = None;
     let mut merged_highest_observed = None;
+    let mut merged_warrants = HashSet::new();
     for result in results {
         let AgentActivityResponse {
             status,
             agent: the_agent,
             highest_observed,
+            warrants,
             ..
         } = result;
         if the_agent != agent {
             continue;
         }
+        merged_warrants.extend(warrants);
         match (merged_highest_observed.take(), highest_observed) {
             (None, None) => {}
             (Some(h), None) | (None, Some(h)) => {
@@ -169,6 +186,14 @@ fn merge_status_only(
         }
         match merged_status.take() {
             Some(last) => match (status, last) {
+                (ChainStatus::Warranted(a), ChainStatus::Warranted(b)) => {
+                    let c = if a.action_seq < b.action_seq { a } else { b };
+                    merged_status = Some(ChainStatus::Warranted(c));
+                }
+                // A warranted chain is warranted no matter what else was observed.
+                (ChainStatus::Warranted(c), _) | (_, ChainStatus::Warranted(c)) => {
+                    merged_status = Some(ChainStatus::Warranted(c));
+                }
                 (ChainStatus::Empty, ChainStatus::Empty) => {
                     merged_status = Some(ChainStatus::Empty);
                 }
@@ -234,5 +259,6 @@ fn merge_status_only(
         valid_activity: ChainItems::NotRequested,
         rejected_activity: ChainItems::NotRequested,
         highest_observed: merged_highest_observed,
+        warrants: merged_warrants.into_iter().collect(),
     }
 }
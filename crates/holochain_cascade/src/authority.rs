@@ -1,6 +1,7 @@
 use self::get_agent_activity_query::hashes::GetAgentActivityQuery;
 use self::get_entry_ops_query::GetEntryOpsQuery;
 use self::get_links_ops_query::GetLinksOpsQuery;
+use self::get_meta_query::GetMetaQuery;
 use self::{
     get_agent_activity_query::deterministic::DeterministicGetAgentActivityQuery,
     get_record_query::GetRecordOpsQuery,
@@ -9,6 +10,8 @@ use self::{
 use super::error::CascadeResult;
 use holo_hash::ActionHash;
 use holo_hash::AgentPubKey;
+use holo_hash::AnyDhtHash;
+use holochain_state::query::link_details::GetLinkDetailsQuery;
 use holochain_state::query::Query;
 use holochain_state::query::Txn;
 use holochain_types::prelude::*;
@@ -21,6 +24,7 @@ mod test;
 pub(crate) mod get_agent_activity_query;
 pub(crate) mod get_entry_ops_query;
 pub(crate) mod get_links_ops_query;
+pub(crate) mod get_meta_query;
 pub(crate) mod get_record_query;
 
 #[instrument(skip(db))]
@@ -49,6 +53,19 @@ pub async fn handle_get_record(
     Ok(results)
 }
 
+#[instrument(skip(env))]
+pub async fn handle_get_meta(
+    env: DbRead<DbKindDht>,
+    hash: AnyDhtHash,
+    options: holochain_p2p::event::GetMetaOptions,
+) -> CascadeResult<MetadataSet> {
+    let query = GetMetaQuery::new(hash, options.metadata_request);
+    let results = env
+        .async_reader(move |txn| query.run(Txn::from(&txn)))
+        .await?;
+    Ok(results)
+}
+
 #[instrument(skip(env))]
 pub async fn handle_get_agent_activity(
     env: DbRead<DbKindDht>,
@@ -89,3 +106,39 @@ pub async fn handle_get_links(
         .await?;
     Ok(results)
 }
+
+#[instrument(skip(env, _options))]
+pub async fn handle_count_links(
+    env: DbRead<DbKindDht>,
+    link_key: WireLinkKey,
+    _options: holochain_p2p::event::GetLinksOptions,
+) -> CascadeResult<CountLinksResponse> {
+    // The limit is applied once the responses of all authorities are merged.
+    let query = GetLinkDetailsQuery::filtered(
+        link_key.base,
+        link_key.type_query,
+        link_key.tag,
+        LinkFilter {
+            limit: None,
+            ..link_key.filter
+        },
+    );
+    let details = env
+        .async_reader(move |txn| query.run(Txn::from(&txn)))
+        .await?;
+    Ok(count_link_details(&details))
+}
+
+/// Count the links in the result of a [`GetLinkDetailsQuery`],
+/// and how many of them have been deleted.
+pub(crate) fn count_link_details(
+    details: &[(SignedActionHashed, Vec<SignedActionHashed>)],
+) -> CountLinksResponse {
+    CountLinksResponse::new(
+        details.len(),
+        details
+            .iter()
+            .filter(|(_, deletes)| !deletes.is_empty())
+            .count(),
+    )
+}
@@ -32,12 +32,16 @@ pub struct State {
     rejected: Vec<ActionHashed>,
     pending: Vec<ActionHashed>,
     status: Option<ChainStatus>,
+    warranted: Option<ChainHead>,
+    warrants: Vec<SignedWarrant>,
 }
 
 #[derive(Debug)]
 pub enum Item {
     Integrated(ActionHashed),
     Pending(ActionHashed),
+    Warrant(ActionHashed, SignedWarrant),
+    PendingWarrant,
 }
 
 impl Query for GetAgentActivityQuery {
@@ -48,11 +52,12 @@ impl Query for GetAgentActivityQuery {
     fn query(&self) -> String {
         "
             SELECT Action.hash, DhtOp.validation_status, Action.blob AS action_blob,
-            DhtOp.when_integrated
+            DhtOp.when_integrated, DhtOp.type AS dht_type, Warrant.blob AS warrant_blob
             FROM Action
             JOIN DhtOp ON DhtOp.action_hash = Action.hash
+            LEFT JOIN Warrant ON DhtOp.hash = Warrant.hash
             WHERE Action.author = :author
-            AND DhtOp.type = :op_type
+            AND DhtOp.type IN (:op_type, :warrant_type)
             ORDER BY Action.seq ASC
         "
         .to_string()
@@ -62,6 +67,7 @@ impl Query for GetAgentActivityQuery {
         (named_params! {
             ":author": self.agent,
             ":op_type": DhtOpType::RegisterAgentActivity,
+            ":warrant_type": DhtOpType::RegisterWarrant,
         })
         .to_vec()
     }
@@ -80,11 +86,19 @@ impl Query for GetAgentActivityQuery {
             let hash: ActionHash = row.get("hash")?;
             from_blob::<SignedAction>(row.get("action_blob")?).and_then(|action| {
                 let integrated: Option<Timestamp> = row.get("when_integrated")?;
+                let op_type: DhtOpType = row.get("dht_type")?;
                 let action = ActionHashed::with_pre_hashed(action.0, hash);
-                let item = if integrated.is_some() {
-                    Item::Integrated(action)
-                } else {
-                    Item::Pending(action)
+                let item = match (op_type, integrated) {
+                    (DhtOpType::RegisterWarrant, Some(_)) => {
+                        let warrant: Option<Vec<u8>> = row.get("warrant_blob")?;
+                        match warrant {
+                            Some(warrant) => Item::Warrant(action, from_blob(warrant)?),
+                            None => Item::PendingWarrant,
+                        }
+                    }
+                    (DhtOpType::RegisterWarrant, None) => Item::PendingWarrant,
+                    (_, Some(_)) => Item::Integrated(action),
+                    (_, None) => Item::Pending(action),
                 };
                 Ok(Judged::raw(item, validation_status))
             })
@@ -125,6 +139,17 @@ impl Query for GetAgentActivityQuery {
                 state.rejected.push(action);
             }
             (_, Item::Pending(data)) => state.pending.push(data),
+            (Some(ValidationStatus::Valid), Item::Warrant(action, warrant)) => {
+                // Rows are in sequence order so the first warranted
+                // action is the lowest on the chain.
+                if state.warranted.is_none() {
+                    state.warranted = Some(ChainHead {
+                        action_seq: action.action_seq(),
+                        hash: action.as_hash().clone(),
+                    });
+                }
+                state.warrants.push(warrant);
+            }
             _ => (),
         }
         Ok(state)
@@ -135,7 +160,11 @@ impl Query for GetAgentActivityQuery {
         S: Store,
     {
         let highest_observed = compute_highest_observed(&state);
-        let status = compute_chain_status(&state);
+        let status = match state.warranted.clone() {
+            Some(head) => ChainStatus::Warranted(head),
+            None => compute_chain_status(&state),
+        };
+        let warrants = state.warrants;
 
         let valid = state.valid;
         let rejected = state.rejected;
@@ -168,6 +197,7 @@ impl Query for GetAgentActivityQuery {
             rejected_activity,
             status,
             highest_observed,
+            warrants,
         })
     }
 }
@@ -1,8 +1,11 @@
+use std::collections::HashSet;
 use std::sync::Arc;
 
+use holo_hash::ActionHash;
 use holo_hash::AnyLinkableHash;
 use holochain_sqlite::rusqlite::named_params;
 use holochain_sqlite::rusqlite::Row;
+use holochain_state::query::link::LinksQuery;
 use holochain_state::query::prelude::*;
 use holochain_state::query::StateQueryError;
 use holochain_types::dht_op::DhtOpType;
@@ -13,9 +16,11 @@ use holochain_types::sql::ToSqlStatement;
 use holochain_zome_types::Action;
 use holochain_zome_types::HasValidationStatus;
 use holochain_zome_types::Judged;
+use holochain_zome_types::LinkFilter;
 use holochain_zome_types::LinkTag;
 use holochain_zome_types::LinkTypeFilter;
 use holochain_zome_types::SignedAction;
+use holochain_zome_types::ValidationStatus;
 
 use super::WireLinkKey;
 
@@ -24,6 +29,7 @@ pub struct GetLinksOpsQuery {
     base: Arc<AnyLinkableHash>,
     type_query: LinkTypeFilter,
     tag: Option<Arc<LinkTag>>,
+    filter: Arc<LinkFilter>,
 }
 
 impl GetLinksOpsQuery {
@@ -32,6 +38,7 @@ impl GetLinksOpsQuery {
             base: Arc::new(key.base),
             type_query: key.type_query,
             tag: key.tag.map(Arc::new),
+            filter: Arc::new(key.filter),
         }
     }
     pub fn tag_to_hex(tag: &LinkTag) -> String {
@@ -46,17 +53,24 @@ impl GetLinksOpsQuery {
 
 pub struct Item {
     action: SignedAction,
+    hash: ActionHash,
     op_type: DhtOpType,
 }
 
+#[derive(Default)]
+pub struct LinkOpsState {
+    creates: Vec<(ActionHash, WireCreateLink)>,
+    deletes: Vec<WireDeleteLink>,
+}
+
 impl Query for GetLinksOpsQuery {
     type Item = Judged<Item>;
-    type State = WireLinkOps;
-    type Output = Self::State;
+    type State = LinkOpsState;
+    type Output = WireLinkOps;
 
     fn query(&self) -> String {
         let create = "
-            SELECT Action.blob AS action_blob, DhtOp.type AS dht_type,
+            SELECT Action.blob AS action_blob, Action.hash AS action_hash, DhtOp.type AS dht_type,
             DhtOp.validation_status AS status
             FROM DhtOp
         ";
@@ -72,6 +86,7 @@ impl Query for GetLinksOpsQuery {
             DhtOp.when_integrated IS NOT NULL
         "
         .to_string();
+        common_query = LinksQuery::add_filter(common_query, &self.filter);
 
         if let Some(tag) = &self.tag {
             let tag = Self::tag_to_hex(tag.as_ref());
@@ -96,7 +111,7 @@ impl Query for GetLinksOpsQuery {
         let sub_create_query = format!("{}{}", sub_create, common_query);
         let delete_query = format!(
             "
-            SELECT Action.blob AS action_blob, DhtOp.type AS dht_type,
+            SELECT Action.blob AS action_blob, Action.hash AS action_hash, DhtOp.type AS dht_type,
             DhtOp.validation_status AS status
             FROM DhtOp
             JOIN Action On DhtOp.action_hash = Action.hash
@@ -112,44 +127,55 @@ impl Query for GetLinksOpsQuery {
     }
 
     fn params(&self) -> Vec<Params> {
-        named_params! {
+        let mut params = named_params! {
             ":create": DhtOpType::RegisterAddLink,
             ":delete": DhtOpType::RegisterRemoveLink,
             ":base_hash": self.base,
         }
-        .to_vec()
+        .to_vec();
+        params.extend(LinksQuery::filter_params(&self.filter));
+        params
     }
 
     fn as_map(&self) -> Arc<dyn Fn(&Row) -> StateQueryResult<Self::Item>> {
         let f = |row: &Row| {
             let action =
                 from_blob::<SignedAction>(row.get(row.as_ref().column_index("action_blob")?)?)?;
+            let hash = row.get(row.as_ref().column_index("action_hash")?)?;
             let op_type = row.get(row.as_ref().column_index("dht_type")?)?;
             let validation_status = row.get(row.as_ref().column_index("status")?)?;
-            Ok(Judged::raw(Item { action, op_type }, validation_status))
+            Ok(Judged::raw(
+                Item {
+                    action,
+                    hash,
+                    op_type,
+                },
+                validation_status,
+            ))
         };
         Arc::new(f)
     }
 
     fn init_fold(&self) -> StateQueryResult<Self::State> {
-        Ok(WireLinkOps::new())
+        Ok(LinkOpsState::default())
     }
 
     fn fold(&self, mut state: Self::State, dht_op: Self::Item) -> StateQueryResult<Self::State> {
         match &dht_op.data.op_type {
             DhtOpType::RegisterAddLink => {
                 let validation_status = dht_op.validation_status();
-                let item = dht_op.data.action;
+                let Item { action, hash, .. } = dht_op.data;
                 if let (
                     SignedAction(Action::CreateLink(action), signature),
                     Some(validation_status),
-                ) = (item, validation_status)
+                ) = (action, validation_status)
                 {
-                    state.creates.push(WireCreateLink::condense(
-                        action,
-                        signature,
-                        validation_status,
-                    ));
+                    if self.filter.matches(&action.author, action.timestamp, &hash) {
+                        state.creates.push((
+                            hash,
+                            WireCreateLink::condense(action, signature, validation_status),
+                        ));
+                    }
                 }
             }
             DhtOpType::RegisterRemoveLink => {
@@ -176,6 +202,44 @@ impl Query for GetLinksOpsQuery {
     where
         S: Store,
     {
-        Ok(state)
+        let LinkOpsState {
+            mut creates,
+            mut deletes,
+        } = state;
+        self.filter
+            .sort(&mut creates, |(hash, create)| (create.timestamp, hash));
+        if let Some(limit) = self.filter.limit {
+            // Deleted and invalid links don't count towards the limit, but
+            // are still sent so that the requester doesn't return them from
+            // a stale cache.
+            let deleted: HashSet<&ActionHash> = deletes
+                .iter()
+                .filter(|delete| delete.validation_status == ValidationStatus::Valid)
+                .map(|delete| &delete.link_add_address)
+                .collect();
+            let mut live = 0;
+            let end = creates
+                .iter()
+                .position(|(hash, create)| {
+                    if live == limit {
+                        return true;
+                    }
+                    if create.validation_status == ValidationStatus::Valid
+                        && !deleted.contains(hash)
+                    {
+                        live += 1;
+                    }
+                    false
+                })
+                .unwrap_or(creates.len());
+            creates.truncate(end);
+        }
+        // Only send the deletes of the links which are being sent.
+        let sent: HashSet<&ActionHash> = creates.iter().map(|(hash, _)| hash).collect();
+        deletes.retain(|delete| sent.contains(&delete.link_add_address));
+        Ok(WireLinkOps {
+            creates: creates.into_iter().map(|(_, create)| create).collect(),
+            deletes,
+        })
     }
 }
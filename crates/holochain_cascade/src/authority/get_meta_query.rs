@@ -0,0 +1,193 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use holo_hash::ActionHash;
+use holo_hash::AnyDhtHash;
+use holochain_sqlite::rusqlite::named_params;
+use holochain_sqlite::rusqlite::Row;
+use holochain_state::query::prelude::*;
+use holochain_state::query::StateQueryError;
+use holochain_types::dht_op::DhtOpType;
+use holochain_types::metadata::EntryDhtStatus;
+use holochain_types::metadata::MetadataSet;
+use holochain_types::metadata::TimedActionHash;
+use holochain_zome_types::Action;
+use holochain_zome_types::HasValidationStatus;
+use holochain_zome_types::Judged;
+use holochain_zome_types::MetadataRequest;
+use holochain_zome_types::SignedAction;
+use holochain_zome_types::ValidationStatus;
+
+/// Summarise the integrated ops on a basis hash into a [`MetadataSet`]
+/// without loading any entries.
+#[derive(Debug, Clone)]
+pub struct GetMetaQuery {
+    hash: AnyDhtHash,
+    request: MetadataRequest,
+}
+
+impl GetMetaQuery {
+    pub fn new(hash: AnyDhtHash, request: MetadataRequest) -> Self {
+        Self { hash, request }
+    }
+}
+
+pub struct Item {
+    op_type: DhtOpType,
+    hash: ActionHash,
+    action: Action,
+}
+
+#[derive(Debug, Default)]
+pub struct State {
+    meta: MetadataSet,
+    entry_creates: HashSet<ActionHash>,
+    rejected_entry_creates: bool,
+    deleted_creates: HashSet<ActionHash>,
+    links_created: HashSet<ActionHash>,
+    links_removed: HashSet<ActionHash>,
+}
+
+impl Query for GetMetaQuery {
+    type Item = Judged<Item>;
+    type State = State;
+    type Output = MetadataSet;
+
+    fn query(&self) -> String {
+        "
+        SELECT Action.hash AS hash, Action.blob AS action_blob,
+        DhtOp.type AS dht_type, DhtOp.validation_status AS status
+        FROM DhtOp
+        JOIN Action On DhtOp.action_hash = Action.hash
+        WHERE DhtOp.type IN (
+            :store_record, :store_entry, :updated_content, :updated_record,
+            :deleted_by, :deleted_entry_action, :add_link, :remove_link
+        )
+        AND
+        DhtOp.basis_hash = :hash
+        AND
+        DhtOp.when_integrated IS NOT NULL
+        "
+        .into()
+    }
+
+    fn params(&self) -> Vec<Params> {
+        let params = named_params! {
+            ":store_record": DhtOpType::StoreRecord,
+            ":store_entry": DhtOpType::StoreEntry,
+            ":updated_content": DhtOpType::RegisterUpdatedContent,
+            ":updated_record": DhtOpType::RegisterUpdatedRecord,
+            ":deleted_by": DhtOpType::RegisterDeletedBy,
+            ":deleted_entry_action": DhtOpType::RegisterDeletedEntryAction,
+            ":add_link": DhtOpType::RegisterAddLink,
+            ":remove_link": DhtOpType::RegisterRemoveLink,
+            ":hash": self.hash,
+        };
+        params.to_vec()
+    }
+
+    fn as_map(&self) -> Arc<dyn Fn(&Row) -> StateQueryResult<Self::Item>> {
+        let f = |row: &Row| {
+            let hash = row.get(row.as_ref().column_index("hash")?)?;
+            let action =
+                from_blob::<SignedAction>(row.get(row.as_ref().column_index("action_blob")?)?)?;
+            let op_type = row.get(row.as_ref().column_index("dht_type")?)?;
+            let validation_status = row.get(row.as_ref().column_index("status")?)?;
+            Ok(Judged::raw(
+                Item {
+                    op_type,
+                    hash,
+                    action: action.0,
+                },
+                validation_status,
+            ))
+        };
+        Arc::new(f)
+    }
+
+    fn init_fold(&self) -> StateQueryResult<Self::State> {
+        Ok(Default::default())
+    }
+
+    fn fold(&self, mut state: Self::State, dht_op: Self::Item) -> StateQueryResult<Self::State> {
+        let status = dht_op.validation_status();
+        let Item {
+            op_type,
+            hash,
+            action,
+        } = dht_op.data;
+        let timed = TimedActionHash {
+            timestamp: action.timestamp(),
+            action_hash: hash.clone(),
+        };
+        match status {
+            Some(ValidationStatus::Valid) => match op_type {
+                DhtOpType::StoreRecord | DhtOpType::StoreEntry => {
+                    if op_type == DhtOpType::StoreEntry {
+                        state.entry_creates.insert(hash);
+                    }
+                    if self.request.all_valid_actions {
+                        state.meta.actions.insert(timed);
+                    }
+                }
+                DhtOpType::RegisterUpdatedContent | DhtOpType::RegisterUpdatedRecord => {
+                    if self.request.all_updates {
+                        state.meta.updates.insert(timed);
+                    }
+                }
+                DhtOpType::RegisterDeletedBy | DhtOpType::RegisterDeletedEntryAction => {
+                    if let Action::Delete(delete) = action {
+                        state.deleted_creates.insert(delete.deletes_address);
+                    }
+                    if self.request.all_deletes {
+                        state.meta.deletes.insert(timed);
+                    }
+                }
+                DhtOpType::RegisterAddLink => {
+                    state.links_created.insert(hash);
+                }
+                DhtOpType::RegisterRemoveLink => {
+                    if let Action::DeleteLink(delete_link) = action {
+                        state.links_removed.insert(delete_link.link_add_address);
+                    }
+                }
+                _ => return Err(StateQueryError::UnexpectedOp(op_type)),
+            },
+            Some(ValidationStatus::Rejected) => {
+                if op_type == DhtOpType::StoreEntry {
+                    state.rejected_entry_creates = true;
+                }
+                if self.request.all_invalid_actions {
+                    state.meta.invalid_actions.insert(timed);
+                }
+            }
+            Some(ValidationStatus::Abandoned) | None => (),
+        }
+        Ok(state)
+    }
+
+    fn render<S>(&self, mut state: Self::State, _stores: S) -> StateQueryResult<Self::Output>
+    where
+        S: Store,
+    {
+        if self.request.link_count {
+            state.meta.link_count = state.links_created.difference(&state.links_removed).count();
+        }
+        if self.request.entry_dht_status {
+            state.meta.entry_dht_status = if state
+                .entry_creates
+                .iter()
+                .any(|create| !state.deleted_creates.contains(create))
+            {
+                Some(EntryDhtStatus::Live)
+            } else if !state.entry_creates.is_empty() {
+                Some(EntryDhtStatus::Dead)
+            } else if state.rejected_entry_creates {
+                Some(EntryDhtStatus::Rejected)
+            } else {
+                None
+            };
+        }
+        Ok(state.meta)
+    }
+}
@@ -6,6 +6,7 @@ use holochain_p2p::actor;
 use holochain_p2p::event::GetRequest;
 use holochain_state::prelude::test_dht_db;
 use holochain_types::activity::ChainItems;
+use std::collections::BTreeSet;
 
 fn options() -> holochain_p2p::event::GetOptions {
     holochain_p2p::event::GetOptions {
@@ -63,6 +64,49 @@ async fn get_entry() {
     assert_eq!(result, expected);
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn get_meta() {
+    observability::test_run().ok();
+    let db = test_dht_db();
+
+    let td = EntryTestData::create();
+    let options = holochain_p2p::event::GetMetaOptions {
+        metadata_request: MetadataRequest {
+            entry_dht_status: true,
+            ..Default::default()
+        },
+    };
+    let get_meta = || handle_get_meta(db.to_db().into(), td.hash.clone().into(), options.clone());
+    let hashes = |set: &BTreeSet<TimedActionHash>| {
+        set.iter()
+            .map(|t| t.action_hash.clone())
+            .collect::<Vec<_>>()
+    };
+
+    let result = get_meta().await.unwrap();
+    assert!(result.actions.is_empty());
+    assert_eq!(result.entry_dht_status, None);
+
+    fill_db(&db.to_db(), td.store_entry_op.clone());
+    fill_db(&db.to_db(), td.create_link_op.clone());
+
+    let result = get_meta().await.unwrap();
+    assert_eq!(hashes(&result.actions), vec![td.create_hash.clone()]);
+    assert_eq!(result.entry_dht_status, Some(EntryDhtStatus::Live));
+    assert_eq!(result.link_count, 1);
+
+    fill_db(&db.to_db(), td.delete_entry_action_op.clone());
+    fill_db(&db.to_db(), td.update_content_op.clone());
+    fill_db(&db.to_db(), td.delete_link_op.clone());
+
+    let result = get_meta().await.unwrap();
+    assert_eq!(hashes(&result.deletes), vec![td.delete_hash.clone()]);
+    assert_eq!(hashes(&result.updates), vec![td.update_hash.clone()]);
+    assert_eq!(result.entry_dht_status, Some(EntryDhtStatus::Dead));
+    assert_eq!(result.link_count, 0);
+    assert!(result.invalid_actions.is_empty());
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn get_record() {
     observability::test_run().ok();
@@ -189,6 +233,167 @@ async fn get_links() {
     assert_eq!(result, expected);
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn get_links_filtered() {
+    use ::fixt::prelude::*;
+    use holochain_zome_types::fixt::*;
+
+    observability::test_run().ok();
+    let db = test_dht_db();
+
+    let td = EntryTestData::create();
+    let author = fixt!(AgentPubKey);
+
+    // Five links on the same base, a second apart, where every other link
+    // is by the same author and the second link has been deleted.
+    let mut hashes = Vec::new();
+    for i in 0..5 {
+        let mut create_link = fixt!(CreateLink);
+        create_link.base_address = td.link_key.base.clone();
+        create_link.zome_id = 0.into();
+        create_link.timestamp = Timestamp::from_micros(i * 1_000_000);
+        if i % 2 == 0 {
+            create_link.author = author.clone();
+        }
+        hashes.push(ActionHash::with_data_sync(&Action::CreateLink(
+            create_link.clone(),
+        )));
+        fill_db(
+            &db.to_db(),
+            DhtOpHashed::from_content_sync(DhtOp::RegisterAddLink(fixt!(Signature), create_link)),
+        );
+    }
+    let mut delete_link = fixt!(DeleteLink);
+    delete_link.base_address = td.link_key.base.clone();
+    delete_link.link_add_address = hashes[1].clone();
+    fill_db(
+        &db.to_db(),
+        DhtOpHashed::from_content_sync(DhtOp::RegisterRemoveLink(fixt!(Signature), delete_link)),
+    );
+
+    let options = actor::GetLinksOptions::default();
+    // The seconds of the creates returned, and the number of deletes.
+    let get = |filter: LinkFilter| {
+        let key = WireLinkKey {
+            filter,
+            ..td.link_key.clone()
+        };
+        let db = db.to_db().into();
+        let options = (&options).into();
+        async move {
+            let result = handle_get_links(db, key, options).await.unwrap();
+            let creates = result
+                .creates
+                .iter()
+                .map(|create| create.timestamp.as_micros() / 1_000_000)
+                .collect::<Vec<_>>();
+            (creates, result.deletes.len())
+        }
+    };
+
+    assert_eq!(get(LinkFilter::default()).await, (vec![0, 1, 2, 3, 4], 1));
+    assert_eq!(
+        get(LinkFilter {
+            after: Some(Timestamp::from_micros(1_000_000)),
+            before: Some(Timestamp::from_micros(4_000_000)),
+            ..Default::default()
+        })
+        .await,
+        (vec![2, 3], 0)
+    );
+    assert_eq!(
+        get(LinkFilter {
+            author: Some(author),
+            ..Default::default()
+        })
+        .await,
+        (vec![0, 2, 4], 0)
+    );
+    assert_eq!(
+        get(LinkFilter {
+            order: LinkOrder::NewestFirst,
+            limit: Some(2),
+            ..Default::default()
+        })
+        .await,
+        (vec![4, 3], 0)
+    );
+    // The deleted link doesn't count towards the limit.
+    assert_eq!(
+        get(LinkFilter {
+            limit: Some(2),
+            ..Default::default()
+        })
+        .await,
+        (vec![0, 1, 2], 1)
+    );
+    assert_eq!(
+        get(LinkFilter {
+            limit: Some(2),
+            cursor: Some(LinkCursor {
+                timestamp: Timestamp::from_micros(2_000_000),
+                create_link_hash: hashes[2].clone(),
+            }),
+            ..Default::default()
+        })
+        .await,
+        (vec![3, 4], 0)
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn count_links() {
+    use ::fixt::prelude::*;
+    use holochain_zome_types::fixt::*;
+
+    observability::test_run().ok();
+    let db = test_dht_db();
+
+    let td = EntryTestData::create();
+
+    // Three links on the same base, one of which has been deleted.
+    let mut hashes = Vec::new();
+    for i in 0..3 {
+        let mut create_link = fixt!(CreateLink);
+        create_link.base_address = td.link_key.base.clone();
+        create_link.zome_id = 0.into();
+        create_link.timestamp = Timestamp::from_micros(i * 1_000_000);
+        hashes.push(ActionHash::with_data_sync(&Action::CreateLink(
+            create_link.clone(),
+        )));
+        fill_db(
+            &db.to_db(),
+            DhtOpHashed::from_content_sync(DhtOp::RegisterAddLink(fixt!(Signature), create_link)),
+        );
+    }
+    let mut delete_link = fixt!(DeleteLink);
+    delete_link.base_address = td.link_key.base.clone();
+    delete_link.link_add_address = hashes[0].clone();
+    fill_db(
+        &db.to_db(),
+        DhtOpHashed::from_content_sync(DhtOp::RegisterRemoveLink(fixt!(Signature), delete_link)),
+    );
+
+    let options = actor::GetLinksOptions::default();
+    let result = handle_count_links(db.to_db().into(), td.link_key.clone(), (&options).into())
+        .await
+        .unwrap();
+    assert_eq!(result, CountLinksResponse::new(3, 1));
+    assert_eq!(result.count(), 2);
+
+    let key = WireLinkKey {
+        filter: LinkFilter {
+            after: Some(Timestamp::from_micros(1_000_000)),
+            ..Default::default()
+        },
+        ..td.link_key.clone()
+    };
+    let result = handle_count_links(db.to_db().into(), key, (&options).into())
+        .await
+        .unwrap();
+    assert_eq!(result, CountLinksResponse::new(1, 0));
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn get_agent_activity() {
     observability::test_run().ok();
@@ -224,6 +429,7 @@ async fn get_agent_activity() {
         rejected_activity: ChainItems::NotRequested,
         status: ChainStatus::Valid(td.chain_head.clone()),
         highest_observed: Some(td.highest_observed.clone()),
+        warrants: Vec::new(),
     };
     assert_eq!(result, expected);
 
@@ -246,3 +452,43 @@ async fn get_agent_activity() {
 
     assert_eq!(result, expected);
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_agent_activity_warranted() {
+    observability::test_run().ok();
+    let db = test_dht_db();
+
+    let td = ActivityTestData::valid_chain_scenario();
+
+    for hash_op in td.hash_ops.iter().cloned() {
+        fill_db(&db.to_db(), hash_op);
+    }
+    let (warrant_op, warrant, warranted_head) = td.warrant(10);
+    fill_db(&db.to_db(), warrant_op);
+
+    let options = actor::GetActivityOptions {
+        include_valid_activity: true,
+        include_rejected_activity: false,
+        include_full_actions: false,
+        ..Default::default()
+    };
+
+    let result = handle_get_agent_activity(
+        db.to_db().into(),
+        td.agent.clone(),
+        td.query_filter.clone(),
+        (&options).into(),
+    )
+    .await
+    .unwrap();
+    let expected = AgentActivityResponse {
+        agent: td.agent.clone(),
+        valid_activity: td.valid_hashes.clone(),
+        rejected_activity: ChainItems::NotRequested,
+        // The warrant overrides the otherwise valid chain.
+        status: ChainStatus::Warranted(warranted_head),
+        highest_observed: Some(td.highest_observed.clone()),
+        warrants: vec![warrant],
+    };
+    assert_eq!(result, expected);
+}
@@ -14,6 +14,7 @@ use holo_hash::AnyDhtHash;
 use holo_hash::EntryHash;
 use holochain_p2p::actor::GetActivityOptions;
 use holochain_p2p::actor::GetLinksOptions;
+use holochain_p2p::actor::GetMetaOptions;
 use holochain_p2p::actor::GetOptions as NetworkGetOptions;
 use holochain_p2p::HolochainP2pDna;
 use holochain_p2p::HolochainP2pDnaT;
@@ -276,6 +277,16 @@ where
         Ok(())
     }
 
+    #[instrument(skip(self, options))]
+    async fn fetch_meta(
+        &mut self,
+        hash: AnyDhtHash,
+        options: GetMetaOptions,
+    ) -> CascadeResult<Vec<MetadataSet>> {
+        let network = ok_or_return!(self.network.as_mut(), Vec::with_capacity(0));
+        Ok(network.get_meta(hash, options).await?)
+    }
+
     #[instrument(skip(self, options))]
     async fn fetch_links(
         &mut self,
@@ -290,6 +301,16 @@ where
         Ok(())
     }
 
+    #[instrument(skip(self, options))]
+    async fn fetch_link_counts(
+        &mut self,
+        link_key: WireLinkKey,
+        options: GetLinksOptions,
+    ) -> CascadeResult<Vec<CountLinksResponse>> {
+        let network = ok_or_return!(self.network.as_mut(), Vec::with_capacity(0));
+        Ok(network.count_links(link_key, options).await?)
+    }
+
     #[instrument(skip(self, options))]
     async fn fetch_agent_activity(
         &mut self,
@@ -705,6 +726,31 @@ where
         }
     }
 
+    #[instrument(skip(self, options))]
+    /// Get the metadata on a hash without fetching any records or entries.
+    /// This is a cheap way to check whether something has been updated
+    /// or deleted, or how many links there are on a base.
+    /// If we are an authority for the hash the metadata is read from our
+    /// DHT database, otherwise the responses of the authorities are merged.
+    pub async fn get_meta(
+        &mut self,
+        hash: AnyDhtHash,
+        options: GetMetaOptions,
+    ) -> CascadeResult<MetadataSet> {
+        let authority = self.am_i_an_authority(hash.clone()).await?;
+        let results = match self.dht.clone() {
+            Some(dht) if authority => {
+                vec![authority::handle_get_meta(dht, hash, (&options).into()).await?]
+            }
+            _ => self.fetch_meta(hash, options).await?,
+        };
+        let mut meta = MetadataSet::default();
+        for result in results {
+            meta.merge(result);
+        }
+        Ok(meta)
+    }
+
     #[instrument(skip(self, options))]
     /// Gets an links from the cas or cache depending on it's metadata
     // The default behavior is to skip deleted or replaced entries.
@@ -717,11 +763,42 @@ where
         if !authority {
             self.fetch_links(key.clone(), options).await?;
         }
-        let query = GetLinksQuery::new(key.base, key.type_query, key.tag);
+        let query = GetLinksQuery::filtered(key.base, key.type_query, key.tag, key.filter);
         let results = self.cascading(query).await?;
         Ok(results)
     }
 
+    #[instrument(skip(self, options))]
+    /// Count the live links on a base without fetching them.
+    /// If we aren't an authority for the base, the counts of the
+    /// authorities are merged with the count of the links held locally.
+    pub async fn dht_count_links(
+        &mut self,
+        key: WireLinkKey,
+        options: GetLinksOptions,
+    ) -> CascadeResult<usize> {
+        let authority = self.am_i_an_authority(key.base.clone().into()).await?;
+        let mut responses = if !authority {
+            self.fetch_link_counts(key.clone(), options).await?
+        } else {
+            Vec::new()
+        };
+        let limit = key.filter.limit;
+        let query = GetLinkDetailsQuery::filtered(
+            key.base,
+            key.type_query,
+            key.tag,
+            LinkFilter {
+                limit: None,
+                ..key.filter
+            },
+        );
+        let details = self.cascading(query).await?;
+        responses.push(authority::count_link_details(&details));
+        let count = CountLinksResponse::merge(responses).count();
+        Ok(limit.map_or(count, |limit| count.min(limit as usize)))
+    }
+
     #[instrument(skip(self, key, options))]
     /// Return all CreateLink actions
     /// and DeleteLink actions ordered by time.
@@ -734,7 +811,7 @@ where
         if !authority {
             self.fetch_links(key.clone(), options).await?;
         }
-        let query = GetLinkDetailsQuery::new(key.base, key.type_query, key.tag);
+        let query = GetLinkDetailsQuery::filtered(key.base, key.type_query, key.tag, key.filter);
         let results = self.cascading(query).await?;
         Ok(results)
     }
@@ -807,6 +884,7 @@ where
             rejected_activity,
             status,
             highest_observed,
+            warrants,
         } = merged_response;
         let valid_activity = match valid_activity {
             ChainItems::Hashes(hashes) => {
@@ -861,6 +939,7 @@ where
             rejected_activity,
             status,
             highest_observed,
+            warrants,
         };
         Ok(r)
     }
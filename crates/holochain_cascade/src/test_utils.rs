@@ -129,10 +129,17 @@ impl HolochainP2pDnaT for PassThroughNetwork {
     }
     async fn get_meta(
         &self,
-        _dht_hash: holo_hash::AnyDhtHash,
-        _options: actor::GetMetaOptions,
+        dht_hash: holo_hash::AnyDhtHash,
+        options: actor::GetMetaOptions,
     ) -> actor::HolochainP2pResult<Vec<MetadataSet>> {
-        todo!()
+        let mut out = Vec::new();
+        for env in &self.envs {
+            let r = authority::handle_get_meta(env.clone(), dht_hash.clone(), (&options).into())
+                .await
+                .map_err(|e| HolochainP2pError::Other(e.into()))?;
+            out.push(r);
+        }
+        Ok(out)
     }
     async fn get_links(
         &self,
@@ -148,6 +155,20 @@ impl HolochainP2pDnaT for PassThroughNetwork {
         }
         Ok(out)
     }
+    async fn count_links(
+        &self,
+        link_key: WireLinkKey,
+        options: actor::GetLinksOptions,
+    ) -> actor::HolochainP2pResult<Vec<CountLinksResponse>> {
+        let mut out = Vec::new();
+        for env in &self.envs {
+            let r = authority::handle_count_links(env.clone(), link_key.clone(), (&options).into())
+                .await
+                .map_err(|e| HolochainP2pError::Other(e.into()))?;
+            out.push(r);
+        }
+        Ok(out)
+    }
     async fn get_agent_activity(
         &self,
         agent: AgentPubKey,
@@ -334,6 +355,14 @@ impl HolochainP2pDnaT for MockNetwork {
         self.0.lock().await.get_links(link_key, options).await
     }
 
+    async fn count_links(
+        &self,
+        link_key: WireLinkKey,
+        options: actor::GetLinksOptions,
+    ) -> actor::HolochainP2pResult<Vec<CountLinksResponse>> {
+        self.0.lock().await.count_links(link_key, options).await
+    }
+
     async fn get_agent_activity(
         &self,
         agent: AgentPubKey,
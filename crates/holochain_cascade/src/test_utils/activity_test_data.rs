@@ -139,4 +139,35 @@ impl ActivityTestData {
             valid_records: ChainItems::Full(valid_records),
         }
     }
+
+    /// A warrant, issued by a random agent, against the action at
+    /// this sequence number on the chain. Returns the op which carries
+    /// the warrant and the head of the chain it warrants.
+    pub fn warrant(&self, action_seq: usize) -> (DhtOpHashed, SignedWarrant, ChainHead) {
+        let action = match self.hash_ops[action_seq].as_content() {
+            DhtOp::RegisterAgentActivity(_, action) => action.clone(),
+            _ => unreachable!(),
+        };
+        let hash = ActionHash::with_data_sync(&action);
+        let warrant = SignedWarrant(
+            Warrant::new(
+                hash.clone(),
+                self.agent.clone(),
+                "invalid".into(),
+                fixt!(AgentPubKey),
+                Timestamp::now(),
+            ),
+            fixt!(Signature),
+        );
+        let op = DhtOpHashed::from_content_sync(DhtOp::RegisterWarrant(
+            fixt!(Signature),
+            action,
+            Box::new(warrant.clone()),
+        ));
+        let head = ChainHead {
+            action_seq: action_seq as u32,
+            hash,
+        };
+        (op, warrant, head)
+    }
 }
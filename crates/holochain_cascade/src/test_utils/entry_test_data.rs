@@ -168,11 +168,13 @@ impl EntryTestData {
             base: create_link.base_address.clone(),
             type_query: LinkTypeFilter::single_dep(0.into()),
             tag: None,
+            filter: Default::default(),
         };
         let link_key_tag = WireLinkKey {
             base: create_link.base_address.clone(),
             type_query: LinkTypeFilter::single_dep(0.into()),
             tag: Some(create_link.tag.clone()),
+            filter: Default::default(),
         };
 
         let link = Link {
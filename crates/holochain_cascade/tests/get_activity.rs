@@ -52,6 +52,73 @@ async fn get_activity() {
         rejected_activity: ChainItems::NotRequested,
         status: ChainStatus::Valid(td.chain_head.clone()),
         highest_observed: Some(td.highest_observed.clone()),
+        warrants: Vec::new(),
     };
     assert_eq!(r, expected);
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_activity_merges_warrants() {
+    observability::test_run().ok();
+
+    // Environments
+    let cache = test_cache_db();
+    let authority = test_dht_db();
+    let other_authority = test_dht_db();
+
+    // Data
+    // - Each authority holds a different warrant against the agent.
+    let td = ActivityTestData::valid_chain_scenario();
+    let (warrant_op, warrant, _) = td.warrant(20);
+    let (other_warrant_op, other_warrant, lowest_warranted_head) = td.warrant(10);
+
+    for hash_op in td.hash_ops.iter().cloned() {
+        fill_db(&authority.to_db(), hash_op.clone());
+        fill_db(&other_authority.to_db(), hash_op);
+    }
+    fill_db(&authority.to_db(), warrant_op);
+    fill_db(&other_authority.to_db(), other_warrant_op);
+
+    let options = holochain_p2p::actor::GetActivityOptions {
+        include_valid_activity: true,
+        include_rejected_activity: false,
+        include_full_actions: false,
+        ..Default::default()
+    };
+
+    // Network
+    let network = PassThroughNetwork::authority_for_nothing(vec![
+        authority.to_db().clone().into(),
+        other_authority.to_db().clone().into(),
+    ]);
+
+    // Cascade
+    let mut cascade = Cascade::empty().with_network(network, cache.to_db());
+
+    let r = cascade
+        .get_agent_activity(td.agent.clone(), td.query_filter.clone(), options.clone())
+        .await
+        .unwrap();
+
+    // The chain is warranted from the lowest warranted action
+    // and the warrants from both authorities are returned.
+    assert_eq!(
+        r.status,
+        ChainStatus::Warranted(lowest_warranted_head.clone())
+    );
+    assert_eq!(r.warrants.len(), 2);
+    assert!(r.warrants.contains(&warrant));
+    assert!(r.warrants.contains(&other_warrant));
+
+    // Asking only for the status merges the warrants too.
+    let status_only = holochain_p2p::actor::GetActivityOptions {
+        include_valid_activity: false,
+        ..options
+    };
+    let r = cascade
+        .get_agent_activity(td.agent.clone(), td.query_filter.clone(), status_only)
+        .await
+        .unwrap();
+    assert_eq!(r.status, ChainStatus::Warranted(lowest_warranted_head));
+    assert_eq!(r.warrants.len(), 2);
+}
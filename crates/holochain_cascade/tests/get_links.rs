@@ -178,3 +178,46 @@ async fn links_authoring() {
 async fn test_links_can_match_a_partial_tag() {
     todo!()
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn count_links_subtracts_deletes_from_other_authorities() {
+    observability::test_run().ok();
+
+    // Environments
+    let cache = test_cache_db();
+    let stale_authority = test_dht_db();
+    let authority = test_dht_db();
+
+    // Data
+    // - Only one of the authorities has seen the delete.
+    let td = EntryTestData::create();
+    fill_db(&stale_authority.to_db(), td.store_entry_op.clone());
+    fill_db(&stale_authority.to_db(), td.create_link_op.clone());
+    fill_db(&authority.to_db(), td.store_entry_op.clone());
+    fill_db(&authority.to_db(), td.create_link_op.clone());
+
+    // Network
+    let network = PassThroughNetwork::authority_for_nothing(vec![
+        stale_authority.to_db().clone().into(),
+        authority.to_db().clone().into(),
+    ]);
+
+    // Cascade
+    let mut cascade = Cascade::empty().with_network(network, cache.to_db());
+
+    let count = cascade
+        .dht_count_links(td.link_key.clone(), Default::default())
+        .await
+        .unwrap();
+
+    assert_eq!(count, 1);
+
+    fill_db(&authority.to_db(), td.delete_link_op.clone());
+
+    let count = cascade
+        .dht_count_links(td.link_key.clone(), Default::default())
+        .await
+        .unwrap();
+
+    assert_eq!(count, 0);
+}
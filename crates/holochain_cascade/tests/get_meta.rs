@@ -0,0 +1,106 @@
+use ghost_actor::dependencies::observability;
+use holo_hash::ActionHash;
+use holochain_cascade::test_utils::*;
+use holochain_cascade::Cascade;
+use holochain_p2p::actor::GetMetaOptions;
+use holochain_p2p::MockHolochainP2pDnaT;
+use holochain_state::prelude::test_cache_db;
+use holochain_state::prelude::test_dht_db;
+use holochain_types::metadata::TimedActionHash;
+use holochain_zome_types::EntryDhtStatus;
+use holochain_zome_types::MetadataRequest;
+use std::collections::BTreeSet;
+
+fn options() -> GetMetaOptions {
+    GetMetaOptions {
+        metadata_request: MetadataRequest {
+            entry_dht_status: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+fn hashes(set: &BTreeSet<TimedActionHash>) -> Vec<ActionHash> {
+    set.iter().map(|t| t.action_hash.clone()).collect()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn meta_not_authority() {
+    observability::test_run().ok();
+
+    // Environments
+    let cache = test_cache_db();
+    let stale_authority = test_dht_db();
+    let authority = test_dht_db();
+
+    // Data
+    // - Only one of the authorities has seen the delete, update and delete link.
+    let td = EntryTestData::create();
+    for db in [&stale_authority, &authority] {
+        fill_db(&db.to_db(), td.store_entry_op.clone());
+        fill_db(&db.to_db(), td.create_link_op.clone());
+    }
+    fill_db(&authority.to_db(), td.delete_entry_action_op.clone());
+    fill_db(&authority.to_db(), td.update_content_op.clone());
+    fill_db(&authority.to_db(), td.delete_link_op.clone());
+
+    // Network
+    let network = PassThroughNetwork::authority_for_nothing(vec![
+        stale_authority.to_db().clone().into(),
+        authority.to_db().clone().into(),
+    ]);
+
+    // Cascade
+    let mut cascade = Cascade::empty().with_network(network, cache.to_db());
+
+    let r = cascade
+        .get_meta(td.hash.clone().into(), options())
+        .await
+        .unwrap();
+
+    // The action sets are merged, and the stale authority still sees the
+    // entry and the link as live.
+    assert_eq!(hashes(&r.actions), vec![td.create_hash.clone()]);
+    assert_eq!(hashes(&r.deletes), vec![td.delete_hash.clone()]);
+    assert_eq!(hashes(&r.updates), vec![td.update_hash.clone()]);
+    assert_eq!(r.entry_dht_status, Some(EntryDhtStatus::Live));
+    assert_eq!(r.link_count, 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn meta_authority() {
+    observability::test_run().ok();
+
+    // Environments
+    let cache = test_cache_db();
+    let dht = test_dht_db();
+
+    // Data
+    let td = EntryTestData::create();
+    fill_db(&dht.to_db(), td.store_entry_op.clone());
+    fill_db(&dht.to_db(), td.create_link_op.clone());
+    fill_db(&dht.to_db(), td.delete_entry_action_op.clone());
+
+    // Network
+    // - Not expecting any calls to the network.
+    let mut mock = MockHolochainP2pDnaT::new();
+    mock.expect_authority_for_hash().returning(|_| Ok(true));
+    let mock = MockNetwork::new(mock);
+
+    // Cascade
+    let mut cascade = Cascade::empty()
+        .with_network(mock, cache.to_db())
+        .with_dht(dht.to_db().into());
+
+    let r = cascade
+        .get_meta(td.hash.clone().into(), options())
+        .await
+        .unwrap();
+
+    assert_eq!(hashes(&r.actions), vec![td.create_hash.clone()]);
+    assert_eq!(hashes(&r.deletes), vec![td.delete_hash.clone()]);
+    assert!(r.updates.is_empty());
+    assert_eq!(r.entry_dht_status, Some(EntryDhtStatus::Dead));
+    assert_eq!(r.link_count, 1);
+}
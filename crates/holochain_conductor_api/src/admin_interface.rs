@@ -47,6 +47,37 @@ pub enum AdminRequest {
     /// [`AdminResponse::CloneCellCreated`]
     CreateCloneCell(Box<CreateCloneCellPayload>),
 
+    /// Archive a clone cell of an app.
+    ///
+    /// The cell is stopped and no longer counts towards the `clone_limit` of
+    /// its role, but its source chain and other data are kept, so that it can
+    /// be brought back with [`AdminRequest::RestoreArchivedCloneCell`].
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::CloneCellArchived`]
+    ArchiveCloneCell(Box<ArchiveCloneCellPayload>),
+
+    /// Restore an archived clone cell of an app.
+    ///
+    /// The cell is added back to the clones of its role, subject to the
+    /// `clone_limit`, and started again if the app is running.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::ArchivedCloneCellRestored`]
+    RestoreArchivedCloneCell(Box<RestoreArchivedCloneCellPayload>),
+
+    /// Delete a clone cell of an app, whether archived or not.
+    ///
+    /// The cell is removed from the app and its authored, DHT and cache
+    /// databases are purged. This cannot be undone.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::CloneCellDeleted`]
+    DeleteCloneCell(Box<DeleteCloneCellPayload>),
+
     /// Install an app from a list of DNA paths.
     ///
     /// Triggers genesis to be run on all cells and to be stored.
@@ -83,12 +114,33 @@ pub enum AdminRequest {
     /// [`EnableApp`]: AdminRequest::EnableApp
     InstallAppBundle(Box<InstallAppBundlePayload>),
 
+    /// Upgrade a role of an installed app to a successor DNA.
+    ///
+    /// The successor DNA is registered and a cell is created for it with the
+    /// app's agent. The `migrate_agent` callback is run on the old cell, whose
+    /// chain is then closed with a `CloseChain` action pointing at the new DNA.
+    /// The role is then switched over to the new cell, whose chain is opened
+    /// with an `OpenChain` action pointing back at the old DNA, after running
+    /// the `migrate_agent` callback on the new cell too.
+    ///
+    /// The app must be running. Once the old chain is closed it cannot be
+    /// written to again, so if the callback fails on the new cell the role is
+    /// left pointing at the new cell with its chain unopened.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::AppUpgraded`]
+    UpgradeApp(Box<UpgradeAppPayload>),
+
     /// Uninstalls the app specified by argument `installed_app_id` from the conductor.
     ///
     /// The app will be removed from the list of installed apps, and any cells
     /// which were referenced only by this app will be disabled and removed, clearing up
     /// any persisted data.
     /// Cells which are still referenced by other installed apps will not be removed.
+    /// If another installed app uses any of the cells created by this app,
+    /// via the `use_existing` or `create_if_not_exists` provisioning strategies,
+    /// the app cannot be uninstalled until those apps are uninstalled.
     ///
     /// # Returns
     ///
@@ -105,6 +157,14 @@ pub enum AdminRequest {
     /// [`AdminResponse::DnasListed`]
     ListDnas,
 
+    /// Get the zome functions, callbacks, entry defs and link types
+    /// exposed by each zome of a registered DNA.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::DnaDefinitionReturned`]
+    GetDnaDefinition(Box<DnaHash>),
+
     /// Generate a new [`AgentPubKey`].
     ///
     /// # Returns
@@ -199,12 +259,33 @@ pub enum AdminRequest {
         port: Option<u16>,
     },
 
+    /// Open up a new unix domain socket for processing [`AppRequest`]s,
+    /// using the same websocket framing as [`AttachAppInterface`].
+    ///
+    /// Only processes with write permission on the socket file can connect.
+    /// A socket file left over from a previous interface at the same path
+    /// is replaced.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::AppInterfaceAttachedUnixSocket`]
+    ///
+    /// [`AttachAppInterface`]: AdminRequest::AttachAppInterface
+    /// [`AppRequest`]: super::AppRequest
+    AttachAppInterfaceUnixSocket {
+        /// Path of the socket file
+        path: std::path::PathBuf,
+        /// Permissions of the socket file, owner only if `None`
+        #[serde(default)]
+        mode: Option<u32>,
+    },
+
     /// List all the app interfaces currently attached with [`AttachAppInterface`].
     ///
     /// # Returns
     ///
     /// [`AdminResponse::AppInterfacesListed`], a list of websocket ports that can
-    /// process [`AppRequest`]s.
+    /// process [`AppRequest`]s. Interfaces on unix domain sockets are not included.
     ///
     /// [`AttachAppInterface`]: AdminRequest::AttachAppInterface
     /// [`AppRequest`]: super::AppRequest
@@ -333,6 +414,54 @@ pub enum AdminRequest {
         /// The records to be inserted into the source chain.
         records: Vec<Record>,
     },
+
+    /// Generate a new master key for the encryption of the conductor's
+    /// databases, and re-encrypt all databases with it.
+    ///
+    /// The new key is held in the keystore like the previous one.
+    /// This is only supported with the new lair keystore.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::DatabaseKeyRotated`]
+    RotateDatabaseKey,
+
+    /// Refuse to communicate with a remote agent or node.
+    ///
+    /// The block is persisted, and lasts until it is lifted with
+    /// [`AdminRequest::UnblockAgent`] or it expires.
+    /// Connections from a blocked node are refused, and broadcasts from it
+    /// are dropped. Blocked agents and nodes are never chosen as the target
+    /// of a call, a get or a gossip round.
+    /// Blocking an agent which is already blocked replaces its expiry.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::AgentBlocked`]
+    BlockAgent {
+        /// The agent or node to block.
+        target: BlockTarget,
+        /// When the block should be lifted automatically.
+        /// If this is `None` the block lasts until it is lifted manually.
+        expires_at: Option<Timestamp>,
+    },
+
+    /// Lift the block on a remote agent or node.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::AgentUnblocked`]
+    UnblockAgent {
+        /// The agent or node to unblock.
+        target: BlockTarget,
+    },
+
+    /// List the agents and nodes which are currently blocked.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::BlocksListed`]
+    ListBlocks,
 }
 
 /// Represents the possible responses to an [`AdminRequest`]
@@ -368,6 +497,12 @@ pub enum AdminResponse {
     /// of the newly installed DNAs.
     AppBundleInstalled(InstalledAppInfo),
 
+    /// The successful response to an [`AdminRequest::UpgradeApp`].
+    ///
+    /// Contains the [`CellId`] of the cell of the successor DNA, which now
+    /// fills the upgraded role.
+    AppUpgraded(CellId),
+
     /// The successful response to an [`AdminRequest::UninstallApp`].
     ///
     /// It means the app was uninstalled successfully.
@@ -378,6 +513,21 @@ pub enum AdminResponse {
     /// The response contains the [`CellId`] of the newly created clone.
     CloneCellCreated(CellId),
 
+    /// The successful response to an [`AdminRequest::ArchiveCloneCell`].
+    ///
+    /// It means the clone cell was stopped and archived.
+    CloneCellArchived,
+
+    /// The successful response to an [`AdminRequest::RestoreArchivedCloneCell`].
+    ///
+    /// It means the clone cell is part of its app again.
+    ArchivedCloneCellRestored,
+
+    /// The successful response to an [`AdminRequest::DeleteCloneCell`].
+    ///
+    /// It means the clone cell and all of its data were removed.
+    CloneCellDeleted,
+
     /// The successful response to an [`AdminRequest::AddAdminInterfaces`].
     ///
     /// It means the `AdminInterface`s have successfully been added.
@@ -393,6 +543,11 @@ pub enum AdminResponse {
     /// Contains a list of the hashes of all installed DNAs.
     DnasListed(Vec<DnaHash>),
 
+    /// The successful response to an [`AdminRequest::GetDnaDefinition`].
+    ///
+    /// Contains what each zome of the DNA exposes.
+    DnaDefinitionReturned(DnaDefinition),
+
     /// The successful response to an [`AdminRequest::ListCellIds`].
     ///
     /// Contains a list of all the cell IDs in the conductor.
@@ -421,6 +576,12 @@ pub enum AdminResponse {
         port: u16,
     },
 
+    /// The successful response to an [`AdminRequest::AttachAppInterfaceUnixSocket`].
+    AppInterfaceAttachedUnixSocket {
+        /// Path of the socket file of the new `AppInterfaceApi`
+        path: std::path::PathBuf,
+    },
+
     /// The list of attached app interfaces.
     AppInterfacesListed(Vec<u16>),
 
@@ -486,6 +647,24 @@ pub enum AdminResponse {
 
     /// The successful response to an [`AdminRequest::AddRecords`].
     RecordsAdded,
+
+    /// The successful response to an [`AdminRequest::RotateDatabaseKey`].
+    ///
+    /// It means all databases are now encrypted with the new key.
+    DatabaseKeyRotated,
+
+    /// The successful response to an [`AdminRequest::BlockAgent`].
+    AgentBlocked,
+
+    /// The successful response to an [`AdminRequest::UnblockAgent`].
+    ///
+    /// Contains `false` if the target wasn't blocked.
+    AgentUnblocked(bool),
+
+    /// The successful response to an [`AdminRequest::ListBlocks`].
+    ///
+    /// Blocks which have expired are not included.
+    BlocksListed(Vec<Block>),
 }
 
 /// Error type that goes over the websocket wire.
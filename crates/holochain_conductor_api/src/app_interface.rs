@@ -39,8 +39,38 @@ pub enum AppRequest {
     #[deprecated = "use ZomeCall"]
     ZomeCallInvocation(Box<ZomeCall>),
 
-    /// Is currently unimplemented and will return
-    /// an [`AppResponse::Unimplemented`].
+    /// Archive a clone cell of the app. See [`ArchiveCloneCellPayload`].
+    ///
+    /// The cell is stopped and frees up its slot in the `clone_limit` of its
+    /// role, but its data is kept so that an admin can restore it.
+    ///
+    /// # Returns
+    ///
+    /// [`AppResponse::CloneCellArchived`]
+    ArchiveCloneCell(Box<ArchiveCloneCellPayload>),
+
+    /// Delete a clone cell of the app, along with all of its data.
+    /// See [`DeleteCloneCellPayload`].
+    ///
+    /// Fails unless the cell is a clone of the given role of the given app,
+    /// so the clones of another app can't be deleted by naming this one.
+    ///
+    /// # Returns
+    ///
+    /// [`AppResponse::CloneCellDeleted`]
+    DeleteCloneCell(Box<DeleteCloneCellPayload>),
+
+    /// Set which signals from the cells of an app are sent over this
+    /// connection. See [`SignalSubscription`].
+    ///
+    /// The subscription replaces any previous subscription for the same app,
+    /// and only applies to signals from that app's cells. It is also kept for
+    /// the interface, so that connections made to it later start out with
+    /// the same subscriptions.
+    ///
+    /// # Returns
+    ///
+    /// [`AppResponse::SignalSubscriptionUpdated`]
     SignalSubscription(SignalSubscription),
 }
 
@@ -72,6 +102,15 @@ pub enum AppResponse {
 
     #[deprecated = "use ZomeCall"]
     ZomeCallInvocation(Box<ExternIO>),
+
+    /// The successful response to an [`AppRequest::ArchiveCloneCell`].
+    CloneCellArchived,
+
+    /// The successful response to an [`AppRequest::DeleteCloneCell`].
+    CloneCellDeleted,
+
+    /// The successful response to an [`AppRequest::SignalSubscription`].
+    SignalSubscriptionUpdated,
 }
 
 /// The data provided over an app interface in order to make a zome call
@@ -115,6 +154,11 @@ pub struct InstalledAppInfo {
     pub installed_app_id: InstalledAppId,
     /// Info about the cells installed in this app
     pub cell_data: Vec<InstalledCell>,
+    /// Info about the cells which were cloned at runtime, along with the
+    /// role they were cloned from. Roles with the `disabled` provisioning
+    /// strategy only ever have cells in this list.
+    #[serde(default)]
+    pub clone_cell_data: Vec<InstalledCell>,
     /// The app's current status, in an API-friendly format
     pub status: InstalledAppInfoStatus,
 }
@@ -127,9 +171,18 @@ impl InstalledAppInfo {
             .provisioned_cells()
             .map(|(role_id, id)| InstalledCell::new(id.clone(), role_id.clone()))
             .collect();
+        let clone_cell_data = app
+            .roles()
+            .iter()
+            .flat_map(|(role_id, role)| {
+                role.clones()
+                    .map(move |id| InstalledCell::new(id.clone(), role_id.clone()))
+            })
+            .collect();
         Self {
             installed_app_id,
             cell_data,
+            clone_cell_data,
             status,
         }
     }
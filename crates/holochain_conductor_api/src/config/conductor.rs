@@ -225,6 +225,68 @@ pub mod tests {
         );
     }
 
+    #[test]
+    fn test_config_lair_in_proc_keystore() {
+        let yaml = r#"---
+    environment_path: /path/to/env
+
+    keystore:
+      type: lair_server_in_proc
+      lair_root: /path/to/lair
+    "#;
+        let result: ConductorConfig = config_from_yaml(yaml).unwrap();
+        assert_eq!(
+            result,
+            ConductorConfig {
+                environment_path: PathBuf::from("/path/to/env").into(),
+                network: None,
+                dpki: None,
+                keystore: KeystoreConfig::LairServerInProc {
+                    lair_root: PathBuf::from("/path/to/lair"),
+                },
+                admin_interfaces: None,
+                db_sync_strategy: DbSyncStrategy::default(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_config_unix_socket_admin_interfaces() {
+        let yaml = r#"---
+    environment_path: /path/to/env
+
+    keystore:
+      type: danger_test_keystore_legacy_deprecated
+
+    admin_interfaces:
+      - driver:
+          type: unix_socket
+          path: /run/holochain/admin.sock
+      - driver:
+          type: unix_socket
+          path: /run/holochain/group-admin.sock
+          mode: 0o660
+    "#;
+        let result: ConductorConfig = config_from_yaml(yaml).unwrap();
+        assert_eq!(
+            result.admin_interfaces,
+            Some(vec![
+                AdminInterfaceConfig {
+                    driver: InterfaceDriver::UnixSocket {
+                        path: PathBuf::from("/run/holochain/admin.sock"),
+                        mode: 0o600,
+                    }
+                },
+                AdminInterfaceConfig {
+                    driver: InterfaceDriver::UnixSocket {
+                        path: PathBuf::from("/run/holochain/group-admin.sock"),
+                        mode: 0o660,
+                    }
+                },
+            ])
+        );
+    }
+
     /* TODO uncomment when lair_keystore_api initialization is implemented
     #[test]
     fn test_config_new_lair_keystore() {
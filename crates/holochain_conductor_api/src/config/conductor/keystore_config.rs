@@ -41,18 +41,18 @@ pub enum KeystoreConfig {
         /// This value is also accessible by running `lair-keystore url`.
         connection_url: url2::Url2,
     },
-    //
-    // DISABLED - we can't pull the full lair_keystore crate in as a dep
-    //            until we make db-encryption feature the default.
-    // /// Run a lair-keystore server in-process. It will require exclusive
-    // /// access to the root directory (no other conductors can share this lair).
-    // /// This keystore type requires a secure passphrase specified
-    // /// to the cli binary entrypoint for this Holochain conductor process.
-    // LairServerInProc {
-    //     /// The "lair_root" path, i.e. the directory containing the
-    //     /// "lair-keystore-config.yaml" file.
-    //     lair_root: std::path::PathBuf,
-    // },
+
+    /// Run a lair-keystore server in-process. It will require exclusive
+    /// access to the root directory (no other conductors can share this lair).
+    /// This keystore type requires a secure passphrase specified
+    /// to the cli binary entrypoint for this Holochain conductor process.
+    /// If the root directory has no "lair-keystore-config.yaml" yet,
+    /// a new lair config and store are created there with that passphrase.
+    LairServerInProc {
+        /// The "lair_root" path, i.e. the directory containing the
+        /// "lair-keystore-config.yaml" file.
+        lair_root: std::path::PathBuf,
+    },
 }
 
 impl Default for KeystoreConfig {
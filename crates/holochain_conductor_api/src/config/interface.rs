@@ -1,11 +1,12 @@
 use serde::Deserialize;
 use serde::Serialize;
+use std::path::PathBuf;
 
 /// Information neeeded to spawn an admin interface
 #[derive(Clone, Deserialize, Serialize, Debug, PartialEq)]
 pub struct AdminInterfaceConfig {
-    /// By what means the interface will be exposed.
-    /// Currently the only option is a local websocket running on a configurable port.
+    /// By what means the interface will be exposed: a local websocket running
+    /// on a configurable port, or a unix domain socket.
     pub driver: InterfaceDriver,
     // How long will this interface be accessible between authentications?
     // TODO: implement once we have authentication
@@ -29,13 +30,43 @@ pub enum InterfaceDriver {
         /// The port on which to establish the WebsocketListener
         port: u16,
     },
+    /// An interface implemented via websockets over a unix domain socket,
+    /// so that access can be restricted with filesystem permissions.
+    UnixSocket {
+        /// The path of the socket file
+        path: PathBuf,
+        /// The permissions of the socket file. Processes need write
+        /// permission to connect.
+        #[serde(default = "default_unix_socket_mode")]
+        mode: u32,
+    },
+}
+
+/// The permissions of a unix socket interface if none are given,
+/// so that only the user running the conductor can connect.
+pub const DEFAULT_UNIX_SOCKET_MODE: u32 = 0o600;
+
+fn default_unix_socket_mode() -> u32 {
+    DEFAULT_UNIX_SOCKET_MODE
+}
+
+impl std::fmt::Display for InterfaceDriver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InterfaceDriver::Websocket { port } => write!(f, "port {}", port),
+            InterfaceDriver::UnixSocket { path, .. } => {
+                write!(f, "unix socket {}", path.display())
+            }
+        }
+    }
 }
 
 impl InterfaceDriver {
-    /// Get the port for this driver.
-    pub fn port(&self) -> u16 {
+    /// Get the port for this driver, if it listens on one.
+    pub fn port(&self) -> Option<u16> {
         match self {
-            InterfaceDriver::Websocket { port } => *port,
+            InterfaceDriver::Websocket { port } => Some(*port),
+            InterfaceDriver::UnixSocket { .. } => None,
         }
     }
 }
@@ -1,7 +1,9 @@
 use holochain_serialized_bytes::prelude::*;
 use holochain_types::app::InstalledAppId;
+use holochain_types::signal::Signal;
 use holochain_zome_types::cell::CellId;
 use std::collections::HashMap;
+use std::collections::HashSet;
 
 /// Declares updated Signal subscription settings for an App.
 /// This message is part of the AppInterfaceApi
@@ -13,12 +15,35 @@ pub struct SignalSubscription {
     filters: SignalFilterSet,
 }
 
+impl SignalSubscription {
+    /// Constructor
+    pub fn new(installed_app_id: InstalledAppId, filters: SignalFilterSet) -> Self {
+        Self {
+            installed_app_id,
+            filters,
+        }
+    }
+
+    /// Accessor
+    pub fn installed_app_id(&self) -> &InstalledAppId {
+        &self.installed_app_id
+    }
+
+    /// Accessor
+    pub fn filters(&self) -> &SignalFilterSet {
+        &self.filters
+    }
+}
+
 /// Associate a SignalFilter with each Cell in an App.
 /// The filtering can be interpreted as inclusive or exclusive,
 /// depending on the use case.
 ///
 /// An empty Exclude filter means "allow all signals" (subscribe to all).
 /// An empty Include filter means "block all signals" (unsubscribe from all).
+///
+/// System signals are not emitted by any particular Cell, so they are
+/// matched by the filter of every Cell in the set.
 #[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize, SerializedBytes)]
 pub enum SignalFilterSet {
     /// Only allow signals from the specified Cells with the specified filters,
@@ -46,11 +71,28 @@ impl SignalFilterSet {
     pub fn block_all() -> Self {
         SignalFilterSet::Include(HashMap::new())
     }
+
+    /// Whether this filter set lets the given signal through.
+    pub fn allows(&self, signal: &Signal) -> bool {
+        match self {
+            SignalFilterSet::Include(filters) => filters
+                .iter()
+                .any(|(cell_id, filter)| filter.matches(cell_id, signal)),
+            SignalFilterSet::Exclude(filters) => !filters
+                .iter()
+                .any(|(cell_id, filter)| filter.matches(cell_id, signal)),
+        }
+    }
 }
 
 /// Specifies fine-grained filter controls for the signals
 #[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize, SerializedBytes)]
-pub struct SignalFilter;
+pub struct SignalFilter {
+    /// The types of signal this filter matches.
+    /// An empty set matches signals of every type.
+    #[serde(default)]
+    pub signal_types: HashSet<SignalType>,
+}
 
 impl Default for SignalFilter {
     fn default() -> Self {
@@ -62,6 +104,94 @@ impl Default for SignalFilter {
 impl SignalFilter {
     /// A passthrough filter which filters nothing
     pub fn empty() -> Self {
-        SignalFilter
+        SignalFilter {
+            signal_types: HashSet::new(),
+        }
+    }
+
+    /// A filter which only matches signals of the given types
+    pub fn signal_types(signal_types: impl IntoIterator<Item = SignalType>) -> Self {
+        SignalFilter {
+            signal_types: signal_types.into_iter().collect(),
+        }
+    }
+
+    /// Whether a signal matches this filter when it is applied to the given Cell.
+    pub fn matches(&self, cell_id: &CellId, signal: &Signal) -> bool {
+        let from_cell = match signal {
+            Signal::App(signal_cell_id, _) => signal_cell_id == cell_id,
+            Signal::System(_) => true,
+        };
+        from_cell
+            && (self.signal_types.is_empty() || self.signal_types.contains(&SignalType::of(signal)))
+    }
+}
+
+/// The types of [`Signal`] which can be filtered
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignalType {
+    /// Signals emitted by a Cell via `emit_signal`
+    App,
+    /// Signals emitted by Holochain itself
+    System,
+}
+
+impl SignalType {
+    /// The type of the given signal
+    pub fn of(signal: &Signal) -> Self {
+        match signal {
+            Signal::App(_, _) => SignalType::App,
+            Signal::System(_) => SignalType::System,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use holochain_types::signal::SystemSignal;
+    use holochain_zome_types::signal::AppSignal;
+    use holochain_zome_types::ExternIO;
+
+    fn cell_id(n: u8) -> CellId {
+        CellId::new(
+            holo_hash::DnaHash::from_raw_36(vec![n; 36]),
+            holo_hash::AgentPubKey::from_raw_36(vec![n; 36]),
+        )
+    }
+
+    fn app_signal(cell_id: CellId) -> Signal {
+        Signal::App(cell_id, AppSignal::new(ExternIO::encode(()).unwrap()))
+    }
+
+    #[test]
+    fn include_and_exclude_by_cell_and_type() {
+        let system = Signal::System(SystemSignal::Test("test".into()));
+        let a = app_signal(cell_id(1));
+        let b = app_signal(cell_id(2));
+
+        assert!(SignalFilterSet::allow_all().allows(&a));
+        assert!(SignalFilterSet::allow_all().allows(&system));
+        assert!(!SignalFilterSet::block_all().allows(&a));
+        assert!(!SignalFilterSet::block_all().allows(&system));
+
+        let include = SignalFilterSet::Include(
+            [(cell_id(1), SignalFilter::signal_types([SignalType::App]))].into(),
+        );
+        assert!(include.allows(&a));
+        assert!(!include.allows(&b));
+        assert!(!include.allows(&system));
+
+        let exclude = SignalFilterSet::Exclude([(cell_id(1), SignalFilter::empty())].into());
+        assert!(!exclude.allows(&a));
+        assert!(exclude.allows(&b));
+        assert!(!exclude.allows(&system));
+
+        let exclude = SignalFilterSet::Exclude(
+            [(cell_id(1), SignalFilter::signal_types([SignalType::System]))].into(),
+        );
+        assert!(exclude.allows(&a));
+        assert!(!exclude.allows(&system));
     }
 }
@@ -470,7 +470,7 @@ pub struct DeleteLink {
 }
 
 /// When migrating to a new version of a DNA, this action is committed to the
-/// new chain to declare the migration path taken. **Currently unused**
+/// new chain to declare the migration path taken.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, SerializedBytes, Hash)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct OpenChain {
@@ -483,7 +483,8 @@ pub struct OpenChain {
 }
 
 /// When migrating to a new version of a DNA, this action is committed to the
-/// old chain to declare the migration path taken. **Currently unused**
+/// old chain to declare the migration path taken.
+/// No further actions may be written to a chain after it has been closed.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, SerializedBytes, Hash)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct CloseChain {
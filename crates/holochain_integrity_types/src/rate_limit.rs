@@ -2,6 +2,7 @@
 
 use holochain_serialized_bytes::prelude::*;
 
+use crate::timestamp::Timestamp;
 use crate::{Create, CreateLink, Delete, Entry, Update};
 
 /// Input to the `weigh` callback. Includes an "unweighed" action, and Entry
@@ -99,3 +100,174 @@ impl From<EntryRateWeight> for RateWeight {
         }
     }
 }
+
+/// A DNA-level definition of a rate limiting bucket.
+///
+/// Each action's [`RateWeight`] fills the bucket it names by its `units`,
+/// and the bucket drains at a constant rate over time. An action which would
+/// fill its bucket past capacity is invalid. Buckets which have no limit
+/// defined by the DNA are never enforced.
+#[derive(
+    Debug,
+    Clone,
+    serde::Serialize,
+    serde::Deserialize,
+    PartialEq,
+    Eq,
+    SerializedBytes,
+    Hash,
+    PartialOrd,
+    Ord,
+)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct RateBucketLimit {
+    /// The bucket this limit applies to.
+    pub bucket_id: RateBucketId,
+    /// The number of units the bucket can hold.
+    pub capacity: RateBucketCapacity,
+    /// The number of units which drain out of the bucket each second.
+    pub drain_per_second: RateBucketCapacity,
+}
+
+/// The number of micro-units in a unit. Bucket levels are tracked in
+/// micro-units so that the drain between closely spaced actions isn't
+/// rounded away.
+const MICROS_PER_UNIT: u64 = 1_000_000;
+
+/// The fill level of a single rate limiting bucket, as it is replayed over
+/// a sequence of actions from one author's chain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RateBucket {
+    limit: RateBucketLimit,
+    level_micros: u64,
+    last_fill: Option<Timestamp>,
+}
+
+/// A bucket was filled past its capacity.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct RateBucketError {
+    /// The bucket which overflowed.
+    pub bucket_id: RateBucketId,
+    /// The level the bucket would have reached.
+    pub level: u64,
+    /// The capacity of the bucket.
+    pub capacity: RateBucketCapacity,
+}
+
+impl core::fmt::Display for RateBucketError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "Rate limit bucket {} overflowed: level {} exceeds capacity {}",
+            self.bucket_id, self.level, self.capacity
+        )
+    }
+}
+
+impl RateBucket {
+    /// Create an empty bucket for this limit.
+    pub fn new(limit: RateBucketLimit) -> Self {
+        Self {
+            limit,
+            level_micros: 0,
+            last_fill: None,
+        }
+    }
+
+    /// The current fill level of this bucket, rounded up to whole units.
+    pub fn level(&self) -> u64 {
+        (self.level_micros + MICROS_PER_UNIT - 1) / MICROS_PER_UNIT
+    }
+
+    /// Drain the bucket up to `timestamp` and then fill it with `units`.
+    ///
+    /// Timestamps must be passed in chain order. Returns an error if the
+    /// bucket overflows, in which case the bucket is left unchanged.
+    pub fn fill(&mut self, timestamp: Timestamp, units: RateUnits) -> Result<(), RateBucketError> {
+        // One microsecond drains `drain_per_second` micro-units.
+        let drained_micros = match self.last_fill {
+            Some(last) => {
+                let elapsed = timestamp
+                    .as_micros()
+                    .saturating_sub(last.as_micros())
+                    .max(0) as u64;
+                elapsed.saturating_mul(self.limit.drain_per_second as u64)
+            }
+            None => 0,
+        };
+        let level_micros =
+            self.level_micros.saturating_sub(drained_micros) + units as u64 * MICROS_PER_UNIT;
+        if level_micros > self.limit.capacity as u64 * MICROS_PER_UNIT {
+            return Err(RateBucketError {
+                bucket_id: self.limit.bucket_id,
+                level: (level_micros + MICROS_PER_UNIT - 1) / MICROS_PER_UNIT,
+                capacity: self.limit.capacity,
+            });
+        }
+        self.level_micros = level_micros;
+        self.last_fill = Some(timestamp);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limit() -> RateBucketLimit {
+        RateBucketLimit {
+            bucket_id: 0,
+            capacity: 10,
+            drain_per_second: 2,
+        }
+    }
+
+    #[test]
+    fn bucket_overflows_at_capacity() {
+        let mut bucket = RateBucket::new(limit());
+        let t = Timestamp::from_micros(0);
+        bucket.fill(t, 6).unwrap();
+        bucket.fill(t, 4).unwrap();
+        assert_eq!(bucket.level(), 10);
+        let err = bucket.fill(t, 1).unwrap_err();
+        assert_eq!(err.level, 11);
+        // A failed fill leaves the bucket unchanged.
+        assert_eq!(bucket.level(), 10);
+    }
+
+    #[test]
+    fn bucket_drains_over_time() {
+        let mut bucket = RateBucket::new(limit());
+        bucket.fill(Timestamp::from_micros(0), 10).unwrap();
+        // Two and a half seconds drains 5 units.
+        bucket.fill(Timestamp::from_micros(2_500_000), 5).unwrap();
+        assert_eq!(bucket.level(), 10);
+        // Draining past empty doesn't bank capacity.
+        bucket.fill(Timestamp::from_micros(60_000_000), 10).unwrap();
+        assert_eq!(bucket.level(), 10);
+    }
+
+    #[test]
+    fn bucket_drains_between_frequent_actions() {
+        let mut bucket = RateBucket::new(limit());
+        bucket.fill(Timestamp::from_micros(0), 10).unwrap();
+        // Each tenth of a second drains a fifth of a unit, which must
+        // add up rather than being rounded away on every fill.
+        for i in 1..=50 {
+            bucket.fill(Timestamp::from_micros(i * 100_000), 0).unwrap();
+        }
+        assert_eq!(bucket.level(), 0);
+        bucket.fill(Timestamp::from_micros(5_000_000), 10).unwrap();
+
+        // A partly drained unit still counts towards the level.
+        bucket.fill(Timestamp::from_micros(5_100_000), 0).unwrap();
+        assert_eq!(bucket.level(), 10);
+        assert_eq!(
+            bucket
+                .fill(Timestamp::from_micros(5_100_000), 1)
+                .unwrap_err()
+                .level,
+            11
+        );
+    }
+}
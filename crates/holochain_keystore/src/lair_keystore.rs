@@ -36,3 +36,138 @@ pub async fn spawn_new_lair_keystore(
     let client = ipc_keystore_connect_options(opts).await?;
     Ok(MetaLairClient::NewLair(client))
 }
+
+/// The name of the lair config file within a lair root directory.
+pub const LAIR_CONFIG_FILE: &str = "lair-keystore-config.yaml";
+
+/// Spawn a lair keystore running inside this process, backed by the
+/// store in the given lair root directory.
+///
+/// If the directory does not yet contain a "lair-keystore-config.yaml"
+/// a new config, and a new store, is created there, locked with the given
+/// passphrase. The keystore requires exclusive access to the directory,
+/// so this fails if another running process already holds it.
+pub async fn spawn_lair_keystore_in_proc(
+    lair_root: std::path::PathBuf,
+    passphrase: sodoken::BufRead,
+) -> LairResult<MetaLairClient> {
+    use lair_keystore_api::in_proc_keystore::*;
+    let config = load_or_create_config(&lair_root, passphrase.clone()).await?;
+    claim_pid_file(&config.pid_file)?;
+    let store_factory = lair_keystore::create_sql_pool_factory(&config.store_file);
+    let keystore = InProcKeystore::new(config, store_factory, passphrase).await?;
+    let client = keystore.new_client().await?;
+    Ok(MetaLairClient::NewLair(client))
+}
+
+async fn load_or_create_config(
+    lair_root: &std::path::Path,
+    passphrase: sodoken::BufRead,
+) -> LairResult<lair_keystore_api::config::LairServerConfig> {
+    use lair_keystore_api::config::*;
+    let config_path = lair_root.join(LAIR_CONFIG_FILE);
+    match tokio::fs::read(&config_path).await {
+        Ok(bytes) => Ok(std::sync::Arc::new(LairServerConfigInner::from_bytes(
+            &bytes,
+        )?)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            tokio::fs::create_dir_all(lair_root).await?;
+            let config = LairServerConfigInner::new(lair_root, passphrase).await?;
+            tokio::fs::write(&config_path, config.to_string()).await?;
+            Ok(std::sync::Arc::new(config))
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Record this process in the lair pid file, unless the pid file names
+/// another process which is still running. The file is created with
+/// `create_new`, so of two processes racing to claim it only one wins.
+fn claim_pid_file(pid_file: &std::path::Path) -> LairResult<()> {
+    use std::io::Write;
+    use sysinfo::SystemExt;
+    let this_pid = sysinfo::get_current_pid().map_err(one_err::OneErr::new)?;
+    // A stale pid file is removed and the claim retried once.
+    for _ in 0..2 {
+        match std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(pid_file)
+        {
+            Ok(mut file) => {
+                file.write_all(this_pid.to_string().as_bytes())?;
+                return Ok(());
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => (),
+            Err(e) => return Err(e.into()),
+        }
+        let contents = match std::fs::read_to_string(pid_file) {
+            Ok(contents) => contents,
+            // Removed by its owner since we tried to create it.
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e.into()),
+        };
+        if let Ok(pid) = contents.trim().parse::<sysinfo::Pid>() {
+            if pid == this_pid {
+                return Ok(());
+            }
+            let mut system = sysinfo::System::new_with_specifics(sysinfo::RefreshKind::new());
+            if system.refresh_process(pid) {
+                return Err(one_err::OneErr::new(format!(
+                    "lair root is in use by another running process (pid {})",
+                    pid
+                )));
+            }
+        }
+        match std::fs::remove_file(pid_file) {
+            Ok(()) => (),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => (),
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Err(one_err::OneErr::new(
+        "lair root is being claimed by another process",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn in_proc_keystore_persists_in_lair_root() {
+        let tmp = tempfile::tempdir().unwrap();
+        let lair_root = tmp.path().join("lair");
+        let passphrase = || sodoken::BufRead::new_no_lock(b"passphrase");
+
+        let keystore = spawn_lair_keystore_in_proc(lair_root.clone(), passphrase())
+            .await
+            .unwrap();
+        assert!(lair_root.join(LAIR_CONFIG_FILE).is_file());
+        let (dig1, _, _) = keystore.get_or_create_first_tls_cert().await.unwrap();
+        keystore.shutdown().await.unwrap();
+
+        let keystore = spawn_lair_keystore_in_proc(lair_root, passphrase())
+            .await
+            .unwrap();
+        let (dig2, _, _) = keystore.get_or_create_first_tls_cert().await.unwrap();
+        assert_eq!(dig1, dig2);
+    }
+
+    #[test]
+    fn claim_pid_file_replaces_stale_pid() {
+        let tmp = tempfile::tempdir().unwrap();
+        let pid_file = tmp.path().join("pid_file");
+        let this_pid = sysinfo::get_current_pid().unwrap().to_string();
+
+        claim_pid_file(&pid_file).unwrap();
+        assert_eq!(std::fs::read_to_string(&pid_file).unwrap(), this_pid);
+        // Claiming it again from the same process is fine.
+        claim_pid_file(&pid_file).unwrap();
+
+        // No process can have this pid.
+        std::fs::write(&pid_file, i32::MAX.to_string()).unwrap();
+        claim_pid_file(&pid_file).unwrap();
+        assert_eq!(std::fs::read_to_string(&pid_file).unwrap(), this_pid);
+    }
+}
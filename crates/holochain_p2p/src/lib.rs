@@ -103,6 +103,13 @@ pub trait HolochainP2pDnaT {
         options: actor::GetLinksOptions,
     ) -> actor::HolochainP2pResult<Vec<WireLinkOps>>;
 
+    /// Count the live links on a base in the DHT.
+    async fn count_links(
+        &self,
+        link_key: WireLinkKey,
+        options: actor::GetLinksOptions,
+    ) -> actor::HolochainP2pResult<Vec<CountLinksResponse>>;
+
     /// Get agent activity from the DHT.
     async fn get_agent_activity(
         &self,
@@ -285,6 +292,17 @@ impl HolochainP2pDnaT for HolochainP2pDna {
             .await
     }
 
+    /// Count the live links on a base in the DHT.
+    async fn count_links(
+        &self,
+        link_key: WireLinkKey,
+        options: actor::GetLinksOptions,
+    ) -> actor::HolochainP2pResult<Vec<CountLinksResponse>> {
+        self.sender
+            .count_links((*self.dna_hash).clone(), link_key, options)
+            .await
+    }
+
     /// Get agent activity from the DHT.
     async fn get_agent_activity(
         &self,
@@ -207,6 +207,19 @@ impl WrapEvtSender {
         )
     }
 
+    fn count_links(
+        &self,
+        dna_hash: DnaHash,
+        to_agent: AgentPubKey,
+        link_key: WireLinkKey,
+        options: event::GetLinksOptions,
+    ) -> impl Future<Output = HolochainP2pResult<CountLinksResponse>> + 'static + Send {
+        timing_trace!(
+            { self.0.count_links(dna_hash, to_agent, link_key, options) },
+            "(hp2p:handle) count_links",
+        )
+    }
+
     fn get_agent_activity(
         &self,
         dna_hash: DnaHash,
@@ -306,10 +319,25 @@ impl WrapEvtSender {
     }
 }
 
+/// Ask the conductor if an agent is blocked.
+/// If it can't tell us, the agent is treated as not blocked.
+async fn is_blocked(host: &kitsune_p2p::HostApi, agent: &AgentPubKey) -> bool {
+    let target = kitsune_p2p::block::BlockTarget::Agent(agent.to_kitsune());
+    match host.is_blocked(target).await {
+        Ok(blocked) => blocked,
+        Err(e) => {
+            tracing::warn!(?e, "failed to check if agent is blocked");
+            false
+        }
+    }
+}
+
 pub(crate) struct HolochainP2pActor {
     tuning_params: kitsune_p2p_types::config::KitsuneP2pTuningParams,
     evt_sender: WrapEvtSender,
     kitsune_p2p: ghost_actor::GhostSender<kitsune_p2p::actor::KitsuneP2p>,
+    /// The conductor, which holds the blocklist.
+    host: kitsune_p2p::HostApi,
 }
 
 impl ghost_actor::GhostControlHandler for HolochainP2pActor {}
@@ -325,7 +353,7 @@ impl HolochainP2pActor {
     ) -> HolochainP2pResult<Self> {
         let tuning_params = config.tuning_params.clone();
         let (kitsune_p2p, kitsune_p2p_events) =
-            kitsune_p2p::spawn_kitsune_p2p(config, tls_config, host).await?;
+            kitsune_p2p::spawn_kitsune_p2p(config, tls_config, host.clone()).await?;
 
         channel_factory.attach_receiver(kitsune_p2p_events).await?;
 
@@ -333,6 +361,7 @@ impl HolochainP2pActor {
             tuning_params,
             evt_sender: WrapEvtSender(evt_sender),
             kitsune_p2p,
+            host,
         })
     }
 
@@ -348,8 +377,12 @@ impl HolochainP2pActor {
         cap_secret: Option<CapSecret>,
         data: Vec<u8>,
     ) -> kitsune_p2p::actor::KitsuneP2pHandlerResult<Vec<u8>> {
+        let host = self.host.clone();
         let evt_sender = self.evt_sender.clone();
         Ok(async move {
+            if is_blocked(&host, &from_agent).await {
+                return Err(HolochainP2pError::AgentBlocked(from_agent).into());
+            }
             let res = evt_sender
                 .call_remote(
                     dna_hash,
@@ -431,6 +464,27 @@ impl HolochainP2pActor {
         .into())
     }
 
+    /// receiving an incoming count_links request from a remote node
+    fn handle_incoming_count_links(
+        &mut self,
+        dna_hash: DnaHash,
+        to_agent: AgentPubKey,
+        link_key: WireLinkKey,
+        options: event::GetLinksOptions,
+    ) -> kitsune_p2p::actor::KitsuneP2pHandlerResult<Vec<u8>> {
+        let evt_sender = self.evt_sender.clone();
+        Ok(async move {
+            let res = evt_sender
+                .count_links(dna_hash, to_agent, link_key, options)
+                .await;
+            res.and_then(|r| Ok(SerializedBytes::try_from(r)?))
+                .map_err(kitsune_p2p::KitsuneP2pError::from)
+                .map(|res| UnsafeBytes::from(res).into())
+        }
+        .boxed()
+        .into())
+    }
+
     /// receiving an incoming get_links request from a remote node
     fn handle_incoming_get_agent_activity(
         &mut self,
@@ -461,8 +515,18 @@ impl HolochainP2pActor {
         countersigning_session: bool,
         ops: Vec<holochain_types::dht_op::DhtOp>,
     ) -> kitsune_p2p::actor::KitsuneP2pHandlerResult<()> {
+        let host = self.host.clone();
         let evt_sender = self.evt_sender.clone();
         Ok(async move {
+            // Drop anything authored by a blocked agent, but keep
+            // warrants against them so they can still be held.
+            let mut kept = Vec::with_capacity(ops.len());
+            for op in ops {
+                if op.warrant().is_some() || !is_blocked(&host, op.action().author()).await {
+                    kept.push(op);
+                }
+            }
+            let ops = kept;
             evt_sender
                 .publish(
                     dna_hash,
@@ -669,6 +733,9 @@ impl kitsune_p2p::event::KitsuneP2pEventHandler for HolochainP2pActor {
             crate::wire::WireMessage::GetLinks { link_key, options } => {
                 self.handle_incoming_get_links(space, to_agent, link_key, options)
             }
+            crate::wire::WireMessage::CountLinks { link_key, options } => {
+                self.handle_incoming_count_links(space, to_agent, link_key, options)
+            }
             crate::wire::WireMessage::GetAgentActivity {
                 agent,
                 query,
@@ -717,6 +784,7 @@ impl kitsune_p2p::event::KitsuneP2pEventHandler for HolochainP2pActor {
             crate::wire::WireMessage::Get { .. }
             | crate::wire::WireMessage::GetMeta { .. }
             | crate::wire::WireMessage::GetLinks { .. }
+            | crate::wire::WireMessage::CountLinks { .. }
             | crate::wire::WireMessage::GetAgentActivity { .. }
             | crate::wire::WireMessage::GetValidationPackage { .. }
             | crate::wire::WireMessage::ValidationReceipt { .. } => {
@@ -1112,6 +1180,39 @@ impl HolochainP2pHandler for HolochainP2pActor {
         .into())
     }
 
+    #[tracing::instrument(skip(self), level = "trace")]
+    fn handle_count_links(
+        &mut self,
+        dna_hash: DnaHash,
+        link_key: WireLinkKey,
+        options: actor::GetLinksOptions,
+    ) -> HolochainP2pHandlerResult<Vec<CountLinksResponse>> {
+        let space = dna_hash.into_kitsune();
+        let basis = AnyDhtHash::from(link_key.base.clone()).to_kitsune();
+        let r_options: event::GetLinksOptions = (&options).into();
+
+        let payload = crate::wire::WireMessage::count_links(link_key, r_options).encode()?;
+
+        let kitsune_p2p = self.kitsune_p2p.clone();
+        let tuning_params = self.tuning_params.clone();
+        Ok(async move {
+            // Unlike get_links, ask several authorities, so that a link
+            // missing from one of them is still counted.
+            let input = kitsune_p2p::actor::RpcMulti::new(&tuning_params, space, basis, payload);
+            let result = kitsune_p2p.rpc_multi(input).await?;
+
+            let mut out = Vec::new();
+            for item in result {
+                let kitsune_p2p::actor::RpcMultiResponse { response, .. } = item;
+                out.push(SerializedBytes::from(UnsafeBytes::from(response)).try_into()?);
+            }
+
+            Ok(out)
+        }
+        .boxed()
+        .into())
+    }
+
     #[tracing::instrument(skip(self), level = "trace")]
     fn handle_get_agent_activity(
         &mut self,
@@ -1242,4 +1343,22 @@ impl HolochainP2pHandler for HolochainP2pActor {
         .boxed()
         .into())
     }
+
+    #[tracing::instrument(skip(self), level = "trace")]
+    fn handle_block(
+        &mut self,
+        target: holochain_types::block::BlockTarget,
+    ) -> HolochainP2pHandlerResult<()> {
+        use holochain_types::block::BlockTarget;
+        let target = match target {
+            BlockTarget::Agent(agent) => kitsune_p2p::block::BlockTarget::Agent(agent.to_kitsune()),
+            BlockTarget::Node(cert) => {
+                kitsune_p2p::block::BlockTarget::Node(cert.as_ref().to_vec().into())
+            }
+        };
+        let kitsune_p2p = self.kitsune_p2p.clone();
+        Ok(async move { Ok(kitsune_p2p.block(target).await?) }
+            .boxed()
+            .into())
+    }
 }
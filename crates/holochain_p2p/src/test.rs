@@ -93,6 +93,14 @@ impl HolochainP2pHandler for StubNetwork {
     ) -> HolochainP2pHandlerResult<Vec<WireLinkOps>> {
         Err("stub".into())
     }
+    fn handle_count_links(
+        &mut self,
+        dna_hash: DnaHash,
+        link_key: WireLinkKey,
+        options: actor::GetLinksOptions,
+    ) -> HolochainP2pHandlerResult<Vec<CountLinksResponse>> {
+        Err("stub".into())
+    }
     fn handle_get_agent_activity(
         &mut self,
         dna_hash: DnaHash,
@@ -134,6 +142,12 @@ impl HolochainP2pHandler for StubNetwork {
     ) -> HolochainP2pHandlerResult<String> {
         Err("stub".into())
     }
+    fn handle_block(
+        &mut self,
+        target: holochain_types::block::BlockTarget,
+    ) -> HolochainP2pHandlerResult<()> {
+        Err("stub".into())
+    }
 }
 
 /// Spawn a stub network that doesn't respond to any messages.
@@ -381,6 +395,121 @@ mod tests {
         r_task.await.unwrap();
     }
 
+    /// A host which blocks a fixed set of targets.
+    struct BlockingHost(Vec<kitsune_p2p::block::BlockTarget>);
+
+    impl kitsune_p2p::KitsuneHostDefaultError for BlockingHost {
+        const NAME: &'static str = "BlockingHost";
+
+        fn is_blocked(
+            &self,
+            target: kitsune_p2p::block::BlockTarget,
+        ) -> kitsune_p2p::KitsuneHostResult<bool> {
+            kitsune_p2p::dependencies::kitsune_p2p_types::box_fut(Ok(self.0.contains(&target)))
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_blocked_agent_workflow() {
+        let (dna, a1, a2, _) = test_setup();
+
+        let (p2p, mut evt) = spawn_holochain_p2p(
+            KitsuneP2pConfig::default(),
+            TlsConfig::new_ephemeral().await.unwrap(),
+            Arc::new(BlockingHost(vec![kitsune_p2p::block::BlockTarget::Agent(
+                a1.to_kitsune(),
+            )])),
+        )
+        .await
+        .unwrap();
+
+        let received = Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        let received_clone = received.clone();
+        let r_task = tokio::task::spawn(async move {
+            use tokio_stream::StreamExt;
+            while let Some(evt) = evt.next().await {
+                use crate::types::event::HolochainP2pEvent::*;
+                match evt {
+                    CallRemote { respond, .. } => {
+                        respond.r(Ok(
+                            async move { Ok(UnsafeBytes::from(b"yada".to_vec()).into()) }
+                                .boxed()
+                                .into(),
+                        ));
+                    }
+                    Publish { respond, ops, .. } => {
+                        received_clone.lock().unwrap().extend(ops);
+                        respond.r(Ok(async move { Ok(()) }.boxed().into()));
+                    }
+                    SignNetworkData { respond, .. } => {
+                        respond.r(Ok(async move { Ok([0; 64].into()) }.boxed().into()));
+                    }
+                    PutAgentInfoSigned { respond, .. } => {
+                        respond.r(Ok(async move { Ok(()) }.boxed().into()));
+                    }
+                    QueryAgentInfoSigned { respond, .. } => {
+                        respond.r(Ok(async move { Ok(vec![]) }.boxed().into()));
+                    }
+                    QueryPeerDensity { respond, .. } => {
+                        let view = test_peer_view();
+                        respond.r(Ok(async move { Ok(view) }.boxed().into()));
+                    }
+                    _ => {}
+                }
+            }
+        });
+
+        p2p.join(dna.clone(), a1.clone(), None).await.unwrap();
+        p2p.join(dna.clone(), a2.clone(), None).await.unwrap();
+
+        // calls from the blocked agent are refused
+        let res = p2p
+            .call_remote(
+                dna.clone(),
+                a1.clone(),
+                a2.clone(),
+                "".into(),
+                "".into(),
+                None,
+                ExternIO::encode(b"yippo").unwrap(),
+            )
+            .await;
+        assert!(res.is_err());
+
+        // ops authored by the blocked agent are dropped,
+        // but warrants against it are kept
+        let action = Action::Dna(holochain_zome_types::Dna {
+            author: a1.clone(),
+            timestamp: Timestamp::now(),
+            hash: dna.clone(),
+        });
+        let warrant = SignedWarrant(
+            Warrant::new(
+                ActionHash::with_data_sync(&action),
+                a1.clone(),
+                "invalid".into(),
+                a2.clone(),
+                Timestamp::now(),
+            ),
+            fixt!(Signature),
+        );
+        let ops = vec![
+            DhtOp::RegisterAgentActivity(fixt!(Signature), action.clone()),
+            DhtOp::RegisterWarrant(fixt!(Signature), action, Box::new(warrant.clone())),
+        ];
+        let _ = p2p
+            .publish(dna, false, false, a1.into(), ops, Some(200))
+            .await;
+
+        let received = received.lock().unwrap().clone();
+        assert!(!received.is_empty());
+        assert!(received.iter().all(|op| op.warrant() == Some(&warrant)));
+
+        p2p.ghost_actor_shutdown().await.unwrap();
+        r_task.await.unwrap();
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn test_get_workflow() {
         observability::test_run().ok();
@@ -546,6 +675,7 @@ mod tests {
             base: hash.into(),
             type_query: LinkTypeFilter::single_dep(0.into()),
             tag: None,
+            filter: Default::default(),
         };
 
         let res = p2p
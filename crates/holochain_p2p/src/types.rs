@@ -26,6 +26,10 @@ pub enum HolochainP2pError {
     #[error("InvalidP2pMessage: {0}")]
     InvalidP2pMessage(String),
 
+    /// The remote agent has been blocked
+    #[error("Agent is blocked: {0}")]
+    AgentBlocked(holo_hash::AgentPubKey),
+
     /// Other
     #[error("Other: {0}")]
     Other(Box<dyn std::error::Error + Send + Sync>),
@@ -272,6 +272,13 @@ ghost_actor::ghost_chan! {
             options: GetLinksOptions,
         ) -> Vec<WireLinkOps>;
 
+        /// Count the live links on a base in the DHT.
+        fn count_links(
+            dna_hash: DnaHash,
+            link_key: WireLinkKey,
+            options: GetLinksOptions,
+        ) -> Vec<CountLinksResponse>;
+
         /// Get agent activity from the DHT.
         fn get_agent_activity(
             dna_hash: DnaHash,
@@ -300,6 +307,10 @@ ghost_actor::ghost_chan! {
         fn dump_network_metrics(
             dna_hash: Option<DnaHash>,
         ) -> String;
+
+        /// The conductor has blocked an agent or node in every dna.
+        /// Open connections to a blocked node are closed.
+        fn block(target: holochain_types::block::BlockTarget) -> ();
     }
 }
 
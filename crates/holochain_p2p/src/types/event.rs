@@ -49,12 +49,18 @@ impl Default for GetRequest {
 }
 
 /// GetMeta options help control how the get is processed at various levels.
-#[derive(Debug, serde::Serialize, serde::Deserialize)]
-pub struct GetMetaOptions {}
+#[derive(Debug, Default, Clone, serde::Serialize, serde::Deserialize)]
+pub struct GetMetaOptions {
+    /// Which metadata to return.
+    #[serde(default)]
+    pub metadata_request: MetadataRequest,
+}
 
 impl From<&actor::GetMetaOptions> for GetMetaOptions {
-    fn from(_a: &actor::GetMetaOptions) -> Self {
-        Self {}
+    fn from(a: &actor::GetMetaOptions) -> Self {
+        Self {
+            metadata_request: a.metadata_request.clone(),
+        }
     }
 }
 
@@ -214,6 +220,14 @@ ghost_actor::ghost_chan! {
             options: GetLinksOptions,
         ) -> WireLinkOps;
 
+        /// A remote node is requesting a count of the links on a base.
+        fn count_links(
+            dna_hash: DnaHash,
+            to_agent: AgentPubKey,
+            link_key: WireLinkKey,
+            options: GetLinksOptions,
+        ) -> CountLinksResponse;
+
         /// A remote node is requesting agent activity from us.
         fn get_agent_activity(
             dna_hash: DnaHash,
@@ -276,6 +290,7 @@ macro_rules! match_p2p_evt {
             HolochainP2pEvent::Get { $i, .. } => { $($t)* }
             HolochainP2pEvent::GetMeta { $i, .. } => { $($t)* }
             HolochainP2pEvent::GetLinks { $i, .. } => { $($t)* }
+            HolochainP2pEvent::CountLinks { $i, .. } => { $($t)* }
             HolochainP2pEvent::GetAgentActivity { $i, .. } => { $($t)* }
             HolochainP2pEvent::ValidationReceiptReceived { $i, .. } => { $($t)* }
             HolochainP2pEvent::SignNetworkData { $i, .. } => { $($t)* }
@@ -55,6 +55,10 @@ pub enum WireMessage {
         link_key: WireLinkKey,
         options: event::GetLinksOptions,
     },
+    CountLinks {
+        link_key: WireLinkKey,
+        options: event::GetLinksOptions,
+    },
     GetAgentActivity {
         agent: AgentPubKey,
         query: ChainQueryFilter,
@@ -129,6 +133,10 @@ impl WireMessage {
         Self::GetLinks { link_key, options }
     }
 
+    pub fn count_links(link_key: WireLinkKey, options: event::GetLinksOptions) -> WireMessage {
+        Self::CountLinks { link_key, options }
+    }
+
     pub fn get_agent_activity(
         agent: AgentPubKey,
         query: ChainQueryFilter,
@@ -3,6 +3,7 @@
 use crate::{
     conn::{new_connection_pool, ConnectionPool, DbSyncLevel, PConn, DATABASE_HANDLES},
     prelude::*,
+    schema::SchemaMigration,
 };
 use derive_more::Into;
 use futures::Future;
@@ -101,13 +102,21 @@ impl<Kind: DbKindT> ReadAccess<Kind> for DbRead<Kind> {
 pub struct DbRead<Kind: DbKindT> {
     kind: Kind,
     path: PathBuf,
-    connection_pool: ConnectionPool,
+    sync_level: DbSyncLevel,
+    connection_pool: Arc<parking_lot::RwLock<KeyedPool>>,
+    migration: Option<SchemaMigration>,
     write_semaphore: Arc<Semaphore>,
     read_semaphore: Arc<Semaphore>,
     max_readers: usize,
     num_readers: Arc<AtomicUsize>,
 }
 
+/// A connection pool along with the key its connections are opened with.
+struct KeyedPool {
+    pool: ConnectionPool,
+    key: Option<DbKey>,
+}
+
 #[derive(Shrinkwrap)]
 #[shrinkwrap(mutable)]
 pub struct PConnGuard(#[shrinkwrap(main_field)] pub PConn, OwnedSemaphorePermit);
@@ -150,11 +159,18 @@ impl<Kind: DbKindT> DbRead<Kind> {
         &self.path
     }
 
+    /// The schema migration which was run when this database was opened,
+    /// if it had to be upgraded.
+    pub fn migration(&self) -> Option<&SchemaMigration> {
+        self.migration.as_ref()
+    }
+
     /// Get a connection from the pool.
     /// TODO: We should eventually swap this for an async solution.
     fn connection_pooled(&self) -> DatabaseResult<PConn> {
         let now = std::time::Instant::now();
-        let r = Ok(PConn::new(self.connection_pool.get()?));
+        let pool = self.connection_pool.read().pool.clone();
+        let r = Ok(PConn::new(pool.get()?));
         let el = now.elapsed();
         if el.as_millis() > 20 {
             tracing::error!("Connection pool took {:?} to be free'd", el);
@@ -211,6 +227,21 @@ impl<Kind: DbKindT + Send + Sync + 'static> DbWrite<Kind> {
         Self::open_with_sync_level(path_prefix, kind, DbSyncLevel::default())
     }
 
+    /// Create or open an existing database reference, encrypted with a key
+    /// derived from the current key of the keyring.
+    /// If the database is still encrypted with one of the previous keys
+    /// or the legacy key, it is re-keyed to the current key.
+    pub fn open_with_keyring(
+        path_prefix: &Path,
+        kind: Kind,
+        sync_level: DbSyncLevel,
+        keyring: &DbKeyring,
+    ) -> DatabaseResult<Self> {
+        DATABASE_HANDLES.get_or_insert(&kind, path_prefix, |kind| {
+            Self::new(Some(path_prefix), kind, sync_level, keyring)
+        })
+    }
+
     pub async fn conn_write_permit(&self) -> PConnPermit {
         let g = self.acquire_writer_permit().await;
         PConnPermit(g)
@@ -221,16 +252,16 @@ impl<Kind: DbKindT + Send + Sync + 'static> DbWrite<Kind> {
         kind: Kind,
         sync_level: DbSyncLevel,
     ) -> DatabaseResult<Self> {
-        DATABASE_HANDLES.get_or_insert(&kind, path_prefix, |kind| {
-            Self::new(Some(path_prefix), kind, sync_level)
-        })
+        Self::open_with_keyring(path_prefix, kind, sync_level, &DbKeyring::default())
     }
 
     pub(crate) fn new(
         path_prefix: Option<&Path>,
         kind: Kind,
         sync_level: DbSyncLevel,
+        keyring: &DbKeyring,
     ) -> DatabaseResult<Self> {
+        let key = keyring.key_for(&kind.filename());
         let path = match path_prefix {
             Some(path_prefix) => {
                 let path = path_prefix.join(kind.filename());
@@ -243,12 +274,7 @@ impl<Kind: DbKindT + Send + Sync + 'static> DbWrite<Kind> {
                 }
                 // Check if the database is valid and take the appropriate
                 // action if it isn't.
-                match Connection::open(&path)
-                    // For some reason calling pragma_update is necessary to prove the database file is valid.
-                    .and_then(|mut c| {
-                        crate::conn::initialize_connection(&mut c, sync_level)?;
-                        c.pragma_update(None, "synchronous", &"0".to_string())
-                    }) {
+                match open_and_rekey(&path, &kind.filename(), sync_level, keyring) {
                     Ok(_) => (),
                     // These are the two errors that can
                     // occur if the database is not valid.
@@ -266,11 +292,30 @@ impl<Kind: DbKindT + Send + Sync + 'static> DbWrite<Kind> {
                         },
                         ..,
                     )) => {
+                        // With encryption, a database whose key isn't in the
+                        // keyring looks just like one which is not a database,
+                        // so it must not be wiped.
+                        let maybe_missing_key = cfg!(feature = "db-encryption")
+                            && matches!(
+                                err,
+                                Err(Error::SqliteFailure(
+                                    rusqlite::ffi::Error {
+                                        code: ErrorCode::NotADatabase,
+                                        ..
+                                    },
+                                    ..,
+                                ))
+                            );
                         // Check if this database kind requires wiping.
-                        if kind.if_corrupt_wipe() {
+                        if kind.if_corrupt_wipe() && !maybe_missing_key {
+                            tracing::warn!(?path, "Wiping corrupt database");
                             std::fs::remove_file(&path)?;
                         } else {
                             // If we don't wipe we need to return an error.
+                            tracing::error!(
+                                ?path,
+                                "Database is corrupt or encrypted with a key which is not held"
+                            );
                             err?;
                         }
                     }
@@ -283,11 +328,11 @@ impl<Kind: DbKindT + Send + Sync + 'static> DbWrite<Kind> {
         };
 
         // Now we know the database file is valid we can open a connection pool.
-        let pool = new_connection_pool(path.as_ref().map(|p| p.as_ref()), sync_level);
+        let pool = new_connection_pool(path.as_ref().map(|p| p.as_ref()), sync_level, key.clone());
         let mut conn = pool.get()?;
         // set to faster write-ahead-log mode
         conn.pragma_update(None, "journal_mode", &"WAL".to_string())?;
-        crate::table::initialize_database(&mut conn, kind.kind())?;
+        let migration = crate::table::initialize_database(&mut conn, kind.kind(), path.as_deref())?;
 
         Ok(DbWrite(DbRead {
             write_semaphore: Self::get_write_semaphore(kind.kind()),
@@ -296,10 +341,53 @@ impl<Kind: DbKindT + Send + Sync + 'static> DbWrite<Kind> {
             num_readers: Arc::new(AtomicUsize::new(0)),
             kind,
             path: path.unwrap_or_default(),
-            connection_pool: pool,
+            sync_level,
+            connection_pool: Arc::new(parking_lot::RwLock::new(KeyedPool { pool, key })),
+            migration,
         }))
     }
 
+    /// Re-encrypt this database with a key derived from the current key of
+    /// the keyring.
+    /// This waits until no connections are in use and then replaces the
+    /// connection pool, so that all new connections use the new key.
+    pub async fn rekey(&self, keyring: &DbKeyring) -> DatabaseResult<()> {
+        let path = self.0.path.clone();
+        // In memory databases are never encrypted.
+        if path.as_os_str().is_empty() {
+            return Ok(());
+        }
+        let _w = self.acquire_writer_permit().await;
+        let _r = self
+            .0
+            .read_semaphore
+            .clone()
+            .acquire_many_owned(self.0.max_readers as u32)
+            .await
+            .expect("We don't ever close these semaphores");
+        let new_key = keyring.key_for(&self.kind().filename());
+        let sync_level = self.0.sync_level;
+        let connection_pool = self.0.connection_pool.clone();
+        task::spawn_blocking(move || {
+            let mut keyed = connection_pool.write();
+            let old_key = keyed.key.clone();
+            // Replace the pool with an empty one so that no other connections
+            // are open while the database is re-keyed.
+            keyed.pool = new_connection_pool(Some(&path), sync_level, old_key.clone());
+            let mut conn = Connection::open(&path)?;
+            crate::conn::initialize_connection(&mut conn, sync_level, old_key.as_ref())?;
+            crate::conn::rekey_connection(&conn, new_key.as_ref())?;
+            drop(conn);
+            *keyed = KeyedPool {
+                pool: new_connection_pool(Some(&path), sync_level, new_key.clone()),
+                key: new_key,
+            };
+            DatabaseResult::Ok(())
+        })
+        .await
+        .map_err(DatabaseError::from)?
+    }
+
     fn get_write_semaphore(kind: DbKind) -> Arc<Semaphore> {
         static MAP: once_cell::sync::Lazy<Mutex<HashMap<DbKind, Arc<Semaphore>>>> =
             once_cell::sync::Lazy::new(|| Mutex::new(HashMap::new()));
@@ -322,12 +410,17 @@ impl<Kind: DbKindT + Send + Sync + 'static> DbWrite<Kind> {
     /// connection pool, useful for testing.
     #[cfg(any(test, feature = "test_utils"))]
     pub fn test(path: &Path, kind: Kind) -> DatabaseResult<Self> {
-        Self::new(Some(path), kind, DbSyncLevel::default())
+        Self::new(
+            Some(path),
+            kind,
+            DbSyncLevel::default(),
+            &DbKeyring::default(),
+        )
     }
 
     #[cfg(any(test, feature = "test_utils"))]
     pub fn test_in_mem(kind: Kind) -> DatabaseResult<Self> {
-        Self::new(None, kind, DbSyncLevel::default())
+        Self::new(None, kind, DbSyncLevel::default(), &DbKeyring::default())
     }
 
     /// Remove the db and directory
@@ -385,6 +478,78 @@ impl<Kind: DbKindT + Send + Sync + 'static> DbWrite<Kind> {
     }
 }
 
+/// Re-key every database file under the database root to the current key
+/// of the keyring, including the databases which are not open.
+///
+/// Databases which are already encrypted with the current key are left as
+/// they are, so this can be used after re-keying the open databases to make
+/// sure that the previous keys of the keyring are no longer needed.
+pub fn rekey_database_files(db_root: &Path, keyring: &DbKeyring) -> DatabaseResult<()> {
+    fn visit(dir: &Path, db_root: &Path, keyring: &DbKeyring) -> DatabaseResult<()> {
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                visit(&path, db_root, keyring)?;
+            } else if path.extension().map_or(false, |ext| ext == "sqlite3") {
+                let filename = path.strip_prefix(db_root).unwrap_or(&path);
+                open_and_rekey(&path, filename, DbSyncLevel::default(), keyring)?;
+            }
+        }
+        Ok(())
+    }
+    if !db_root.is_dir() {
+        return Ok(());
+    }
+    visit(db_root, db_root, keyring)
+}
+
+/// Open the database at the given path to check that it is valid.
+/// The keys of the keyring are tried in turn, and if the database can only
+/// be opened with an older key it is re-keyed to the current one.
+fn open_and_rekey(
+    path: &Path,
+    filename: &Path,
+    sync_level: DbSyncLevel,
+    keyring: &DbKeyring,
+) -> rusqlite::Result<()> {
+    let open = |key: Option<&DbKey>| {
+        Connection::open(path).and_then(|mut c| {
+            crate::conn::initialize_connection(&mut c, sync_level, key)?;
+            // For some reason calling pragma_update is necessary to prove the database file is valid.
+            c.pragma_update(None, "synchronous", &"0".to_string())?;
+            // Reading the schema proves that the key is correct.
+            c.query_row("SELECT count(*) FROM sqlite_master", [], |_| Ok(()))?;
+            Ok(c)
+        })
+    };
+    let mut candidates = keyring.candidates_for(filename).into_iter();
+    let current = candidates.next().flatten();
+    let err = match open(current.as_ref()) {
+        Ok(_) => return Ok(()),
+        Err(err) => err,
+    };
+    // A wrong key looks exactly like a file which is not a database.
+    if !matches!(
+        err,
+        Error::SqliteFailure(
+            rusqlite::ffi::Error {
+                code: ErrorCode::NotADatabase,
+                ..
+            },
+            ..,
+        )
+    ) {
+        return Err(err);
+    }
+    for candidate in candidates {
+        if let Ok(conn) = open(candidate.as_ref()) {
+            tracing::info!(?filename, "Re-keying database to the current key");
+            return crate::conn::rekey_connection(&conn, current.as_ref());
+        }
+    }
+    Err(err)
+}
+
 pub fn num_read_threads() -> usize {
     let num_cpus = num_cpus::get();
     let num_threads = num_cpus.checked_div(2).unwrap_or(0);
@@ -0,0 +1,288 @@
+//! Keys used to encrypt databases at rest.
+//!
+//! Each database is encrypted with its own key, which is derived from a
+//! single conductor-wide master [`DbKey`]. The master key itself never
+//! touches the disk in the clear: it is wrapped by the keystore and only
+//! unwrapped in memory when the conductor starts up.
+//!
+//! Encryption only takes effect when the `db-encryption` feature is enabled.
+
+use std::path::Path;
+use std::sync::Arc;
+
+/// Personalization for deriving per-database keys from a master key.
+const DB_KEY_PERSONAL: &[u8] = b"hc_db_key";
+
+/// A 32 byte secret key used for database encryption.
+#[derive(Clone, PartialEq, Eq)]
+pub struct DbKey(Arc<[u8; 32]>);
+
+impl std::fmt::Debug for DbKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("DbKey(<redacted>)")
+    }
+}
+
+impl DbKey {
+    /// Construct a key from raw bytes.
+    pub fn from_bytes(bytes: [u8; 32]) -> Self {
+        Self(Arc::new(bytes))
+    }
+
+    /// Generate a new random key.
+    pub fn generate() -> Self {
+        use rand::RngCore;
+        let mut bytes = [0; 32];
+        rand::thread_rng().fill_bytes(&mut bytes);
+        Self::from_bytes(bytes)
+    }
+
+    /// Accessor for the raw key bytes.
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    /// Derive the key for the database at the given path, relative to the
+    /// database root. Every database therefore has a distinct key, even
+    /// though they all share the same master key.
+    pub fn derive(&self, filename: &Path) -> Self {
+        let hash = blake2b_simd::Params::new()
+            .hash_length(32)
+            .key(&self.0[..])
+            .personal(DB_KEY_PERSONAL)
+            .hash(filename.to_string_lossy().as_bytes());
+        let mut bytes = [0; 32];
+        bytes.copy_from_slice(hash.as_bytes());
+        Self::from_bytes(bytes)
+    }
+
+    /// The value to pass to the SQLCipher `key` and `rekey` pragmas,
+    /// as a raw hex key literal.
+    pub(crate) fn pragma_value(&self) -> String {
+        let mut out = String::with_capacity(67);
+        out.push_str("x'");
+        for b in self.0.iter() {
+            out.push_str(&format!("{:02X}", b));
+        }
+        out.push('\'');
+        out
+    }
+}
+
+/// The master key databases are encrypted with, along with any previous
+/// master keys which databases may still be encrypted with.
+///
+/// Databases that are opened with one of the previous keys are re-keyed
+/// to the current key. A keyring without a current key uses the fixed
+/// legacy key that databases were encrypted with before keys were held
+/// in the keystore.
+#[derive(Clone, Debug, Default)]
+pub struct DbKeyring {
+    current: Option<DbKey>,
+    previous: Vec<DbKey>,
+}
+
+impl DbKeyring {
+    /// Constructor.
+    pub fn new(current: DbKey, previous: Vec<DbKey>) -> Self {
+        Self {
+            current: Some(current),
+            previous,
+        }
+    }
+
+    /// Accessor
+    pub fn current(&self) -> Option<&DbKey> {
+        self.current.as_ref()
+    }
+
+    /// Accessor
+    pub fn previous(&self) -> &[DbKey] {
+        &self.previous
+    }
+
+    /// Replace the current key, keeping the old current key as the most
+    /// recent previous key.
+    pub fn rotate(&self, new_key: DbKey) -> Self {
+        let previous = self
+            .current
+            .iter()
+            .chain(self.previous.iter())
+            .cloned()
+            .collect();
+        Self::new(new_key, previous)
+    }
+
+    /// Drop all previous keys.
+    pub fn without_previous(&self) -> Self {
+        Self {
+            current: self.current.clone(),
+            previous: Vec::new(),
+        }
+    }
+
+    /// The key for the database at the given path, relative to the
+    /// database root. `None` means the legacy key.
+    pub(crate) fn key_for(&self, filename: &Path) -> Option<DbKey> {
+        self.current.as_ref().map(|k| k.derive(filename))
+    }
+
+    /// All keys the database at the given path may currently be encrypted
+    /// with, in the order they should be tried. The legacy key comes last.
+    pub(crate) fn candidates_for(&self, filename: &Path) -> Vec<Option<DbKey>> {
+        self.current
+            .iter()
+            .chain(self.previous.iter())
+            .map(|k| Some(k.derive(filename)))
+            .chain(std::iter::once(None))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn derived_keys_are_distinct_per_database() {
+        let master = DbKey::generate();
+        let a = master.derive(Path::new("dht/dht-a.sqlite3"));
+        let b = master.derive(Path::new("dht/dht-b.sqlite3"));
+        assert_ne!(a, b);
+        assert_eq!(a, master.derive(Path::new("dht/dht-a.sqlite3")));
+        assert_ne!(a, DbKey::generate().derive(Path::new("dht/dht-a.sqlite3")));
+    }
+
+    #[test]
+    fn keyring_candidates_end_with_legacy_key() {
+        let path = Path::new("conductor/conductor.sqlite3");
+        assert_eq!(DbKeyring::default().candidates_for(path), vec![None]);
+
+        let old = DbKey::generate();
+        let new = DbKey::generate();
+        let keyring = DbKeyring::new(old.clone(), vec![]).rotate(new.clone());
+        assert_eq!(
+            keyring.candidates_for(path),
+            vec![Some(new.derive(path)), Some(old.derive(path)), None]
+        );
+        assert_eq!(
+            keyring.without_previous().candidates_for(path),
+            vec![Some(new.derive(path)), None]
+        );
+        assert_eq!(keyring.key_for(path), Some(new.derive(path)));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn database_is_readable_after_rekey() {
+        let dir = tempfile::tempdir().unwrap();
+        let old = DbKeyring::new(DbKey::generate(), vec![]);
+        let db = crate::db::DbWrite::new(
+            Some(dir.path()),
+            crate::db::DbKindConductor,
+            Default::default(),
+            &old,
+        )
+        .unwrap();
+        db.test_commit(|txn| txn.pragma_update(None, "application_id", &42).unwrap());
+
+        let new = old.rotate(DbKey::generate());
+        db.rekey(&new).await.unwrap();
+        let version: u32 = db
+            .conn()
+            .unwrap()
+            .query_row("PRAGMA application_id", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(version, 42);
+
+        // The database can be reopened with just the new key.
+        drop(db);
+        let db = crate::db::DbWrite::new(
+            Some(dir.path()),
+            crate::db::DbKindConductor,
+            Default::default(),
+            &new.without_previous(),
+        )
+        .unwrap();
+        let version: u32 = db
+            .conn()
+            .unwrap()
+            .query_row("PRAGMA application_id", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(version, 42);
+    }
+
+    #[test]
+    fn databases_which_are_not_open_are_rekeyed() {
+        let dir = tempfile::tempdir().unwrap();
+        let old = DbKeyring::new(DbKey::generate(), vec![]);
+        let db = crate::db::DbWrite::new(
+            Some(dir.path()),
+            crate::db::DbKindConductor,
+            Default::default(),
+            &old,
+        )
+        .unwrap();
+        db.test_commit(|txn| txn.pragma_update(None, "application_id", &42).unwrap());
+        drop(db);
+
+        let new = old.rotate(DbKey::generate());
+        crate::db::rekey_database_files(dir.path(), &new).unwrap();
+
+        // The database can be opened with just the new key.
+        let db = crate::db::DbWrite::new(
+            Some(dir.path()),
+            crate::db::DbKindConductor,
+            Default::default(),
+            &new.without_previous(),
+        )
+        .unwrap();
+        let version: u32 = db
+            .conn()
+            .unwrap()
+            .query_row("PRAGMA application_id", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(version, 42);
+    }
+
+    /// A database which would be wiped if it were corrupt is kept when it
+    /// can't be opened because its key is missing.
+    #[cfg(feature = "db-encryption")]
+    #[test]
+    fn database_with_missing_key_is_not_wiped() {
+        let dir = tempfile::tempdir().unwrap();
+        let kind = crate::db::DbKindCache(Arc::new(holo_hash::DnaHash::from_raw_32(vec![0; 32])));
+        let keyring = DbKeyring::new(DbKey::generate(), vec![]);
+        let db =
+            crate::db::DbWrite::new(Some(dir.path()), kind.clone(), Default::default(), &keyring)
+                .unwrap();
+        db.test_commit(|txn| txn.pragma_update(None, "application_id", &42).unwrap());
+        drop(db);
+
+        let other = DbKeyring::new(DbKey::generate(), vec![]);
+        assert!(crate::db::DbWrite::new(
+            Some(dir.path()),
+            kind.clone(),
+            Default::default(),
+            &other
+        )
+        .is_err());
+
+        let db =
+            crate::db::DbWrite::new(Some(dir.path()), kind, Default::default(), &keyring).unwrap();
+        let version: u32 = db
+            .conn()
+            .unwrap()
+            .query_row("PRAGMA application_id", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(version, 42);
+    }
+
+    #[test]
+    fn pragma_value_is_hex_literal() {
+        let key = DbKey::from_bytes([0xab; 32]);
+        let value = key.pragma_value();
+        assert_eq!(value.len(), 67);
+        assert!(value.starts_with("x'ABAB"));
+        assert!(value.ends_with("AB'"));
+    }
+}
@@ -52,6 +52,13 @@ pub enum DatabaseError {
 
     #[error("transparent")]
     FailedToJoinBlocking(#[from] tokio::task::JoinError),
+
+    #[error("The database {db_kind} has schema version {found}, but this version of Holochain only supports up to version {supported}")]
+    SchemaVersionTooNew {
+        db_kind: String,
+        found: u16,
+        supported: u16,
+    },
 }
 
 impl PartialEq for DatabaseError {
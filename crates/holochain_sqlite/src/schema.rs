@@ -1,138 +1,288 @@
+use std::path::{Path, PathBuf};
+
 use once_cell::sync::Lazy;
 use rusqlite::Connection;
 
 use crate::db::DbKind;
+use crate::error::{DatabaseError, DatabaseResult};
 use crate::sql::*;
 
-pub static SCHEMA_CELL: Lazy<Schema> = Lazy::new(|| {
-    let migration_0 = Migration::initial(sql_cell::SCHEMA);
-
-    Schema {
-        current_index: 0,
-        migrations: vec![migration_0],
-    }
+pub static SCHEMA_CELL: Lazy<Schema> = Lazy::new(|| Schema {
+    migrations: vec![
+        Migration::initial(sql_cell::SCHEMA),
+        Migration::forward(sql_cell::migrations::ADD_INDEXES),
+        Migration::forward(sql_cell::migrations::ADD_WARRANTS),
+    ],
 });
 
-pub static SCHEMA_CONDUCTOR: Lazy<Schema> = Lazy::new(|| {
-    let migration_0 = Migration::initial(sql_conductor::SCHEMA);
-
-    Schema {
-        current_index: 0,
-        migrations: vec![migration_0],
-    }
+pub static SCHEMA_CONDUCTOR: Lazy<Schema> = Lazy::new(|| Schema {
+    migrations: vec![
+        Migration::initial(sql_conductor::SCHEMA),
+        Migration::forward(sql_conductor::migrations::ADD_BLOCKS),
+    ],
 });
 
-pub static SCHEMA_WASM: Lazy<Schema> = Lazy::new(|| {
-    let migration_0 = Migration::initial(sql_wasm::SCHEMA);
-
-    Schema {
-        current_index: 0,
-        migrations: vec![migration_0],
-    }
+pub static SCHEMA_WASM: Lazy<Schema> = Lazy::new(|| Schema {
+    migrations: vec![Migration::initial(sql_wasm::SCHEMA)],
 });
 
-pub static SCHEMA_P2P_STATE: Lazy<Schema> = Lazy::new(|| {
-    let migration_0 = Migration::initial(sql_p2p_agent_store::SCHEMA);
-
-    Schema {
-        current_index: 0,
-        migrations: vec![migration_0],
-    }
+pub static SCHEMA_P2P_STATE: Lazy<Schema> = Lazy::new(|| Schema {
+    migrations: vec![Migration::initial(sql_p2p_agent_store::SCHEMA)],
 });
 
-pub static SCHEMA_P2P_METRICS: Lazy<Schema> = Lazy::new(|| {
-    let migration_0 = Migration::initial(sql_p2p_metrics::SCHEMA);
-
-    Schema {
-        current_index: 0,
-        migrations: vec![migration_0],
-    }
+pub static SCHEMA_P2P_METRICS: Lazy<Schema> = Lazy::new(|| Schema {
+    migrations: vec![Migration::initial(sql_p2p_metrics::SCHEMA)],
 });
 
+/// A chain of migrations for one kind of database.
+///
+/// The first migration creates the initial schema, every following
+/// migration upgrades the schema from the previous version.
+/// The `user_version` pragma of a database holds the number of migrations
+/// which have been run on it, so a database created from this schema has
+/// the version `migrations.len()`.
 pub struct Schema {
-    current_index: usize,
     migrations: Vec<Migration>,
 }
 
+/// A record of the forward migrations which were run on a database
+/// when it was opened.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SchemaMigration {
+    /// The version of the database before it was migrated.
+    pub from_version: u16,
+    /// The version of the database after it was migrated.
+    pub to_version: u16,
+    /// A copy of the database as it was before it was migrated.
+    pub backup: Option<PathBuf>,
+}
+
 impl Schema {
+    /// The version of databases created or migrated with this schema.
+    pub fn current_version(&self) -> u16 {
+        self.migrations.len() as u16
+    }
+
     /// Determine if any database migrations need to run, and run them if so.
     /// The decision is based on the difference between this Schema's
-    /// current_index and the user_version pragma value in the database itself.
-    /// NB: The current_index is 0-based, and the user_version is 1-based.
+    /// current version and the user_version pragma value in the database itself.
+    ///
+    /// All pending migrations are run in a single transaction. If the
+    /// database at `path` already has data in it, it is backed up before
+    /// migrating. Databases which were created by a newer version of
+    /// Holochain can't be opened.
     pub fn initialize(
         &self,
         conn: &mut Connection,
         db_kind: Option<DbKind>,
-    ) -> rusqlite::Result<()> {
-        let user_version: u16 = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+        path: Option<&Path>,
+    ) -> DatabaseResult<Option<SchemaMigration>> {
         let db_kind = db_kind
             .as_ref()
             .map(ToString::to_string)
             .unwrap_or_else(|| "<no name>".to_string());
+        let current_version = self.current_version();
+        let user_version: u16 = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
 
-        if user_version == 0 {
-            // database just needs to be created / initialized
-            self.migrations[self.current_index].initialize(conn)?;
-            tracing::info!("database initialized: {}", db_kind);
-            return Ok(());
+        // Databases created before versions were recorded have the
+        // initial schema but a user_version of 0.
+        let (is_empty, is_legacy) = if user_version == 0 {
+            let num_tables: u32 = conn.query_row(
+                "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table'",
+                [],
+                |row| row.get(0),
+            )?;
+            (num_tables == 0, num_tables > 0)
         } else {
-            let current_index = user_version as usize - 1;
-            match current_index.cmp(&self.current_index) {
-                std::cmp::Ordering::Less => {
-                    // run forward migrations
-                    for v in current_index..self.current_index + 1 {
-                        self.migrations[v].run(conn)?;
-                    }
-                    // set the DB user_version so that next time we don't run
-                    // the same migration
-                    let new_user_version = (self.current_index + 1) as u16;
-                    conn.pragma_update(None, "user_version", &new_user_version)?;
-                    tracing::info!(
-                        "database forward migrated: {} from {} to {}",
-                        db_kind,
-                        current_index,
-                        self.current_index
-                    );
-                }
-                std::cmp::Ordering::Equal => {
-                    tracing::debug!(
-                        "database needed no migration or initialization, good to go: {}",
-                        db_kind
-                    );
-                }
-                std::cmp::Ordering::Greater => {
-                    unimplemented!("backward migrations unimplemented");
-                }
-            }
+            (false, false)
+        };
+        let from_version = if is_legacy { 1 } else { user_version };
+
+        if from_version > current_version {
+            return Err(DatabaseError::SchemaVersionTooNew {
+                db_kind,
+                found: from_version,
+                supported: current_version,
+            });
+        }
+        if user_version == current_version {
+            tracing::debug!(
+                "database needed no migration or initialization, good to go: {}",
+                db_kind
+            );
+            return Ok(None);
         }
 
-        Ok(())
+        let is_upgrade = !is_empty && from_version < current_version;
+        let backup = match path {
+            Some(path) if is_upgrade => Some(Self::backup(conn, path, from_version)?),
+            _ => None,
+        };
+
+        let txn = conn.transaction()?;
+        for migration in &self.migrations[user_version as usize..] {
+            migration.run(&txn)?;
+        }
+        // set the DB user_version so that next time we don't run
+        // the same migrations
+        txn.pragma_update(None, "user_version", &current_version)?;
+        txn.commit()?;
+
+        if is_upgrade {
+            tracing::info!(
+                "database forward migrated: {} from {} to {}",
+                db_kind,
+                from_version,
+                current_version
+            );
+            Ok(Some(SchemaMigration {
+                from_version,
+                to_version: current_version,
+                backup,
+            }))
+        } else {
+            tracing::info!("database initialized: {}", db_kind);
+            Ok(None)
+        }
+    }
+
+    /// Copy the database file next to itself, with the version it is being
+    /// migrated from in the name. The copy is encrypted just like the original.
+    fn backup(conn: &Connection, path: &Path, version: u16) -> DatabaseResult<PathBuf> {
+        // Make sure all changes are in the database file itself.
+        conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))?;
+        let mut backup = path.as_os_str().to_owned();
+        backup.push(format!(".v{}.bak", version));
+        let backup = PathBuf::from(backup);
+        std::fs::copy(path, &backup)?;
+        Ok(backup)
     }
 }
 
 pub struct Migration {
-    schema: Sql,
-    _forward: Sql,
-    _backward: Option<Sql>,
+    sql: Sql,
 }
 
 impl Migration {
+    /// The migration which creates the initial schema.
+    /// Its statements must be idempotent, because it is also run on
+    /// databases created before versions were recorded.
     pub fn initial(schema: &str) -> Self {
-        Self {
-            schema: schema.into(),
-            _forward: "".into(),
-            _backward: None,
-        }
+        Self { sql: schema.into() }
     }
 
-    pub fn initialize(&self, conn: &mut Connection) -> rusqlite::Result<()> {
-        conn.execute_batch(&self.schema)?;
-        Ok(())
+    /// A migration from the previous version of the schema.
+    pub fn forward(sql: &str) -> Self {
+        Self { sql: sql.into() }
     }
 
-    pub fn run(&self, _conn: &mut Connection) -> rusqlite::Result<()> {
-        unimplemented!("actual migrations not yet implemented")
+    pub fn run(&self, conn: &Connection) -> rusqlite::Result<()> {
+        conn.execute_batch(&self.sql)?;
+        Ok(())
     }
 }
 
 type Sql = String;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema() -> Schema {
+        Schema {
+            migrations: vec![
+                Migration::initial("CREATE TABLE IF NOT EXISTS Foo (a INTEGER);"),
+                Migration::forward("ALTER TABLE Foo ADD COLUMN b INTEGER;"),
+            ],
+        }
+    }
+
+    fn user_version(conn: &Connection) -> u16 {
+        conn.pragma_query_value(None, "user_version", |row| row.get(0))
+            .unwrap()
+    }
+
+    #[test]
+    fn new_database_is_created_at_current_version() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        assert_eq!(schema().initialize(&mut conn, None, None).unwrap(), None);
+        assert_eq!(user_version(&conn), 2);
+        conn.execute("INSERT INTO Foo (a, b) VALUES (1, 2)", [])
+            .unwrap();
+    }
+
+    #[test]
+    fn database_is_backed_up_and_migrated() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("test.sqlite3");
+        let mut conn = Connection::open(&path).unwrap();
+        // A database created before versions were recorded.
+        conn.execute_batch("CREATE TABLE Foo (a INTEGER); INSERT INTO Foo (a) VALUES (1);")
+            .unwrap();
+
+        let migration = schema()
+            .initialize(&mut conn, None, Some(&path))
+            .unwrap()
+            .unwrap();
+        assert_eq!(migration.from_version, 1);
+        assert_eq!(migration.to_version, 2);
+        assert_eq!(user_version(&conn), 2);
+        let b: Option<i64> = conn
+            .query_row("SELECT b FROM Foo WHERE a = 1", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(b, None);
+
+        let backup = Connection::open(migration.backup.unwrap()).unwrap();
+        assert!(backup
+            .query_row("SELECT b FROM Foo", [], |row| row.get::<_, i64>(0))
+            .is_err());
+
+        // Nothing left to migrate.
+        assert_eq!(
+            schema().initialize(&mut conn, None, Some(&path)).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn failed_migration_is_rolled_back() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        Schema {
+            migrations: vec![Migration::initial("CREATE TABLE Foo (a INTEGER);")],
+        }
+        .initialize(&mut conn, None, None)
+        .unwrap();
+
+        let broken = Schema {
+            migrations: vec![
+                Migration::initial("CREATE TABLE IF NOT EXISTS Foo (a INTEGER);"),
+                Migration::forward("CREATE TABLE Bar (a INTEGER); NOT SQL;"),
+            ],
+        };
+        assert!(broken.initialize(&mut conn, None, None).is_err());
+        assert_eq!(user_version(&conn), 1);
+        let bar: u32 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM sqlite_master WHERE name = 'Bar'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(bar, 0);
+    }
+
+    #[test]
+    fn newer_database_is_refused() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        schema().initialize(&mut conn, None, None).unwrap();
+        conn.pragma_update(None, "user_version", &3).unwrap();
+        assert!(matches!(
+            schema().initialize(&mut conn, None, None),
+            Err(DatabaseError::SchemaVersionTooNew {
+                found: 3,
+                supported: 2,
+                ..
+            })
+        ));
+    }
+}
@@ -25,6 +25,15 @@ pub mod sql_cell {
 
     pub const FETCH_OP: &str = include_str!("sql/cell/fetch_op.sql");
 
+    pub const DELETE_ALL: &str = include_str!("sql/cell/delete_all.sql");
+
+    pub(crate) mod migrations {
+        pub(crate) const ADD_INDEXES: &str =
+            include_str!("sql/cell/migrations/0001_add_indexes.sql");
+        pub(crate) const ADD_WARRANTS: &str =
+            include_str!("sql/cell/migrations/0002_add_warrants.sql");
+    }
+
     pub mod schedule {
         pub const UPDATE: &str = include_str!("sql/cell/schedule/update.sql");
         pub const DELETE: &str = include_str!("sql/cell/schedule/delete.sql");
@@ -47,6 +56,11 @@ pub mod sql_cell {
 
 pub(crate) mod sql_conductor {
     pub(crate) const SCHEMA: &str = include_str!("sql/conductor/schema.sql");
+
+    pub(crate) mod migrations {
+        pub(crate) const ADD_BLOCKS: &str =
+            include_str!("sql/conductor/migrations/0001_add_blocks.sql");
+    }
 }
 
 pub(crate) mod sql_wasm {
@@ -0,0 +1,9 @@
+-- no-sql-format --
+
+DELETE FROM ValidationReceipt;
+DELETE FROM Warrant;
+DELETE FROM DhtOp;
+DELETE FROM Action;
+DELETE FROM Entry;
+DELETE FROM ChainLock;
+DELETE FROM ScheduledFunctions;
@@ -2,10 +2,12 @@ SELECT
   DhtOp.hash,
   DhtOp.type,
   Action.blob AS action_blob,
-  Entry.blob AS entry_blob
+  Entry.blob AS entry_blob,
+  Warrant.blob AS warrant_blob
 FROM
   DhtOp
   JOIN Action ON DhtOp.action_hash = Action.hash
   LEFT JOIN Entry ON Action.entry_hash = Entry.hash
+  LEFT JOIN Warrant ON DhtOp.hash = Warrant.hash
 WHERE
   DhtOp.hash = :hash
@@ -0,0 +1,7 @@
+-- no-sql-format --
+
+-- Validation receipts are looked up and deleted by the op they are for.
+CREATE INDEX IF NOT EXISTS ValidationReceipt_op_hash_idx ON ValidationReceipt ( op_hash );
+
+-- Records and updates are looked up by the entry they create.
+CREATE INDEX IF NOT EXISTS Action_entry_hash_idx ON Action ( entry_hash );
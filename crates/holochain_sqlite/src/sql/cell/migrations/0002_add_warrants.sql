@@ -0,0 +1,17 @@
+-- no-sql-format --
+
+-- Warrants against agents, keyed by the hash of the RegisterWarrant op
+-- which carries them. The warranted action is stored in the Action table.
+CREATE TABLE IF NOT EXISTS Warrant (
+    hash            BLOB           PRIMARY KEY ON CONFLICT IGNORE,
+    action_hash     BLOB           NOT NULL,
+    author          BLOB           NOT NULL,
+    warrantor       BLOB           NOT NULL,
+
+    -- SignedWarrant
+    blob            BLOB           NOT NULL,
+
+    FOREIGN KEY(hash) REFERENCES DhtOp(hash) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS Warrant_author_idx ON Warrant ( author );
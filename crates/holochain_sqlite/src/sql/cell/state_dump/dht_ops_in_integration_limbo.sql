@@ -2,6 +2,7 @@
 SELECT
   Action.blob as action_blob,
   Entry.blob as entry_blob,
+  Warrant.blob as warrant_blob,
   DhtOp.type as dht_type,
   DhtOp.hash as dht_hash,
   DhtOp.rowid as rowid
@@ -9,6 +10,7 @@ FROM
   Action
   JOIN DhtOp ON DhtOp.action_hash = Action.hash
   LEFT JOIN Entry ON Action.entry_hash = Entry.hash
+  LEFT JOIN Warrant ON DhtOp.hash = Warrant.hash
 WHERE
   when_integrated IS NULL
   AND validation_stage = 3
@@ -0,0 +1,14 @@
+-- no-sql-format --
+
+-- Remote agents and nodes the conductor refuses to communicate with.
+-- `target_type` is 'agent' or 'node', and `target_id` is the agent's
+-- public key or the node's certificate digest.
+CREATE TABLE IF NOT EXISTS BlockedTarget (
+    target_type     TEXT           NOT NULL,
+    target_id       BLOB           NOT NULL,
+
+    -- NULL if the block lasts until it is lifted.
+    expires_at      INTEGER        NULL,
+
+    PRIMARY KEY (target_type, target_id) ON CONFLICT REPLACE
+);
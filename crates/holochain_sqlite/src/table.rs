@@ -1,33 +1,27 @@
 //! Functionality for safely accessing databases.
 
+use std::path::Path;
+
 use rusqlite::Connection;
 
 use crate::db::DbKind;
+use crate::error::DatabaseResult;
+use crate::schema::SchemaMigration;
 
 /// Enumeration of all databases needed by Holochain
-pub(crate) fn initialize_database(conn: &mut Connection, db_kind: DbKind) -> rusqlite::Result<()> {
-    match db_kind {
-        DbKind::Dht(_) => {
-            crate::schema::SCHEMA_CELL.initialize(conn, Some(db_kind))?;
-        }
-        DbKind::Authored(_) => {
-            crate::schema::SCHEMA_CELL.initialize(conn, Some(db_kind))?;
-        }
-        DbKind::Conductor => {
-            crate::schema::SCHEMA_CONDUCTOR.initialize(conn, Some(db_kind))?;
-        }
-        DbKind::Wasm => {
-            crate::schema::SCHEMA_WASM.initialize(conn, Some(db_kind))?;
-        }
-        DbKind::P2pAgentStore(_) => {
-            crate::schema::SCHEMA_P2P_STATE.initialize(conn, Some(db_kind))?;
-        }
-        DbKind::P2pMetrics(_) => {
-            crate::schema::SCHEMA_P2P_METRICS.initialize(conn, Some(db_kind))?;
-        }
-        DbKind::Cache(_) => {
-            crate::schema::SCHEMA_CELL.initialize(conn, Some(db_kind))?;
-        }
-    }
-    Ok(())
+pub(crate) fn initialize_database(
+    conn: &mut Connection,
+    db_kind: DbKind,
+    path: Option<&Path>,
+) -> DatabaseResult<Option<SchemaMigration>> {
+    let schema = match db_kind {
+        DbKind::Dht(_) => &crate::schema::SCHEMA_CELL,
+        DbKind::Authored(_) => &crate::schema::SCHEMA_CELL,
+        DbKind::Conductor => &crate::schema::SCHEMA_CONDUCTOR,
+        DbKind::Wasm => &crate::schema::SCHEMA_WASM,
+        DbKind::P2pAgentStore(_) => &crate::schema::SCHEMA_P2P_STATE,
+        DbKind::P2pMetrics(_) => &crate::schema::SCHEMA_P2P_METRICS,
+        DbKind::Cache(_) => &crate::schema::SCHEMA_CELL,
+    };
+    schema.initialize(conn, Some(db_kind), path)
 }
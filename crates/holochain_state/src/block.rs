@@ -0,0 +1,156 @@
+//! Queries on the agents and nodes the conductor has blocked.
+//! Blocks are inserted and deleted with [`insert_block`](crate::mutations::insert_block)
+//! and [`delete_block`](crate::mutations::delete_block), and expired blocks are
+//! pruned with [`delete_expired_blocks`](crate::mutations::delete_expired_blocks).
+
+use crate::prelude::StateQueryResult;
+use holo_hash::AgentPubKey;
+use holochain_sqlite::rusqlite::named_params;
+use holochain_sqlite::rusqlite::types::Type;
+use holochain_sqlite::rusqlite::OptionalExtension;
+use holochain_sqlite::rusqlite::Transaction;
+use holochain_types::block::{Block, BlockTarget};
+use holochain_zome_types::Timestamp;
+
+const AGENT: &str = "agent";
+const NODE: &str = "node";
+
+/// The `target_type` and `target_id` columns a target is stored in.
+pub(crate) fn target_columns(target: &BlockTarget) -> (&'static str, &[u8]) {
+    match target {
+        BlockTarget::Agent(agent) => (AGENT, agent.get_raw_39()),
+        BlockTarget::Node(cert) => (NODE, cert.as_ref()),
+    }
+}
+
+/// True if the target is blocked, and the block hasn't expired at `now`.
+pub fn is_blocked(
+    txn: &Transaction,
+    target: &BlockTarget,
+    now: Timestamp,
+) -> StateQueryResult<bool> {
+    let (target_type, target_id) = target_columns(target);
+    let blocked = txn
+        .query_row(
+            "
+            SELECT 1
+            FROM BlockedTarget
+            WHERE target_type = :target_type
+            AND target_id = :target_id
+            AND (expires_at IS NULL OR expires_at > :now)
+            ",
+            named_params! {
+                ":target_type": target_type,
+                ":target_id": target_id,
+                ":now": now,
+            },
+            |row| row.get::<_, u32>(0),
+        )
+        .optional()?;
+    Ok(blocked.is_some())
+}
+
+/// All the blocks which haven't expired at `now`.
+pub fn list_blocks(txn: &Transaction, now: Timestamp) -> StateQueryResult<Vec<Block>> {
+    let mut stmt = txn.prepare(
+        "
+        SELECT target_type, target_id, expires_at
+        FROM BlockedTarget
+        WHERE expires_at IS NULL OR expires_at > :now
+        ",
+    )?;
+    let blocks = stmt.query_map(named_params! { ":now": now }, |row| {
+        let target_type: String = row.get("target_type")?;
+        let target = match target_type.as_str() {
+            AGENT => BlockTarget::Agent(row.get::<_, AgentPubKey>("target_id")?),
+            NODE => BlockTarget::Node(row.get::<_, Vec<u8>>("target_id")?.into()),
+            _ => {
+                return Err(holochain_sqlite::rusqlite::Error::InvalidColumnType(
+                    0,
+                    target_type,
+                    Type::Text,
+                ))
+            }
+        };
+        Ok(Block::new(target, row.get("expires_at")?))
+    })?;
+    Ok(blocks.collect::<Result<_, _>>()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mutations;
+    use fixt::prelude::*;
+    use holo_hash::fixt::AgentPubKeyFixturator;
+    use holochain_sqlite::prelude::*;
+
+    #[test]
+    fn block_expire_and_unblock() {
+        let test_db = crate::test_utils::test_conductor_db();
+        let db = test_db.to_db();
+
+        let now = Timestamp::now();
+        let later = (now + std::time::Duration::from_secs(60)).unwrap();
+        let agent = BlockTarget::Agent(fixt!(AgentPubKey));
+        let node = BlockTarget::Node(vec![1; 32].into());
+        let expiring = Block::new(agent.clone(), Some(later));
+        let forever = Block::new(node.clone(), None);
+
+        db.conn()
+            .unwrap()
+            .with_commit_sync(|txn| {
+                mutations::insert_block(txn, &expiring)?;
+                mutations::insert_block(txn, &forever)
+            })
+            .unwrap();
+
+        db.conn().unwrap().with_reader_test(|txn| {
+            assert!(is_blocked(&txn, &agent, now).unwrap());
+            assert!(is_blocked(&txn, &node, now).unwrap());
+            let mut blocks = list_blocks(&txn, now).unwrap();
+            blocks.sort_by(|a, b| a.target.cmp(&b.target));
+            assert_eq!(blocks, vec![expiring.clone(), forever.clone()]);
+
+            // Only the block without an expiry outlasts `later`.
+            assert!(!is_blocked(&txn, &agent, later).unwrap());
+            assert!(is_blocked(&txn, &node, later).unwrap());
+            assert_eq!(list_blocks(&txn, later).unwrap(), vec![forever.clone()]);
+        });
+
+        let deleted = db
+            .conn()
+            .unwrap()
+            .with_commit_sync(|txn| {
+                Ok::<_, mutations::StateMutationError>((
+                    mutations::delete_block(txn, &node)?,
+                    mutations::delete_block(txn, &node)?,
+                ))
+            })
+            .unwrap();
+        assert_eq!(deleted, (true, false));
+
+        db.conn().unwrap().with_reader_test(|txn| {
+            assert!(!is_blocked(&txn, &node, now).unwrap());
+            assert_eq!(list_blocks(&txn, now).unwrap(), vec![expiring.clone()]);
+        });
+
+        // Expired blocks are pruned, the others are kept.
+        let pruned = db
+            .conn()
+            .unwrap()
+            .with_commit_sync(|txn| {
+                mutations::insert_block(txn, &forever)?;
+                Ok::<_, mutations::StateMutationError>((
+                    mutations::delete_expired_blocks(txn, now)?,
+                    mutations::delete_expired_blocks(txn, later)?,
+                ))
+            })
+            .unwrap();
+        assert_eq!(pruned, (0, 1));
+
+        db.conn().unwrap().with_reader_test(|txn| {
+            assert_eq!(list_blocks(&txn, now).unwrap(), vec![forever]);
+        });
+    }
+}
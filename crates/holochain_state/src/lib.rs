@@ -35,6 +35,7 @@
 
 #![allow(deprecated)]
 
+pub mod block;
 pub mod chain_lock;
 #[allow(missing_docs)]
 pub mod dna_def;
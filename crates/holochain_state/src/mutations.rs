@@ -10,6 +10,7 @@ use holo_hash::*;
 use holochain_sqlite::rusqlite::named_params;
 use holochain_sqlite::rusqlite::types::Null;
 use holochain_sqlite::rusqlite::Transaction;
+use holochain_types::block::{Block, BlockTarget};
 use holochain_types::dht_op::DhtOpLight;
 use holochain_types::dht_op::OpOrder;
 use holochain_types::dht_op::{DhtOpHashed, DhtOpType};
@@ -47,7 +48,7 @@ pub fn get_dependency(op_type: DhtOpType, action: &Action) -> Dependency {
             Action::Delete(delete) => Dependency::Action(delete.deletes_address.clone()),
             _ => Dependency::Null,
         },
-        DhtOpType::RegisterAddLink => Dependency::Null,
+        DhtOpType::RegisterAddLink | DhtOpType::RegisterWarrant => Dependency::Null,
         DhtOpType::RegisterRemoveLink => match action {
             Action::DeleteLink(delete_link) => {
                 Dependency::Action(delete_link.link_add_address.clone())
@@ -147,6 +148,9 @@ pub fn insert_op(txn: &mut Transaction, op: &DhtOpHashed) -> StateMutationResult
     let op_order = OpOrder::new(op_light.get_type(), action_hashed.action().timestamp());
     insert_action(txn, &action_hashed)?;
     insert_op_lite(txn, &op_light, hash, &op_order, &timestamp)?;
+    if let Some(warrant) = op.warrant() {
+        insert_warrant(txn, hash, warrant)?;
+    }
     set_dependency(txn, hash, dependency)?;
     Ok(())
 }
@@ -554,6 +558,24 @@ pub fn insert_entry(
     Ok(())
 }
 
+/// Insert a [`SignedWarrant`] into the database, keyed by the hash
+/// of the [`DhtOp::RegisterWarrant`](holochain_types::dht_op::DhtOp::RegisterWarrant)
+/// op which carries it.
+pub fn insert_warrant(
+    txn: &mut Transaction,
+    hash: &DhtOpHash,
+    warrant: &SignedWarrant,
+) -> StateMutationResult<()> {
+    sql_insert!(txn, Warrant, {
+        "hash": hash,
+        "action_hash": warrant.warrant().action_hash,
+        "author": warrant.warrant().author,
+        "warrantor": warrant.warrant().warrantor,
+        "blob": to_blob(warrant)?,
+    })?;
+    Ok(())
+}
+
 /// Lock the chain with the given lock id until the given end time.
 /// During this time only the lock id will be unlocked according to `is_chain_locked`.
 /// The chain can be unlocked for all lock ids at any time by calling `unlock_chain`.
@@ -586,6 +608,43 @@ pub fn unlock_chain(txn: &mut Transaction, author: &AgentPubKey) -> StateMutatio
     Ok(())
 }
 
+/// Insert a [`Block`] into the conductor database,
+/// replacing any existing block on the same target.
+pub fn insert_block(txn: &mut Transaction, block: &Block) -> StateMutationResult<()> {
+    let (target_type, target_id) = crate::block::target_columns(&block.target);
+    sql_insert!(txn, BlockedTarget, {
+        "target_type": target_type,
+        "target_id": target_id,
+        "expires_at": block.expires_at,
+    })?;
+    Ok(())
+}
+
+/// Remove the block on a target from the conductor database.
+/// Returns false if the target wasn't blocked.
+pub fn delete_block(txn: &mut Transaction, target: &BlockTarget) -> StateMutationResult<bool> {
+    let (target_type, target_id) = crate::block::target_columns(target);
+    let deleted = txn.execute(
+        "DELETE FROM BlockedTarget WHERE target_type = :target_type AND target_id = :target_id",
+        named_params! {
+            ":target_type": target_type,
+            ":target_id": target_id,
+        },
+    )?;
+    Ok(deleted > 0)
+}
+
+/// Remove every block which has expired at `now` from the conductor database.
+/// Returns the number of blocks removed.
+pub fn delete_expired_blocks(txn: &mut Transaction, now: Timestamp) -> StateMutationResult<usize> {
+    Ok(txn.execute(
+        "DELETE FROM BlockedTarget WHERE expires_at IS NOT NULL AND expires_at <= :now",
+        named_params! {
+            ":now": now,
+        },
+    )?)
+}
+
 pub fn delete_all_ephemeral_scheduled_fns(
     txn: &mut Transaction,
     author: &AgentPubKey,
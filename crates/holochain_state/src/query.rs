@@ -24,6 +24,7 @@ use holochain_zome_types::EntryVisibility;
 use holochain_zome_types::Record;
 use holochain_zome_types::SignedAction;
 use holochain_zome_types::SignedActionHashed;
+use holochain_zome_types::SignedWarrant;
 use serde::de::DeserializeOwned;
 use std::collections::HashMap;
 use std::collections::HashSet;
@@ -988,5 +989,13 @@ pub fn map_sql_dht_op_common(row: &Row) -> StateQueryResult<Option<DhtOp>> {
             None => None,
         };
     }
-    Ok(Some(DhtOp::from_type(op_type, action, entry)?))
+    let warrant: Option<SignedWarrant> = if op_type == DhtOpType::RegisterWarrant {
+        let w: Option<Vec<u8>> = row.get("warrant_blob")?;
+        w.map(from_blob).transpose()?
+    } else {
+        None
+    };
+    Ok(Some(DhtOp::from_type_with_warrant(
+        op_type, action, entry, warrant,
+    )?))
 }
@@ -100,7 +100,7 @@ mod tests {
     fn test_chain_head_query() {
         observability::test_run().ok();
         let mut conn = Connection::open_in_memory().unwrap();
-        SCHEMA_CELL.initialize(&mut conn, None).unwrap();
+        SCHEMA_CELL.initialize(&mut conn, None, None).unwrap();
 
         let mut txn = conn
             .transaction_with_behavior(TransactionBehavior::Exclusive)
@@ -17,18 +17,29 @@ pub struct LinksQuery {
     pub base: Arc<AnyLinkableHash>,
     pub type_query: LinkTypeFilter,
     pub tag: Option<String>,
+    pub filter: Arc<LinkFilter>,
     query: String,
 }
 
 impl LinksQuery {
     pub fn new(base: AnyLinkableHash, type_query: LinkTypeFilter, tag: Option<LinkTag>) -> Self {
+        Self::filtered(base, type_query, tag, LinkFilter::default())
+    }
+
+    pub fn filtered(
+        base: AnyLinkableHash,
+        type_query: LinkTypeFilter,
+        tag: Option<LinkTag>,
+        filter: LinkFilter,
+    ) -> Self {
         let tag = tag.map(|tag| Self::tag_to_hex(&tag));
-        let create_string = Self::create_query_string(&type_query, tag.clone());
-        let delete_string = Self::delete_query_string(&type_query, tag.clone());
+        let create_string = Self::create_query_string(&type_query, tag.clone(), &filter);
+        let delete_string = Self::delete_query_string(&type_query, tag.clone(), &filter);
         Self {
             base: Arc::new(base),
             type_query,
             tag,
+            filter: Arc::new(filter),
             query: Self::create_query(create_string, delete_string),
         }
     }
@@ -65,7 +76,11 @@ impl LinksQuery {
             AND DhtOp.when_integrated IS NOT NULL
         "
     }
-    fn create_query_string(type_query: &LinkTypeFilter, tag: Option<String>) -> String {
+    fn create_query_string(
+        type_query: &LinkTypeFilter,
+        tag: Option<String>,
+        filter: &LinkFilter,
+    ) -> String {
         let mut s = format!(
             "
             SELECT Action.blob AS action_blob FROM DhtOp
@@ -74,6 +89,7 @@ impl LinksQuery {
             Self::common_query_string()
         );
         s = Self::add_type_query(s, type_query);
+        s = Self::add_filter(s, filter);
         Self::add_tag(s, tag)
     }
     fn add_tag(q: String, tag: Option<String>) -> String {
@@ -92,7 +108,39 @@ impl LinksQuery {
     fn add_type_query(q: String, type_query: &LinkTypeFilter) -> String {
         format!("{} {} ", q, type_query.to_sql_statement())
     }
-    fn delete_query_string(type_query: &LinkTypeFilter, tag: Option<String>) -> String {
+    /// Filter creates by time and author in the query.
+    /// The cursor is applied when folding.
+    pub fn add_filter(mut q: String, filter: &LinkFilter) -> String {
+        if filter.after.is_some() {
+            q.push_str(" AND DhtOp.authored_timestamp > :after ");
+        }
+        if filter.before.is_some() {
+            q.push_str(" AND DhtOp.authored_timestamp < :before ");
+        }
+        if filter.author.is_some() {
+            q.push_str(" AND Action.author = :author ");
+        }
+        q
+    }
+    /// The parameters for the clauses added by [`LinksQuery::add_filter`].
+    pub fn filter_params(filter: &LinkFilter) -> Vec<Params> {
+        let mut params: Vec<Params> = Vec::new();
+        if let Some(after) = &filter.after {
+            params.push((":after", after));
+        }
+        if let Some(before) = &filter.before {
+            params.push((":before", before));
+        }
+        if let Some(author) = &filter.author {
+            params.push((":author", author));
+        }
+        params
+    }
+    fn delete_query_string(
+        type_query: &LinkTypeFilter,
+        tag: Option<String>,
+        filter: &LinkFilter,
+    ) -> String {
         let mut sub_create_query = format!(
             "
             SELECT Action.hash FROM DhtOp
@@ -101,6 +149,7 @@ impl LinksQuery {
             Self::common_query_string()
         );
         sub_create_query = Self::add_type_query(sub_create_query, type_query);
+        sub_create_query = Self::add_filter(sub_create_query, filter);
         sub_create_query = Self::add_tag(sub_create_query, tag);
         let delete_query = format!(
             "
@@ -120,7 +169,7 @@ impl LinksQuery {
     }
 
     pub fn params(&self) -> Vec<Params> {
-        {
+        let mut params = {
             named_params! {
                 ":create": DhtOpType::RegisterAddLink,
                 ":delete": DhtOpType::RegisterRemoveLink,
@@ -128,7 +177,15 @@ impl LinksQuery {
                 ":base_hash": self.base,
             }
         }
-        .to_vec()
+        .to_vec();
+        params.extend(Self::filter_params(&self.filter));
+        params
+    }
+
+    /// Does this create link action pass the filter?
+    pub fn matches(&self, create_link: &CreateLink, hash: &ActionHash) -> bool {
+        self.filter
+            .matches(&create_link.author, create_link.timestamp, hash)
     }
 }
 
@@ -139,6 +196,17 @@ impl GetLinksQuery {
         }
     }
 
+    pub fn filtered(
+        base: AnyLinkableHash,
+        type_query: LinkTypeFilter,
+        tag: Option<LinkTag>,
+        filter: LinkFilter,
+    ) -> Self {
+        Self {
+            query: LinksQuery::filtered(base, type_query, tag, filter),
+        }
+    }
+
     pub fn base(base: AnyLinkableHash, dependencies: Vec<ZomeId>) -> Self {
         Self {
             query: LinksQuery::base(base, dependencies),
@@ -199,7 +267,7 @@ impl Query for GetLinksQuery {
         let (action, hash) = action.into_inner();
         match action {
             Action::CreateLink(create_link) => {
-                if !state.deletes.contains(&hash) {
+                if !state.deletes.contains(&hash) && self.query.matches(&create_link, &hash) {
                     state
                         .creates
                         .insert(hash, link_from_action(Action::CreateLink(create_link))?);
@@ -218,9 +286,11 @@ impl Query for GetLinksQuery {
     where
         S: Store,
     {
-        let mut links: Self::Output = state.creates.into_iter().map(|(_, v)| v).collect();
-        links.sort_by_key(|l| l.timestamp);
-        Ok(links)
+        let links: Self::Output = state.creates.into_iter().map(|(_, v)| v).collect();
+        Ok(self
+            .query
+            .filter
+            .page(links, |l| (l.timestamp, &l.create_link_hash)))
     }
 }
 
@@ -16,6 +16,17 @@ impl GetLinkDetailsQuery {
             query: LinksQuery::new(base, type_query, tag),
         }
     }
+
+    pub fn filtered(
+        base: AnyLinkableHash,
+        type_query: LinkTypeFilter,
+        tag: Option<LinkTag>,
+        filter: LinkFilter,
+    ) -> Self {
+        Self {
+            query: LinksQuery::filtered(base, type_query, tag, filter),
+        }
+    }
 }
 
 impl Query for GetLinkDetailsQuery {
@@ -69,10 +80,12 @@ impl Query for GetLinkDetailsQuery {
         let shh = data.data;
         let action = shh.action();
         match action {
-            Action::CreateLink(_) => {
-                state
-                    .entry(shh.as_hash().clone())
-                    .or_insert((Some(shh), HashSet::new()));
+            Action::CreateLink(create_link) => {
+                if self.query.matches(create_link, shh.as_hash()) {
+                    state
+                        .entry(shh.as_hash().clone())
+                        .or_insert((Some(shh), HashSet::new()));
+                }
             }
             Action::DeleteLink(delete_link) => {
                 let entry = state
@@ -93,10 +106,7 @@ impl Query for GetLinkDetailsQuery {
     where
         S: Store,
     {
-        // TODO: This could be done above by using BTMaps but deferring this optimization
-        // because it's simpler .
-        // Order by timestamp.
-        let mut r = state
+        let r = state
             .into_iter()
             .filter_map(|(_, (create, deletes))| {
                 create.map(|create| {
@@ -106,7 +116,9 @@ impl Query for GetLinkDetailsQuery {
                 })
             })
             .collect::<Vec<_>>();
-        r.sort_by_key(|l| l.0.action().timestamp());
-        Ok(r)
+        Ok(self
+            .query
+            .filter
+            .page(r, |l| (l.0.action().timestamp(), l.0.as_hash())))
     }
 }
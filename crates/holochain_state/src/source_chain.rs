@@ -1,3 +1,4 @@
+use std::collections::HashSet;
 use std::sync::Arc;
 
 use crate::integrate::authored_ops_to_dht_db;
@@ -31,16 +32,21 @@ use holochain_zome_types::ActionBuilder;
 use holochain_zome_types::ActionBuilderCommon;
 use holochain_zome_types::ActionExt;
 use holochain_zome_types::ActionHashed;
+use holochain_zome_types::ActionType;
 use holochain_zome_types::ActionUnweighed;
 use holochain_zome_types::CapAccess;
+use holochain_zome_types::CapClaimQuery;
 use holochain_zome_types::CapGrant;
+use holochain_zome_types::CapGrantQuery;
 use holochain_zome_types::CapSecret;
 use holochain_zome_types::CellId;
+use holochain_zome_types::ChainQueryFilter;
 use holochain_zome_types::ChainTopOrdering;
 use holochain_zome_types::CounterSigningAgentState;
 use holochain_zome_types::CounterSigningSessionData;
 use holochain_zome_types::Entry;
 use holochain_zome_types::EntryRateWeight;
+use holochain_zome_types::EntryType;
 use holochain_zome_types::EntryVisibility;
 use holochain_zome_types::GrantedFunction;
 use holochain_zome_types::MembraneProof;
@@ -210,6 +216,18 @@ impl SourceChain {
         chain_top_ordering: ChainTopOrdering,
         weight: W,
     ) -> SourceChainResult<ActionHash> {
+        let unweighed = self.build_unweighed(action_builder)?;
+        self.put_unweighed(unweighed, maybe_entry, chain_top_ordering, weight)
+            .await
+    }
+
+    /// Build the next action on this chain without its weight, so that it
+    /// can be passed to the `weigh` callback before being committed with
+    /// [`SourceChain::put_unweighed`].
+    pub fn build_unweighed<U: ActionUnweighed, B: ActionBuilder<U>>(
+        &self,
+        action_builder: B,
+    ) -> SourceChainResult<U> {
         let (prev_action, chain_head_seq, chain_head_timestamp) = self.chain_head()?;
         let action_seq = chain_head_seq + 1;
 
@@ -230,8 +248,20 @@ impl SourceChain {
             action_seq,
             prev_action,
         };
+        Ok(action_builder.build(common))
+    }
+
+    /// Put an action built with [`SourceChain::build_unweighed`] at the end
+    /// of the source chain, with the specified weight for rate limiting.
+    pub async fn put_unweighed<W, U: ActionUnweighed<Weight = W>>(
+        &self,
+        unweighed: U,
+        maybe_entry: Option<Entry>,
+        chain_top_ordering: ChainTopOrdering,
+        weight: W,
+    ) -> SourceChainResult<ActionHash> {
         self.put_with_action(
-            action_builder.build(common).weighed(weight).into(),
+            unweighed.weighed(weight).into(),
             maybe_entry,
             chain_top_ordering,
         )
@@ -689,6 +719,67 @@ where
         Ok(valid_cap_grant)
     }
 
+    /// List the active capability grants on this chain which match the query.
+    /// Grants which have been updated or deleted are no longer active.
+    pub async fn cap_grants(&self, query: CapGrantQuery) -> SourceChainResult<Vec<Record>> {
+        Ok(self
+            .active_records(EntryType::CapGrant)
+            .await?
+            .into_iter()
+            .filter(|record| match record.entry().as_option() {
+                Some(Entry::CapGrant(grant)) => query.check(grant),
+                _ => false,
+            })
+            .collect())
+    }
+
+    /// List the active capability claims on this chain which match the query.
+    /// Claims which have been updated or deleted are no longer active.
+    pub async fn cap_claims(&self, query: CapClaimQuery) -> SourceChainResult<Vec<Record>> {
+        Ok(self
+            .active_records(EntryType::CapClaim)
+            .await?
+            .into_iter()
+            .filter(|record| match record.entry().as_option() {
+                Some(Entry::CapClaim(claim)) => query.check(claim),
+                _ => false,
+            })
+            .collect())
+    }
+
+    /// All records with entries of the given type which have been
+    /// neither updated nor deleted.
+    async fn active_records(&self, entry_type: EntryType) -> SourceChainResult<Vec<Record>> {
+        let records = self
+            .query(
+                ChainQueryFilter::new()
+                    .entry_type(entry_type)
+                    .include_entries(true),
+            )
+            .await?;
+        let mut inactive = HashSet::new();
+        for action_type in [ActionType::Update, ActionType::Delete] {
+            for record in self
+                .query(ChainQueryFilter::new().action_type(action_type))
+                .await?
+            {
+                match record.action() {
+                    Action::Update(update) => {
+                        inactive.insert(update.original_action_address.clone());
+                    }
+                    Action::Delete(delete) => {
+                        inactive.insert(delete.deletes_address.clone());
+                    }
+                    _ => (),
+                }
+            }
+        }
+        Ok(records
+            .into_iter()
+            .filter(|record| !inactive.contains(record.action_address()))
+            .collect())
+    }
+
     /// Query Actions in the source chain.
     /// This returns a Vec rather than an iterator because it is intended to be
     /// used by the `query` host function, which crosses the wasm boundary
@@ -1727,6 +1818,112 @@ pub mod tests {
         Ok(())
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn cap_grants_excludes_updated_and_deleted_grants() -> SourceChainResult<()> {
+        let test_db = test_authored_db();
+        let dht_db = test_dht_db();
+        let dht_db_cache = DhtDbQueryCache::new(dht_db.to_db().into());
+        let keystore = test_keystore();
+        let db = test_db.to_db();
+        let mut mock = MockHolochainP2pDnaT::new();
+        mock.expect_authority_for_hash().returning(|_| Ok(false));
+        let alice = fixt!(AgentPubKey, Predictable, 0);
+        source_chain::genesis(
+            db.clone(),
+            dht_db.to_db(),
+            &dht_db_cache,
+            keystore.clone(),
+            fake_dna_hash(1),
+            alice.clone(),
+            None,
+        )
+        .await
+        .unwrap();
+        let grant = |tag: &str| {
+            let secret = CapSecretFixturator::new(Unpredictable).next().unwrap();
+            let functions = [("foo".into(), "bar".into())].into_iter().collect();
+            let grant = ZomeCallCapGrant::new(tag.into(), secret.into(), functions);
+            EntryHashed::from_content_sync(Entry::CapGrant(grant)).into_inner()
+        };
+
+        // commit a grant which will be updated and one which will be deleted
+        let ((updated_action, updated_entry), (deleted_action, deleted_entry)) = {
+            let chain = SourceChain::new(
+                db.clone(),
+                dht_db.to_db(),
+                dht_db_cache.clone(),
+                keystore.clone(),
+                alice.clone(),
+            )
+            .await?;
+            let mut committed = Vec::new();
+            for tag in ["updated", "deleted"] {
+                let (entry, entry_hash) = grant(tag);
+                let action_builder = builder::Create {
+                    entry_type: EntryType::CapGrant,
+                    entry_hash: entry_hash.clone(),
+                };
+                let action = chain
+                    .put_weightless(action_builder, Some(entry), ChainTopOrdering::default())
+                    .await?;
+                committed.push((action, entry_hash));
+            }
+            chain.flush(&mock).await.unwrap();
+            assert_eq!(chain.cap_grants(CapGrantQuery::new()).await?.len(), 2);
+            let deleted = committed.pop().unwrap();
+            (committed.pop().unwrap(), deleted)
+        };
+
+        let update_action = {
+            let chain = SourceChain::new(
+                db.clone(),
+                dht_db.to_db(),
+                dht_db_cache.clone(),
+                keystore.clone(),
+                alice.clone(),
+            )
+            .await?;
+            let (entry, entry_hash) = grant("updated");
+            let action_builder = builder::Update {
+                entry_type: EntryType::CapGrant,
+                entry_hash,
+                original_action_address: updated_action,
+                original_entry_address: updated_entry,
+            };
+            let update_action = chain
+                .put_weightless(action_builder, Some(entry), ChainTopOrdering::default())
+                .await?;
+            let action_builder = builder::Delete {
+                deletes_address: deleted_action,
+                deletes_entry_address: deleted_entry,
+            };
+            chain
+                .put_weightless(action_builder, None, ChainTopOrdering::default())
+                .await?;
+            chain.flush(&mock).await.unwrap();
+            update_action
+        };
+
+        let chain = SourceChain::new(
+            db.clone(),
+            dht_db.to_db(),
+            dht_db_cache.clone(),
+            keystore.clone(),
+            alice.clone(),
+        )
+        .await?;
+        // only the grant committed by the update is still active
+        let grants = chain.cap_grants(CapGrantQuery::new()).await?;
+        assert_eq!(grants.len(), 1);
+        assert_eq!(grants[0].action_address(), &update_action);
+        assert!(chain
+            .cap_grants(CapGrantQuery::new().tag("deleted"))
+            .await?
+            .is_empty());
+
+        Ok(())
+    }
+
     // @todo bring all this back when we want to administer cap claims better
     // #[tokio::test(flavor = "multi_thread")]
     // async fn test_get_cap_claim() -> SourceChainResult<()> {
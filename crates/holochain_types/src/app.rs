@@ -10,6 +10,7 @@
 mod app_bundle;
 mod app_manifest;
 mod dna_gamut;
+mod download_cache;
 pub mod error;
 use crate::dna::DnaBundle;
 pub use app_bundle::*;
@@ -17,6 +18,7 @@ pub use app_manifest::app_manifest_validated::*;
 pub use app_manifest::*;
 use derive_more::Into;
 pub use dna_gamut::*;
+pub use download_cache::*;
 use holo_hash::{AgentPubKey, DnaHash};
 use holochain_serialized_bytes::prelude::*;
 use holochain_util::ffs;
@@ -61,10 +63,10 @@ pub struct RegisterDnaPayload {
 pub struct CreateCloneCellPayload {
     /// Properties to override when installing this DNA
     pub properties: Option<YamlProperties>,
-    /// The DNA to clone
+    /// The DNA to clone, which must be the DNA of the role's cell
     pub dna_hash: DnaHash,
-    /// The Agent key with which to create this Cell
-    /// (TODO: should this be derived from the App?)
+    /// The Agent key with which to create this Cell, which must be the
+    /// agent of the role's cell
     pub agent_key: AgentPubKey,
     /// The App with which to associate the newly created Cell
     pub installed_app_id: InstalledAppId,
@@ -76,12 +78,47 @@ pub struct CreateCloneCellPayload {
 }
 
 impl CreateCloneCellPayload {
-    /// Get the CellId of the to-be-created clone cell
+    /// Get the CellId of the cell to be cloned. The clone itself gets a
+    /// different DNA hash, as it is created with a random network seed.
     pub fn cell_id(&self) -> CellId {
         CellId::new(self.dna_hash.clone(), self.agent_key.clone())
     }
 }
 
+/// Identifies an existing clone cell of an app, for archiving, restoring or
+/// deleting it
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct ArchiveCloneCellPayload {
+    /// The App which the clone cell belongs to
+    pub app_id: InstalledAppId,
+    /// The Role ID under which the clone was created
+    pub role_id: AppRoleId,
+    /// The CellId of the clone
+    pub cell_id: CellId,
+}
+
+/// Identifies an archived clone cell which should be brought back into use
+pub type RestoreArchivedCloneCellPayload = ArchiveCloneCellPayload;
+
+/// Identifies a clone cell which should be removed for good, along with all of
+/// its data
+pub type DeleteCloneCellPayload = ArchiveCloneCellPayload;
+
+/// Identifies a role of an app whose DNA should be upgraded to a successor
+/// DNA, migrating the agent's source chain across
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct UpgradeAppPayload {
+    /// The App to upgrade
+    pub installed_app_id: InstalledAppId,
+    /// The role whose provisioned cell is replaced by a cell of the new DNA
+    pub role_id: AppRoleId,
+    /// Where to find the successor DNA
+    #[serde(flatten)]
+    pub source: DnaSource,
+    /// Proof-of-membership, if required by the successor DNA
+    pub membrane_proof: Option<MembraneProof>,
+}
+
 /// A collection of [DnaHash]es paired with an [AgentPubKey] and an app id
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct InstallAppPayload {
@@ -127,8 +164,13 @@ pub enum AppBundleSource {
     Bundle(AppBundle),
     /// A local file path
     Path(PathBuf),
-    // /// A URL
-    // Url(String),
+    /// A URL to download the bundle from
+    Url {
+        /// Where to download the bundle from
+        url: String,
+        /// The hash the downloaded bytes must have
+        hash: BundleHash,
+    },
 }
 
 impl AppBundleSource {
@@ -137,9 +179,20 @@ impl AppBundleSource {
         Ok(match self {
             Self::Bundle(bundle) => bundle,
             Self::Path(path) => AppBundle::decode(&ffs::read(&path).await?)?,
-            // Self::Url(url) => todo!("reqwest::get"),
+            Self::Url { url, hash } => AppBundle::decode(&download_app_bundle(&url, &hash).await?)?,
         })
     }
+
+    /// Get the bundle from the source, using the cache for bundles
+    /// which are downloaded from a URL. Consumes the source.
+    pub async fn resolve_cached(self, cache: &DownloadCache) -> Result<AppBundle, AppBundleError> {
+        match self {
+            Self::Url { url, hash } => Ok(AppBundle::decode(
+                &cache.fetch_app_bundle(&url, &hash).await?,
+            )?),
+            source => source.resolve().await,
+        }
+    }
 }
 
 /// Information needed to specify a DNA as part of an App
@@ -416,6 +469,15 @@ impl InstalledAppCommon {
             .filter_map(|(role_id, role)| role.into_provisioned_cell().map(|c| (role_id, c)))
     }
 
+    /// The provisioned cells which were created by this app, as opposed to
+    /// existing cells which it shares with other apps
+    pub fn owned_cells(&self) -> impl Iterator<Item = &CellId> {
+        self.role_assignments
+            .values()
+            .filter(|role| !role.is_existing)
+            .filter_map(|role| role.provisioned_cell())
+    }
+
     /// Accessor
     pub fn cloned_cells(&self) -> impl Iterator<Item = &CellId> {
         self.role_assignments
@@ -423,6 +485,14 @@ impl InstalledAppCommon {
             .flat_map(|(_, role)| &role.clones)
     }
 
+    /// Accessor for the clone cells which have been archived.
+    /// These are not part of [`Self::all_cells`], so they are never run.
+    pub fn archived_clone_cells(&self) -> impl Iterator<Item = &CellId> {
+        self.role_assignments
+            .iter()
+            .flat_map(|(_, role)| &role.archived_clones)
+    }
+
     /// Iterator of all cells, both provisioned and cloned
     pub fn all_cells(&self) -> impl Iterator<Item = &CellId> {
         self.provisioned_cells()
@@ -476,6 +546,76 @@ impl InstalledAppCommon {
         Ok(role.clones.remove(cell_id))
     }
 
+    /// Archive a cloned cell. The clone stops counting towards the
+    /// `clone_limit` of its role, but can later be restored.
+    pub fn archive_clone(&mut self, role_id: &AppRoleId, cell_id: &CellId) -> AppResult<()> {
+        let role = self.role_mut(role_id)?;
+        if !role.clones.remove(cell_id) {
+            return Err(AppError::CloneCellNotFound(
+                role_id.clone(),
+                cell_id.clone(),
+            ));
+        }
+        let _ = role.archived_clones.insert(cell_id.clone());
+        Ok(())
+    }
+
+    /// Restore a previously archived clone cell, subject to the `clone_limit`
+    /// of its role.
+    pub fn restore_archived_clone(
+        &mut self,
+        role_id: &AppRoleId,
+        cell_id: &CellId,
+    ) -> AppResult<()> {
+        let role = self.role_mut(role_id)?;
+        if !role.archived_clones.contains(cell_id) {
+            return Err(AppError::ArchivedCloneCellNotFound(
+                role_id.clone(),
+                cell_id.clone(),
+            ));
+        }
+        if role.clones.len() as u32 >= role.clone_limit {
+            return Err(AppError::CloneLimitExceeded(role.clone_limit, role.clone()));
+        }
+        role.archived_clones.remove(cell_id);
+        let _ = role.clones.insert(cell_id.clone());
+        Ok(())
+    }
+
+    /// Remove a clone cell for good, whether it is active or archived.
+    pub fn delete_clone(&mut self, role_id: &AppRoleId, cell_id: &CellId) -> AppResult<()> {
+        let role = self.role_mut(role_id)?;
+        let was_active = role.clones.remove(cell_id);
+        let was_archived = role.archived_clones.remove(cell_id);
+        if !was_active && !was_archived {
+            return Err(AppError::CloneCellNotFound(
+                role_id.clone(),
+                cell_id.clone(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Replace the provisioned cell of a role with the cell of a successor
+    /// DNA, returning the id of the cell it replaced.
+    ///
+    /// Only provisioned cells which this app owns can be upgraded, since a
+    /// cell shared from another app is not this app's to migrate. Clones of
+    /// the role are left as they are.
+    pub fn upgrade_role(&mut self, role_id: &AppRoleId, cell_id: CellId) -> AppResult<CellId> {
+        let role = self.role_mut(role_id)?;
+        if cell_id.agent_pubkey() != role.agent_key() {
+            return Err(AppError::UpgradeAgentKeyMismatch(
+                role_id.clone(),
+                cell_id.agent_pubkey().clone(),
+            ));
+        }
+        if role.is_existing || !role.is_provisioned {
+            return Err(AppError::RoleNotUpgradable(role_id.clone()));
+        }
+        Ok(std::mem::replace(&mut role.base_cell_id, cell_id))
+    }
+
     /// Accessor
     pub fn _agent_key(&self) -> &AgentPubKey {
         &self._agent_key
@@ -528,7 +668,9 @@ impl InstalledAppCommon {
                 let role = AppRoleAssignment {
                     base_cell_id: cell_id,
                     is_provisioned: true,
+                    is_existing: false,
                     clones: HashSet::new(),
+                    archived_clones: HashSet::new(),
                     clone_limit: 0,
                 };
                 (role_id, role)
@@ -754,11 +896,21 @@ pub struct AppRoleAssignment {
     /// If false, then `base_cell_id` is just recording what that cell will be
     /// called in the future.
     is_provisioned: bool,
+    /// Records whether the base cell was not created by this app, but is an
+    /// existing cell which was already installed by another app and is
+    /// shared with it.
+    #[serde(default)]
+    is_existing: bool,
     /// The number of cloned cells allowed
     clone_limit: u32,
     /// Cells which were cloned at runtime. The length cannot grow beyond
     /// `clone_limit`
     clones: HashSet<CellId>,
+    /// Clone cells which have been archived. They are not run and do not
+    /// count towards the `clone_limit`, but their data is kept so that they
+    /// can be restored.
+    #[serde(default)]
+    archived_clones: HashSet<CellId>,
 }
 
 impl AppRoleAssignment {
@@ -767,11 +919,27 @@ impl AppRoleAssignment {
         Self {
             base_cell_id,
             is_provisioned,
+            is_existing: false,
             clone_limit,
             clones: HashSet::new(),
+            archived_clones: HashSet::new(),
+        }
+    }
+
+    /// Constructor for a role which is filled by an existing cell, shared
+    /// with the app which originally created it.
+    pub fn new_existing(base_cell_id: CellId, clone_limit: u32) -> Self {
+        Self {
+            is_existing: true,
+            ..Self::new(base_cell_id, true, clone_limit)
         }
     }
 
+    /// Accessor
+    pub fn is_existing(&self) -> bool {
+        self.is_existing
+    }
+
     /// Accessor
     pub fn cell_id(&self) -> &CellId {
         &self.base_cell_id
@@ -796,6 +964,11 @@ impl AppRoleAssignment {
         }
     }
 
+    /// Accessor for the cells cloned at runtime from this role
+    pub fn clones(&self) -> impl Iterator<Item = &CellId> {
+        self.clones.iter()
+    }
+
     /// Transformer
     pub fn into_provisioned_cell(self) -> Option<CellId> {
         if self.is_provisioned {
@@ -860,4 +1033,102 @@ mod tests {
             app.all_cells().collect::<HashSet<_>>()
         );
     }
+
+    #[test]
+    fn clone_archival() {
+        let base_cell_id = fixt!(CellId);
+        let agent = base_cell_id.agent_pubkey().clone();
+        let new_clone = || CellId::new(fixt!(DnaHash), agent.clone());
+        let role1 = AppRoleAssignment::new(base_cell_id, false, 2);
+        let role_id: AppRoleId = "role_id".into();
+        let mut app: RunningApp =
+            InstalledAppCommon::new("app", agent.clone(), vec![(role_id.clone(), role1)]).into();
+
+        let clones: Vec<_> = vec![new_clone(), new_clone(), new_clone()];
+        app.add_clone(&role_id, clones[0].clone()).unwrap();
+        app.add_clone(&role_id, clones[1].clone()).unwrap();
+
+        // Archiving frees up a slot in the clone limit
+        app.archive_clone(&role_id, &clones[0]).unwrap();
+        app.add_clone(&role_id, clones[2].clone()).unwrap();
+        assert_eq!(
+            app.archived_clone_cells().collect::<HashSet<_>>(),
+            maplit::hashset! { &clones[0] }
+        );
+        assert_eq!(
+            app.all_cells().collect::<HashSet<_>>(),
+            maplit::hashset! { &clones[1], &clones[2] }
+        );
+
+        // Only an active clone can be archived
+        matches::assert_matches!(
+            app.archive_clone(&role_id, &clones[0]),
+            Err(AppError::CloneCellNotFound(_, _))
+        );
+
+        // Restoring is subject to the clone limit
+        matches::assert_matches!(
+            app.restore_archived_clone(&role_id, &clones[0]),
+            Err(AppError::CloneLimitExceeded(2, _))
+        );
+        app.delete_clone(&role_id, &clones[2]).unwrap();
+        app.restore_archived_clone(&role_id, &clones[0]).unwrap();
+        assert_eq!(app.archived_clone_cells().count(), 0);
+        assert_eq!(
+            app.all_cells().collect::<HashSet<_>>(),
+            maplit::hashset! { &clones[0], &clones[1] }
+        );
+
+        // Archived clones can be deleted too
+        app.archive_clone(&role_id, &clones[1]).unwrap();
+        app.delete_clone(&role_id, &clones[1]).unwrap();
+        assert_eq!(app.archived_clone_cells().count(), 0);
+        matches::assert_matches!(
+            app.delete_clone(&role_id, &clones[1]),
+            Err(AppError::CloneCellNotFound(_, _))
+        );
+    }
+
+    #[test]
+    fn role_upgrade() {
+        let base_cell_id = fixt!(CellId);
+        let agent = base_cell_id.agent_pubkey().clone();
+        let new_cell_id = CellId::new(fixt!(DnaHash), agent.clone());
+        let shared_cell_id = CellId::new(fixt!(DnaHash), agent.clone());
+        let role_id: AppRoleId = "role_id".into();
+        let shared_role_id: AppRoleId = "shared_role_id".into();
+        let mut app: RunningApp = InstalledAppCommon::new(
+            "app",
+            agent.clone(),
+            vec![
+                (
+                    role_id.clone(),
+                    AppRoleAssignment::new(base_cell_id.clone(), true, 0),
+                ),
+                (
+                    shared_role_id.clone(),
+                    AppRoleAssignment::new_existing(shared_cell_id, 0),
+                ),
+            ],
+        )
+        .into();
+
+        assert_eq!(
+            app.upgrade_role(&role_id, new_cell_id.clone()).unwrap(),
+            base_cell_id
+        );
+        assert_eq!(app.role(&role_id).unwrap().cell_id(), &new_cell_id);
+
+        // A cell shared from another app can't be upgraded by this one
+        matches::assert_matches!(
+            app.upgrade_role(&shared_role_id, CellId::new(fixt!(DnaHash), agent)),
+            Err(AppError::RoleNotUpgradable(_))
+        );
+
+        // The successor cell must belong to the same agent
+        matches::assert_matches!(
+            app.upgrade_role(&role_id, CellId::new(fixt!(DnaHash), fixt!(AgentPubKey))),
+            Err(AppError::UpgradeAgentKeyMismatch(_, _))
+        );
+    }
 }
@@ -1,4 +1,8 @@
-use std::{collections::HashMap, path::PathBuf, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+    sync::Arc,
+};
 
 use self::error::AppBundleResult;
 
@@ -45,19 +49,56 @@ impl AppBundle {
         self.0
     }
 
+    /// Fetch every DNA which the manifest locates by URL, using the cache
+    /// where possible, and bundle it in place of the URL.
+    ///
+    /// Each DNA is checked against the version spec of its role, if it has one.
+    pub async fn fetch_remote_dnas(self, cache: &DownloadCache) -> AppBundleResult<Self> {
+        let mut manifest = self.manifest().clone();
+        let mut resources = Vec::new();
+        match &mut manifest {
+            AppManifest::V1(manifest) => {
+                for role in manifest.roles.iter_mut() {
+                    if let Some(mr_bundle::Location::Url(url)) = &role.dna.location {
+                        let version = role.dna.version.clone().map(DnaVersionSpec::from);
+                        let bytes = cache.fetch_dna_bundle(url, version.as_ref()).await?;
+                        let path = PathBuf::from(format!("downloaded/{}.dna", role.id));
+                        role.dna.location = Some(mr_bundle::Location::Bundled(path.clone()));
+                        resources.push((path, bytes));
+                    }
+                }
+            }
+        }
+        if resources.is_empty() {
+            return Ok(self);
+        }
+        Ok(self
+            .into_inner()
+            .update_manifest_with_resources(manifest, resources)?
+            .into())
+    }
+
     /// Given a DnaGamut, decide which of the available DNAs or Cells should be
     /// used for each cell in this app.
     pub async fn resolve_cells(
         self,
         agent: AgentPubKey,
-        _gamut: DnaGamut,
+        gamut: DnaGamut,
         membrane_proofs: HashMap<AppRoleId, MembraneProof>,
     ) -> AppBundleResult<AppRoleResolution> {
         let AppManifestValidated { name: _, roles } = self.manifest().clone().validate()?;
         let bundle = Arc::new(self);
-        let tasks = roles.into_iter().map(|(role_id, role)| async {
+        let gamut = Arc::new(gamut);
+        let tasks = roles.into_iter().map(|(role_id, role)| {
             let bundle = bundle.clone();
-            Ok((role_id, bundle.resolve_cell(role).await?))
+            let gamut = gamut.clone();
+            let agent = agent.clone();
+            async move {
+                Ok((
+                    role_id,
+                    bundle.resolve_cell(role, gamut.as_ref(), &agent).await?,
+                ))
+            }
         });
         let resolution = futures::future::join_all(tasks)
             .await
@@ -80,7 +121,15 @@ impl AppBundle {
                                 resolution.role_assignments.push((role_id, role));
                             }
                             CellProvisioningOp::Existing(cell_id, clone_limit) => {
-                                let role = AppRoleAssignment::new(cell_id, true, clone_limit);
+                                let role = AppRoleAssignment::new_existing(cell_id, clone_limit);
+                                resolution.role_assignments.push((role_id, role));
+                            }
+                            CellProvisioningOp::RegisterOnly(dna, clone_limit) => {
+                                let agent = resolution.agent.clone();
+                                let dna_hash = dna.dna_hash().clone();
+                                let cell_id = CellId::new(dna_hash, agent);
+                                let role = AppRoleAssignment::new(cell_id, false, clone_limit);
+                                resolution.dnas_to_register.push((dna, None));
                                 resolution.role_assignments.push((role_id, role));
                             }
                             CellProvisioningOp::Noop(cell_id, clone_limit) => {
@@ -89,12 +138,11 @@ impl AppBundle {
                                     AppRoleAssignment::new(cell_id, false, clone_limit),
                                 ));
                             }
-                            other => {
-                                tracing::error!(
-                                    "Encountered unexpected CellProvisioningOp: {:?}",
-                                    other
-                                );
-                                unimplemented!()
+                            CellProvisioningOp::NoMatch => {
+                                return Err(AppBundleError::CellResolutionFailure(role_id));
+                            }
+                            CellProvisioningOp::Conflict(_) => {
+                                return Err(AppBundleError::CellResolutionConflict(role_id));
                             }
                         }
                         Ok(resolution)
@@ -111,6 +159,8 @@ impl AppBundle {
     async fn resolve_cell(
         &self,
         role: AppRoleManifestValidated,
+        gamut: &DnaGamut,
+        agent: &AgentPubKey,
     ) -> AppBundleResult<CellProvisioningOp> {
         Ok(match role {
             AppRoleManifestValidated::Create {
@@ -131,14 +181,27 @@ impl AppBundle {
                 .await?
             }
 
-            AppRoleManifestValidated::CreateClone { .. } => {
-                unimplemented!("`create_clone` provisioning strategy is currently unimplemented")
+            AppRoleManifestValidated::CreateClone {
+                location,
+                version,
+                clone_limit,
+                properties,
+                deferred: _,
+            } => {
+                self.resolve_cell_create(
+                    &location,
+                    version.as_ref(),
+                    clone_limit,
+                    Some(random_network_seed()),
+                    properties,
+                )
+                .await?
             }
             AppRoleManifestValidated::UseExisting {
                 version,
                 clone_limit,
                 deferred: _,
-            } => self.resolve_cell_existing(&version, clone_limit),
+            } => self.resolve_cell_existing(gamut, agent, &version, clone_limit),
             AppRoleManifestValidated::CreateIfNotExists {
                 location,
                 version,
@@ -146,7 +209,7 @@ impl AppBundle {
                 properties,
                 network_seed,
                 deferred: _,
-            } => match self.resolve_cell_existing(&version, clone_limit) {
+            } => match self.resolve_cell_existing(gamut, agent, &version, clone_limit) {
                 op @ CellProvisioningOp::Existing(_, _) => op,
                 CellProvisioningOp::NoMatch => {
                     self.resolve_cell_create(
@@ -158,23 +221,48 @@ impl AppBundle {
                     )
                     .await?
                 }
-                CellProvisioningOp::Conflict(_) => {
-                    unimplemented!("conflicts are not handled, or even possible yet")
-                }
+                op @ CellProvisioningOp::Conflict(_) => op,
                 CellProvisioningOp::Create(_, _) => {
                     unreachable!("resolve_cell_existing will never return a Create op")
                 }
-                CellProvisioningOp::Noop(_, _) => {
+                CellProvisioningOp::Noop(_, _) | CellProvisioningOp::RegisterOnly(_, _) => {
                     unreachable!("resolve_cell_existing will never return a Noop")
                 }
             },
             AppRoleManifestValidated::Disabled {
-                version: _,
-                clone_limit: _,
-            } => {
-                unimplemented!("`disabled` provisioning strategy is currently unimplemented")
-                // CellProvisioningOp::Noop(clone_limit)
-            }
+                version,
+                clone_limit,
+                location,
+                properties,
+                network_seed,
+            } => match location {
+                Some(location) => {
+                    match self
+                        .resolve_cell_create(
+                            &location,
+                            Some(&version),
+                            clone_limit,
+                            network_seed,
+                            properties,
+                        )
+                        .await?
+                    {
+                        CellProvisioningOp::Create(dna, clone_limit) => {
+                            CellProvisioningOp::RegisterOnly(dna, clone_limit)
+                        }
+                        other => other,
+                    }
+                }
+                // Without a location, the DNA must already be registered.
+                // The latest version in the spec is used as the basis for clones.
+                None => match version.dna_hashes().first() {
+                    Some(hash) => CellProvisioningOp::Noop(
+                        CellId::new((*hash).clone().into(), agent.clone()),
+                        clone_limit,
+                    ),
+                    None => CellProvisioningOp::NoMatch,
+                },
+            },
         })
     }
 
@@ -200,20 +288,21 @@ impl AppBundle {
 
     fn resolve_cell_existing(
         &self,
-        _version: &DnaVersionSpec,
-        _clone_limit: u32,
+        gamut: &DnaGamut,
+        agent: &AgentPubKey,
+        version: &DnaVersionSpec,
+        clone_limit: u32,
     ) -> CellProvisioningOp {
-        unimplemented!("Reusing existing cells is not yet implemented")
+        match gamut.resolve_cell(version.clone(), agent) {
+            CellResolution::Match(cell_id, _) => CellProvisioningOp::Existing(cell_id, clone_limit),
+            CellResolution::NoMatch => CellProvisioningOp::NoMatch,
+            CellResolution::Conflict => {
+                CellProvisioningOp::Conflict(CellProvisioningConflict::AmbiguousExistingCell)
+            }
+        }
     }
 }
 
-/// This function is called in places where it will be necessary to rework that
-/// area after use_existing has been implemented
-#[deprecated = "Raising visibility into a change that needs to happen after `use_existing` is implemented"]
-pub fn we_must_remember_to_rework_cell_panic_handling_after_implementing_use_existing_cell_resolution(
-) {
-}
-
 /// The answer to the question:
 /// "how do we concretely assign DNAs to the open roles of this App?"
 /// Includes the DNAs selected to fill the roles and the details of the role assignments.
@@ -237,8 +326,14 @@ impl AppRoleResolution {
     }
 
     /// Return the IDs of new cells to be created as part of the resolution.
-    /// Does not return existing cells to be reused.
+    /// Does not return existing cells to be reused, nor cells of roles which
+    /// are not provisioned, even if their DNA is registered.
     pub fn cells_to_create(&self) -> Vec<(CellId, Option<MembraneProof>)> {
+        let provisioned: HashSet<&CellId> = self
+            .role_assignments
+            .iter()
+            .filter_map(|(_, role)| role.provisioned_cell())
+            .collect();
         self.dnas_to_register
             .iter()
             .map(|(dna, proof)| {
@@ -247,6 +342,7 @@ impl AppRoleResolution {
                     proof.clone(),
                 )
             })
+            .filter(|(cell_id, _)| provisioned.contains(cell_id))
             .collect()
     }
 }
@@ -262,12 +358,19 @@ pub enum CellProvisioningOp {
     /// No provisioning needed, but there might be a clone_limit, and so we need
     /// to know which DNA and Agent to use for making clones
     Noop(CellId, u32),
+    /// Register the DNA without provisioning a Cell for it, so that clones
+    /// can be made from it later
+    RegisterOnly(DnaFile, u32),
     /// Couldn't find a DNA that matches the version spec; can't provision (should this be an Err?)
     NoMatch,
     /// Ambiguous result, needs manual resolution; can't provision (should this be an Err?)
     Conflict(CellProvisioningConflict),
 }
 
-/// Uninhabitable placeholder
+/// The reasons why a Cell can't be provisioned without manual resolution
 #[derive(Debug)]
-pub enum CellProvisioningConflict {}
+pub enum CellProvisioningConflict {
+    /// More than one existing Cell matches the version spec, so it's not
+    /// clear which one should be used
+    AmbiguousExistingCell,
+}
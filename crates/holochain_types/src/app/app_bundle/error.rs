@@ -1,7 +1,7 @@
 use holochain_util::ffs;
 use mr_bundle::error::MrBundleError;
 
-use crate::prelude::{AppManifestError, AppRoleId, DnaError};
+use crate::prelude::{AppManifestError, AppRoleId, BundleHash, DnaError, DnaHash, DnaVersionSpec};
 
 /// Errors occurring while installing an AppBundle
 #[derive(thiserror::Error, Debug)]
@@ -9,6 +9,9 @@ pub enum AppBundleError {
     #[error("Could not resolve the app role '{0}'")]
     CellResolutionFailure(AppRoleId),
 
+    #[error("More than one existing cell matches the app role '{0}'")]
+    CellResolutionConflict(AppRoleId),
+
     #[error(transparent)]
     AppManifestError(#[from] AppManifestError),
 
@@ -20,6 +23,23 @@ pub enum AppBundleError {
 
     #[error(transparent)]
     FfsIoError(#[from] ffs::IoError),
+
+    #[error("Invalid app bundle hash {0:?}: expected 64 lowercase hex characters")]
+    InvalidBundleHash(String),
+
+    #[error("The app bundle downloaded from {url} has hash {actual}, but {expected} was expected")]
+    BundleHashMismatch {
+        url: String,
+        expected: BundleHash,
+        actual: BundleHash,
+    },
+
+    #[error("The DNA downloaded from {url} has hash {actual}, which does not match the expected version {expected:?}")]
+    DnaHashMismatch {
+        url: String,
+        expected: DnaVersionSpec,
+        actual: DnaHash,
+    },
 }
 
 pub type AppBundleResult<T> = Result<T, AppBundleError>;
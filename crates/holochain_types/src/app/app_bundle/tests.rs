@@ -69,3 +69,111 @@ async fn provisioning_1_create() {
     };
     assert_eq!(resolution, expected);
 }
+
+/// Test that an app with a single UseExisting cell is provisioned with a
+/// matching cell from the gamut, and fails to install without one
+#[tokio::test]
+async fn provisioning_1_use_existing() {
+    observability::test_run().ok();
+    let agent = fixt!(AgentPubKey);
+    let dna_hash = fixt!(DnaHash);
+    let cell_id = CellId::new(dna_hash.clone(), agent.clone());
+
+    let manifest: AppManifest = AppManifestV1 {
+        name: "Test app".to_string(),
+        description: None,
+        roles: vec![AppRoleManifest {
+            id: "role_id".into(),
+            dna: AppRoleDnaManifest {
+                location: None,
+                properties: None,
+                network_seed: None,
+                version: Some(DnaHashB64::from(dna_hash).into()),
+                clone_limit: 3,
+            },
+            provisioning: Some(CellProvisioning::UseExisting { deferred: false }),
+        }],
+    }
+    .into();
+    let new_bundle = || async {
+        AppBundle::new(manifest.clone(), vec![], PathBuf::from("."))
+            .await
+            .unwrap()
+    };
+
+    // A cell of another agent with the same DNA is not a match
+    let other_cell_id = CellId::new(cell_id.dna_hash().clone(), fixt!(AgentPubKey));
+    let gamut = DnaGamut::new(vec![other_cell_id]);
+    matches::assert_matches!(
+        new_bundle()
+            .await
+            .resolve_cells(agent.clone(), gamut, Default::default())
+            .await,
+        Err(AppBundleError::CellResolutionFailure(role_id)) if role_id == "role_id"
+    );
+
+    let gamut = DnaGamut::new(vec![cell_id.clone()]);
+    let resolution = new_bundle()
+        .await
+        .resolve_cells(agent.clone(), gamut, Default::default())
+        .await
+        .unwrap();
+
+    let expected = AppRoleResolution {
+        agent,
+        dnas_to_register: vec![],
+        role_assignments: vec![(
+            "role_id".into(),
+            AppRoleAssignment::new_existing(cell_id, 3),
+        )],
+    };
+    assert_eq!(resolution, expected);
+    assert!(resolution.cells_to_create().is_empty());
+}
+
+/// Test that a Disabled role registers its DNA so that it can be cloned
+/// later, but does not create any cell at install time
+#[tokio::test]
+async fn provisioning_1_disabled() {
+    observability::test_run().ok();
+    let agent = fixt!(AgentPubKey);
+    let (_, dna) = app_bundle_fixture().await;
+    let path = PathBuf::from(format!("{}", dna.dna_hash()));
+
+    let manifest: AppManifest = AppManifestV1 {
+        name: "Test app".to_string(),
+        description: None,
+        roles: vec![AppRoleManifest {
+            id: "role_id".into(),
+            dna: AppRoleDnaManifest {
+                location: Some(DnaLocation::Bundled(path.clone())),
+                properties: None,
+                network_seed: None,
+                version: Some(DnaHashB64::from(dna.dna_hash().clone()).into()),
+                clone_limit: 5,
+            },
+            provisioning: Some(CellProvisioning::Disabled),
+        }],
+    }
+    .into();
+    let resources = vec![(path, DnaBundle::from_dna_file(dna.clone()).await.unwrap())];
+    let bundle = AppBundle::new(manifest, resources, PathBuf::from("."))
+        .await
+        .unwrap();
+
+    let resolution = bundle
+        .resolve_cells(agent.clone(), DnaGamut::placeholder(), Default::default())
+        .await
+        .unwrap();
+
+    assert_eq!(resolution.dnas_to_register.len(), 1);
+    let registered = resolution.dnas_to_register[0].0.dna_hash().clone();
+    let (role_id, role) = &resolution.role_assignments[0];
+    assert_eq!(role_id, "role_id");
+    assert_eq!(
+        role,
+        &AppRoleAssignment::new(CellId::new(registered, agent), false, 5)
+    );
+    assert!(role.provisioned_cell().is_none());
+    assert!(resolution.cells_to_create().is_empty());
+}
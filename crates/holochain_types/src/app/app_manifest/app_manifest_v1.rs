@@ -179,6 +179,8 @@ pub enum CellProvisioning {
     CreateIfNotExists { deferred: bool },
     /// Disallow provisioning altogether. In this case, we expect
     /// `clone_limit > 0`: otherwise, no Cells will ever be created.
+    /// If a location is given, the DNA is registered at install time so
+    /// that clones can be created from it later.
     Disabled,
 }
 
@@ -266,6 +268,9 @@ impl AppManifestV1 {
                         CellProvisioning::Disabled => AppRoleManifestValidated::Disabled {
                             clone_limit,
                             version: Self::require(version, "roles.dna.version")?,
+                            location,
+                            properties,
+                            network_seed,
                         },
                     };
                     Ok((id, validated))
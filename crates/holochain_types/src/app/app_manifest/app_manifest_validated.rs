@@ -83,8 +83,14 @@ pub enum AppRoleManifestValidated {
     },
     /// Disallow provisioning altogether. In this case, we expect
     /// `clone_limit > 0`: otherwise, no cells will ever be created.
+    /// If a location is given, the DNA is registered during installation so
+    /// that clones can be made from it; otherwise it must already be
+    /// registered in the conductor.
     Disabled {
         version: DnaVersionSpec,
         clone_limit: u32,
+        location: Option<DnaLocation>,
+        properties: Option<YamlProperties>,
+        network_seed: Option<String>,
     },
 }
@@ -62,18 +62,20 @@ impl DnaGamut {
         DnaResolution::NoMatch
     }
 
-    /// Given a version spec, return the best-matching CellId
-    // TODO: use DPKI to filter Cells which belong to Agents that are not
+    /// Given a version spec, return the best-matching CellId.
+    ///
+    /// Only Cells belonging to the given agent are considered, since without
+    /// DPKI there is no other way to know which Agents are associated with it.
+    /// The hashes of the spec are tried in order, i.e. latest version first.
+    // TODO: use DPKI to also allow Cells which belong to Agents that are
     //       associated with the provided agent
-    pub fn resolve_cell(&self, spec: DnaVersionSpec, _agent: &AgentPubKey) -> CellResolution {
+    pub fn resolve_cell(&self, spec: DnaVersionSpec, agent: &AgentPubKey) -> CellResolution {
         for hash in spec.dna_hashes() {
-            if let Some(agent) = self
+            if self
                 .0
                 .get(hash.as_ref())
-                // TODO: this is where an agent check could go, but for now we
-                //       just return the first one available
-                .map(|agents| agents.iter().next())
-                .unwrap_or(None)
+                .map(|agents| agents.contains(agent))
+                .unwrap_or(false)
             {
                 return CellResolution::Match(
                     CellId::new(hash.clone().into(), agent.clone()),
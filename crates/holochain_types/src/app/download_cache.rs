@@ -0,0 +1,304 @@
+//! A local cache of app and DNA bundles downloaded from URLs.
+
+use super::AppBundleError;
+use super::AppBundleResult;
+use super::DnaVersionSpec;
+use crate::prelude::*;
+use holo_hash::DnaHashB64;
+use holochain_util::ffs;
+use mr_bundle::ResourceBytes;
+use std::path::PathBuf;
+
+/// The hash of the bytes of an app bundle: a lowercase hex encoded
+/// BLAKE2b-256 digest, as printed by `b2sum -l 256`.
+pub type BundleHash = String;
+
+/// Compute the [`BundleHash`] of some bundle bytes.
+pub fn bundle_hash(bytes: &[u8]) -> BundleHash {
+    holo_hash::encode::blake2b_256(bytes)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// Check that a [`BundleHash`] is well formed, so that it is safe to use
+/// as a file name.
+fn check_bundle_hash(hash: &str) -> AppBundleResult<()> {
+    if hash.len() == 64 && hash.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f')) {
+        Ok(())
+    } else {
+        Err(AppBundleError::InvalidBundleHash(hash.to_string()))
+    }
+}
+
+/// Download an app bundle and check that it has the expected hash.
+pub async fn download_app_bundle(
+    url: &str,
+    expected: &BundleHash,
+) -> AppBundleResult<ResourceBytes> {
+    check_bundle_hash(expected)?;
+    let bytes = mr_bundle::resolve_remote(url).await?;
+    let actual = bundle_hash(&bytes);
+    if actual != *expected {
+        return Err(AppBundleError::BundleHashMismatch {
+            url: url.to_string(),
+            expected: expected.clone(),
+            actual,
+        });
+    }
+    Ok(bytes)
+}
+
+/// Download a DNA bundle and check that it matches the version spec, if any.
+/// Returns the bytes along with the hash of the DNA they contain.
+async fn download_dna_bundle(
+    url: &str,
+    expected: Option<&DnaVersionSpec>,
+) -> AppBundleResult<(ResourceBytes, DnaHash)> {
+    let bytes = mr_bundle::resolve_remote(url).await?;
+    let (_, actual) = DnaBundle::decode(&bytes)?.into_dna_file(None, None).await?;
+    match expected {
+        Some(spec) if !spec.matches(actual.clone()) => Err(AppBundleError::DnaHashMismatch {
+            url: url.to_string(),
+            expected: spec.clone(),
+            actual,
+        }),
+        _ => Ok((bytes, actual)),
+    }
+}
+
+/// The hash of the DNA in some cached DNA bundle bytes, if they still decode.
+async fn cached_dna_hash(bytes: &[u8]) -> Option<DnaHashB64> {
+    let (_, hash) = DnaBundle::decode(bytes)
+        .ok()?
+        .into_dna_file(None, None)
+        .await
+        .ok()?;
+    Some(hash.into())
+}
+
+/// A directory of bundles which have been downloaded from URLs, keyed by the
+/// hash they were checked against.
+///
+/// A bundle is only written to the cache once its hash has been verified, and
+/// cached bundles are used instead of downloading them again, so an app can
+/// be installed without network access once its resources have been fetched.
+/// Cached bundles are verified again when they are read, and downloaded again
+/// if they no longer match.
+#[derive(Clone, Debug)]
+pub struct DownloadCache {
+    dir: PathBuf,
+}
+
+impl DownloadCache {
+    /// Use the given directory for the cache. It is created when first needed.
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// Get an app bundle with the expected hash from the cache, or else
+    /// download it from the URL.
+    pub async fn fetch_app_bundle(
+        &self,
+        url: &str,
+        expected: &BundleHash,
+    ) -> AppBundleResult<ResourceBytes> {
+        check_bundle_hash(expected)?;
+        let path = self.dir.join(format!("{}.happ", expected));
+        if let Ok(bytes) = ffs::read(&path).await {
+            if bundle_hash(&bytes) == *expected {
+                return Ok(bytes);
+            }
+            tracing::warn!(?path, "Cached app bundle does not match its hash");
+        }
+        let bytes = download_app_bundle(url, expected).await?;
+        self.store(path, &bytes).await;
+        Ok(bytes)
+    }
+
+    /// Get a DNA bundle matching the version spec from the cache, or else
+    /// download it from the URL.
+    ///
+    /// Without a version spec there is no way to know which cached DNA the URL
+    /// refers to, so the DNA is always downloaded, and then cached by its hash.
+    pub async fn fetch_dna_bundle(
+        &self,
+        url: &str,
+        expected: Option<&DnaVersionSpec>,
+    ) -> AppBundleResult<ResourceBytes> {
+        for hash in expected.into_iter().flat_map(|spec| spec.dna_hashes()) {
+            let path = self.dna_path(hash);
+            if let Ok(bytes) = ffs::read(&path).await {
+                if cached_dna_hash(&bytes).await.as_ref() == Some(hash) {
+                    return Ok(bytes);
+                }
+                tracing::warn!(?path, "Cached DNA bundle does not match its hash");
+            }
+        }
+        let (bytes, dna_hash) = download_dna_bundle(url, expected).await?;
+        self.store(self.dna_path(&dna_hash.into()), &bytes).await;
+        Ok(bytes)
+    }
+
+    /// DNA hashes display as url-safe base64, so they are safe to use as a
+    /// file name.
+    fn dna_path(&self, dna_hash: &DnaHashB64) -> PathBuf {
+        self.dir.join(format!("{}.dna", dna_hash))
+    }
+
+    /// Failing to cache a bundle is not fatal, it just means
+    /// the bundle will have to be downloaded again next time.
+    async fn store(&self, path: PathBuf, bytes: &[u8]) {
+        let result = match ffs::create_dir_all(&self.dir).await {
+            Ok(()) => ffs::write(&path, bytes).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            tracing::warn!(?path, ?e, "Could not write downloaded bundle to the cache");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::fake_dna_file;
+    use tokio::io::AsyncReadExt;
+    use tokio::io::AsyncWriteExt;
+    use tokio::net::TcpListener;
+
+    /// Serve the same bytes in response to every request, until aborted.
+    async fn serve(bytes: Vec<u8>) -> (String, tokio::task::JoinHandle<()>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/bundle", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut request = Vec::new();
+                let mut buf = [0; 1024];
+                while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                    let n = stream.read(&mut buf).await.unwrap();
+                    if n == 0 {
+                        break;
+                    }
+                    request.extend_from_slice(&buf[..n]);
+                }
+                let header = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    bytes.len()
+                );
+                stream.write_all(header.as_bytes()).await.unwrap();
+                stream.write_all(&bytes).await.unwrap();
+                stream.shutdown().await.ok();
+            }
+        });
+        (url, server)
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn app_bundle_is_verified_and_cached() {
+        let tmp = tempfile::tempdir().unwrap();
+        let cache = DownloadCache::new(tmp.path().join("cache"));
+        let bytes = b"not really a happ".to_vec();
+        let hash = bundle_hash(&bytes);
+        let (url, server) = serve(bytes.clone()).await;
+
+        let wrong_hash = bundle_hash(b"something else");
+        matches::assert_matches!(
+            cache.fetch_app_bundle(&url, &wrong_hash).await,
+            Err(AppBundleError::BundleHashMismatch { .. })
+        );
+
+        assert_eq!(cache.fetch_app_bundle(&url, &hash).await.unwrap(), bytes);
+
+        // Once cached, the bundle is available without the server.
+        server.abort();
+        let _ = server.await;
+        assert_eq!(cache.fetch_app_bundle(&url, &hash).await.unwrap(), bytes);
+        assert!(cache.fetch_app_bundle(&url, &wrong_hash).await.is_err());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn app_bundle_hash_must_be_hex() {
+        let tmp = tempfile::tempdir().unwrap();
+        let cache = DownloadCache::new(tmp.path().join("cache"));
+        for hash in [
+            "../../etc/passwd".to_string(),
+            bundle_hash(b"bundle").to_uppercase(),
+            bundle_hash(b"bundle")[1..].to_string(),
+        ] {
+            matches::assert_matches!(
+                cache
+                    .fetch_app_bundle("http://localhost:1/bundle", &hash)
+                    .await,
+                Err(AppBundleError::InvalidBundleHash(_))
+            );
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn tampered_cache_is_downloaded_again() {
+        let tmp = tempfile::tempdir().unwrap();
+        let cache = DownloadCache::new(tmp.path().join("cache"));
+        let bytes = b"not really a happ".to_vec();
+        let hash = bundle_hash(&bytes);
+        let (url, server) = serve(bytes.clone()).await;
+
+        let path = tmp.path().join("cache").join(format!("{}.happ", hash));
+        ffs::create_dir_all(tmp.path().join("cache")).await.unwrap();
+        ffs::write(&path, b"tampered").await.unwrap();
+        assert_eq!(cache.fetch_app_bundle(&url, &hash).await.unwrap(), bytes);
+        assert_eq!(ffs::read(&path).await.unwrap(), bytes);
+
+        // Without the server a tampered bundle is an error, not a cache hit.
+        server.abort();
+        let _ = server.await;
+        ffs::write(&path, b"tampered").await.unwrap();
+        assert!(cache.fetch_app_bundle(&url, &hash).await.is_err());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn dna_bundle_is_verified_and_cached() {
+        let tmp = tempfile::tempdir().unwrap();
+        let cache = DownloadCache::new(tmp.path().join("cache"));
+        let dna_file = fake_dna_file("download");
+        let dna_hash = dna_file.dna_hash().clone();
+        let bytes = DnaBundle::from_dna_file(dna_file)
+            .await
+            .unwrap()
+            .encode()
+            .unwrap();
+        let (url, server) = serve(bytes.clone()).await;
+
+        let wrong_spec =
+            DnaVersionSpec::from(vec![fake_dna_file("other").dna_hash().clone().into()]);
+        matches::assert_matches!(
+            cache.fetch_dna_bundle(&url, Some(&wrong_spec)).await,
+            Err(AppBundleError::DnaHashMismatch { .. })
+        );
+
+        let spec = DnaVersionSpec::from(vec![dna_hash.into()]);
+        assert_eq!(
+            cache.fetch_dna_bundle(&url, Some(&spec)).await.unwrap(),
+            bytes
+        );
+
+        // Once cached, the bundle is available without the server.
+        server.abort();
+        let _ = server.await;
+        assert_eq!(
+            cache.fetch_dna_bundle(&url, Some(&spec)).await.unwrap(),
+            bytes
+        );
+
+        // A cached DNA which doesn't match its hash is not used.
+        let other = DnaBundle::from_dna_file(fake_dna_file("other"))
+            .await
+            .unwrap()
+            .encode()
+            .unwrap();
+        let path = cache.dna_path(spec.dna_hashes()[0]);
+        ffs::write(&path, &other).await.unwrap();
+        assert!(cache.fetch_dna_bundle(&url, Some(&spec)).await.is_err());
+    }
+}
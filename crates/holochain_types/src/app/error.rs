@@ -8,6 +8,21 @@ pub enum AppError {
     #[error("Clone limit of {0} exceeded for cell: {1:?}")]
     CloneLimitExceeded(u32, AppRoleAssignment),
 
+    #[error("Role '{0}' has no clone cell with id: {1:?}")]
+    CloneCellNotFound(AppRoleId, CellId),
+
+    #[error("Role '{0}' can't be cloned from cell {1:?}, which is not the cell of the role")]
+    CloneBaseCellMismatch(AppRoleId, CellId),
+
+    #[error("Role '{0}' has no archived clone cell with id: {1:?}")]
+    ArchivedCloneCellNotFound(AppRoleId, CellId),
+
+    #[error("Role '{0}' is not provisioned by this app, so its cell can't be upgraded")]
+    RoleNotUpgradable(AppRoleId),
+
+    #[error("Role '{0}' can't be upgraded to a cell of a different agent: {1}")]
+    UpgradeAgentKeyMismatch(AppRoleId, AgentPubKey),
+
     #[error("Tried to access missing role id: '{0}'")]
     AppRoleIdMissing(AppRoleId),
 
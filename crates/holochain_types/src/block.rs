@@ -0,0 +1,57 @@
+//! Types for blocking misbehaving remote agents and nodes.
+
+use holo_hash::AgentPubKey;
+use holochain_zome_types::Timestamp;
+
+/// The digest of the certificate a node's tls connections are made with,
+/// which identifies the node on the network.
+#[derive(
+    Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize, serde::Deserialize,
+)]
+pub struct NodeCert(#[serde(with = "serde_bytes")] Vec<u8>);
+
+impl From<Vec<u8>> for NodeCert {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self(bytes)
+    }
+}
+
+impl AsRef<[u8]> for NodeCert {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+/// Something the conductor can refuse to communicate with.
+#[derive(
+    Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize, serde::Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum BlockTarget {
+    /// An agent, in every DNA it is running.
+    Agent(AgentPubKey),
+    /// A node, along with every agent running on it.
+    Node(NodeCert),
+}
+
+/// A block on a target, which lasts until it is lifted or expires.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct Block {
+    /// The agent or node which is blocked.
+    pub target: BlockTarget,
+    /// When the block is lifted automatically.
+    /// If this is `None` the block lasts until it is lifted manually.
+    pub expires_at: Option<Timestamp>,
+}
+
+impl Block {
+    /// Construct a new block.
+    pub fn new(target: BlockTarget, expires_at: Option<Timestamp>) -> Self {
+        Self { target, expires_at }
+    }
+
+    /// Has this block expired at the given time?
+    pub fn is_expired(&self, now: Timestamp) -> bool {
+        matches!(self.expires_at, Some(expires_at) if expires_at <= now)
+    }
+}
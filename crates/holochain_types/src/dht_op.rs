@@ -16,6 +16,7 @@ use holochain_sqlite::rusqlite::types::FromSql;
 use holochain_sqlite::rusqlite::ToSql;
 use holochain_zome_types::action;
 use holochain_zome_types::prelude::*;
+use kitsune_p2p_dht::hash::RegionHash;
 use kitsune_p2p_dht::region::RegionData;
 use kitsune_p2p_dht::Loc;
 use serde::Deserialize;
@@ -111,6 +112,15 @@ pub enum DhtOp {
     #[display(fmt = "RegisterRemoveLink")]
     /// Op for removing a link
     RegisterRemoveLink(Signature, action::DeleteLink),
+
+    #[display(fmt = "RegisterWarrant")]
+    /// Op for registering a warrant against an agent with the
+    /// authorities for that agent's activity.
+    ///
+    /// The action the warrant is about is included along with its
+    /// author's signature, so that authorities can check that the
+    /// warranted agent really did author it.
+    RegisterWarrant(Signature, Action, Box<SignedWarrant>),
 }
 
 impl kitsune_p2p_dht::prelude::OpRegion for DhtOp {
@@ -122,12 +132,41 @@ impl kitsune_p2p_dht::prelude::OpRegion for DhtOp {
         self.timestamp()
     }
 
+    /// The hash, size and count of this op as `fetch_op_region.sql` adds
+    /// them up, so that region sets built from ops in memory match the ones
+    /// queried from a database.
+    ///
+    /// The query counts the size of the entry an op's action refers to
+    /// whenever the database holds that entry, but an op which doesn't carry
+    /// its entry, e.g. the agent activity op of a `Create`, can't know its size.
+    /// So the two only agree for such ops when the database doesn't also hold
+    /// an op carrying the same entry.
     fn region_data(&self) -> RegionData {
-        unimplemented!()
+        let hash = DhtOpHash::with_data_sync(self);
+        RegionData {
+            hash: RegionHash::from_vec(hash.get_raw_39().to_vec())
+                .expect("a DhtOpHash is 39 bytes"),
+            size: self.region_size(),
+            count: 1,
+        }
     }
 
-    fn bound(_timestamp: Timestamp, _loc: kitsune_p2p_dht::Loc) -> Self {
-        unimplemented!()
+    /// An agent activity op with the given timestamp, authored by an agent
+    /// whose key has the given location. Agent activity ops come first among
+    /// ops with the same timestamp and location, so this is the least op
+    /// with them. Its action and signature are placeholders which will not
+    /// validate.
+    fn bound(timestamp: Timestamp, loc: kitsune_p2p_dht::Loc) -> Self {
+        let mut author = vec![0; 32];
+        author.extend_from_slice(&loc.as_u32().to_le_bytes());
+        DhtOp::RegisterAgentActivity(
+            Signature([0; 64]),
+            Action::Dna(action::Dna {
+                author: AgentPubKey::from_raw_36(author),
+                timestamp,
+                hash: DnaHash::from_raw_36(vec![0; 36]),
+            }),
+        )
     }
 }
 
@@ -157,6 +196,8 @@ pub enum DhtOpLight {
     RegisterAddLink(ActionHash, DhtBasis),
     #[display(fmt = "RegisterRemoveLink")]
     RegisterRemoveLink(ActionHash, DhtBasis),
+    #[display(fmt = "RegisterWarrant")]
+    RegisterWarrant(ActionHash, DhtBasis),
 }
 
 impl PartialEq for DhtOpLight {
@@ -210,6 +251,8 @@ pub enum DhtOpType {
     RegisterAddLink,
     #[display(fmt = "RegisterRemoveLink")]
     RegisterRemoveLink,
+    #[display(fmt = "RegisterWarrant")]
+    RegisterWarrant,
 }
 
 impl ToSql for DhtOpType {
@@ -249,6 +292,7 @@ impl DhtOp {
             }
             Self::RegisterAddLink(_, action) => UniqueForm::RegisterAddLink(action),
             Self::RegisterRemoveLink(_, action) => UniqueForm::RegisterRemoveLink(action),
+            Self::RegisterWarrant(_, _, warrant) => UniqueForm::RegisterWarrant(&warrant.0),
         }
     }
 
@@ -305,6 +349,10 @@ impl DhtOp {
                 let h = ActionHash::with_data_sync(&Action::from(h.clone()));
                 DhtOpLight::RegisterRemoveLink(h, basis)
             }
+            DhtOp::RegisterWarrant(_, h, _) => {
+                let h = ActionHash::with_data_sync(h);
+                DhtOpLight::RegisterWarrant(h, basis)
+            }
         }
     }
 
@@ -319,7 +367,8 @@ impl DhtOp {
             | DhtOp::RegisterDeletedBy(s, _)
             | DhtOp::RegisterDeletedEntryAction(s, _)
             | DhtOp::RegisterAddLink(s, _)
-            | DhtOp::RegisterRemoveLink(s, _) => s,
+            | DhtOp::RegisterRemoveLink(s, _)
+            | DhtOp::RegisterWarrant(s, _, _) => s,
         }
     }
 
@@ -335,6 +384,7 @@ impl DhtOp {
             DhtOp::RegisterDeletedEntryAction(s, h) => (s, h.into(), None),
             DhtOp::RegisterAddLink(s, h) => (s, h.into(), None),
             DhtOp::RegisterRemoveLink(s, h) => (s, h.into(), None),
+            DhtOp::RegisterWarrant(s, h, _) => (s, h, None),
         }
     }
 
@@ -352,6 +402,23 @@ impl DhtOp {
             DhtOp::RegisterDeletedEntryAction(_, h) => h.clone().into(),
             DhtOp::RegisterAddLink(_, h) => h.clone().into(),
             DhtOp::RegisterRemoveLink(_, h) => h.clone().into(),
+            DhtOp::RegisterWarrant(_, h, _) => h.clone(),
+        }
+    }
+
+    /// Access to the Timestamp
+    pub fn timestamp(&self) -> Timestamp {
+        match self {
+            DhtOp::StoreRecord(_, h, _) => h.timestamp(),
+            DhtOp::StoreEntry(_, h, _) => h.timestamp(),
+            DhtOp::RegisterAgentActivity(_, h) => h.timestamp(),
+            DhtOp::RegisterUpdatedContent(_, h, _) => h.timestamp,
+            DhtOp::RegisterUpdatedRecord(_, h, _) => h.timestamp,
+            DhtOp::RegisterDeletedBy(_, h) => h.timestamp,
+            DhtOp::RegisterDeletedEntryAction(_, h) => h.timestamp,
+            DhtOp::RegisterAddLink(_, h) => h.timestamp,
+            DhtOp::RegisterRemoveLink(_, h) => h.timestamp,
+            DhtOp::RegisterWarrant(_, h, _) => h.timestamp(),
         }
     }
 
@@ -367,6 +434,7 @@ impl DhtOp {
             DhtOp::RegisterDeletedEntryAction(_, _) => None,
             DhtOp::RegisterAddLink(_, _) => None,
             DhtOp::RegisterRemoveLink(_, _) => None,
+            DhtOp::RegisterWarrant(_, _, _) => None,
         }
     }
 
@@ -382,6 +450,7 @@ impl DhtOp {
             DhtOp::RegisterDeletedEntryAction(_, _) => DhtOpType::RegisterDeletedEntryAction,
             DhtOp::RegisterAddLink(_, _) => DhtOpType::RegisterAddLink,
             DhtOp::RegisterRemoveLink(_, _) => DhtOpType::RegisterRemoveLink,
+            DhtOp::RegisterWarrant(_, _, _) => DhtOpType::RegisterWarrant,
         }
     }
 
@@ -418,10 +487,54 @@ impl DhtOp {
             DhtOpType::RegisterRemoveLink => {
                 DhtOp::RegisterRemoveLink(signature, action.try_into()?)
             }
+            DhtOpType::RegisterWarrant => return Err(DhtOpError::WarrantMissing),
         };
         Ok(r)
     }
 
+    /// From a type, action and an entry (if there is one), along with
+    /// the warrant if this is a [`DhtOp::RegisterWarrant`].
+    pub fn from_type_with_warrant(
+        op_type: DhtOpType,
+        action: SignedAction,
+        entry: Option<Entry>,
+        warrant: Option<SignedWarrant>,
+    ) -> DhtOpResult<Self> {
+        match (op_type, warrant) {
+            (DhtOpType::RegisterWarrant, Some(warrant)) => {
+                let SignedAction(action, signature) = action;
+                Ok(DhtOp::RegisterWarrant(signature, action, Box::new(warrant)))
+            }
+            _ => Self::from_type(op_type, action, entry),
+        }
+    }
+
+    /// Get the warrant from this op, if it is a [`DhtOp::RegisterWarrant`].
+    pub fn warrant(&self) -> Option<&SignedWarrant> {
+        match self {
+            DhtOp::RegisterWarrant(_, _, warrant) => Some(warrant),
+            _ => None,
+        }
+    }
+
+    /// The size of this op as it is stored in a database: the serialized
+    /// signed action, plus the serialized entry if the op carries one.
+    fn region_size(&self) -> u32 {
+        #[derive(Serialize, Debug)]
+        struct SignedActionRef<'a>(&'a Action, &'a Signature);
+        let action = self.action();
+        let action_size =
+            holochain_serialized_bytes::encode(&SignedActionRef(&action, self.signature()))
+                .expect("an action can always be serialized")
+                .len();
+        let entry_size = self.entry().map_or(0, |entry| {
+            holochain_serialized_bytes::encode(entry)
+                .expect("an entry can always be serialized")
+                .len()
+        });
+        (action_size + entry_size).min(u32::MAX as usize) as u32
+    }
+
     fn to_order(&self) -> OpOrder {
         OpOrder::new(self.get_type(), self.timestamp())
     }
@@ -453,9 +566,16 @@ impl PartialOrd for DhtOp {
     }
 }
 
+/// Ops are ordered by time and then location, as range queries over an
+/// [`OpRegion`](kitsune_p2p_dht::prelude::OpRegion) require, and then by their [`OpOrder`].
 impl Ord for DhtOp {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.to_order().cmp(&other.to_order())
+        use kitsune_p2p_dht::prelude::OpRegion;
+        (self.timestamp(), self.loc(), self.to_order()).cmp(&(
+            other.timestamp(),
+            other.loc(),
+            other.to_order(),
+        ))
     }
 }
 
@@ -471,7 +591,8 @@ impl DhtOpLight {
             | DhtOpLight::RegisterDeletedBy(_, b)
             | DhtOpLight::RegisterDeletedEntryAction(_, b)
             | DhtOpLight::RegisterAddLink(_, b)
-            | DhtOpLight::RegisterRemoveLink(_, b) => b,
+            | DhtOpLight::RegisterRemoveLink(_, b)
+            | DhtOpLight::RegisterWarrant(_, b) => b,
         }
     }
     /// Get the action hash from this op
@@ -485,7 +606,8 @@ impl DhtOpLight {
             | DhtOpLight::RegisterDeletedBy(h, _)
             | DhtOpLight::RegisterDeletedEntryAction(h, _)
             | DhtOpLight::RegisterAddLink(h, _)
-            | DhtOpLight::RegisterRemoveLink(h, _) => h,
+            | DhtOpLight::RegisterRemoveLink(h, _)
+            | DhtOpLight::RegisterWarrant(h, _) => h,
         }
     }
 
@@ -501,6 +623,7 @@ impl DhtOpLight {
             DhtOpLight::RegisterDeletedEntryAction(_, _) => DhtOpType::RegisterDeletedEntryAction,
             DhtOpLight::RegisterAddLink(_, _) => DhtOpType::RegisterAddLink,
             DhtOpLight::RegisterRemoveLink(_, _) => DhtOpType::RegisterRemoveLink,
+            DhtOpLight::RegisterWarrant(_, _) => DhtOpType::RegisterWarrant,
         }
     }
 
@@ -575,6 +698,9 @@ impl DhtOpLight {
                 };
                 Self::RegisterRemoveLink(action_hash, basis.into())
             }
+            DhtOpType::RegisterWarrant => {
+                Self::RegisterWarrant(action_hash, action.author().clone().into())
+            }
         };
         Ok(op)
     }
@@ -594,6 +720,7 @@ pub enum UniqueForm<'a> {
     RegisterDeletedEntryAction(&'a action::Delete),
     RegisterAddLink(&'a action::CreateLink),
     RegisterRemoveLink(&'a action::DeleteLink),
+    RegisterWarrant(&'a Warrant),
 }
 
 impl<'a> UniqueForm<'a> {
@@ -614,6 +741,7 @@ impl<'a> UniqueForm<'a> {
             }
             UniqueForm::RegisterAddLink(action) => action.base_address.clone().into(),
             UniqueForm::RegisterRemoveLink(action) => action.base_address.clone().into(),
+            UniqueForm::RegisterWarrant(warrant) => warrant.author.clone().into(),
         }
     }
 
@@ -664,6 +792,9 @@ impl<'a> UniqueForm<'a> {
                 let hash = DhtOpHash::with_data_sync(&UniqueForm::RegisterRemoveLink(&action));
                 Ok((action.into(), hash))
             }
+            // The hash of a warrant op depends on the warrant,
+            // which can't be recovered from the action.
+            DhtOpType::RegisterWarrant => Err(DhtOpError::WarrantMissing),
         }
     }
 }
@@ -729,6 +860,8 @@ pub fn produce_ops_from_record(record: &Record) -> DhtOpResult<Vec<DhtOp>> {
                 let link_remove = action.try_into()?;
                 DhtOp::RegisterRemoveLink(signature, link_remove)
             }
+            // Warrants are not produced from records.
+            DhtOpLight::RegisterWarrant(_, _) => continue,
         };
         ops.push(op);
     }
@@ -1023,6 +1156,7 @@ pub enum OpNumericalOrder {
     RegisterDeletedEntryAction,
     RegisterAddLink,
     RegisterRemoveLink,
+    RegisterWarrant,
 }
 
 /// This is used as an index for ordering ops in our database.
@@ -1059,6 +1193,7 @@ impl OpOrder {
             DhtOpType::RegisterDeletedEntryAction => OpNumericalOrder::RegisterDeletedEntryAction,
             DhtOpType::RegisterAddLink => OpNumericalOrder::RegisterAddLink,
             DhtOpType::RegisterRemoveLink => OpNumericalOrder::RegisterRemoveLink,
+            DhtOpType::RegisterWarrant => OpNumericalOrder::RegisterWarrant,
         };
         Self { order, timestamp }
     }
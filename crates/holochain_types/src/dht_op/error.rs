@@ -18,6 +18,8 @@ pub enum DhtOpError {
     OpActionMismatch(DhtOpType, ActionType),
     #[error("Link requests without tags require a tag in the response")]
     LinkKeyTagMissing,
+    #[error("Tried to create a RegisterWarrant DhtOp without a warrant")]
+    WarrantMissing,
 }
 
 pub type DhtOpResult<T> = Result<T, DhtOpError>;
@@ -120,21 +120,7 @@ impl DhtOp {
             DhtOp::RegisterDeletedEntryAction(_, h) => &mut h.author,
             DhtOp::RegisterAddLink(_, h) => &mut h.author,
             DhtOp::RegisterRemoveLink(_, h) => &mut h.author,
-        }
-    }
-
-    /// Access to the Timestamp
-    pub fn timestamp(&self) -> Timestamp {
-        match self {
-            DhtOp::StoreRecord(_, h, _) => h.timestamp(),
-            DhtOp::StoreEntry(_, h, _) => h.timestamp(),
-            DhtOp::RegisterAgentActivity(_, h) => h.timestamp(),
-            DhtOp::RegisterUpdatedContent(_, h, _) => h.timestamp,
-            DhtOp::RegisterUpdatedRecord(_, h, _) => h.timestamp,
-            DhtOp::RegisterDeletedBy(_, h) => h.timestamp,
-            DhtOp::RegisterDeletedEntryAction(_, h) => h.timestamp,
-            DhtOp::RegisterAddLink(_, h) => h.timestamp,
-            DhtOp::RegisterRemoveLink(_, h) => h.timestamp,
+            DhtOp::RegisterWarrant(_, h, _) => h.author_mut(),
         }
     }
 
@@ -150,6 +136,7 @@ impl DhtOp {
             DhtOp::RegisterDeletedEntryAction(_, h) => &mut h.timestamp,
             DhtOp::RegisterAddLink(_, h) => &mut h.timestamp,
             DhtOp::RegisterRemoveLink(_, h) => &mut h.timestamp,
+            DhtOp::RegisterWarrant(_, h, _) => h.timestamp_mut(),
         }
     }
 
@@ -165,6 +152,7 @@ impl DhtOp {
             DhtOp::RegisterDeletedEntryAction(s, _) => s,
             DhtOp::RegisterAddLink(s, _) => s,
             DhtOp::RegisterRemoveLink(s, _) => s,
+            DhtOp::RegisterWarrant(s, _, _) => s,
         }
     }
 
@@ -180,6 +168,7 @@ impl DhtOp {
             DhtOp::RegisterDeletedEntryAction(_, ref mut h) => Some(&mut h.action_seq),
             DhtOp::RegisterAddLink(_, ref mut h) => Some(&mut h.action_seq),
             DhtOp::RegisterRemoveLink(_, ref mut h) => Some(&mut h.action_seq),
+            DhtOp::RegisterWarrant(_, ref mut h, _) => h.action_seq_mut(),
         }
     }
 
@@ -13,6 +13,7 @@ use crate::fixt::UpdateFixturator;
 use crate::prelude::*;
 use ::fixt::prelude::*;
 use holo_hash::fixt::ActionHashFixturator;
+use holo_hash::fixt::AgentPubKeyFixturator;
 use holo_hash::*;
 use holochain_zome_types::ActionHashed;
 use holochain_zome_types::Entry;
@@ -308,6 +309,9 @@ fn get_type_op() {
                 DhtOp::RegisterRemoveLink(_, _) => {
                     assert_eq!(op_type, DhtOpType::RegisterRemoveLink)
                 }
+                DhtOp::RegisterWarrant(_, _, _) => {
+                    assert_eq!(op_type, DhtOpType::RegisterWarrant)
+                }
             }
         };
         for op in ops {
@@ -444,6 +448,9 @@ fn test_all_ops_basis() {
                     )
                 )
             }
+            (DhtOpType::RegisterWarrant, basis) => {
+                assert_eq!(basis, AnyDhtHash::from(record.action().author().clone()))
+            }
         };
         for op in ops {
             assert_eq!(*op.to_light().dht_basis(), op.dht_basis());
@@ -454,3 +461,72 @@ fn test_all_ops_basis() {
         check_all_ops(record);
     }
 }
+
+#[test]
+fn register_warrant_op() {
+    let action = Action::CreateLink(fixt!(CreateLink));
+    let action_hash = ActionHash::with_data_sync(&action);
+    let author = action.author().clone();
+    let warrant = SignedWarrant(
+        Warrant::new(
+            action_hash.clone(),
+            author.clone(),
+            "invalid".into(),
+            fixt!(AgentPubKey),
+            Timestamp::now(),
+        ),
+        fixt!(Signature),
+    );
+    let signed_action = SignedAction(action.clone(), fixt!(Signature));
+
+    // The warrant can't be recovered from the action alone.
+    assert!(matches!(
+        DhtOp::from_type(DhtOpType::RegisterWarrant, signed_action.clone(), None),
+        Err(DhtOpError::WarrantMissing)
+    ));
+
+    let op = DhtOp::from_type_with_warrant(
+        DhtOpType::RegisterWarrant,
+        signed_action,
+        None,
+        Some(warrant.clone()),
+    )
+    .unwrap();
+    assert_eq!(op.get_type(), DhtOpType::RegisterWarrant);
+    assert_eq!(op.warrant(), Some(&warrant));
+    assert_eq!(op.action(), action);
+    assert_eq!(op.entry(), None);
+    assert_eq!(op.dht_basis(), AnyDhtHash::from(author.clone()));
+
+    let light = op.to_light();
+    assert_eq!(light.action_hash(), &action_hash);
+    assert_eq!(light.dht_basis(), &AnyDhtHash::from(author));
+    assert_eq!(
+        DhtOpLight::from_type(DhtOpType::RegisterWarrant, action_hash, &action).unwrap(),
+        light
+    );
+}
+
+#[test]
+fn op_region_bound() {
+    use kitsune_p2p_dht::prelude::OpRegion;
+    let mut create = fixt!(Create);
+    let entry = fixt!(Entry);
+    create.entry_hash = EntryHash::with_data_sync(&entry);
+    let op = DhtOp::StoreEntry(
+        fixt!(Signature),
+        NewEntryAction::Create(create),
+        Box::new(entry),
+    );
+    let (timestamp, loc) = (op.timestamp(), op.loc());
+
+    let bound = DhtOp::bound(timestamp, loc);
+    assert_eq!(OpRegion::timestamp(&bound), timestamp);
+    assert_eq!(bound.loc(), loc);
+
+    // The bound is the least op with the same time and location,
+    // and ops are ordered by time before location.
+    assert!(bound < op);
+    let later = (timestamp + std::time::Duration::from_micros(1)).unwrap();
+    assert!(op < DhtOp::bound(later, kitsune_p2p_dht::Loc::from(0u32)));
+}
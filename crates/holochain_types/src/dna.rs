@@ -4,6 +4,7 @@
 //! as well as serializing and deserializing dna, mainly to json format.
 
 mod dna_bundle;
+mod dna_definition;
 mod dna_file;
 mod dna_manifest;
 mod ribosome_store;
@@ -12,6 +13,7 @@ mod ribosome_store;
 pub mod error;
 pub mod wasm;
 pub use dna_bundle::*;
+pub use dna_definition::*;
 pub use dna_file::*;
 pub use dna_manifest::*;
 pub use error::DnaError;
@@ -131,6 +131,7 @@ impl DnaBundle {
                     origin_time: manifest.integrity.origin_time.into(),
                     integrity_zomes,
                     coordinator_zomes,
+                    rate_limits: manifest.integrity.rate_limits.clone(),
                 };
 
                 if network_seed.is_none() && properties.is_none() {
@@ -232,6 +233,7 @@ impl DnaBundle {
                 })?),
                 origin_time: dna_def.origin_time.into(),
                 zomes: integrity,
+                rate_limits: dna_def.rate_limits,
             },
             coordinator: CoordinatorManifest { zomes: coordinator },
         }
@@ -306,6 +308,7 @@ mod tests {
                         dependencies: Default::default(),
                     },
                 ],
+                rate_limits: Vec::new(),
             },
             coordinator: CoordinatorManifest { zomes: vec![] },
         };
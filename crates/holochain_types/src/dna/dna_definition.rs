@@ -0,0 +1,30 @@
+use crate::prelude::*;
+
+/// Everything a DNA exposes, found by inspecting its zomes rather than
+/// calling them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, SerializedBytes)]
+pub struct DnaDefinition {
+    /// The hash of the DNA
+    pub dna_hash: DnaHash,
+    /// The name of the DNA
+    pub name: String,
+    /// The integrity zomes, in the order they appear in the DNA
+    pub integrity_zomes: Vec<ZomeDefinition>,
+    /// The coordinator zomes, in the order they appear in the DNA
+    pub coordinator_zomes: Vec<ZomeDefinition>,
+}
+
+/// The functions and types exposed by a single zome.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, SerializedBytes)]
+pub struct ZomeDefinition {
+    /// The name of the zome
+    pub name: ZomeName,
+    /// The functions which can be called with a zome call
+    pub zome_fns: Vec<FunctionName>,
+    /// The callbacks which the conductor invokes, such as `init` or `validate`
+    pub callbacks: Vec<FunctionName>,
+    /// The entry types defined by this zome. Always empty for coordinator zomes.
+    pub entry_defs: Vec<EntryDef>,
+    /// The link types defined by this zome. Always empty for coordinator zomes.
+    pub link_types: Vec<LinkType>,
+}
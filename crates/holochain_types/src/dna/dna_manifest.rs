@@ -56,7 +56,13 @@ impl DnaManifest {
     ) -> Self {
         DnaManifestCurrent::new(
             name,
-            IntegrityManifest::new(network_seed, properties, origin_time, integrity_zomes),
+            IntegrityManifest::new(
+                network_seed,
+                properties,
+                origin_time,
+                integrity_zomes,
+                Vec::new(),
+            ),
             CoordinatorManifest {
                 zomes: coordinator_zomes,
             },
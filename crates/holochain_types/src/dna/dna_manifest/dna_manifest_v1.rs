@@ -23,6 +23,10 @@ use serde_with::serde_as;
 ///       bundled: ../dna1/zomes/zome1.wasm
 ///     - name: zome2
 ///       bundled: ../dna2/zomes/zome1.wasm
+///   rate_limits:
+///     - bucket_id: 0
+///       capacity: 100
+///       drain_per_second: 1
 /// coordinator:
 ///   zomes:
 ///     - name: zome3
@@ -126,6 +130,12 @@ pub struct IntegrityManifest {
     /// The order is significant: it determines initialization order.
     /// The integrity zome manifests.
     pub zomes: Vec<ZomeManifest>,
+
+    /// Limits on the rate limiting buckets which actions are weighed into
+    /// by the `weigh` callback. Buckets without a limit are not enforced.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[builder(default)]
+    pub rate_limits: Vec<RateBucketLimit>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Default)]
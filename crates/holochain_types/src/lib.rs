@@ -17,6 +17,7 @@ pub mod action;
 pub mod activity;
 pub mod app;
 pub mod autonomic;
+pub mod block;
 pub mod chain;
 pub mod combinators;
 pub mod db;
@@ -46,6 +46,9 @@ pub struct WireLinkKey {
     pub type_query: LinkTypeFilter,
     /// Optionally specify a tag for more specific queries.
     pub tag: Option<LinkTag>,
+    /// Narrow down, order and page through the links.
+    #[serde(default)]
+    pub filter: LinkFilter,
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SerializedBytes, Default)]
@@ -76,6 +79,58 @@ impl WireLinkOps {
     }
 }
 
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SerializedBytes, Default)]
+/// Response to a count links request.
+///
+/// An authority counts the links it holds which match the request, and how
+/// many of those it has seen deleted. The authorities of a base converge on
+/// the same links, which are only ever created and then deleted, so the
+/// most creates and the most deletes any of them has seen are the closest
+/// to the full picture.
+pub struct CountLinksResponse {
+    create_count: usize,
+    delete_count: usize,
+}
+
+impl CountLinksResponse {
+    /// Create a response from the number of links which match the request,
+    /// and how many of them have been deleted.
+    pub fn new(create_count: usize, delete_count: usize) -> Self {
+        Self {
+            create_count,
+            delete_count,
+        }
+    }
+
+    /// The number of links which match the request, deleted or not.
+    pub fn create_count(&self) -> usize {
+        self.create_count
+    }
+
+    /// The number of matching links which have been deleted.
+    pub fn delete_count(&self) -> usize {
+        self.delete_count
+    }
+
+    /// The number of live links.
+    pub fn count(&self) -> usize {
+        self.create_count.saturating_sub(self.delete_count)
+    }
+
+    /// Merge the responses of several authorities, so that a link deleted
+    /// according to any of them is not counted.
+    pub fn merge(responses: impl IntoIterator<Item = Self>) -> Self {
+        responses
+            .into_iter()
+            .fold(Self::default(), |merged, response| {
+                Self::new(
+                    merged.create_count.max(response.create_count),
+                    merged.delete_count.max(response.delete_count),
+                )
+            })
+    }
+}
+
 #[allow(missing_docs)]
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SerializedBytes)]
 /// Condensed version of a [`CreateLink`]
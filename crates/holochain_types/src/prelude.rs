@@ -13,6 +13,7 @@ pub use crate::activity::*;
 pub use crate::app::error::*;
 pub use crate::app::*;
 pub use crate::autonomic::*;
+pub use crate::block::*;
 pub use crate::chain::*;
 pub use crate::combinators::*;
 pub use crate::db::*;
@@ -44,6 +44,7 @@ pub fn fake_dna_zomes_named(
         origin_time: Timestamp::HOLOCHAIN_EPOCH,
         integrity_zomes: Vec::new(),
         coordinator_zomes: Vec::new(),
+        rate_limits: Vec::new(),
     };
     tokio_helper::block_forever_on(async move {
         let mut wasm_code = Vec::new();
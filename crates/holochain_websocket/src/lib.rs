@@ -12,6 +12,9 @@
 //! [`WebsocketReceiver`]
 //! ).
 //!
+//! Both ends can also be run over a unix domain socket rather than tcp, by
+//! using a `unix:///path/to/socket` url, see [`unix_socket_url`].
+//!
 //! If you want to be able to shutdown the stream use [`WebsocketListener::bind_with_handle`]
 //! which will give you a tuple ([`ListenerHandle`], [`ListenerStream`]).
 //! You can use [`ListenerHandle::close`] to close immediately or
@@ -85,6 +88,7 @@ use stream_cancel::Valve;
 use tracing::instrument;
 use url2::Url2;
 use util::url_to_addr;
+use util::RawSocket;
 use websocket::Websocket;
 
 mod websocket_config;
@@ -106,26 +110,45 @@ pub use websocket_receiver::*;
 mod websocket;
 
 mod util;
+#[cfg(unix)]
+pub use util::unix_socket_url;
+pub use util::UNIX_SOCKET_SCHEME;
 
 #[instrument(skip(config))]
 /// Create a new external websocket connection.
+/// The url is either a websocket url, e.g. `ws://127.0.0.1:12345`,
+/// or the url of a unix domain socket, e.g. `unix:///tmp/admin.sock`.
 pub async fn connect(
     url: Url2,
     config: Arc<WebsocketConfig>,
 ) -> WebsocketResult<(WebsocketSender, WebsocketReceiver)> {
+    #[cfg(unix)]
+    if url.scheme() == UNIX_SOCKET_SCHEME {
+        let path = util::unix_url_to_path(&url)?;
+        let socket = tokio::net::UnixStream::connect(path).await?;
+        // The handshake still needs a websocket url,
+        // but the host in it is never looked up.
+        return handshake("ws://localhost/", RawSocket::Unix(socket), config).await;
+    }
     let addr = url_to_addr(&url, config.scheme).await?;
     let socket = tokio::net::TcpStream::connect(addr).await?;
     // TODO: find equivalent of this in new tokio
     // socket.set_keepalive(Some(std::time::Duration::from_secs(
     //     config.tcp_keepalive_s as u64,
     // )))?;
-    let (socket, _) = tokio_tungstenite::client_async_with_config(
-        url.as_str(),
-        socket,
-        Some(config.to_tungstenite()),
-    )
-    .await
-    .map_err(|e| Error::new(ErrorKind::Other, e))?;
+    handshake(url.as_str(), RawSocket::Tcp(socket), config).await
+}
+
+/// Perform the client side of the websocket handshake.
+async fn handshake(
+    url: &str,
+    socket: RawSocket,
+    config: Arc<WebsocketConfig>,
+) -> WebsocketResult<(WebsocketSender, WebsocketReceiver)> {
+    let (socket, _) =
+        tokio_tungstenite::client_async_with_config(url, socket, Some(config.to_tungstenite()))
+            .await
+            .map_err(|e| Error::new(ErrorKind::Other, e))?;
     tracing::debug!("Client connected");
 
     // Noop valve because we don't have a listener to shutdown the
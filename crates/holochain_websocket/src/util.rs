@@ -1,12 +1,141 @@
 //! internal websocket utility types and code
 
 use std::net::SocketAddr;
+use std::pin::Pin;
+use std::task::Context;
+use std::task::Poll;
 
+use tokio::io::AsyncRead;
+use tokio::io::AsyncWrite;
+use tokio::io::ReadBuf;
 use url2::{url2, Url2};
 
 use std::io::{Error, ErrorKind, Result};
 
-pub(crate) type ToFromSocket = tokio_tungstenite::WebSocketStream<tokio::net::TcpStream>;
+pub(crate) type ToFromSocket = tokio_tungstenite::WebSocketStream<RawSocket>;
+
+/// The url scheme for websockets served over a unix domain socket,
+/// e.g. `unix:///run/holochain/admin.sock`.
+pub const UNIX_SOCKET_SCHEME: &str = "unix";
+
+/// The stream a websocket runs over.
+pub(crate) enum RawSocket {
+    Tcp(tokio::net::TcpStream),
+    #[cfg(unix)]
+    Unix(tokio::net::UnixStream),
+}
+
+impl RawSocket {
+    /// Url of the other end of this socket.
+    pub(crate) fn remote_url(&self, scheme: &str) -> Result<Url2> {
+        match self {
+            RawSocket::Tcp(socket) => Ok(addr_to_url(socket.peer_addr()?, scheme)),
+            #[cfg(unix)]
+            RawSocket::Unix(socket) => {
+                // The connecting end of a unix socket is usually unnamed,
+                // in which case the path of the listening end is used.
+                let peer = socket.peer_addr()?;
+                let local = socket.local_addr()?;
+                Ok(match peer.as_pathname().or_else(|| local.as_pathname()) {
+                    Some(path) => path_to_unix_url(path),
+                    None => url2!("{}:", UNIX_SOCKET_SCHEME),
+                })
+            }
+        }
+    }
+}
+
+impl AsyncRead for RawSocket {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<Result<()>> {
+        match self.get_mut() {
+            RawSocket::Tcp(socket) => Pin::new(socket).poll_read(cx, buf),
+            #[cfg(unix)]
+            RawSocket::Unix(socket) => Pin::new(socket).poll_read(cx, buf),
+        }
+    }
+}
+
+impl AsyncWrite for RawSocket {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<Result<usize>> {
+        match self.get_mut() {
+            RawSocket::Tcp(socket) => Pin::new(socket).poll_write(cx, buf),
+            #[cfg(unix)]
+            RawSocket::Unix(socket) => Pin::new(socket).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        match self.get_mut() {
+            RawSocket::Tcp(socket) => Pin::new(socket).poll_flush(cx),
+            #[cfg(unix)]
+            RawSocket::Unix(socket) => Pin::new(socket).poll_flush(cx),
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        match self.get_mut() {
+            RawSocket::Tcp(socket) => Pin::new(socket).poll_shutdown(cx),
+            #[cfg(unix)]
+            RawSocket::Unix(socket) => Pin::new(socket).poll_shutdown(cx),
+        }
+    }
+}
+
+/// Characters which can't appear as themselves in the path of a url.
+#[cfg(unix)]
+const PATH_ESCAPES: &percent_encoding::AsciiSet = &percent_encoding::CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'`')
+    .add(b'{')
+    .add(b'}');
+
+/// Get the url for connecting to, or listening on, the unix domain socket at
+/// this path. Relative paths are taken to be relative to the current directory.
+#[cfg(unix)]
+pub fn unix_socket_url(path: impl AsRef<std::path::Path>) -> Result<Url2> {
+    Ok(path_to_unix_url(&std::env::current_dir()?.join(path)))
+}
+
+/// internal helper to convert an absolute socket path to a url
+#[cfg(unix)]
+pub(crate) fn path_to_unix_url(path: &std::path::Path) -> Url2 {
+    use std::os::unix::ffi::OsStrExt;
+    url2!(
+        "{}://{}",
+        UNIX_SOCKET_SCHEME,
+        percent_encoding::percent_encode(path.as_os_str().as_bytes(), PATH_ESCAPES)
+    )
+}
+
+/// internal helper convert unix socket urls to paths for binding / connection
+#[cfg(unix)]
+pub(crate) fn unix_url_to_path(url: &Url2) -> Result<std::path::PathBuf> {
+    use std::os::unix::ffi::OsStringExt;
+    if url.scheme() != UNIX_SOCKET_SCHEME
+        || !matches!(url.host_str(), None | Some(""))
+        || !url.path().starts_with('/')
+    {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "got: '{}', expected: '{}:///path/to/socket'",
+                url, UNIX_SOCKET_SCHEME
+            ),
+        ));
+    }
+    let path: Vec<u8> = percent_encoding::percent_decode_str(url.path()).collect();
+    Ok(std::ffi::OsString::from_vec(path).into())
+}
 
 /// Amount of time to spend waiting for channels to empty before forcing them to close.
 pub(crate) const CLOSE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(2);
@@ -26,6 +26,11 @@ pub struct WebsocketConfig {
 
     /// Maximum number of pending new incoming connections. [default = 255]
     pub max_pending_connections: usize,
+
+    /// Permissions given to the socket file when listening on a unix domain
+    /// socket. Only processes with write permission can connect.
+    /// [default = 0o600]
+    pub unix_socket_mode: u32,
 }
 
 impl Default for WebsocketConfig {
@@ -38,6 +43,7 @@ impl Default for WebsocketConfig {
             max_message_size: 64 << 20,
             max_frame_size: 16 << 20,
             max_pending_connections: 255,
+            unix_socket_mode: 0o600,
         }
     }
 }
@@ -78,6 +84,12 @@ impl WebsocketConfig {
         self.max_frame_size = max;
         self
     }
+
+    /// Builder-style setter.
+    pub fn unix_socket_mode(mut self, mode: u32) -> Self {
+        self.unix_socket_mode = mode;
+        self
+    }
 }
 
 /// internal helper to convert our configs into tungstenite configs
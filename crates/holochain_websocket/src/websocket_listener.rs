@@ -13,6 +13,9 @@ use url2::Url2;
 
 use crate::util::addr_to_url;
 use crate::util::url_to_addr;
+use crate::util::RawSocket;
+#[cfg(unix)]
+use crate::util::UNIX_SOCKET_SCHEME;
 use crate::websocket::Websocket;
 use crate::WebsocketConfig;
 use crate::WebsocketError;
@@ -86,6 +89,12 @@ pub type ListenerStream = BoxStream<'static, ListenerItem>;
 
 impl WebsocketListener {
     /// Bind to a socket to accept incoming connections.
+    ///
+    /// Binding to a `unix:///path/to/socket` url listens on a unix domain
+    /// socket, which is given the permissions [`WebsocketConfig::unix_socket_mode`].
+    /// A socket file left behind by a listener which is no longer running
+    /// is replaced, and the socket file is removed again once the listener
+    /// is closed and dropped.
     pub async fn bind(addr: Url2, config: Arc<WebsocketConfig>) -> WebsocketResult<Self> {
         let (handle, stream) = Self::bind_with_handle(addr, config).await?;
         Ok(Self {
@@ -191,18 +200,14 @@ async fn websocket_bind(
     ListenerHandle,
     impl futures::stream::Stream<Item = ListenerItem>,
 )> {
-    let addr = url_to_addr(&addr, config.scheme).await?;
-    let socket = match &addr {
-        SocketAddr::V4(_) => net2::TcpBuilder::new_v4()?,
-        SocketAddr::V6(_) => net2::TcpBuilder::new_v6()?,
-    }
-    .reuse_address(true)?
-    .bind(addr)?
-    .listen(config.max_pending_connections as i32)?;
-    socket.set_nonblocking(true)?;
-    let local_addr = addr_to_url(socket.local_addr()?, config.scheme);
-    let listener = tokio::net::TcpListener::from_std(socket)?;
-    let listener_stream = tokio_stream::wrappers::TcpListenerStream::new(listener);
+    #[cfg(unix)]
+    let (local_addr, listener_stream) = if addr.scheme() == UNIX_SOCKET_SCHEME {
+        bind_unix(&addr, &config)?
+    } else {
+        bind_tcp(&addr, &config).await?
+    };
+    #[cfg(not(unix))]
+    let (local_addr, listener_stream) = bind_tcp(&addr, &config).await?;
 
     // Setup proper shutdown
     let (shutdown, valve) = Valve::new();
@@ -227,10 +232,122 @@ async fn websocket_bind(
     Ok((listener_handle, stream))
 }
 
+/// Incoming raw connections, before the websocket handshake.
+type RawListenerStream = BoxStream<'static, std::io::Result<RawSocket>>;
+
+async fn bind_tcp(
+    addr: &Url2,
+    config: &WebsocketConfig,
+) -> WebsocketResult<(Url2, RawListenerStream)> {
+    let addr = url_to_addr(addr, config.scheme).await?;
+    let socket = match &addr {
+        SocketAddr::V4(_) => net2::TcpBuilder::new_v4()?,
+        SocketAddr::V6(_) => net2::TcpBuilder::new_v6()?,
+    }
+    .reuse_address(true)?
+    .bind(addr)?
+    .listen(config.max_pending_connections as i32)?;
+    socket.set_nonblocking(true)?;
+    let local_addr = addr_to_url(socket.local_addr()?, config.scheme);
+    let listener = tokio::net::TcpListener::from_std(socket)?;
+    let listener_stream = tokio_stream::wrappers::TcpListenerStream::new(listener)
+        .map_ok(RawSocket::Tcp)
+        .boxed();
+    Ok((local_addr, listener_stream))
+}
+
+#[cfg(unix)]
+fn bind_unix(addr: &Url2, config: &WebsocketConfig) -> WebsocketResult<(Url2, RawListenerStream)> {
+    use std::os::unix::fs::FileTypeExt;
+
+    let path = crate::util::unix_url_to_path(addr)?;
+    // A socket nobody is listening on is left over from a previous run
+    // and can be replaced, but one which accepts connections is in use.
+    if let Ok(metadata) = std::fs::symlink_metadata(&path) {
+        if metadata.file_type().is_socket() {
+            match std::os::unix::net::UnixStream::connect(&path) {
+                Err(e) if e.kind() == ErrorKind::ConnectionRefused => std::fs::remove_file(&path)?,
+                _ => {
+                    return Err(Error::new(
+                        ErrorKind::AddrInUse,
+                        format!("the unix socket {} is already in use", path.display()),
+                    )
+                    .into())
+                }
+            }
+        }
+    }
+    let listener = bind_unix_private(&path, config.unix_socket_mode)?;
+    let local_addr = crate::util::path_to_unix_url(&path);
+    let socket_file = UnixSocketFile(path);
+    let listener_stream = tokio_stream::wrappers::UnixListenerStream::new(listener)
+        .map_ok(move |socket| {
+            // The stream owns the socket file, so it is removed once the
+            // listener is shut down and dropped.
+            let _ = &socket_file;
+            RawSocket::Unix(socket)
+        })
+        .boxed();
+    Ok((local_addr, listener_stream))
+}
+
+/// Binds the socket inside a directory only we can access and links it to
+/// `path` once it has its permissions, so nobody can connect to it before.
+#[cfg(unix)]
+fn bind_unix_private(
+    path: &std::path::Path,
+    mode: u32,
+) -> WebsocketResult<tokio::net::UnixListener> {
+    use std::os::unix::fs::DirBuilderExt;
+    use std::os::unix::fs::PermissionsExt;
+
+    let file_name = path.file_name().ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!("{} is not a valid unix socket path", path.display()),
+        )
+    })?;
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => std::path::Path::new("."),
+    };
+    let private_dir = parent.join(format!(
+        ".{}.{}",
+        file_name.to_string_lossy(),
+        nanoid::nanoid!()
+    ));
+    std::fs::DirBuilder::new()
+        .mode(0o700)
+        .create(&private_dir)?;
+    let private_path = private_dir.join(file_name);
+    // Linking rather than renaming fails if something else appeared at `path`.
+    let result = tokio::net::UnixListener::bind(&private_path).and_then(|listener| {
+        std::fs::set_permissions(&private_path, std::fs::Permissions::from_mode(mode))?;
+        std::fs::hard_link(&private_path, path)?;
+        Ok(listener)
+    });
+    let _ = std::fs::remove_file(&private_path);
+    std::fs::remove_dir(&private_dir)?;
+    Ok(result?)
+}
+
+/// Removes the file of a unix socket listener when dropped.
+#[cfg(unix)]
+struct UnixSocketFile(std::path::PathBuf);
+
+#[cfg(unix)]
+impl Drop for UnixSocketFile {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_file(&self.0) {
+            tracing::warn!(failed_to_remove_unix_socket = ?e, path = ?self.0);
+        }
+    }
+}
+
 #[instrument(skip(config, socket, valve))]
 async fn connect(
     config: Arc<WebsocketConfig>,
-    socket: tokio::net::TcpStream,
+    socket: RawSocket,
     valve: Valve,
 ) -> WebsocketResult<Pair> {
     // TODO: find alternative to set the keepalive
@@ -239,7 +356,7 @@ async fn connect(
     // )))?;
     tracing::debug!(
         message = "accepted incoming raw socket",
-        remote_addr = %socket.peer_addr()?,
+        remote_addr = %socket.remote_url(config.scheme)?,
     );
     let socket = tokio_tungstenite::accept_async_with_config(
         socket,
@@ -452,3 +452,61 @@ async fn cancel_response() {
     c_jh.await.unwrap();
     s_jh.await.unwrap();
 }
+
+#[cfg(unix)]
+#[tokio::test(flavor = "multi_thread")]
+async fn can_send_request_over_unix_socket() {
+    use std::os::unix::fs::PermissionsExt;
+    observability::test_run().ok();
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("has space#and hash.sock");
+    let url = holochain_websocket::unix_socket_url(&path).unwrap();
+
+    // A socket file left behind by a previous listener is replaced.
+    drop(std::os::unix::net::UnixListener::bind(&path).unwrap());
+    assert!(path.exists());
+
+    let (handle, mut listener) =
+        WebsocketListener::bind_with_handle(url.clone(), Arc::new(WebsocketConfig::default()))
+            .await
+            .unwrap();
+    assert_eq!(handle.local_addr(), &url);
+    let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o600);
+
+    // A socket which is in use is not.
+    assert!(
+        WebsocketListener::bind_with_handle(url.clone(), Arc::new(WebsocketConfig::default()))
+            .await
+            .is_err()
+    );
+
+    let jh = tokio::task::spawn(async move {
+        // Skip the connection made by the bind which failed.
+        let (_sender, mut receiver) = loop {
+            if let Ok(pair) = listener.next().await.unwrap() {
+                break pair;
+            }
+        };
+        let (msg, resp) = receiver.next().await.unwrap();
+        let msg: TestString = msg.try_into().unwrap();
+        assert_eq!(msg.0, "Hey from client");
+        resp.respond(TestString("Bye from server".into()).try_into().unwrap())
+            .await
+            .unwrap();
+    });
+
+    let (mut sender, _receiver) = connect(url, Arc::new(WebsocketConfig::default()))
+        .await
+        .unwrap();
+    let resp: TestString = sender
+        .request(TestString("Hey from client".into()))
+        .await
+        .unwrap();
+    assert_eq!(resp.0, "Bye from server");
+    jh.await.unwrap();
+
+    // The socket file is removed once the listener is gone.
+    handle.close();
+    assert!(!path.exists());
+}
@@ -25,6 +25,8 @@
 //! to send to the Grantor.
 
 mod grant;
+mod query;
 pub use grant::*;
+pub use query::*;
 
 pub use holochain_integrity_types::capability::*;
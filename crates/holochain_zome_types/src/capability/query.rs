@@ -0,0 +1,125 @@
+use super::*;
+use holo_hash::ActionHash;
+use holo_hash::AgentPubKey;
+use holochain_serialized_bytes::prelude::*;
+
+/// Filter for the active capability grants on the local source chain,
+/// as listed by the `capability_grants` host function.
+///
+/// A grant is active if it has been neither updated nor deleted.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, SerializedBytes)]
+pub struct CapGrantQuery {
+    /// Only list grants with this tag.
+    pub tag: Option<String>,
+    /// Only list grants which give access to all of these functions.
+    pub functions: Option<GrantedFunctions>,
+}
+
+impl CapGrantQuery {
+    /// A query for all active grants.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only list grants with this tag.
+    pub fn tag(mut self, tag: impl Into<String>) -> Self {
+        self.tag = Some(tag.into());
+        self
+    }
+
+    /// Only list grants which give access to all of these functions.
+    pub fn functions(mut self, functions: GrantedFunctions) -> Self {
+        self.functions = Some(functions);
+        self
+    }
+
+    /// Check if a grant matches this query.
+    pub fn check(&self, grant: &ZomeCallCapGrant) -> bool {
+        self.tag.as_ref().map_or(true, |tag| *tag == grant.tag)
+            && self
+                .functions
+                .as_ref()
+                .map_or(true, |functions| functions.is_subset(&grant.functions))
+    }
+}
+
+/// Filter for the active capability claims on the local source chain,
+/// as listed by the `capability_claims` host function.
+///
+/// A claim is active if it has been neither updated nor deleted.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, SerializedBytes)]
+pub struct CapClaimQuery {
+    /// Only list claims with this tag.
+    pub tag: Option<String>,
+    /// Only list claims on grants by this agent.
+    pub grantor: Option<AgentPubKey>,
+}
+
+impl CapClaimQuery {
+    /// A query for all active claims.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only list claims with this tag.
+    pub fn tag(mut self, tag: impl Into<String>) -> Self {
+        self.tag = Some(tag.into());
+        self
+    }
+
+    /// Only list claims on grants by this agent.
+    pub fn grantor(mut self, grantor: AgentPubKey) -> Self {
+        self.grantor = Some(grantor);
+        self
+    }
+
+    /// Check if a claim matches this query.
+    pub fn check(&self, claim: &CapClaim) -> bool {
+        self.tag.as_ref().map_or(true, |tag| tag == claim.tag())
+            && self
+                .grantor
+                .as_ref()
+                .map_or(true, |grantor| grantor == claim.grantor())
+    }
+}
+
+/// Info about the capability grant which authorized the current zome call,
+/// as returned by the `capability_info` host function.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SerializedBytes)]
+pub struct CapabilityInfo {
+    /// The agent who made the call.
+    pub provenance: AgentPubKey,
+    /// The grant which authorized the call.
+    pub cap_grant: CapGrant,
+    /// The action which committed the grant to the source chain.
+    /// This is `None` for the implicit grant the chain author always has.
+    pub action_hash: Option<ActionHash>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn grant_query_filters_by_tag_and_functions() {
+        let foo: GrantedFunction = ("zome".into(), "foo".into());
+        let bar: GrantedFunction = ("zome".into(), "bar".into());
+        let grant = ZomeCallCapGrant::new(
+            "tag".into(),
+            CapAccess::Unrestricted,
+            [foo.clone(), bar.clone()].into_iter().collect(),
+        );
+
+        assert!(CapGrantQuery::new().check(&grant));
+        assert!(CapGrantQuery::new().tag("tag").check(&grant));
+        assert!(!CapGrantQuery::new().tag("other").check(&grant));
+        assert!(CapGrantQuery::new()
+            .functions([foo.clone()].into_iter().collect())
+            .check(&grant));
+        assert!(!CapGrantQuery::new()
+            .tag("tag")
+            .functions([foo, ("zome".into(), "baz".into())].into_iter().collect())
+            .check(&grant));
+    }
+}
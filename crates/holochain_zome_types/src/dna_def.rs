@@ -60,6 +60,12 @@ pub struct DnaDef {
     /// A vector of zomes that do not affect
     /// the [`DnaHash`].
     pub coordinator_zomes: CoordinatorZomes,
+
+    /// The rate limiting buckets enforced by sys validation.
+    /// Buckets without a limit here are not enforced.
+    #[serde(default)]
+    #[cfg_attr(feature = "full-dna-def", builder(default))]
+    pub rate_limits: Vec<RateBucketLimit>,
 }
 
 #[derive(Serialize, Debug, PartialEq, Eq)]
@@ -69,6 +75,13 @@ struct DnaDefHash<'a> {
     network_seed: &'a String,
     properties: &'a SerializedBytes,
     integrity_zomes: &'a IntegrityZomes,
+    // Skipped when empty so that DNAs without rate limits keep their hashes.
+    #[serde(skip_serializing_if = "no_rate_limits")]
+    rate_limits: &'a [RateBucketLimit],
+}
+
+fn no_rate_limits(rate_limits: &&[RateBucketLimit]) -> bool {
+    rate_limits.is_empty()
 }
 
 #[cfg(feature = "test_utils")]
@@ -188,6 +201,13 @@ impl DnaDef {
         clone
     }
 
+    /// Get the limit for a rate limiting bucket, if this DNA defines one.
+    pub fn rate_limit(&self, bucket_id: RateBucketId) -> Option<&RateBucketLimit> {
+        self.rate_limits
+            .iter()
+            .find(|limit| limit.bucket_id == bucket_id)
+    }
+
     /// Get the topology to use for kitsune gossip
     pub fn topology(&self, cutoff: std::time::Duration) -> kitsune_p2p_dht::spacetime::Topology {
         kitsune_p2p_dht::spacetime::Topology::standard(self.origin_time, cutoff)
@@ -227,6 +247,7 @@ impl HashableContent for DnaDef {
             network_seed: &self.network_seed,
             properties: &self.properties,
             integrity_zomes: &self.integrity_zomes,
+            rate_limits: &self.rate_limits,
         };
         HashableContentBytes::Content(
             holochain_serialized_bytes::UnsafeBytes::from(
@@ -745,6 +745,7 @@ fixturator!(
         coordinator_zomes: CoordinatorZomesFixturator::new_indexed(Empty, get_fixt_index!())
             .next()
             .unwrap(),
+        rate_limits: Vec::new(),
     };
 
     curve Unpredictable DnaDef {
@@ -764,6 +765,7 @@ fixturator!(
         coordinator_zomes: CoordinatorZomesFixturator::new_indexed(Empty, get_fixt_index!())
             .next()
             .unwrap(),
+        rate_limits: Vec::new(),
     };
 
     curve Predictable DnaDef {
@@ -783,6 +785,7 @@ fixturator!(
         coordinator_zomes: CoordinatorZomesFixturator::new_indexed(Empty, get_fixt_index!())
             .next()
             .unwrap(),
+        rate_limits: Vec::new(),
     };
 );
 
@@ -1,6 +1,7 @@
 use crate::record::SignedActionHashed;
 use crate::ChainTopOrdering;
 use holo_hash::ActionHash;
+use holo_hash::AgentPubKey;
 use holochain_integrity_types::ZomeId;
 use holochain_serialized_bytes::prelude::*;
 
@@ -87,6 +88,9 @@ pub struct GetLinksInput {
     /// The link types to include in this get.
     pub link_type: LinkTypeFilter,
     pub tag_prefix: Option<crate::link::LinkTag>,
+    /// Narrow down, order and page through the links.
+    #[serde(default)]
+    pub filter: LinkFilter,
 }
 
 impl GetLinksInput {
@@ -99,8 +103,122 @@ impl GetLinksInput {
             base_address,
             link_type,
             tag_prefix,
+            filter: LinkFilter::default(),
         }
     }
+
+    /// Only get the links which pass the given filter.
+    pub fn with_filter(mut self, filter: LinkFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+}
+
+/// The order links are returned in, by the timestamp of their create action.
+/// Links with the same timestamp are ordered by their create action hash.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum LinkOrder {
+    /// Oldest links first. This is the default.
+    OldestFirst,
+    /// Newest links first.
+    NewestFirst,
+}
+
+impl Default for LinkOrder {
+    fn default() -> Self {
+        Self::OldestFirst
+    }
+}
+
+/// A position in a list of links: the link at the cursor and all links
+/// before it, in the order of the query, are skipped.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct LinkCursor {
+    /// The timestamp of the last link already seen.
+    pub timestamp: crate::Timestamp,
+    /// The create action hash of the last link already seen.
+    pub create_link_hash: ActionHash,
+}
+
+impl From<&Link> for LinkCursor {
+    fn from(link: &Link) -> Self {
+        Self {
+            timestamp: link.timestamp,
+            create_link_hash: link.create_link_hash.clone(),
+        }
+    }
+}
+
+/// Narrows down, orders and pages through the links returned by a link query.
+///
+/// The default filter returns every link, oldest first.
+/// To get the next page of a limited query, set the cursor to the last
+/// link of the previous page with [`LinkFilter::continue_after`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LinkFilter {
+    /// Only links created after this time.
+    pub after: Option<crate::Timestamp>,
+    /// Only links created before this time.
+    pub before: Option<crate::Timestamp>,
+    /// Only links created by this agent.
+    pub author: Option<AgentPubKey>,
+    /// The order to return links in.
+    pub order: LinkOrder,
+    /// Return at most this many links.
+    pub limit: Option<u32>,
+    /// Only links which come after this position, in the order of the query.
+    pub cursor: Option<LinkCursor>,
+}
+
+impl LinkFilter {
+    /// Continue a query from after the given link.
+    pub fn continue_after(mut self, link: &Link) -> Self {
+        self.cursor = Some(link.into());
+        self
+    }
+
+    /// Does a link created by this author at this time with this
+    /// create action hash pass the filter?
+    pub fn matches(
+        &self,
+        author: &AgentPubKey,
+        timestamp: crate::Timestamp,
+        create_link_hash: &ActionHash,
+    ) -> bool {
+        self.after.map_or(true, |after| timestamp > after)
+            && self.before.map_or(true, |before| timestamp < before)
+            && self.author.as_ref().map_or(true, |a| a == author)
+            && self.cursor.as_ref().map_or(true, |cursor| {
+                let position = (timestamp, create_link_hash);
+                let cursor = (cursor.timestamp, &cursor.create_link_hash);
+                match self.order {
+                    LinkOrder::OldestFirst => position > cursor,
+                    LinkOrder::NewestFirst => position < cursor,
+                }
+            })
+    }
+
+    /// Sort items into the order of this filter, given the
+    /// timestamp and create action hash of each item's link.
+    pub fn sort<T>(&self, items: &mut [T], key: impl Fn(&T) -> (crate::Timestamp, &ActionHash)) {
+        match self.order {
+            LinkOrder::OldestFirst => items.sort_by(|a, b| key(a).cmp(&key(b))),
+            LinkOrder::NewestFirst => items.sort_by(|a, b| key(b).cmp(&key(a))),
+        }
+    }
+
+    /// Sort items into the order of this filter and keep only the first page.
+    pub fn page<T>(
+        &self,
+        mut items: Vec<T>,
+        key: impl Fn(&T) -> (crate::Timestamp, &ActionHash),
+    ) -> Vec<T> {
+        self.sort(&mut items, key);
+        if let Some(limit) = self.limit {
+            items.truncate(limit as usize);
+        }
+        items
+    }
 }
 
 type CreateLinkWithDeleteLinks = Vec<(SignedActionHashed, Vec<SignedActionHashed>)>;
@@ -2,8 +2,12 @@
 use crate::record::Record;
 use crate::record::SignedActionHashed;
 use crate::validate::ValidationStatus;
+use crate::ActionHashed;
 use crate::Entry;
+use crate::Timestamp;
+use holo_hash::ActionHash;
 use holochain_serialized_bytes::prelude::*;
+use std::collections::BTreeSet;
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, SerializedBytes)]
 #[serde(tag = "type", content = "content")]
@@ -90,3 +94,76 @@ pub enum EntryDhtStatus {
     /// **not implemented** We have agreed to drop this [Entry] content from the system. Action can stay with no entry
     Purged,
 }
+
+/// Timestamp of when the action was created with the actions hash.
+#[derive(Debug, Hash, PartialOrd, Ord, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct TimedActionHash {
+    /// Time when this action was created
+    pub timestamp: Timestamp,
+    /// Hash of the action
+    pub action_hash: ActionHash,
+}
+
+/// Metadata returned from a GetMeta request.
+/// The Ord derive on TimedActionHash means each set is ordered by time.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, SerializedBytes)]
+pub struct MetadataSet {
+    /// Actions that created or updated an entry.
+    /// These are the actions that show the entry exists.
+    pub actions: BTreeSet<TimedActionHash>,
+    /// Actions on this basis which have been rejected by validation.
+    pub invalid_actions: BTreeSet<TimedActionHash>,
+    /// Deletes on an action
+    pub deletes: BTreeSet<TimedActionHash>,
+    /// Updates on an action or entry
+    pub updates: BTreeSet<TimedActionHash>,
+    /// The status of an entry from an authority.
+    /// This is simply a faster way of determining if
+    /// there are any live actions on an entry.
+    pub entry_dht_status: Option<EntryDhtStatus>,
+    /// The number of links on this basis which have not been removed.
+    #[serde(default)]
+    pub link_count: usize,
+}
+
+impl MetadataSet {
+    /// Combine the metadata returned by several authorities.
+    /// The action sets are merged, and an entry is considered live
+    /// if any authority has seen a live action for it.
+    pub fn merge(&mut self, other: MetadataSet) {
+        self.actions.extend(other.actions);
+        self.invalid_actions.extend(other.invalid_actions);
+        self.deletes.extend(other.deletes);
+        self.updates.extend(other.updates);
+        // Authorities may not have seen all links yet,
+        // so the most complete count is the largest one.
+        self.link_count = self.link_count.max(other.link_count);
+        self.entry_dht_status = match (self.entry_dht_status.take(), other.entry_dht_status) {
+            (Some(EntryDhtStatus::Live), _) | (_, Some(EntryDhtStatus::Live)) => {
+                Some(EntryDhtStatus::Live)
+            }
+            (status, None) | (None, status) => status,
+            (Some(status), Some(_)) => Some(status),
+        };
+    }
+}
+
+impl From<ActionHashed> for TimedActionHash {
+    fn from(h: ActionHashed) -> Self {
+        let (action, hash) = h.into_inner();
+        TimedActionHash {
+            timestamp: action.timestamp(),
+            action_hash: hash,
+        }
+    }
+}
+
+#[cfg(feature = "test_utils")]
+impl From<ActionHash> for TimedActionHash {
+    fn from(h: ActionHash) -> Self {
+        TimedActionHash {
+            timestamp: Timestamp::now(),
+            action_hash: h,
+        }
+    }
+}
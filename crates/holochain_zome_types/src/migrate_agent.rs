@@ -1,4 +1,6 @@
+use crate::action::ChainTopOrdering;
 use crate::CallbackResult;
+use holo_hash::DnaHash;
 use holochain_serialized_bytes::prelude::*;
 use holochain_wasmer_common::*;
 
@@ -37,3 +39,41 @@ impl CallbackResult for MigrateAgentCallbackResult {
         }
     }
 }
+
+/// Input to the `close_chain` host function.
+#[derive(Clone, PartialEq, Serialize, Deserialize, SerializedBytes, Debug)]
+pub struct CloseChainInput {
+    /// The DNA which this agent's chain is migrating to.
+    pub new_dna_hash: DnaHash,
+    /// Strictness of the chain ordering for the `CloseChain` action.
+    pub chain_top_ordering: ChainTopOrdering,
+}
+
+impl CloseChainInput {
+    /// Constructor.
+    pub fn new(new_dna_hash: DnaHash, chain_top_ordering: ChainTopOrdering) -> Self {
+        Self {
+            new_dna_hash,
+            chain_top_ordering,
+        }
+    }
+}
+
+/// Input to the `open_chain` host function.
+#[derive(Clone, PartialEq, Serialize, Deserialize, SerializedBytes, Debug)]
+pub struct OpenChainInput {
+    /// The DNA which this agent's chain was migrated from.
+    pub prev_dna_hash: DnaHash,
+    /// Strictness of the chain ordering for the `OpenChain` action.
+    pub chain_top_ordering: ChainTopOrdering,
+}
+
+impl OpenChainInput {
+    /// Constructor.
+    pub fn new(prev_dna_hash: DnaHash, chain_top_ordering: ChainTopOrdering) -> Self {
+        Self {
+            prev_dna_hash,
+            chain_top_ordering,
+        }
+    }
+}
@@ -21,6 +21,9 @@ pub struct MetadataRequest {
     /// This is faster then getting all the actions
     /// and checking for live actions.
     pub entry_dht_status: bool,
+    /// Count the links on a basis which have not been removed.
+    #[serde(default)]
+    pub link_count: bool,
 }
 
 impl Default for MetadataRequest {
@@ -32,6 +35,26 @@ impl Default for MetadataRequest {
             all_updates: true,
             follow_redirects: false,
             entry_dht_status: false,
+            link_count: true,
+        }
+    }
+}
+
+/// Zome input for get_meta calls.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct GetMetaInput {
+    /// Any DHT hash to get the metadata of.
+    pub any_dht_hash: holo_hash::AnyDhtHash,
+    /// The metadata to return.
+    pub metadata_request: MetadataRequest,
+}
+
+impl GetMetaInput {
+    /// Constructor.
+    pub fn new(any_dht_hash: holo_hash::AnyDhtHash, metadata_request: MetadataRequest) -> Self {
+        Self {
+            any_dht_hash,
+            metadata_request,
         }
     }
 }
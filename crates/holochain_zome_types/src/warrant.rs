@@ -1,6 +1,67 @@
 //! Types for warrants
+use crate::signature::Signature;
+use crate::timestamp::Timestamp;
+use holo_hash::ActionHash;
+use holo_hash::AgentPubKey;
 pub use holochain_serialized_bytes::prelude::*;
 
-#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize, SerializedBytes)]
-/// Placeholder for warrant type
-pub struct Warrant;
+#[derive(
+    Clone, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize, SerializedBytes,
+)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+/// A claim by a validator that an agent authored an action
+/// which failed validation.
+///
+/// Warrants are gossiped to the agent activity authorities of the
+/// warranted agent, so that anyone asking for the agent's activity
+/// can find out that they misbehaved.
+pub struct Warrant {
+    /// The hash of the action which failed validation.
+    pub action_hash: ActionHash,
+    /// The author of the invalid action.
+    pub author: AgentPubKey,
+    /// Why the action failed validation.
+    pub reason: String,
+    /// The agent who validated the action and issued this warrant.
+    pub warrantor: AgentPubKey,
+    /// When this warrant was issued.
+    pub timestamp: Timestamp,
+}
+
+impl Warrant {
+    /// Constructor.
+    pub fn new(
+        action_hash: ActionHash,
+        author: AgentPubKey,
+        reason: String,
+        warrantor: AgentPubKey,
+        timestamp: Timestamp,
+    ) -> Self {
+        Self {
+            action_hash,
+            author,
+            reason,
+            warrantor,
+            timestamp,
+        }
+    }
+}
+
+#[derive(
+    Clone, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize, SerializedBytes,
+)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+/// A [`Warrant`] signed by its warrantor.
+pub struct SignedWarrant(pub Warrant, pub Signature);
+
+impl SignedWarrant {
+    /// Accessor for the Warrant
+    pub fn warrant(&self) -> &Warrant {
+        &self.0
+    }
+
+    /// Accessor for the Signature
+    pub fn signature(&self) -> &Signature {
+        &self.1
+    }
+}
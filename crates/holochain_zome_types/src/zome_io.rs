@@ -51,14 +51,17 @@ wasm_io_types! {
 
     fn call (Vec<zt::call::Call>) -> Vec<zt::ZomeCallResponse>;
 
-    // @todo List all the local capability claims.
-    fn capability_claims (()) -> ();
+    // List the active capability claims on the local source chain.
+    fn capability_claims (zt::capability::CapClaimQuery) -> Vec<crate::Record>;
 
-    // @todo List all the local capability grants.
-    fn capability_grants (()) -> ();
+    // List the active capability grants on the local source chain.
+    fn capability_grants (zt::capability::CapGrantQuery) -> Vec<crate::Record>;
 
-    // @todo Get the capability for the current zome call.
-    fn capability_info (()) -> ();
+    // Get the capability grant which authorized the current zome call.
+    fn capability_info (()) -> zt::capability::CapabilityInfo;
+
+    // Close the source chain, declaring the DNA the agent is migrating to.
+    fn close_chain (zt::migrate_agent::CloseChainInput) -> holo_hash::ActionHash;
 
     // Returns ActionHash of the newly created record.
     fn create (zt::entry::CreateInput) -> holo_hash::ActionHash;
@@ -91,6 +94,12 @@ wasm_io_types! {
     // Get links by entry hash from the cascade.
     fn get_links (Vec<zt::link::GetLinksInput>) -> Vec<Vec<zt::link::Link>>;
 
+    // Count links by entry hash from the cascade without fetching them.
+    fn count_links (zt::link::GetLinksInput) -> usize;
+
+    // Get the metadata on a hash from the cascade without fetching any records.
+    fn get_meta (zt::request::GetMetaInput) -> zt::metadata::MetadataSet;
+
     // Attempt to get a live entry from the cascade.
     fn get (Vec<zt::entry::GetInput>) -> Vec<Option<zt::record::Record>>;
 
@@ -106,6 +115,9 @@ wasm_io_types! {
     // Retrieve an action from the DHT or short circuit.
     fn must_get_action (zt::entry::MustGetActionInput) -> zt::SignedActionHashed;
 
+    // Open the source chain, declaring the DNA the agent migrated from.
+    fn open_chain (zt::migrate_agent::OpenChainInput) -> holo_hash::ActionHash;
+
     // Query the source chain for data.
     fn query (zt::query::ChainQueryFilter) -> Vec<crate::Record>;
 
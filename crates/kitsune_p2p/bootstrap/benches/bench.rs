@@ -9,7 +9,9 @@ use fixt::prelude::*;
 use kitsune_p2p::agent_store::AgentInfoSigned;
 use kitsune_p2p::dependencies::url2::url2;
 use kitsune_p2p::fixt::*;
+use kitsune_p2p::KitsuneAgent;
 use kitsune_p2p::KitsuneP2pResult;
+use kitsune_p2p::KitsuneSignature;
 use kitsune_p2p::KitsuneSpace;
 use kitsune_p2p_types::bootstrap::RandomLimit;
 use kitsune_p2p_types::bootstrap::RandomQuery;
@@ -56,17 +58,7 @@ fn bootstrap(bench: &mut Criterion) {
     group.bench_function(BenchmarkId::new("test", format!("put")), |b| {
         b.iter(|| {
             runtime.block_on(async {
-                let info = AgentInfoSigned::sign(
-                    space.clone(),
-                    Arc::new(fixt!(KitsuneAgent, Unpredictable)),
-                    u32::MAX / 4,
-                    fixt!(UrlList, Empty),
-                    0,
-                    std::time::UNIX_EPOCH.elapsed().unwrap().as_millis() as u64 + 60_000_000,
-                    |_| async move { Ok(Arc::new(fixt!(KitsuneSignature, Unpredictable))) },
-                )
-                .await
-                .unwrap();
+                let info = signed_info(space.clone()).await;
                 let _: Option<()> = do_api(url.clone(), "put", info, &client)
                     .await
                     .unwrap()
@@ -119,6 +111,42 @@ async fn do_api<I: serde::Serialize, O: serde::de::DeserializeOwned>(
     }
 }
 
+/// The bootstrap server verifies signatures,
+/// so sign with a real keypair for a new agent.
+async fn signed_info(space: Arc<KitsuneSpace>) -> AgentInfoSigned {
+    let pk = sodoken::BufWriteSized::new_no_lock();
+    let sk = sodoken::BufWriteSized::new_no_lock();
+    sodoken::sign::keypair(pk.clone(), sk.clone())
+        .await
+        .unwrap();
+    let mut agent = pk.read_lock().to_vec();
+    agent.extend_from_slice(&[0; 4]);
+    let now = std::time::UNIX_EPOCH.elapsed().unwrap().as_millis() as u64;
+
+    AgentInfoSigned::sign(
+        space,
+        Arc::new(KitsuneAgent(agent)),
+        u32::MAX / 4,
+        fixt!(UrlList, Empty),
+        now,
+        now + 60_000_000,
+        |data| {
+            let data = sodoken::BufRead::new_no_lock(data);
+            let sk = sk.to_read_sized();
+            async move {
+                let sig = sodoken::BufWriteSized::<64>::new_no_lock();
+                sodoken::sign::detached(sig.clone(), data, sk)
+                    .await
+                    .unwrap();
+                let sig = sig.read_lock().to_vec();
+                Ok(Arc::new(KitsuneSignature(sig)))
+            }
+        },
+    )
+    .await
+    .unwrap()
+}
+
 pub fn rt() -> Runtime {
     Builder::new_multi_thread().enable_all().build().unwrap()
 }
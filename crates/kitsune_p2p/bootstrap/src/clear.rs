@@ -1,26 +1,38 @@
+use crate::operator::{authorized, unauthorized};
 use crate::store::Store;
 
 use super::*;
 use warp::Filter;
 
+/// Remove every agent info, including from the database. This is an
+/// operator route, so it needs the operator token like the others.
 pub(crate) fn clear(
     store: Store,
+    token: Option<Arc<str>>,
 ) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
     warp::post()
         .and(warp::header::exact("X-Op", "clear"))
+        .and(authorized(token))
         .and(with_store(store))
         .and_then(clear_info)
 }
 
-async fn clear_info(store: Store) -> Result<impl warp::Reply, warp::Rejection> {
-    store.clear();
-    Ok(warp::reply())
+async fn clear_info(
+    authorized: bool,
+    store: Store,
+) -> Result<Box<dyn warp::Reply>, warp::Rejection> {
+    if !authorized {
+        return Ok(unauthorized());
+    }
+    let status = match tokio::task::spawn_blocking(move || store.clear()).await {
+        Ok(Ok(())) => warp::http::StatusCode::OK,
+        _ => warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    Ok(Box::new(warp::reply::with_status(warp::reply(), status)))
 }
 
 #[cfg(test)]
 mod tests {
-    use std::sync::Arc;
-
     use super::*;
     use fixt::prelude::*;
     use kitsune_p2p::{agent_store::AgentInfoSigned, fixt::*, KitsuneSpace};
@@ -29,7 +41,7 @@ mod tests {
     async fn test_clear() {
         let store = Store::new(vec![]);
 
-        let filter = super::clear(store.clone());
+        let filter = super::clear(store.clone(), Some("let-me-in".into()));
         let space: Arc<KitsuneSpace> = Arc::new(fixt!(KitsuneSpace));
 
         for _ in 0..20 {
@@ -44,16 +56,35 @@ mod tests {
             )
             .await
             .unwrap();
-            store.put(info);
+            store.put(info).unwrap();
         }
 
+        // The operator token is required.
         let res = warp::test::request()
             .method("POST")
             .header("Content-type", "application/octet")
             .header("X-Op", "clear")
             .reply(&filter)
             .await;
+        assert_eq!(res.status(), 401);
+        assert_eq!(store.all().len(), 1);
+
+        let res = warp::test::request()
+            .method("POST")
+            .header("Content-type", "application/octet")
+            .header("X-Op", "clear")
+            .header("Authorization", "Bearer let-me-in")
+            .reply(&filter)
+            .await;
         assert_eq!(res.status(), 200);
         assert!(store.all().is_empty());
+
+        // Without an operator token there is no way to clear.
+        let res = warp::test::request()
+            .method("POST")
+            .header("X-Op", "clear")
+            .reply(&super::clear(store, None))
+            .await;
+        assert_eq!(res.status(), 404);
     }
 }
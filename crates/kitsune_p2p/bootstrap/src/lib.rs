@@ -1,8 +1,13 @@
+use std::collections::HashSet;
 use std::net::SocketAddr;
+use std::path::PathBuf;
 use std::sync::atomic::AtomicUsize;
+use std::sync::Arc;
 
+use kitsune_p2p_types::bin_types::KitsuneSpace;
 use kitsune_p2p_types::codec::rmp_decode;
 use kitsune_p2p_types::codec::rmp_encode;
+use rate_limit::RateLimit;
 use store::Store;
 use warp::{hyper::body::Bytes, Filter};
 
@@ -12,10 +17,14 @@ static PUT: AtomicUsize = AtomicUsize::new(0);
 
 mod clear;
 mod now;
+mod operator;
 mod proxy_list;
 mod put;
 mod random;
+mod rate_limit;
 mod store;
+#[cfg(test)]
+mod test_util;
 
 /// No reason to accept a peer data bigger then 1KB.
 // TODO: Maybe even that's too high?
@@ -24,6 +33,44 @@ const SIZE_LIMIT: u64 = 1024;
 /// how often should we prune the expired entries?
 pub const PRUNE_EXPIRED_FREQ: std::time::Duration = std::time::Duration::from_secs(5);
 
+/// The window over which [`BootstrapConfig::max_puts_per_minute`] is counted.
+const RATE_LIMIT_WINDOW: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Settings for a bootstrap server.
+#[derive(Clone, Debug)]
+pub struct BootstrapConfig {
+    /// How often expired agent infos are pruned.
+    pub prune_frequency: std::time::Duration,
+    /// A SQLite database to keep agent infos in, so that they survive a
+    /// restart. If `None`, agent infos are only kept in memory.
+    pub database: Option<PathBuf>,
+    /// The most agents that can be stored in each space, or `None` for no limit.
+    pub max_agents_per_space: Option<usize>,
+    /// The most agent infos that can be put from one IP address each minute,
+    /// or `None` for no limit.
+    pub max_puts_per_minute: Option<u32>,
+    /// If set, only agent infos for these spaces are accepted.
+    /// See [`parse_allowed_spaces`].
+    pub allowed_spaces: Option<HashSet<Arc<KitsuneSpace>>>,
+    /// The bearer token operators must present to use the operator routes,
+    /// which list spaces and agents, evict agents and clear the store. If `None`, the
+    /// operator routes are not served.
+    pub operator_token: Option<String>,
+}
+
+impl Default for BootstrapConfig {
+    fn default() -> Self {
+        Self {
+            prune_frequency: PRUNE_EXPIRED_FREQ,
+            database: None,
+            max_agents_per_space: None,
+            max_puts_per_minute: None,
+            allowed_spaces: None,
+            operator_token: None,
+        }
+    }
+}
+
 pub type BootstrapDriver = futures::future::BoxFuture<'static, ()>;
 
 pub type BootstrapShutdown = Box<dyn FnOnce() + 'static + Send>;
@@ -42,23 +89,61 @@ pub async fn run_with_prune_freq(
     proxy_list: Vec<String>,
     prune_frequency: std::time::Duration,
 ) -> Result<(BootstrapDriver, SocketAddr, BootstrapShutdown), String> {
-    let store = Store::new(proxy_list);
+    run_with_config(
+        addr,
+        proxy_list,
+        BootstrapConfig {
+            prune_frequency,
+            ..Default::default()
+        },
+    )
+    .await
+}
+
+/// Run a bootstrap server with the given [`BootstrapConfig`].
+pub async fn run_with_config(
+    addr: impl Into<SocketAddr> + 'static,
+    proxy_list: Vec<String>,
+    config: BootstrapConfig,
+) -> Result<(BootstrapDriver, SocketAddr, BootstrapShutdown), String> {
+    let store = match &config.database {
+        Some(path) => Store::open(path, proxy_list)
+            .map_err(|e| format!("Failed to open database {}: {:?}", path.display(), e))?,
+        None => Store::new(proxy_list),
+    }
+    .with_max_agents_per_space(config.max_agents_per_space)
+    .with_allowed_spaces(config.allowed_spaces);
+    let operator_token: Option<Arc<str>> = config.operator_token.map(Into::into);
+    let rate_limit = config
+        .max_puts_per_minute
+        .map(|max| RateLimit::new(max, RATE_LIMIT_WINDOW));
 
     {
         let store = store.clone();
+        let rate_limit = rate_limit.clone();
+        let prune_frequency = config.prune_frequency;
         tokio::task::spawn(async move {
             loop {
                 tokio::time::sleep(prune_frequency).await;
-                store.prune();
+                let store = store.clone();
+                if let Err(e) = tokio::task::spawn_blocking(move || store.prune()).await {
+                    tracing::error!(?e, "Pruning the store panicked");
+                }
+                if let Some(rate_limit) = &rate_limit {
+                    rate_limit.prune();
+                }
             }
         });
     }
 
     let boot = now::now()
-        .or(put::put(store.clone()))
+        .or(put::put(store.clone(), rate_limit))
         .or(random::random(store.clone()))
         .or(proxy_list::proxy_list(store.clone()))
-        .or(clear::clear(store));
+        .or(clear::clear(store.clone(), operator_token.clone()))
+        .or(operator::spaces(store.clone(), operator_token.clone()))
+        .or(operator::agents(store.clone(), operator_token.clone()))
+        .or(operator::evict(store, operator_token));
 
     let (s, r) = tokio::sync::oneshot::channel();
     let shutdown = Box::new(move || {
@@ -76,6 +161,33 @@ pub async fn run_with_prune_freq(
     }
 }
 
+/// Parse a list of allowed spaces, one per line, each in the url-safe
+/// base64 form they are displayed in. Blank lines and lines starting with
+/// `#` are ignored.
+pub fn parse_allowed_spaces(list: &str) -> Result<HashSet<Arc<KitsuneSpace>>, String> {
+    list.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            decode_b64(line)
+                .map(|space| Arc::new(KitsuneSpace(space)))
+                .ok_or_else(|| format!("Invalid space: {}", line))
+        })
+        .collect()
+}
+
+/// Decode a space or agent from the url-safe base64 form it is displayed in.
+fn decode_b64(s: &str) -> Option<Vec<u8>> {
+    base64::decode_config(s, base64::URL_SAFE_NO_PAD).ok()
+}
+
+fn error_reply(
+    status: warp::http::StatusCode,
+    reason: impl ToString,
+) -> warp::reply::WithStatus<Vec<u8>> {
+    warp::reply::with_status(reason.to_string().into_bytes(), status)
+}
+
 fn with_store(
     store: Store,
 ) -> impl Filter<Extract = (Store,), Error = std::convert::Infallible> + Clone {
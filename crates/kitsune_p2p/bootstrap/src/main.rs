@@ -1,4 +1,8 @@
 use clap::Parser;
+use kitsune_p2p_types::bin_types::KitsuneSpace;
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::Arc;
 
 #[derive(Parser, Debug)]
 #[clap(version, about, long_about = None)]
@@ -12,6 +16,33 @@ struct Args {
     /// multiple times
     #[clap(short, long, verbatim_doc_comment)]
     proxy: Vec<String>,
+
+    /// keep agent infos in this SQLite database
+    /// so they survive a restart, instead of
+    /// only in memory
+    #[clap(long, verbatim_doc_comment)]
+    database: Option<std::path::PathBuf>,
+
+    /// the most agents that can be stored
+    /// in each space
+    #[clap(long, verbatim_doc_comment)]
+    max_agents_per_space: Option<usize>,
+
+    /// the most agent infos that can be put
+    /// from one IP address each minute
+    #[clap(long, verbatim_doc_comment)]
+    max_puts_per_minute: Option<u32>,
+
+    /// only accept agent infos for the spaces
+    /// listed in this file, one per line in
+    /// url-safe base64
+    #[clap(long, verbatim_doc_comment)]
+    allowed_spaces: Option<std::path::PathBuf>,
+
+    /// serve the operator routes, authorized
+    /// by the bearer token in this file
+    #[clap(long, verbatim_doc_comment)]
+    operator_token_file: Option<std::path::PathBuf>,
 }
 
 #[tokio::main(flavor = "multi_thread")]
@@ -27,7 +58,29 @@ async fn main() {
         .next()
         .unwrap();
 
-    match kitsune_p2p_bootstrap::run(addr, args.proxy).await {
+    let allowed_spaces = args
+        .allowed_spaces
+        .as_deref()
+        .map(read_allowed_spaces)
+        .transpose()
+        .unwrap_or_else(|err| exit_with(err));
+    let operator_token = args
+        .operator_token_file
+        .as_deref()
+        .map(read_token)
+        .transpose()
+        .unwrap_or_else(|err| exit_with(err));
+
+    let config = kitsune_p2p_bootstrap::BootstrapConfig {
+        database: args.database,
+        max_agents_per_space: args.max_agents_per_space,
+        max_puts_per_minute: args.max_puts_per_minute,
+        allowed_spaces,
+        operator_token,
+        ..Default::default()
+    };
+
+    match kitsune_p2p_bootstrap::run_with_config(addr, args.proxy, config).await {
         Ok((driver, addr, _shutdown)) => {
             println!("http://{}", addr);
             driver.await;
@@ -35,3 +88,25 @@ async fn main() {
         Err(err) => eprintln!("{}", err),
     }
 }
+
+fn read_allowed_spaces(path: &Path) -> Result<HashSet<Arc<KitsuneSpace>>, String> {
+    let list = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read allowed spaces {}: {}", path.display(), e))?;
+    kitsune_p2p_bootstrap::parse_allowed_spaces(&list)
+        .map_err(|e| format!("Failed to parse allowed spaces {}: {}", path.display(), e))
+}
+
+fn read_token(path: &Path) -> Result<String, String> {
+    let token = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read operator token {}: {}", path.display(), e))?;
+    let token = token.trim();
+    if token.is_empty() {
+        return Err(format!("Operator token {} is empty", path.display()));
+    }
+    Ok(token.to_string())
+}
+
+fn exit_with(err: String) -> ! {
+    eprintln!("{}", err);
+    std::process::exit(1)
+}
@@ -0,0 +1,266 @@
+//! Routes for the operator of a bootstrap server, to see what is stored
+//! and evict agents. These are only served if an operator token is
+//! configured, and each request must carry it as a bearer token in the
+//! `Authorization` header. Requests and responses are JSON.
+
+use crate::store::Store;
+
+use super::*;
+use kitsune_p2p_types::bin_types::{KitsuneAgent, KitsuneSpace};
+use warp::http::StatusCode;
+use warp::Filter;
+
+/// Operator requests can name many agents, so allow a bigger body than
+/// the public routes.
+const OPERATOR_SIZE_LIMIT: u64 = 64 * 1024;
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub(crate) struct AgentsRequest {
+    pub space: String,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub(crate) struct EvictRequest {
+    pub space: String,
+    pub agents: Vec<String>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub(crate) struct EvictResponse {
+    pub evicted: usize,
+}
+
+/// List every space with its agent count and when it was last put to.
+pub(crate) fn spaces(
+    store: Store,
+    token: Option<Arc<str>>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::post()
+        .and(warp::header::exact("X-Op", "spaces"))
+        .and(authorized(token))
+        .and(with_store(store))
+        .and_then(list_spaces)
+}
+
+/// List the agents in one space.
+pub(crate) fn agents(
+    store: Store,
+    token: Option<Arc<str>>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::post()
+        .and(warp::header::exact("X-Op", "agents"))
+        .and(authorized(token))
+        .and(warp::body::content_length_limit(OPERATOR_SIZE_LIMIT))
+        .and(warp::body::json())
+        .and(with_store(store))
+        .and_then(list_agents)
+}
+
+/// Remove agents from a space. They can be put again afterwards,
+/// unless the space is no longer allowed.
+pub(crate) fn evict(
+    store: Store,
+    token: Option<Arc<str>>,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::post()
+        .and(warp::header::exact("X-Op", "evict"))
+        .and(authorized(token))
+        .and(warp::body::content_length_limit(OPERATOR_SIZE_LIMIT))
+        .and(warp::body::json())
+        .and(with_store(store))
+        .and_then(evict_agents)
+}
+
+/// Passes if the request carries the operator token. The routes are not
+/// found at all if there is no operator token configured.
+pub(crate) fn authorized(
+    token: Option<Arc<str>>,
+) -> impl Filter<Extract = (bool,), Error = warp::Rejection> + Clone {
+    warp::header::optional::<String>("Authorization").and_then(move |header: Option<String>| {
+        let token = token.clone();
+        async move {
+            let token = token.ok_or_else(warp::reject::not_found)?;
+            let given = header
+                .as_deref()
+                .and_then(|h| h.strip_prefix("Bearer "))
+                .unwrap_or_default();
+            Ok::<_, warp::Rejection>(constant_time_eq(given.as_bytes(), token.as_bytes()))
+        }
+    })
+}
+
+/// Compare without returning early, so the token can't be guessed
+/// from how long a comparison takes.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+pub(crate) fn unauthorized() -> Box<dyn warp::Reply> {
+    Box::new(error_reply(StatusCode::UNAUTHORIZED, "unauthorized"))
+}
+
+async fn list_spaces(
+    authorized: bool,
+    store: Store,
+) -> Result<Box<dyn warp::Reply>, warp::Rejection> {
+    if !authorized {
+        return Ok(unauthorized());
+    }
+    Ok(Box::new(warp::reply::json(&store.spaces())))
+}
+
+async fn list_agents(
+    authorized: bool,
+    request: AgentsRequest,
+    store: Store,
+) -> Result<Box<dyn warp::Reply>, warp::Rejection> {
+    if !authorized {
+        return Ok(unauthorized());
+    }
+    let space = match decode_b64(&request.space) {
+        Some(space) => KitsuneSpace(space),
+        None => {
+            return Ok(Box::new(error_reply(
+                StatusCode::BAD_REQUEST,
+                "invalid space",
+            )))
+        }
+    };
+    Ok(Box::new(warp::reply::json(&store.agents(&space))))
+}
+
+async fn evict_agents(
+    authorized: bool,
+    request: EvictRequest,
+    store: Store,
+) -> Result<Box<dyn warp::Reply>, warp::Rejection> {
+    if !authorized {
+        return Ok(unauthorized());
+    }
+    let space = match decode_b64(&request.space) {
+        Some(space) => KitsuneSpace(space),
+        None => {
+            return Ok(Box::new(error_reply(
+                StatusCode::BAD_REQUEST,
+                "invalid space",
+            )))
+        }
+    };
+    let agents: Option<Vec<_>> = request
+        .agents
+        .iter()
+        .map(|agent| decode_b64(agent).map(KitsuneAgent))
+        .collect();
+    let agents = match agents {
+        Some(agents) => agents,
+        None => {
+            return Ok(Box::new(error_reply(
+                StatusCode::BAD_REQUEST,
+                "invalid agent",
+            )))
+        }
+    };
+    match tokio::task::spawn_blocking(move || store.evict(&space, &agents)).await {
+        Ok(Ok(evicted)) => Ok(Box::new(warp::reply::json(&EvictResponse { evicted }))),
+        Ok(Err(e)) => Ok(Box::new(error_reply(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("failed to evict agents: {}", e),
+        ))),
+        Err(e) => Ok(Box::new(error_reply(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("failed to evict agents: {}", e),
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::{AgentSummary, SpaceSummary};
+    use crate::test_util::signed_info;
+    use fixt::prelude::*;
+    use kitsune_p2p::fixt::*;
+
+    const TOKEN: &str = "let-me-in";
+
+    fn now() -> u64 {
+        std::time::UNIX_EPOCH.elapsed().unwrap().as_millis() as u64
+    }
+
+    fn request(op: &str, token: Option<&str>) -> warp::test::RequestBuilder {
+        let mut request = warp::test::request().method("POST").header("X-Op", op);
+        if let Some(token) = token {
+            request = request.header("Authorization", format!("Bearer {}", token));
+        }
+        request
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_operator() {
+        let store = Store::new(vec![]);
+        let token: Option<Arc<str>> = Some(TOKEN.into());
+        let spaces_filter = spaces(store.clone(), token.clone());
+        let agents_filter = agents(store.clone(), token.clone());
+        let evict_filter = evict(store.clone(), token);
+
+        let space = Arc::new(fixt!(KitsuneSpace, Unpredictable));
+        let first = signed_info(space.clone(), now() + 60_000).await;
+        let second = signed_info(space.clone(), now() + 60_000).await;
+        store.put(first.clone()).unwrap();
+        store.put(second.clone()).unwrap();
+
+        // The token is required.
+        assert_eq!(
+            request("spaces", None).reply(&spaces_filter).await.status(),
+            401
+        );
+        assert_eq!(
+            request("spaces", Some("wrong"))
+                .reply(&spaces_filter)
+                .await
+                .status(),
+            401
+        );
+
+        let res = request("spaces", Some(TOKEN)).reply(&spaces_filter).await;
+        assert_eq!(res.status(), 200);
+        let summary: Vec<SpaceSummary> = serde_json::from_slice(res.body()).unwrap();
+        assert_eq!(summary.len(), 1);
+        assert_eq!(summary[0].space, space.to_string());
+        assert_eq!(summary[0].agents, 2);
+        assert!(summary[0].last_seen_ms > 0);
+
+        let res = request("agents", Some(TOKEN))
+            .json(&AgentsRequest {
+                space: space.to_string(),
+            })
+            .reply(&agents_filter)
+            .await;
+        assert_eq!(res.status(), 200);
+        let agents: Vec<AgentSummary> = serde_json::from_slice(res.body()).unwrap();
+        assert_eq!(agents.len(), 2);
+
+        let res = request("evict", Some(TOKEN))
+            .json(&EvictRequest {
+                space: space.to_string(),
+                agents: vec![first.agent.to_string()],
+            })
+            .reply(&evict_filter)
+            .await;
+        assert_eq!(res.status(), 200);
+        let evicted: EvictResponse = serde_json::from_slice(res.body()).unwrap();
+        assert_eq!(evicted.evicted, 1);
+        let all = store.all();
+        assert!(!all[&space].contains_key(&first.agent));
+        assert!(all[&space].contains_key(&second.agent));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_operator_disabled() {
+        let filter = spaces(Store::new(vec![]), None);
+        assert_eq!(
+            request("spaces", Some(TOKEN)).reply(&filter).await.status(),
+            404
+        );
+    }
+}
@@ -1,41 +1,98 @@
-use crate::store::Store;
+use crate::rate_limit::RateLimit;
+use crate::store::{PutError, Store};
 
 use super::*;
 use kitsune_p2p_types::agent_info::AgentInfoSigned;
+use warp::http::StatusCode;
 use warp::Filter;
 
 pub(crate) fn put(
     store: Store,
+    rate_limit: Option<RateLimit>,
 ) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
     warp::post()
         .and(warp::header::exact("X-Op", "put"))
         .and(warp::body::content_length_limit(SIZE_LIMIT))
         .and(warp::body::bytes())
+        .and(warp::addr::remote())
         .and(with_store(store))
+        .and(warp::any().map(move || rate_limit.clone()))
         .and_then(put_info)
 }
 
-async fn put_info(peer: Bytes, store: Store) -> Result<impl warp::Reply, warp::Rejection> {
+async fn put_info(
+    peer: Bytes,
+    remote: Option<SocketAddr>,
+    store: Store,
+    rate_limit: Option<RateLimit>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    if let (Some(rate_limit), Some(remote)) = (rate_limit, remote) {
+        if !rate_limit.check(remote.ip()) {
+            return Ok(error_reply(
+                StatusCode::TOO_MANY_REQUESTS,
+                "too many requests",
+            ));
+        }
+    }
     let peer: AgentInfoSigned =
         rmp_decode(&mut AsRef::<[u8]>::as_ref(&peer)).map_err(|_| warp::reject())?;
-    // TODO: Return rejection if agent info was invalid?
-    if valid(&peer) {
-        store.put(peer);
+    if let Err(reason) = valid(&peer).await {
+        return Ok(error_reply(StatusCode::BAD_REQUEST, reason));
+    }
+    match tokio::task::spawn_blocking(move || store.put(peer)).await {
+        Ok(Ok(())) => (),
+        Ok(Err(e @ (PutError::SpaceNotAllowed | PutError::SpaceFull))) => {
+            return Ok(error_reply(StatusCode::FORBIDDEN, e))
+        }
+        Ok(Err(e @ PutError::Database(_))) => {
+            return Ok(error_reply(StatusCode::INTERNAL_SERVER_ERROR, e))
+        }
+        Err(_) => {
+            return Ok(error_reply(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "failed to store agent info",
+            ))
+        }
     }
     PUT.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
     let mut buf = Vec::with_capacity(1);
     rmp_encode(&mut buf, ()).map_err(|_| warp::reject())?;
-    Ok(buf)
+    Ok(warp::reply::with_status(buf, StatusCode::OK))
 }
 
-fn valid(peer: &AgentInfoSigned) -> bool {
-    // TODO: verify signature
+/// Check that an agent info has not expired and was signed by its agent.
+async fn valid(peer: &AgentInfoSigned) -> Result<(), &'static str> {
     // Verify time
-    peer.expires_at_ms as u128
-        > std::time::UNIX_EPOCH
+    if peer.expires_at_ms as u128
+        <= std::time::UNIX_EPOCH
             .elapsed()
             .expect("Bootstrap system clock is set before the epoch")
             .as_millis()
+    {
+        return Err("agent info has expired");
+    }
+
+    // Verify signature
+    // The first 32 bytes of an agent are its ed25519 public key,
+    // and the rest are location bytes.
+    if peer.agent.len() < 32 || peer.signature.len() != 64 {
+        return Err("invalid signature");
+    }
+    let mut pub_key = [0; 32];
+    pub_key.copy_from_slice(&peer.agent[..32]);
+    let mut signature = [0; 64];
+    signature.copy_from_slice(&peer.signature[..]);
+    let verified = sodoken::sign::verify_detached(
+        sodoken::BufReadSized::new_no_lock(signature),
+        sodoken::BufRead::new_no_lock(&peer.encoded_bytes),
+        sodoken::BufReadSized::new_no_lock(pub_key),
+    )
+    .await
+    .unwrap_or(false);
+    if !verified {
+        return Err("invalid signature");
+    }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -43,44 +100,131 @@ mod tests {
     use std::sync::Arc;
 
     use super::*;
+    use crate::test_util::signed_info;
     use fixt::prelude::*;
     use kitsune_p2p::fixt::*;
 
+    fn now() -> u64 {
+        std::time::UNIX_EPOCH.elapsed().unwrap().as_millis() as u64
+    }
+
+    async fn put_request<F>(filter: &F, info: &AgentInfoSigned) -> warp::http::Response<Bytes>
+    where
+        F: Filter + 'static,
+        F::Extract: warp::Reply + Send,
+    {
+        let mut buf = Vec::new();
+        rmp_encode(&mut buf, info).unwrap();
+        warp::test::request()
+            .method("POST")
+            .header("Content-type", "application/octet")
+            .header("X-Op", "put")
+            .remote_addr(([127, 0, 0, 1], 12345).into())
+            .body(buf)
+            .reply(filter)
+            .await
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn test_put() {
         let store = Store::new(vec![]);
-        let filter = put(store.clone());
+        let filter = put(store.clone(), None);
+
+        let info = signed_info(Arc::new(fixt!(KitsuneSpace, Unpredictable)), now() + 60_000).await;
+
+        let res = put_request(&filter, &info).await;
+        assert_eq!(res.status(), 200);
+        assert_eq!(
+            *store
+                .all()
+                .get(info.space.as_ref())
+                .unwrap()
+                .get(info.agent.as_ref())
+                .unwrap(),
+            info
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_put_rejects_invalid() {
+        let store = Store::new(vec![]);
+        let filter = put(store.clone(), None);
+        let space = Arc::new(fixt!(KitsuneSpace, Unpredictable));
 
+        // Forged signature
         let info = AgentInfoSigned::sign(
-            Arc::new(fixt!(KitsuneSpace, Unpredictable)),
+            space.clone(),
             Arc::new(fixt!(KitsuneAgent, Unpredictable)),
             u32::MAX / 4,
             fixt!(UrlList, Empty),
             0,
-            std::time::UNIX_EPOCH.elapsed().unwrap().as_millis() as u64 + 60_000_000,
+            now() + 60_000,
             |_| async move { Ok(Arc::new(fixt!(KitsuneSignature, Unpredictable))) },
         )
         .await
         .unwrap();
-        let mut buf = Vec::new();
-        rmp_encode(&mut buf, info.clone()).unwrap();
+        assert_eq!(put_request(&filter, &info).await.status(), 400);
+
+        // Signed by a different agent
+        let other = signed_info(space.clone(), now() + 60_000).await;
+        let info = AgentInfoSigned::sign(
+            space.clone(),
+            Arc::new(fixt!(KitsuneAgent, Unpredictable)),
+            u32::MAX / 4,
+            fixt!(UrlList, Empty),
+            0,
+            now() + 60_000,
+            |_| async move { Ok(other.signature.clone()) },
+        )
+        .await
+        .unwrap();
+        assert_eq!(put_request(&filter, &info).await.status(), 400);
 
+        // Expired
+        let info = signed_info(space.clone(), now() + 10).await;
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        assert_eq!(put_request(&filter, &info).await.status(), 400);
+
+        // Oversized
         let res = warp::test::request()
             .method("POST")
             .header("Content-type", "application/octet")
             .header("X-Op", "put")
-            .body(buf)
+            .body(vec![0; SIZE_LIMIT as usize + 1])
             .reply(&filter)
             .await;
-        assert_eq!(res.status(), 200);
-        assert_eq!(
-            *store
-                .all()
-                .get(info.space.as_ref())
-                .unwrap()
-                .get(info.agent.as_ref())
-                .unwrap(),
-            info
-        );
+        assert_eq!(res.status(), 413);
+
+        assert!(store.all().is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_put_allowed_spaces() {
+        let allowed = Arc::new(fixt!(KitsuneSpace, Unpredictable));
+        let list = format!("# Our spaces\n\n{}\n", allowed);
+        let store =
+            Store::new(vec![]).with_allowed_spaces(Some(parse_allowed_spaces(&list).unwrap()));
+        let filter = put(store.clone(), None);
+
+        let info = signed_info(allowed.clone(), now() + 60_000).await;
+        assert_eq!(put_request(&filter, &info).await.status(), 200);
+        let other = Arc::new(fixt!(KitsuneSpace, Unpredictable));
+        let info = signed_info(other.clone(), now() + 60_000).await;
+        assert_eq!(put_request(&filter, &info).await.status(), 403);
+        assert!(store.all().get(&other).is_none());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_put_limits() {
+        let store = Store::new(vec![]).with_max_agents_per_space(Some(1));
+        let rate_limit = RateLimit::new(2, std::time::Duration::from_secs(60));
+        let filter = put(store.clone(), Some(rate_limit));
+        let space = Arc::new(fixt!(KitsuneSpace, Unpredictable));
+
+        let info = signed_info(space.clone(), now() + 60_000).await;
+        assert_eq!(put_request(&filter, &info).await.status(), 200);
+        let info = signed_info(space.clone(), now() + 60_000).await;
+        assert_eq!(put_request(&filter, &info).await.status(), 403);
+        assert_eq!(put_request(&filter, &info).await.status(), 429);
     }
 }
@@ -0,0 +1,74 @@
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use parking_lot::Mutex;
+
+/// Counts requests from each IP address over a fixed window of time.
+#[derive(Clone, Debug)]
+pub(crate) struct RateLimit {
+    max_per_window: u32,
+    window: Duration,
+    /// When each address's current window started,
+    /// and how many requests it has made since.
+    counts: Arc<Mutex<HashMap<IpAddr, (Instant, u32)>>>,
+}
+
+impl RateLimit {
+    pub fn new(max_per_window: u32, window: Duration) -> Self {
+        Self {
+            max_per_window,
+            window,
+            counts: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Count a request from this address.
+    /// Returns false if the address has already made too many requests.
+    pub fn check(&self, ip: IpAddr) -> bool {
+        let now = Instant::now();
+        let mut counts = self.counts.lock();
+        let (start, count) = counts.entry(ip).or_insert((now, 0));
+        if now.duration_since(*start) >= self.window {
+            *start = now;
+            *count = 0;
+        }
+        if *count >= self.max_per_window {
+            return false;
+        }
+        *count += 1;
+        true
+    }
+
+    /// Forget addresses whose window has passed.
+    pub fn prune(&self) {
+        let now = Instant::now();
+        self.counts
+            .lock()
+            .retain(|_, (start, _)| now.duration_since(*start) < self.window);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_limit() {
+        let limit = RateLimit::new(2, Duration::from_millis(100));
+        let a = IpAddr::from([127, 0, 0, 1]);
+        let b = IpAddr::from([127, 0, 0, 2]);
+        assert!(limit.check(a));
+        assert!(limit.check(a));
+        assert!(!limit.check(a));
+        assert!(limit.check(b));
+
+        std::thread::sleep(Duration::from_millis(100));
+        limit.prune();
+        assert!(limit.counts.lock().is_empty());
+        assert!(limit.check(a));
+    }
+}
@@ -1,70 +1,251 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+    sync::Arc,
+};
 
 use kitsune_p2p_types::{
     agent_info::AgentInfoSigned,
     bin_types::{KitsuneAgent, KitsuneSpace},
     bootstrap::RandomQuery,
-    codec::rmp_encode,
+    codec::{rmp_decode, rmp_encode},
 };
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use rand::seq::IteratorRandom;
+use rusqlite::{params, Connection};
 
-type AgentMap = HashMap<Arc<KitsuneAgent>, AgentInfoSigned>;
+type AgentMap = HashMap<Arc<KitsuneAgent>, StoredInfo>;
 type SpaceMap = HashMap<Arc<KitsuneSpace>, AgentMap>;
 
 #[derive(Clone, Debug)]
-pub(crate) struct Store(Arc<RwLock<SpaceMap>>, Arc<Vec<String>>);
+struct StoredInfo {
+    info: AgentInfoSigned,
+    /// When the agent last put its info, by the bootstrap server's clock.
+    last_seen_ms: u64,
+}
+
+/// What an operator sees of a space.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub(crate) struct SpaceSummary {
+    pub space: String,
+    pub agents: usize,
+    /// The most recent time any agent in the space put its info.
+    pub last_seen_ms: u64,
+}
+
+/// What an operator sees of an agent.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub(crate) struct AgentSummary {
+    pub agent: String,
+    pub url_list: Vec<String>,
+    pub signed_at_ms: u64,
+    pub expires_at_ms: u64,
+    pub last_seen_ms: u64,
+}
+
+/// Why an agent info could not be stored.
+#[derive(Debug)]
+pub(crate) enum PutError {
+    /// The space is not one this bootstrap server serves.
+    SpaceNotAllowed,
+    /// The space already holds as many agents as it is allowed.
+    SpaceFull,
+    /// The agent info could not be written to the database.
+    Database(rusqlite::Error),
+}
+
+impl std::fmt::Display for PutError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PutError::SpaceNotAllowed => write!(f, "space is not allowed"),
+            PutError::SpaceFull => write!(f, "space is full"),
+            PutError::Database(e) => write!(f, "failed to store agent info: {}", e),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct Store {
+    spaces: Arc<RwLock<SpaceMap>>,
+    proxy_list: Arc<Vec<String>>,
+    /// If set, every agent info in `spaces` is also written here,
+    /// so that they survive a restart.
+    db: Option<Arc<Mutex<Connection>>>,
+    max_agents_per_space: Option<usize>,
+    /// If set, only agent infos for these spaces are stored.
+    allowed_spaces: Option<Arc<HashSet<Arc<KitsuneSpace>>>>,
+}
 
 impl Store {
     pub fn new(proxy_list: Vec<String>) -> Self {
-        Self(Arc::new(RwLock::new(HashMap::new())), Arc::new(proxy_list))
+        Self {
+            spaces: Arc::new(RwLock::new(HashMap::new())),
+            proxy_list: Arc::new(proxy_list),
+            db: None,
+            max_agents_per_space: None,
+            allowed_spaces: None,
+        }
+    }
+
+    /// Open a store backed by the SQLite database at `path`,
+    /// loading any unexpired agent infos it already holds.
+    pub fn open(path: &Path, proxy_list: Vec<String>) -> rusqlite::Result<Self> {
+        let db = Connection::open(path)?;
+        db.execute(
+            "CREATE TABLE IF NOT EXISTS agent_info (
+                space BLOB NOT NULL,
+                agent BLOB NOT NULL,
+                signed_at_ms INTEGER NOT NULL,
+                expires_at_ms INTEGER NOT NULL,
+                last_seen_ms INTEGER NOT NULL,
+                encoded BLOB NOT NULL,
+                PRIMARY KEY (space, agent)
+            )",
+            [],
+        )?;
+        db.execute(
+            "DELETE FROM agent_info WHERE expires_at_ms < ?",
+            [now() as i64],
+        )?;
+
+        let mut spaces = SpaceMap::new();
+        {
+            let mut stmt = db.prepare("SELECT encoded, last_seen_ms FROM agent_info")?;
+            let rows = stmt.query_map([], |row| {
+                Ok((row.get::<_, Vec<u8>>(0)?, row.get::<_, i64>(1)?))
+            })?;
+            for row in rows {
+                let (encoded, last_seen_ms) = row?;
+                // Skip anything that no longer decodes rather than refusing to start.
+                if let Ok(info) = rmp_decode::<_, AgentInfoSigned>(&mut encoded.as_slice()) {
+                    spaces.entry(info.space.clone()).or_default().insert(
+                        info.agent.clone(),
+                        StoredInfo {
+                            info,
+                            last_seen_ms: last_seen_ms as u64,
+                        },
+                    );
+                }
+            }
+        }
+
+        Ok(Self {
+            spaces: Arc::new(RwLock::new(spaces)),
+            proxy_list: Arc::new(proxy_list),
+            db: Some(Arc::new(Mutex::new(db))),
+            max_agents_per_space: None,
+            allowed_spaces: None,
+        })
+    }
+
+    /// Limit how many agents can be stored in each space.
+    pub fn with_max_agents_per_space(mut self, max_agents_per_space: Option<usize>) -> Self {
+        self.max_agents_per_space = max_agents_per_space;
+        self
+    }
+
+    /// Only store agent infos for these spaces,
+    /// forgetting any others which were loaded from the database.
+    pub fn with_allowed_spaces(
+        mut self,
+        allowed_spaces: Option<HashSet<Arc<KitsuneSpace>>>,
+    ) -> Self {
+        if let Some(allowed_spaces) = &allowed_spaces {
+            self.spaces
+                .write()
+                .retain(|space, _| allowed_spaces.contains(space));
+        }
+        self.allowed_spaces = allowed_spaces.map(Arc::new);
+        self
     }
 
     pub fn proxy_list(&self) -> Arc<Vec<String>> {
-        self.1.clone()
+        self.proxy_list.clone()
     }
 
+    /// Forget expired agent infos. Like the other methods which write to
+    /// the database this blocks, so call it from `spawn_blocking`.
     pub fn prune(&self) {
-        let now = std::time::UNIX_EPOCH
-            .elapsed()
-            .expect("Bootstrap server time set before epoch")
-            .as_millis() as u64;
+        let now = now();
 
-        self.0.write().retain(|_, map| {
-            map.retain(|_, info| info.expires_at_ms >= now);
+        self.spaces.write().retain(|_, map| {
+            map.retain(|_, stored| stored.info.expires_at_ms >= now);
             !map.is_empty()
         });
+        if let Some(db) = &self.db {
+            if let Err(e) = db.lock().execute(
+                "DELETE FROM agent_info WHERE expires_at_ms < ?",
+                [now as i64],
+            ) {
+                tracing::error!(?e, "Failed to prune expired agent infos");
+            }
+        }
     }
 
-    pub fn put(&self, info: AgentInfoSigned) {
-        let mut lock = self.0.write();
+    pub fn put(&self, info: AgentInfoSigned) -> Result<(), PutError> {
+        if matches!(&self.allowed_spaces, Some(allowed) if !allowed.contains(&info.space)) {
+            return Err(PutError::SpaceNotAllowed);
+        }
+        let last_seen_ms = now();
+        let mut lock = self.spaces.write();
         let space_map = lock.entry(info.space.clone()).or_insert_with(HashMap::new);
-        match space_map.entry(info.agent.clone()) {
-            std::collections::hash_map::Entry::Occupied(mut e) => {
-                if info.signed_at_ms > e.get().signed_at_ms {
-                    e.insert(info);
+        match space_map.get_mut(&info.agent) {
+            Some(existing) if info.signed_at_ms <= existing.info.signed_at_ms => {
+                if let Some(db) = &self.db {
+                    db.lock()
+                        .execute(
+                            "UPDATE agent_info SET last_seen_ms = ? WHERE space = ? AND agent = ?",
+                            params![last_seen_ms as i64, &info.space.0, &info.agent.0],
+                        )
+                        .map_err(PutError::Database)?;
                 }
+                existing.last_seen_ms = last_seen_ms;
+                return Ok(());
             }
-            std::collections::hash_map::Entry::Vacant(e) => {
-                e.insert(info);
+            Some(_) => (),
+            None => {
+                if matches!(self.max_agents_per_space, Some(max) if space_map.len() >= max) {
+                    return Err(PutError::SpaceFull);
+                }
             }
         }
+        if let Some(db) = &self.db {
+            let mut encoded = Vec::new();
+            rmp_encode(&mut encoded, &info).map_err(|e| {
+                PutError::Database(rusqlite::Error::ToSqlConversionFailure(e.into()))
+            })?;
+            db.lock()
+                .execute(
+                    "INSERT OR REPLACE INTO agent_info
+                        (space, agent, signed_at_ms, expires_at_ms, last_seen_ms, encoded)
+                        VALUES (?, ?, ?, ?, ?, ?)",
+                    params![
+                        &info.space.0,
+                        &info.agent.0,
+                        info.signed_at_ms as i64,
+                        info.expires_at_ms as i64,
+                        last_seen_ms as i64,
+                        encoded,
+                    ],
+                )
+                .map_err(PutError::Database)?;
+        }
+        space_map.insert(info.agent.clone(), StoredInfo { info, last_seen_ms });
+        Ok(())
     }
 
     pub fn random(&self, query: RandomQuery) -> Vec<Vec<u8>> {
         // TODO: Max this limit
         let limit = query.limit.0 as usize;
         let mut rng = rand::thread_rng();
-        let now = std::time::UNIX_EPOCH
-            .elapsed()
-            .expect("Bootstrap server time set before epoch")
-            .as_millis() as u64;
-        self.0
+        let now = now();
+        self.spaces
             .read()
             .get(query.space.as_ref())
             .map(|space| {
                 space
                     .values()
+                    .map(|stored| &stored.info)
                     .filter_map(|i| {
                         if i.expires_at_ms <= now {
                             return None;
@@ -83,12 +264,154 @@ impl Store {
             .unwrap_or_default()
     }
 
-    pub fn clear(&self) {
-        self.0.write().clear()
+    pub fn clear(&self) -> rusqlite::Result<()> {
+        let mut lock = self.spaces.write();
+        if let Some(db) = &self.db {
+            db.lock().execute("DELETE FROM agent_info", [])?;
+        }
+        lock.clear();
+        Ok(())
+    }
+
+    pub fn spaces(&self) -> Vec<SpaceSummary> {
+        self.spaces
+            .read()
+            .iter()
+            .map(|(space, agents)| SpaceSummary {
+                space: space.to_string(),
+                agents: agents.len(),
+                last_seen_ms: agents
+                    .values()
+                    .map(|stored| stored.last_seen_ms)
+                    .max()
+                    .unwrap_or_default(),
+            })
+            .collect()
+    }
+
+    pub fn agents(&self, space: &KitsuneSpace) -> Vec<AgentSummary> {
+        self.spaces
+            .read()
+            .get(space)
+            .map(|agents| {
+                agents
+                    .iter()
+                    .map(|(agent, stored)| AgentSummary {
+                        agent: agent.to_string(),
+                        url_list: stored.info.url_list.iter().map(|u| u.to_string()).collect(),
+                        signed_at_ms: stored.info.signed_at_ms,
+                        expires_at_ms: stored.info.expires_at_ms,
+                        last_seen_ms: stored.last_seen_ms,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Remove these agents from a space, returning how many were there.
+    /// This is not a ban: an evicted agent is stored again the next time
+    /// it puts its info, as long as its space is allowed.
+    pub fn evict(&self, space: &KitsuneSpace, agents: &[KitsuneAgent]) -> rusqlite::Result<usize> {
+        let mut lock = self.spaces.write();
+        let space_map = match lock.get_mut(space) {
+            Some(space_map) => space_map,
+            None => return Ok(0),
+        };
+        if let Some(db) = &self.db {
+            let mut db = db.lock();
+            let txn = db.transaction()?;
+            for agent in agents {
+                txn.execute(
+                    "DELETE FROM agent_info WHERE space = ? AND agent = ?",
+                    params![&space.0, &agent.0],
+                )?;
+            }
+            txn.commit()?;
+        }
+        let evicted = agents
+            .iter()
+            .filter(|agent| space_map.remove(*agent).is_some())
+            .count();
+        if space_map.is_empty() {
+            lock.remove(space);
+        }
+        Ok(evicted)
     }
 
     #[cfg(test)]
     pub fn all(&self) -> HashMap<Arc<KitsuneSpace>, HashMap<Arc<KitsuneAgent>, AgentInfoSigned>> {
-        self.0.read().clone()
+        self.spaces
+            .read()
+            .iter()
+            .map(|(space, agents)| {
+                let agents = agents
+                    .iter()
+                    .map(|(agent, stored)| (agent.clone(), stored.info.clone()))
+                    .collect();
+                (space.clone(), agents)
+            })
+            .collect()
+    }
+}
+
+fn now() -> u64 {
+    std::time::UNIX_EPOCH
+        .elapsed()
+        .expect("Bootstrap server time set before epoch")
+        .as_millis() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::signed_info;
+    use fixt::prelude::*;
+    use kitsune_p2p::fixt::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_persistence() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("bootstrap.sqlite3");
+        let space: Arc<KitsuneSpace> = Arc::new(fixt!(KitsuneSpace));
+
+        let store = Store::open(&path, vec![]).unwrap();
+        let info = signed_info(space.clone(), now() + 60_000).await;
+        let expired = signed_info(space.clone(), now() + 100).await;
+        store.put(info.clone()).unwrap();
+        store.put(expired).unwrap();
+        drop(store);
+
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        let store = Store::open(&path, vec![]).unwrap();
+        let all = store.all();
+        assert_eq!(all.len(), 1);
+        assert_eq!(all[&space].len(), 1);
+        assert_eq!(all[&space][&info.agent], info);
+
+        store.clear().unwrap();
+        drop(store);
+        assert!(Store::open(&path, vec![]).unwrap().all().is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_space_quota() {
+        let store = Store::new(vec![]).with_max_agents_per_space(Some(2));
+        let space: Arc<KitsuneSpace> = Arc::new(fixt!(KitsuneSpace));
+        let first = signed_info(space.clone(), now() + 60_000).await;
+        store.put(first.clone()).unwrap();
+        store
+            .put(signed_info(space.clone(), now() + 60_000).await)
+            .unwrap();
+        assert!(matches!(
+            store.put(signed_info(space.clone(), now() + 60_000).await),
+            Err(PutError::SpaceFull)
+        ));
+
+        // Agents already in the space can still update their info,
+        // and other spaces are unaffected.
+        store.put(first).unwrap();
+        store
+            .put(signed_info(Arc::new(fixt!(KitsuneSpace, Unpredictable)), now() + 60_000).await)
+            .unwrap();
     }
 }
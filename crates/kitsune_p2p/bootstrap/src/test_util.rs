@@ -0,0 +1,43 @@
+use std::sync::Arc;
+
+use kitsune_p2p_types::{
+    agent_info::AgentInfoSigned,
+    bin_types::{KitsuneAgent, KitsuneSignature, KitsuneSpace},
+};
+
+/// Sign an agent info for a new agent with a real ed25519 keypair,
+/// so that it passes signature verification.
+pub(crate) async fn signed_info(space: Arc<KitsuneSpace>, expires_at_ms: u64) -> AgentInfoSigned {
+    let pk = sodoken::BufWriteSized::new_no_lock();
+    let sk = sodoken::BufWriteSized::new_no_lock();
+    sodoken::sign::keypair(pk.clone(), sk.clone())
+        .await
+        .unwrap();
+    let mut agent = pk.read_lock().to_vec();
+    // Pad with location bytes.
+    agent.extend_from_slice(&[0; 4]);
+    let signed_at_ms = std::time::UNIX_EPOCH.elapsed().unwrap().as_millis() as u64;
+
+    AgentInfoSigned::sign(
+        space,
+        Arc::new(KitsuneAgent(agent)),
+        u32::MAX / 4,
+        vec!["fake:".into()],
+        signed_at_ms,
+        expires_at_ms,
+        |data| {
+            let data = sodoken::BufRead::new_no_lock(data);
+            let sk = sk.to_read_sized();
+            async move {
+                let sig = sodoken::BufWriteSized::<64>::new_no_lock();
+                sodoken::sign::detached(sig.clone(), data, sk)
+                    .await
+                    .unwrap();
+                let sig = sig.read_lock().to_vec();
+                Ok(Arc::new(KitsuneSignature(sig)))
+            }
+        },
+    )
+    .await
+    .unwrap()
+}
@@ -15,6 +15,7 @@ pub const BOOTSTRAP_SERVICE_DEV: &str = "https://bootstrap-dev.holohost.workers.
 pub(crate) enum KitsuneP2pTx2Backend {
     Mem,
     Quic { bind_to: TxUrl },
+    Tcp { bind_to: TxUrl },
     Mock { mock_network: AdapterFactory },
 }
 
@@ -69,6 +70,13 @@ fn cnv_bind_to(bind_to: &Option<url2::Url2>) -> TxUrl {
     }
 }
 
+fn cnv_tcp_bind_to(bind_to: &Option<url2::Url2>) -> TxUrl {
+    match bind_to {
+        Some(bind_to) => bind_to.clone().into(),
+        None => "kitsune-tcp://0.0.0.0:0".into(),
+    }
+}
+
 impl KitsuneP2pConfig {
     /// `tx2` is currently designed to use exactly one proxy wrapped transport,
     /// so convert a bunch of the options from the previous transport
@@ -86,7 +94,11 @@ impl KitsuneP2pConfig {
                         let bind_to = cnv_bind_to(bind_to);
                         KitsuneP2pTx2Backend::Quic { bind_to }
                     }
-                    _ => return Err("kitsune tx2 backend must be mem or quic".into()),
+                    TransportConfig::Tcp { bind_to } => {
+                        let bind_to = cnv_tcp_bind_to(bind_to);
+                        KitsuneP2pTx2Backend::Tcp { bind_to }
+                    }
+                    _ => return Err("kitsune tx2 backend must be mem, quic or tcp".into()),
                 };
                 let use_proxy = match proxy_config {
                     ProxyConfig::RemoteProxyClient { proxy_url } => {
@@ -110,6 +122,13 @@ impl KitsuneP2pConfig {
                     use_proxy: NoProxy,
                 })
             }
+            Some(TransportConfig::Tcp { bind_to }) => {
+                let bind_to = cnv_tcp_bind_to(bind_to);
+                Ok(KitsuneP2pTx2Config {
+                    backend: KitsuneP2pTx2Backend::Tcp { bind_to },
+                    use_proxy: NoProxy,
+                })
+            }
             Some(TransportConfig::Mock { mock_network }) => Ok(KitsuneP2pTx2Config {
                 backend: KitsuneP2pTx2Backend::Mock {
                     mock_network: mock_network.0.clone(),
@@ -148,6 +167,13 @@ pub enum TransportConfig {
         /// Default: None = use NIC port
         override_port: Option<u16>,
     },
+    /// A transport that uses TLS over TCP (ALPN kitsune-tcp/0),
+    /// for networks where UDP is blocked
+    Tcp {
+        /// Network interface / port to bind to
+        /// Default: "kitsune-tcp://0.0.0.0:0"
+        bind_to: Option<Url2>,
+    },
     /// A transport that TLS tunnels through a sub-transport (ALPN kitsune-proxy/0)
     Proxy {
         /// The 'Proxy' transport is a wrapper around a sub-transport.
@@ -4,6 +4,7 @@
 
 use crate::agent_store::AgentInfoSigned;
 use crate::gossip::{decode_bloom_filter, encode_bloom_filter};
+use crate::types::compression::{self, WireCompressor};
 use crate::types::event::*;
 use crate::types::gossip::*;
 use crate::{types::*, HostApi};
@@ -112,6 +113,8 @@ pub struct ShardedGossip {
     pub(crate) state: Share<ShardedGossipState>,
     /// Bandwidth for incoming and outgoing gossip.
     bandwidth: Arc<BandwidthThrottle>,
+    /// Compresses outgoing gossip for remotes which accept it.
+    compressor: WireCompressor,
 }
 
 impl std::fmt::Debug for ShardedGossip {
@@ -162,6 +165,7 @@ impl ShardedGossip {
         host_api: HostApi,
         gossip_type: GossipType,
         bandwidth: Arc<BandwidthThrottle>,
+        compressor: WireCompressor,
         metrics: MetricsSync,
         #[cfg(feature = "test")] enable_history: bool,
     ) -> Arc<Self> {
@@ -188,6 +192,7 @@ impl ShardedGossip {
                 closing: AtomicBool::new(false),
             },
             bandwidth,
+            compressor,
         });
         metric_task({
             let this = this.clone();
@@ -235,12 +240,16 @@ impl ShardedGossip {
             }
         };
         let gossip = gossip.encode_vec().map_err(KitsuneError::other)?;
-        let bytes = gossip.len();
+        let mut bytes = gossip.len();
         let gossip = wire::Wire::gossip(
             self.gossip.space.clone(),
             gossip.into(),
             self.gossip.gossip_type.into(),
         );
+        let gossip = self.compressor.compress(&cert, gossip);
+        if let Some(compressed_bytes) = compression::compressed_len(&gossip) {
+            bytes = compressed_bytes;
+        }
 
         let timeout = self.gossip.tuning_params.implicit_timeout();
 
@@ -1120,9 +1129,10 @@ impl AsGossipModule for ShardedGossip {
         con: Tx2ConHnd<wire::Wire>,
         remote_url: TxUrl,
         gossip_data: Box<[u8]>,
+        wire_bytes: usize,
     ) -> KitsuneResult<()> {
         use kitsune_p2p_types::codec::*;
-        let (bytes, gossip) =
+        let (_, gossip) =
             ShardedGossipWire::decode_ref(&gossip_data).map_err(KitsuneError::other)?;
         let new_initiate = matches!(gossip, ShardedGossipWire::Initiate(_));
         self.state.share_mut(move |i, _| {
@@ -1141,7 +1151,7 @@ impl AsGossipModule for ShardedGossip {
                     ShardedGossipWire::busy(),
                 )]);
             } else {
-                i.push_incoming([(con, remote_url, gossip, bytes as usize)]);
+                i.push_incoming([(con, remote_url, gossip, wire_bytes)]);
             }
             Ok(())
         })
@@ -1182,11 +1192,15 @@ impl AsGossipModule for ShardedGossip {
 
 struct ShardedRecentGossipFactory {
     bandwidth: Arc<BandwidthThrottle>,
+    compressor: WireCompressor,
 }
 
 impl ShardedRecentGossipFactory {
-    fn new(bandwidth: Arc<BandwidthThrottle>) -> Self {
-        Self { bandwidth }
+    fn new(bandwidth: Arc<BandwidthThrottle>, compressor: WireCompressor) -> Self {
+        Self {
+            bandwidth,
+            compressor,
+        }
     }
 }
 
@@ -1208,6 +1222,7 @@ impl AsGossipModuleFactory for ShardedRecentGossipFactory {
             host,
             GossipType::Recent,
             self.bandwidth.clone(),
+            self.compressor.clone(),
             metrics,
         ))
     }
@@ -1215,11 +1230,15 @@ impl AsGossipModuleFactory for ShardedRecentGossipFactory {
 
 struct ShardedHistoricalGossipFactory {
     bandwidth: Arc<BandwidthThrottle>,
+    compressor: WireCompressor,
 }
 
 impl ShardedHistoricalGossipFactory {
-    fn new(bandwidth: Arc<BandwidthThrottle>) -> Self {
-        Self { bandwidth }
+    fn new(bandwidth: Arc<BandwidthThrottle>, compressor: WireCompressor) -> Self {
+        Self {
+            bandwidth,
+            compressor,
+        }
     }
 }
 
@@ -1241,19 +1260,30 @@ impl AsGossipModuleFactory for ShardedHistoricalGossipFactory {
             host,
             GossipType::Historical,
             self.bandwidth.clone(),
+            self.compressor.clone(),
             metrics,
         ))
     }
 }
 
 /// Create a recent `GossipModuleFactory`
-pub fn recent_factory(bandwidth: Arc<BandwidthThrottle>) -> GossipModuleFactory {
-    GossipModuleFactory(Arc::new(ShardedRecentGossipFactory::new(bandwidth)))
+pub fn recent_factory(
+    bandwidth: Arc<BandwidthThrottle>,
+    compressor: WireCompressor,
+) -> GossipModuleFactory {
+    GossipModuleFactory(Arc::new(ShardedRecentGossipFactory::new(
+        bandwidth, compressor,
+    )))
 }
 
 /// Create a historical `GossipModuleFactory`
-pub fn historical_factory(bandwidth: Arc<BandwidthThrottle>) -> GossipModuleFactory {
-    GossipModuleFactory(Arc::new(ShardedHistoricalGossipFactory::new(bandwidth)))
+pub fn historical_factory(
+    bandwidth: Arc<BandwidthThrottle>,
+    compressor: WireCompressor,
+) -> GossipModuleFactory {
+    GossipModuleFactory(Arc::new(ShardedHistoricalGossipFactory::new(
+        bandwidth, compressor,
+    )))
 }
 
 #[allow(dead_code)]
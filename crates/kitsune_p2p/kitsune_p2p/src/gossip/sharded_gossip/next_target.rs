@@ -2,6 +2,7 @@ use std::cmp::Ordering;
 
 use super::*;
 use crate::metrics::*;
+use crate::types::block;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 /// A remote node we can connect to.
@@ -43,6 +44,11 @@ impl ShardedGossipLocal {
             .filter(|a| remote_agents_within_arc_set.contains(&a.agent))
             .filter(|a| !a.storage_arc.is_empty())
         {
+            // Don't gossip with blocked agents or nodes.
+            if block::is_agent_info_blocked(&self.host_api, &info).await {
+                continue;
+            }
+
             // Get an address if there is one.
             let info = info
                 .url_list
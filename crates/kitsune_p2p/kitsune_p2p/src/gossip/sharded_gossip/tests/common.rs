@@ -94,6 +94,10 @@ impl KitsuneHost for StandardResponsesHostApi {
     ) -> crate::KitsuneHostResult<dht::spacetime::Topology> {
         box_fut(Ok(self.topology.clone()))
     }
+
+    fn is_blocked(&self, _target: crate::block::BlockTarget) -> crate::KitsuneHostResult<bool> {
+        box_fut(Ok(false))
+    }
 }
 
 // TODO: integrate with `HandlerBuilder`
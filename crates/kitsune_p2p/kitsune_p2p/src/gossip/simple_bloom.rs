@@ -469,6 +469,7 @@ impl AsGossipModule for SimpleBloomMod {
         con: Tx2ConHnd<wire::Wire>,
         remote_url: TxUrl,
         gossip_data: Box<[u8]>,
+        _wire_bytes: usize,
     ) -> KitsuneResult<()> {
         use kitsune_p2p_types::codec::*;
         let (_, gossip) = GossipWire::decode_ref(&gossip_data).map_err(KitsuneError::other)?;
@@ -8,6 +8,7 @@ use kitsune_p2p_types::{
 };
 
 use crate::event::{GetAgentInfoSignedEvt, MetricRecord};
+use crate::types::block::BlockTarget;
 
 /// A boxed future result with dynamic error type
 pub type KitsuneHostResult<'a, T> =
@@ -55,6 +56,10 @@ pub trait KitsuneHost: 'static + Send + Sync {
 
     /// Get the quantum Topology associated with this Space
     fn get_topology(&self, space: Arc<KitsuneSpace>) -> KitsuneHostResult<Topology>;
+
+    /// Check if a node or agent is blocked, in which case kitsune will
+    /// refuse to communicate with it.
+    fn is_blocked(&self, target: BlockTarget) -> KitsuneHostResult<bool>;
 }
 
 /// Trait object for the host interface
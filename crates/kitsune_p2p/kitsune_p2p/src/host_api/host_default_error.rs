@@ -87,6 +87,11 @@ pub trait KitsuneHostDefaultError: KitsuneHost {
         )
         .into()))
     }
+
+    /// Nothing is blocked unless a test handler says otherwise.
+    fn is_blocked(&self, _target: BlockTarget) -> KitsuneHostResult<bool> {
+        box_fut(Ok(false))
+    }
 }
 
 impl<T: KitsuneHostDefaultError> KitsuneHost for T {
@@ -133,4 +138,8 @@ impl<T: KitsuneHostDefaultError> KitsuneHost for T {
     fn get_topology(&self, space: Arc<KitsuneSpace>) -> KitsuneHostResult<Topology> {
         KitsuneHostDefaultError::get_topology(self, space)
     }
+
+    fn is_blocked(&self, target: BlockTarget) -> KitsuneHostResult<bool> {
+        KitsuneHostDefaultError::is_blocked(self, target)
+    }
 }
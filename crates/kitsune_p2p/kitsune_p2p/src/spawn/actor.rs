@@ -4,6 +4,8 @@ use crate::actor;
 use crate::actor::*;
 use crate::event::*;
 use crate::gossip::sharded_gossip::BandwidthThrottles;
+use crate::types::block::{self, BlockTarget};
+use crate::types::compression::{self, WireCompressor};
 use crate::types::gossip::GossipModuleType;
 use crate::types::metrics::KitsuneMetrics;
 use crate::wire::MetricExchangeMsg;
@@ -12,6 +14,8 @@ use futures::future::FutureExt;
 use futures::stream::StreamExt;
 use kitsune_p2p_proxy::tx2::*;
 use kitsune_p2p_transport_quic::tx2::*;
+use kitsune_p2p_transport_quic::tx2_fallback::*;
+use kitsune_p2p_transport_quic::tx2_tcp::*;
 use kitsune_p2p_types::agent_info::AgentInfoSigned;
 use kitsune_p2p_types::async_lazy::AsyncLazy;
 use kitsune_p2p_types::tx2::tx2_api::*;
@@ -62,7 +66,7 @@ ghost_actor::ghost_chan! {
         ) -> ();
 
         /// Incoming Gossip
-        fn incoming_gossip(space: KSpace, con: WireConHnd, remote_url: kitsune_p2p_types::tx2::tx2_utils::TxUrl, data: Payload, module_type: crate::types::gossip::GossipModuleType) -> ();
+        fn incoming_gossip(space: KSpace, con: WireConHnd, remote_url: kitsune_p2p_types::tx2::tx2_utils::TxUrl, data: Payload, wire_bytes: usize, module_type: crate::types::gossip::GossipModuleType) -> ();
 
         /// Incoming Metric Exchange
         fn incoming_metric_exchange(space: KSpace, msgs: VecMXM) -> ();
@@ -91,7 +95,10 @@ pub(crate) struct KitsuneP2pActor {
     >,
     config: Arc<KitsuneP2pConfig>,
     bandwidth_throttles: BandwidthThrottles,
+    compressor: WireCompressor,
     parallel_notify_permit: Arc<tokio::sync::Semaphore>,
+    /// Open connections, so they can be closed if their node is blocked.
+    cons: HashMap<TxUrl, WireConHnd>,
 }
 
 impl KitsuneP2pActor {
@@ -126,8 +133,19 @@ impl KitsuneP2pActor {
                 let mut conf = QuicConfig::default();
                 conf.tls = Some(tls_config.clone());
                 conf.tuning_params = Some(config.tuning_params.clone());
+                let f = if config.tuning_params.tx2_quic_tcp_fallback {
+                    tx2_quic_tcp_fallback_adapter(conf).await
+                } else {
+                    tx2_quic_adapter(conf).await
+                };
+                (f.map_err(KitsuneP2pError::other)?, bind_to)
+            }
+            KitsuneP2pTx2Backend::Tcp { bind_to } => {
+                let mut conf = TcpConfig::default();
+                conf.tls = Some(tls_config.clone());
+                conf.tuning_params = Some(config.tuning_params.clone());
                 (
-                    tx2_quic_adapter(conf)
+                    tx2_tcp_adapter(conf)
                         .await
                         .map_err(KitsuneP2pError::other)?,
                     bind_to,
@@ -219,17 +237,21 @@ impl KitsuneP2pActor {
         // capture endpoint handle
         let ep_hnd = ep.handle().clone();
 
+        let compressor = WireCompressor::new(&config.tuning_params);
+
         let i_s = internal_sender.clone();
         tokio::task::spawn({
             let evt_sender = evt_sender.clone();
             let host = host.clone();
             let tuning_params = config.tuning_params.clone();
+            let compressor = compressor.clone();
             async move {
                 ep.for_each_concurrent(tuning_params.concurrent_limit_per_thread, move |event| {
                     let evt_sender = evt_sender.clone();
                     let host = host.clone();
                     let tuning_params = tuning_params.clone();
                     let i_s = i_s.clone();
+                    let compressor = compressor.clone();
                     async move {
                         macro_rules! resp {
                             ($r:expr, $e:expr) => {
@@ -241,27 +263,69 @@ impl KitsuneP2pActor {
 
                         let evt_sender = &evt_sender;
                         use tx2_api::Tx2EpEvent::*;
+
+                        // refuse to communicate with blocked nodes,
+                        // whichever side opened the connection
+                        let con = match &event {
+                            OutgoingConnection(Tx2EpConnection { con, .. })
+                            | IncomingConnection(Tx2EpConnection { con, .. })
+                            | IncomingRequest(Tx2EpIncomingRequest { con, .. })
+                            | IncomingNotify(Tx2EpIncomingNotify { con, .. }) => Some(con),
+                            _ => None,
+                        };
+                        if let Some(con) = con {
+                            if block::is_blocked(&host, BlockTarget::Node(con.peer_cert())).await {
+                                con.close(403, "blocked").await;
+                                return;
+                            }
+                        }
+
                         #[allow(clippy::single_match)]
                         match event {
                             OutgoingConnection(Tx2EpConnection {
                                 con,
                                 url,
-                            }) => {
-                                let _ = i_s.new_con(url, con).await;
-                            }
-                            IncomingConnection(Tx2EpConnection {
+                            })
+                            | IncomingConnection(Tx2EpConnection {
                                 con,
                                 url,
                             }) => {
+                                // advertise our capabilities to the remote,
+                                // remotes which don't understand the message
+                                // will just ignore it
+                                if let Some(capabilities) = compressor.capabilities() {
+                                    if let Err(err) = con
+                                        .notify(&capabilities, tuning_params.implicit_timeout())
+                                        .await
+                                    {
+                                        tracing::debug!(?err, "failed to send capabilities");
+                                    }
+                                }
                                 let _ = i_s.new_con(url, con).await;
                             }
                             ConnectionClosed(Tx2EpConnectionClosed {
+                                con,
                                 url,
                                 ..
                             }) => {
+                                compressor.remove_remote(&con.peer_cert());
                                 let _ = i_s.del_con(url).await;
                             }
-                            IncomingRequest(Tx2EpIncomingRequest { data, respond, .. }) => {
+                            IncomingRequest(Tx2EpIncomingRequest {
+                                con,
+                                data,
+                                respond,
+                                ..
+                            }) => {
+                                let data = match compression::decompress(data) {
+                                    Ok(data) => data,
+                                    Err(err) => {
+                                        tracing::warn!(?err, "failed to decompress incoming request");
+                                        let fail = wire::Wire::failure(format!("{:?}", err));
+                                        resp!(respond, fail);
+                                        return;
+                                    }
+                                };
                                 match data {
                                     wire::Wire::Call(wire::Call {
                                         space,
@@ -282,6 +346,7 @@ impl KitsuneP2pActor {
                                             Ok(r) => r,
                                         };
                                         let resp = wire::Wire::call_resp(res.into());
+                                        let resp = compressor.compress(&con.peer_cert(), resp);
                                         resp!(respond, resp);
                                     }
                                     wire::Wire::PeerGet(wire::PeerGet { space, agent }) => {
@@ -324,6 +389,14 @@ impl KitsuneP2pActor {
                                 }
                             }
                             IncomingNotify(Tx2EpIncomingNotify { con, data, url, .. }) => {
+                                let compressed_len = compression::compressed_len(&data);
+                                let data = match compression::decompress(data) {
+                                    Ok(data) => data,
+                                    Err(err) => {
+                                        tracing::warn!(?err, "failed to decompress incoming notify");
+                                        return;
+                                    }
+                                };
                                 match data {
                                     wire::Wire::DelegateBroadcast(wire::DelegateBroadcast {
                                         space,
@@ -363,62 +436,72 @@ impl KitsuneP2pActor {
                                         data,
                                         destination,
                                         ..
-                                    }) => match destination {
-                                        BroadcastTo::Notify => {
-                                            // TODO: Should we check if the basis is
-                                            // held before calling notify?
-                                            if let Err(err) = evt_sender
-                                                .notify(
-                                                    space,
-                                                    to_agent,
-                                                    data.into(),
-                                                )
-                                                .await
-                                            {
-                                                tracing::warn!(
-                                                    ?err,
-                                                    "error processing incoming broadcast"
-                                                );
+                                    }) => {
+                                        match destination {
+                                            BroadcastTo::Notify => {
+                                                // TODO: Should we check if the basis is
+                                                // held before calling notify?
+                                                if let Err(err) = evt_sender
+                                                    .notify(
+                                                        space,
+                                                        to_agent,
+                                                        data.into(),
+                                                    )
+                                                    .await
+                                                {
+                                                    tracing::warn!(
+                                                        ?err,
+                                                        "error processing incoming broadcast"
+                                                    );
+                                                }
                                             }
-                                        }
-                                        BroadcastTo::PublishAgentInfo => {
-                                            // TODO: Should we check if the basis is
-                                            // held before calling put_agent_info_signed?
-                                            match AgentInfoSigned::decode(&data[..]) {
-                                                Ok(info) => {
-                                                    if let Err(err) = evt_sender
-                                                        .put_agent_info_signed(
-                                                            PutAgentInfoSignedEvt {
-                                                                space,
-                                                                peer_data: vec![info],
-                                                            },
-                                                        )
-                                                        .await
-                                                    {
+                                            BroadcastTo::PublishAgentInfo => {
+                                                // TODO: Should we check if the basis is
+                                                // held before calling put_agent_info_signed?
+                                                match AgentInfoSigned::decode(&data[..]) {
+                                                    Ok(info) => {
+                                                        // don't learn about blocked agents
+                                                        if block::is_agent_info_blocked(&host, &info)
+                                                            .await
+                                                        {
+                                                            return;
+                                                        }
+                                                        if let Err(err) = evt_sender
+                                                            .put_agent_info_signed(
+                                                                PutAgentInfoSignedEvt {
+                                                                    space,
+                                                                    peer_data: vec![info],
+                                                                },
+                                                            )
+                                                            .await
+                                                        {
+                                                            tracing::warn!(
+                                                                ?err,
+                                                                "error processing incoming agent info broadcast"
+                                                            );
+                                                        }
+                                                    }
+                                                    Err(err) => {
                                                         tracing::warn!(
                                                             ?err,
                                                             "error processing incoming agent info broadcast"
                                                         );
                                                     }
                                                 }
-                                                Err(err) => {
-                                                    tracing::warn!(
-                                                        ?err,
-                                                        "error processing incoming agent info broadcast"
-                                                    );
-                                                }
                                             }
                                         }
-                                    },
+                                    }
                                     wire::Wire::Gossip(wire::Gossip {
                                         space,
                                         data,
                                         module,
                                     }) => {
+                                        let wire_bytes = compressed_len.unwrap_or(data.len());
                                         let data: Vec<u8> = data.into();
                                         let data: Box<[u8]> = data.into_boxed_slice();
-                                        if let Err(e) =
-                                            i_s.incoming_gossip(space, con, url, data, module).await
+                                        if let Err(e) = i_s
+                                            .incoming_gossip(space, con, url, data, wire_bytes, module)
+                                            .await
                                         {
                                             tracing::warn!(
                                                 "failed to handle incoming gossip: {:?}",
@@ -432,6 +515,12 @@ impl KitsuneP2pActor {
                                     }) => {
                                         let _ = i_s.incoming_metric_exchange(space, msgs).await;
                                     }
+                                    wire::Wire::Capabilities(wire::Capabilities {
+                                        compression,
+                                    }) => {
+                                        compressor
+                                            .set_remote_capabilities(con.peer_cert(), &compression);
+                                    }
                                     data => unimplemented!("{:?}", data),
                                 }
                             }
@@ -458,7 +547,9 @@ impl KitsuneP2pActor {
             spaces: HashMap::new(),
             config: Arc::new(config),
             bandwidth_throttles,
+            compressor,
             parallel_notify_permit,
+            cons: HashMap::new(),
         })
     }
 }
@@ -545,6 +636,7 @@ impl InternalHandler for KitsuneP2pActor {
         con: Tx2ConHnd<wire::Wire>,
         remote_url: TxUrl,
         data: Box<[u8]>,
+        wire_bytes: usize,
         module_type: GossipModuleType,
     ) -> InternalHandlerResult<()> {
         let space_sender = match self.spaces.get_mut(&space) {
@@ -557,7 +649,7 @@ impl InternalHandler for KitsuneP2pActor {
         Ok(async move {
             let (_, space_inner) = space_sender.await;
             space_inner
-                .incoming_gossip(space, con, remote_url, data, module_type)
+                .incoming_gossip(space, con, remote_url, data, wire_bytes, module_type)
                 .await
         }
         .boxed()
@@ -588,6 +680,7 @@ impl InternalHandler for KitsuneP2pActor {
         url: TxUrl,
         con: Tx2ConHnd<wire::Wire>,
     ) -> InternalHandlerResult<()> {
+        self.cons.insert(url.clone(), con.clone());
         let spaces = self.spaces.iter().map(|(_, s)| s.get()).collect::<Vec<_>>();
         Ok(async move {
             let mut all = Vec::new();
@@ -602,6 +695,7 @@ impl InternalHandler for KitsuneP2pActor {
     }
 
     fn handle_del_con(&mut self, url: TxUrl) -> InternalHandlerResult<()> {
+        self.cons.remove(&url);
         let spaces = self.spaces.iter().map(|(_, s)| s.get()).collect::<Vec<_>>();
         Ok(async move {
             let mut all = Vec::new();
@@ -709,6 +803,7 @@ impl KitsuneP2pHandler for KitsuneP2pActor {
         let host = self.host.clone();
         let config = Arc::clone(&self.config);
         let bandwidth_throttles = self.bandwidth_throttles.clone();
+        let compressor = self.compressor.clone();
         let parallel_notify_permit = self.parallel_notify_permit.clone();
         let space_sender = match self.spaces.entry(space.clone()) {
             Entry::Occupied(entry) => entry.into_mut(),
@@ -719,6 +814,7 @@ impl KitsuneP2pHandler for KitsuneP2pActor {
                     host,
                     config,
                     bandwidth_throttles,
+                    compressor,
                     parallel_notify_permit,
                 )
                 .await
@@ -901,6 +997,28 @@ impl KitsuneP2pHandler for KitsuneP2pActor {
         .boxed()
         .into())
     }
+
+    fn handle_block(&mut self, target: BlockTarget) -> KitsuneP2pHandlerResult<()> {
+        let cons = match &target {
+            BlockTarget::Node(cert) => self
+                .cons
+                .values()
+                .filter(|con| con.peer_cert() == *cert)
+                .cloned()
+                .collect::<Vec<_>>(),
+            // The agent's node may be running other agents which aren't
+            // blocked, so its connections are left open.
+            BlockTarget::Agent(_) => Vec::new(),
+        };
+        Ok(async move {
+            for con in cons {
+                con.close(403, "blocked").await;
+            }
+            Ok(())
+        }
+        .boxed()
+        .into())
+    }
 }
 
 #[cfg(any(test, feature = "test_utils"))]
@@ -64,7 +64,7 @@ ghost_actor::ghost_chan! {
         ) -> ();
 
         /// Incoming Gossip
-        fn incoming_gossip(space: KSpace, con: WireConHnd, remote_url: TxUrl, data: Payload, module_type: crate::types::gossip::GossipModuleType) -> ();
+        fn incoming_gossip(space: KSpace, con: WireConHnd, remote_url: TxUrl, data: Payload, wire_bytes: usize, module_type: crate::types::gossip::GossipModuleType) -> ();
 
         /// Incoming Metric Exchange
         fn incoming_metric_exchange(space: KSpace, msgs: VecMXM) -> ();
@@ -83,6 +83,7 @@ pub(crate) async fn spawn_space(
     host: HostApi,
     config: Arc<KitsuneP2pConfig>,
     bandwidth_throttles: BandwidthThrottles,
+    compressor: WireCompressor,
     parallel_notify_permit: Arc<tokio::sync::Semaphore>,
 ) -> KitsuneP2pResult<(
     ghost_actor::GhostSender<KitsuneP2p>,
@@ -111,6 +112,7 @@ pub(crate) async fn spawn_space(
         ep_hnd,
         config,
         bandwidth_throttles,
+        compressor,
         parallel_notify_permit,
     )));
 
@@ -366,6 +368,7 @@ impl SpaceInternalHandler for Space {
                 .filter(|info| info.agent.get_loc().as_u32() % mod_cnt == mod_idx)
             {
                 let ro_inner = ro_inner.clone();
+                let compressor = ro_inner.compressor.clone();
                 let space = space.clone();
                 let data = data.clone();
                 all.push(async move {
@@ -384,6 +387,7 @@ impl SpaceInternalHandler for Space {
                     // generate our broadcast payload
                     let payload =
                         wire::Wire::broadcast(space, info.agent.clone(), destination, data);
+                    let payload = compressor.compress(&con_hnd.peer_cert(), payload);
 
                     // forward the data
                     if let Err(err) = con_hnd.notify(&payload, timeout).await {
@@ -406,10 +410,11 @@ impl SpaceInternalHandler for Space {
         con: Tx2ConHnd<wire::Wire>,
         remote_url: TxUrl,
         data: Box<[u8]>,
+        wire_bytes: usize,
         module_type: GossipModuleType,
     ) -> InternalHandlerResult<()> {
         match self.gossip_mod.get(&module_type) {
-            Some(module) => module.incoming_gossip(con, remote_url, data)?,
+            Some(module) => module.incoming_gossip(con, remote_url, data, wire_bytes)?,
             None => tracing::warn!(
                 "Received gossip for {:?} but this gossip module isn't running",
                 module_type
@@ -703,17 +708,26 @@ impl KitsuneP2pHandler for Space {
         );
 
         let metrics = self.ro_inner.metrics.clone();
+        let compressor = self.ro_inner.compressor.clone();
+        let host_api = self.ro_inner.host_api.clone();
 
         Ok(async move {
+            if block::is_blocked(&host_api, BlockTarget::Agent(to_agent.clone())).await {
+                return Err(format!("agent is blocked: {:?}", to_agent).into());
+            }
             match discover_fut.await {
                 discover::PeerDiscoverResult::OkShortcut => {
                     // reflect this request locally
                     evt_sender.call(space, to_agent, payload).await
                 }
                 discover::PeerDiscoverResult::OkRemote { con_hnd, .. } => {
+                    if block::is_blocked(&host_api, BlockTarget::Node(con_hnd.peer_cert())).await {
+                        return Err(format!("node is blocked: {:?}", con_hnd.peer_cert()).into());
+                    }
                     let payload = wire::Wire::call(space.clone(), to_agent.clone(), payload.into());
+                    let payload = compressor.compress(&con_hnd.peer_cert(), payload);
                     let res = con_hnd.request(&payload, timeout).await?;
-                    match res {
+                    match compression::decompress(res)? {
                         wire::Wire::Failure(wire::Failure { reason }) => {
                             metrics
                                 .write()
@@ -908,6 +922,7 @@ impl KitsuneP2pHandler for Space {
                         destination,
                         payload.clone().into(),
                     );
+                    let payload = ro_inner.compressor.compress(&con_hnd.peer_cert(), payload);
 
                     // notify the remote node
                     all.push(async move {
@@ -994,6 +1009,8 @@ impl KitsuneP2pHandler for Space {
                                 BroadcastTo::Notify,
                                 payload.into(),
                             );
+                            let payload =
+                                ro_inner.compressor.compress(&con_hnd.peer_cert(), payload);
                             con_hnd
                                 .notify(&payload, timeout)
                                 .map(|r| {
@@ -1062,6 +1079,12 @@ impl KitsuneP2pHandler for Space {
         .boxed()
         .into())
     }
+
+    fn handle_block(&mut self, _target: BlockTarget) -> KitsuneP2pHandlerResult<()> {
+        unreachable!(
+            "These requests are handled at the to actor level and are never propagated down to the space."
+        )
+    }
 }
 
 pub(crate) struct SpaceReadOnlyInner {
@@ -1076,6 +1099,7 @@ pub(crate) struct SpaceReadOnlyInner {
     pub(crate) parallel_notify_permit: Arc<tokio::sync::Semaphore>,
     pub(crate) metrics: MetricsSync,
     pub(crate) metric_exchange: MetricExchangeSync,
+    pub(crate) compressor: WireCompressor,
 }
 
 /// A Kitsune P2p Node can track multiple "spaces" -- Non-interacting namespaced
@@ -1105,6 +1129,7 @@ impl Space {
         ep_hnd: Tx2EpHnd<wire::Wire>,
         config: Arc<KitsuneP2pConfig>,
         bandwidth_throttles: BandwidthThrottles,
+        compressor: WireCompressor,
         parallel_notify_permit: Arc<tokio::sync::Semaphore>,
     ) -> Self {
         let metrics = MetricsSync::default();
@@ -1146,6 +1171,7 @@ impl Space {
                             GossipModuleType::ShardedRecent,
                             crate::gossip::sharded_gossip::recent_factory(
                                 bandwidth_throttles.recent(),
+                                compressor.clone(),
                             ),
                         ));
                     }
@@ -1153,6 +1179,7 @@ impl Space {
                         GossipModuleType::ShardedHistorical,
                         crate::gossip::sharded_gossip::historical_factory(
                             bandwidth_throttles.historical(),
+                            compressor.clone(),
                         ),
                     ));
                     gossips
@@ -1270,6 +1297,7 @@ impl Space {
             parallel_notify_permit,
             metrics,
             metric_exchange,
+            compressor,
         });
 
         Self {
@@ -359,10 +359,14 @@ impl Outer {
                         };
 
                     let msg = wire::Wire::call(space, info.agent.clone(), payload.into());
+                    let msg = ro_inner.compressor.compress(&con_hnd.peer_cert(), msg);
 
                     let start = tokio::time::Instant::now();
 
-                    let res = con_hnd.request(&msg, max_timeout).await;
+                    let res = con_hnd
+                        .request(&msg, max_timeout)
+                        .await
+                        .and_then(compression::decompress);
 
                     match res {
                         Ok(wire::Wire::CallResp(c)) => {
@@ -463,6 +467,9 @@ impl Outer {
                     );
 
                     for info in infos {
+                        if block::is_agent_info_blocked(&ro_inner.host_api, &info).await {
+                            continue;
+                        }
                         if check_remote_agent(&inner, &info.agent) {
                             continue;
                         }
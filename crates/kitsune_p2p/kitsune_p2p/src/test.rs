@@ -320,6 +320,50 @@ mod tests {
         Ok(())
     }
 
+    /// Test that a node stops talking to a node it has blocked,
+    /// including over a connection which was already open.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_blocked_node_is_refused() -> Result<(), KitsuneP2pError> {
+        observability::test_run().ok();
+        let (harness, _evt) = spawn_test_harness_mem().await?;
+
+        let space = harness.add_space().await?;
+        let (a1, p2p1) = harness.add_direct_agent("one".into()).await?;
+        let (a2, p2p2) = harness.add_direct_agent("two".into()).await?;
+        harness.magic_peer_info_exchange().await?;
+
+        // The nodes can talk before the block.
+        let res = p2p1
+            .rpc_single(space.clone(), a2.clone(), b"hello".to_vec(), None)
+            .await?;
+        assert_eq!(b"echo: hello".to_vec(), res);
+
+        // Node two blocks node one.
+        let peer_data = harness.dump_local_peer_data(a1.clone()).await?;
+        let url = peer_data[&a1].url_list[0].clone();
+        let cert = kitsune_p2p_types::Tx2Cert::from(
+            kitsune_p2p_proxy::ProxyUrl::from_full(url.as_str())
+                .unwrap()
+                .digest(),
+        );
+        harness
+            .block(a2.clone(), block::BlockTarget::Node(cert))
+            .await?;
+
+        // Neither side gets through anymore.
+        assert!(p2p1
+            .rpc_single(space.clone(), a2.clone(), b"hello".to_vec(), Some(2000))
+            .await
+            .is_err());
+        assert!(p2p2
+            .rpc_single(space.clone(), a1.clone(), b"hello".to_vec(), Some(2000))
+            .await
+            .is_err());
+
+        harness.ghost_actor_shutdown().await?;
+        Ok(())
+    }
+
     /// Test that we can publish agent info.
     #[tokio::test(flavor = "multi_thread")]
     // @freesig Can anyone think of a better way to do this?
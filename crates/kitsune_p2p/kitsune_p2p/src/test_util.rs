@@ -4,6 +4,7 @@
 
 use crate::types::actor::*;
 use crate::types::agent_store::*;
+use crate::types::block::BlockTarget;
 use crate::types::event::*;
 use crate::*;
 use futures::future::FutureExt;
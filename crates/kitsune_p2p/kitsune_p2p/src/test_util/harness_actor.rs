@@ -51,6 +51,9 @@ ghost_actor::ghost_chan! {
 
         /// Dump all local peer data from a specific agent
         fn dump_local_peer_data(agent: KAgent) -> HashMap<Arc<KitsuneAgent>, Arc<AgentInfoSigned>>;
+
+        /// Have the node of a specific agent block a target
+        fn block(agent: KAgent, target: BlockTarget) -> ();
     }
 }
 
@@ -415,4 +418,22 @@ impl HarnessControlApiHandler for HarnessActor {
         let fut = ctrl.dump_local_peer_data();
         Ok(async move { fut.await }.boxed().into())
     }
+
+    fn handle_block(
+        &mut self,
+        agent: Arc<KitsuneAgent>,
+        target: BlockTarget,
+    ) -> HarnessControlApiHandlerResult<()> {
+        let (p2p, ctrl) = self
+            .agents
+            .get(&agent)
+            .ok_or_else(|| KitsuneP2pError::from("invalid agent"))?;
+        let (p2p, ctrl) = (p2p.clone(), ctrl.clone());
+        Ok(async move {
+            ctrl.block(target.clone()).await?;
+            p2p.block(target).await
+        }
+        .boxed()
+        .into())
+    }
 }
@@ -2,6 +2,7 @@ use super::*;
 
 type KAgent = Arc<KitsuneAgent>;
 type KAgentMap = HashMap<KAgent, Arc<AgentInfoSigned>>;
+type Blocked = Arc<parking_lot::RwLock<std::collections::HashSet<BlockTarget>>>;
 
 ghost_actor::ghost_chan! {
     /// controller for test harness agent actor
@@ -20,14 +21,23 @@ ghost_actor::ghost_chan! {
 
         /// dump all local peer data from this agent
         fn dump_local_peer_data() -> HashMap<Arc<KitsuneAgent>, Arc<AgentInfoSigned>>;
+
+        /// have this agent's host block a target
+        fn block(target: BlockTarget) -> ();
     }
 }
 
-pub struct HarnessHost;
+pub struct HarnessHost {
+    blocked: Blocked,
+}
 
 impl HarnessHost {
     pub fn new() -> Arc<Self> {
-        Arc::new(Self)
+        Self::with_blocked(Default::default())
+    }
+
+    fn with_blocked(blocked: Blocked) -> Arc<Self> {
+        Arc::new(Self { blocked })
     }
 }
 
@@ -49,6 +59,10 @@ impl KitsuneHostDefaultError for HarnessHost {
     ) -> KitsuneHostResult<RegionSetLtcs> {
         box_fut(Ok(RegionSetLtcs::empty()))
     }
+
+    fn is_blocked(&self, target: BlockTarget) -> KitsuneHostResult<bool> {
+        box_fut(Ok(self.blocked.read().contains(&target)))
+    }
 }
 
 pub(crate) async fn spawn_test_agent(
@@ -63,7 +77,8 @@ pub(crate) async fn spawn_test_agent(
     KitsuneP2pError,
 > {
     let topology = Topology::standard_epoch_full();
-    let host = HarnessHost::new();
+    let blocked = Blocked::default();
+    let host = HarnessHost::with_blocked(blocked.clone());
     let (p2p, evt) = spawn_kitsune_p2p(
         config,
         kitsune_p2p_types::tls::TlsConfig::new_ephemeral()
@@ -83,7 +98,7 @@ pub(crate) async fn spawn_test_agent(
         .create_channel::<HarnessAgentControl>()
         .await?;
 
-    let harness = AgentHarness::new(harness_chan, topology).await?;
+    let harness = AgentHarness::new(harness_chan, topology, blocked).await?;
     let agent = harness.agent.clone();
     tokio::task::spawn(builder.spawn(harness));
 
@@ -107,12 +122,14 @@ struct AgentHarness {
     agent_store: HashMap<Arc<KitsuneAgent>, Arc<AgentInfoSigned>>,
     gossip_store: HashMap<Arc<KitsuneOpHash>, String>,
     topology: Topology,
+    blocked: Blocked,
 }
 
 impl AgentHarness {
     pub async fn new(
         harness_chan: HarnessEventChannel,
         topology: Topology,
+        blocked: Blocked,
     ) -> Result<Self, KitsuneP2pError> {
         let EntrySignEd25519 { priv_key, pub_key } = sign_ed25519_keypair_new_from_entropy()
             .await
@@ -126,6 +143,7 @@ impl AgentHarness {
             agent_store: HashMap::new(),
             gossip_store: HashMap::new(),
             topology,
+            blocked,
         })
     }
 }
@@ -172,6 +190,11 @@ impl HarnessAgentControlHandler for AgentHarness {
         let out = self.agent_store.clone();
         Ok(async move { Ok(out) }.boxed().into())
     }
+
+    fn handle_block(&mut self, target: BlockTarget) -> HarnessAgentControlHandlerResult<()> {
+        self.blocked.write().insert(target);
+        Ok(async move { Ok(()) }.boxed().into())
+    }
 }
 
 impl ghost_actor::GhostHandler<KitsuneP2pEvent> for AgentHarness {}
@@ -165,6 +165,10 @@ impl KitsuneHost for SwitchboardEventHandler {
     ) -> crate::KitsuneHostResult<dht::spacetime::Topology> {
         box_fut(Ok(self.sb.topology.clone()))
     }
+
+    fn is_blocked(&self, _target: crate::block::BlockTarget) -> crate::KitsuneHostResult<bool> {
+        box_fut(Ok(false))
+    }
 }
 
 #[allow(warnings)]
@@ -2,6 +2,7 @@
 
 use crate::gossip::sharded_gossip::{BandwidthThrottle, GossipType, ShardedGossip};
 use crate::test_util::spawn_handler;
+use crate::types::compression::WireCompressor;
 use crate::types::gossip::*;
 use crate::types::wire;
 use futures::stream::StreamExt;
@@ -131,6 +132,7 @@ impl Switchboard {
         let (evt_sender, handler_task) = spawn_handler(evt_handler.clone()).await;
 
         let bandwidth = Arc::new(BandwidthThrottle::new(1000.0, 1000.0));
+        let compressor = WireCompressor::new(&tuning_params);
 
         let gossip = ShardedGossip::new(
             tuning_params,
@@ -140,6 +142,7 @@ impl Switchboard {
             host_api,
             self.gossip_type,
             bandwidth,
+            compressor,
             Default::default(),
         );
         let gossip_module = GossipModule(gossip.clone());
@@ -155,10 +158,11 @@ impl Switchboard {
                                 data,
                                 module: _,
                             }) => {
+                                let wire_bytes = data.len();
                                 let data: Vec<u8> = data.into();
                                 let data: Box<[u8]> = data.into_boxed_slice();
 
-                                gossip_module.incoming_gossip(con, url, data)?
+                                gossip_module.incoming_gossip(con, url, data, wire_bytes)?
                             }
                             _ => unimplemented!(),
                         }
@@ -91,6 +91,8 @@ pub mod agent_store {
 }
 
 pub mod actor;
+pub mod block;
+pub mod compression;
 pub mod event;
 pub(crate) mod gossip;
 #[allow(missing_docs)]
@@ -74,6 +74,7 @@ type KSpaceOpt = Option<Arc<super::KitsuneSpace>>;
 type KAgent = Arc<super::KitsuneAgent>;
 type KAgents = Vec<Arc<super::KitsuneAgent>>;
 type KBasis = Arc<super::KitsuneBasis>;
+type KBlockTarget = super::block::BlockTarget;
 type Payload = Vec<u8>;
 type OptU64 = Option<u64>;
 type OptArc = Option<crate::dht_arc::DhtArc>;
@@ -141,5 +142,10 @@ ghost_actor::ghost_chan! {
         fn dump_network_metrics(
             space: KSpaceOpt,
         ) -> serde_json::Value;
+
+        /// The host has just blocked this target. Any open connections
+        /// to a blocked node are closed, rather than waiting for the
+        /// node to send something.
+        fn block(target: KBlockTarget) -> ();
     }
 }
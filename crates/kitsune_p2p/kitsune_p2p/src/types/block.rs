@@ -0,0 +1,167 @@
+//! Blocking of misbehaving remote nodes and agents.
+//!
+//! The host decides what is blocked, and kitsune asks it through
+//! [`KitsuneHost::is_blocked`](crate::KitsuneHost::is_blocked) before
+//! accepting connections, choosing rpc and gossip targets and handling
+//! incoming broadcasts.
+
+use crate::types::agent_store::AgentInfoSigned;
+use crate::types::KitsuneAgent;
+use crate::HostApi;
+use ghost_actor::dependencies::tracing;
+use kitsune_p2p_types::*;
+use std::sync::Arc;
+
+/// Something the host can refuse to communicate with.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum BlockTarget {
+    /// An agent, in every space it has joined.
+    Agent(Arc<KitsuneAgent>),
+    /// A node, identified by the certificate digest of its tls connections.
+    Node(Tx2Cert),
+}
+
+/// Ask the host if a target is blocked.
+/// If the host can't tell us, the target is treated as not blocked,
+/// so a failing host doesn't cut us off from the network.
+pub(crate) async fn is_blocked(host: &HostApi, target: BlockTarget) -> bool {
+    match host.is_blocked(target.clone()).await {
+        Ok(blocked) => {
+            if blocked {
+                tracing::debug!(?target, "refusing to communicate with blocked target");
+            }
+            blocked
+        }
+        Err(err) => {
+            tracing::warn!(?err, ?target, "failed to check if target is blocked");
+            false
+        }
+    }
+}
+
+/// Is the agent this info belongs to blocked,
+/// or any of the nodes the agent can be reached at?
+///
+/// Nodes can only be resolved from `kitsune-proxy://` urls, which contain
+/// the digest of the node's certificate. Direct `kitsune-quic://` and
+/// `kitsune-tcp://` urls only contain an address, so agents reachable at them
+/// aren't blocked by a node block here. A connection to a blocked node is
+/// still closed as soon as it is made, since the certificate is known then.
+pub(crate) async fn is_agent_info_blocked(host: &HostApi, info: &AgentInfoSigned) -> bool {
+    if is_blocked(host, BlockTarget::Agent(info.agent.clone())).await {
+        return true;
+    }
+    for url in info.url_list.iter() {
+        if let Ok(purl) = kitsune_p2p_proxy::ProxyUrl::from_full(url.as_str()) {
+            let cert = Tx2Cert::from(purl.digest());
+            if is_blocked(host, BlockTarget::Node(cert)).await {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::KitsuneSpace;
+    use crate::{KitsuneHostDefaultError, KitsuneHostResult};
+
+    struct BlockingHost(Vec<BlockTarget>);
+
+    impl KitsuneHostDefaultError for BlockingHost {
+        const NAME: &'static str = "BlockingHost";
+
+        fn is_blocked(&self, target: BlockTarget) -> KitsuneHostResult<bool> {
+            box_fut(Ok(self.0.contains(&target)))
+        }
+    }
+
+    struct FailingHost;
+
+    impl KitsuneHostDefaultError for FailingHost {
+        const NAME: &'static str = "FailingHost";
+
+        fn is_blocked(&self, _target: BlockTarget) -> KitsuneHostResult<bool> {
+            box_fut(Err("database is gone".into()))
+        }
+    }
+
+    const URL: &str = "kitsune-proxy://VYgwCrh2ZCKL1lpnMM1VVUee7ks-9BkmW47C_ys4nqgmqcw/kitsune-quic/h/localhost/p/5000/-";
+
+    async fn agent_info(agent: u8) -> AgentInfoSigned {
+        AgentInfoSigned::sign(
+            Arc::new(KitsuneSpace(vec![0x01; 36])),
+            Arc::new(KitsuneAgent(vec![agent; 36])),
+            42,
+            vec![url2::url2!("{}", URL).into()],
+            42,
+            69,
+            |_| async move { Ok(Arc::new(vec![0x03; 64].into())) },
+        )
+        .await
+        .unwrap()
+    }
+
+    fn node_cert() -> Tx2Cert {
+        Tx2Cert::from(
+            kitsune_p2p_proxy::ProxyUrl::from_full(URL)
+                .unwrap()
+                .digest(),
+        )
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn agent_info_blocked_by_agent_or_node() {
+        let info = agent_info(0x02).await;
+
+        let host: HostApi = Arc::new(BlockingHost(vec![]));
+        assert!(!is_agent_info_blocked(&host, &info).await);
+
+        let host: HostApi = Arc::new(BlockingHost(vec![BlockTarget::Agent(info.agent.clone())]));
+        assert!(is_agent_info_blocked(&host, &info).await);
+        assert!(!is_agent_info_blocked(&host, &agent_info(0x04).await).await);
+
+        // Every agent reachable at a blocked node is blocked.
+        let host: HostApi = Arc::new(BlockingHost(vec![BlockTarget::Node(node_cert())]));
+        assert!(is_agent_info_blocked(&host, &info).await);
+        assert!(is_agent_info_blocked(&host, &agent_info(0x04).await).await);
+    }
+
+    /// Direct urls don't say which node they belong to, so node blocks
+    /// can't be applied to agent infos with only direct urls.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn node_blocks_not_resolved_from_direct_urls() {
+        for url in [
+            "kitsune-quic://localhost:5000",
+            "kitsune-tcp://localhost:5000",
+        ] {
+            let info = AgentInfoSigned::sign(
+                Arc::new(KitsuneSpace(vec![0x01; 36])),
+                Arc::new(KitsuneAgent(vec![0x02; 36])),
+                42,
+                vec![url2::url2!("{}", url).into()],
+                42,
+                69,
+                |_| async move { Ok(Arc::new(vec![0x03; 64].into())) },
+            )
+            .await
+            .unwrap();
+
+            let host: HostApi = Arc::new(BlockingHost(vec![BlockTarget::Node(node_cert())]));
+            assert!(!is_agent_info_blocked(&host, &info).await);
+
+            let host: HostApi =
+                Arc::new(BlockingHost(vec![BlockTarget::Agent(info.agent.clone())]));
+            assert!(is_agent_info_blocked(&host, &info).await);
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn failing_host_blocks_nothing() {
+        let host: HostApi = Arc::new(FailingHost);
+        assert!(!is_blocked(&host, BlockTarget::Node(node_cert())).await);
+        assert!(!is_agent_info_blocked(&host, &agent_info(0x02).await).await);
+    }
+}
@@ -0,0 +1,246 @@
+//! Negotiated compression of wire messages.
+//!
+//! When a connection is established each side advertises the compression
+//! algorithms it can decompress in a [`Wire::Capabilities`] message.
+//! Gossip, broadcast and call messages sent to a remote which advertised
+//! zstd are then wrapped in a [`Wire::Compressed`] message, if that makes
+//! them smaller. Remotes which don't know about compression can't decode
+//! the capabilities message, so they ignore it and are never sent
+//! compressed messages.
+
+use crate::types::metrics::KitsuneMetrics;
+use crate::wire::{self, Wire, WireCompression};
+use ghost_actor::dependencies::tracing;
+use kitsune_p2p_types::codec::Codec;
+use kitsune_p2p_types::config::KitsuneP2pTuningParams;
+use kitsune_p2p_types::*;
+use std::collections::HashMap;
+use std::io::Read;
+use std::sync::Arc;
+
+/// The zstd compression level. Low levels are fast,
+/// and still remove most of the redundancy in op data.
+const ZSTD_LEVEL: i32 = 3;
+
+/// Refuse to decompress messages which would be larger than this,
+/// so a small message can't be used to exhaust our memory.
+const MAX_DECOMPRESSED_BYTES: u64 = 64_000_000;
+
+/// Tracks which remotes accept compressed messages,
+/// and compresses the messages we send them.
+#[derive(Clone)]
+pub struct WireCompressor(Arc<WireCompressorInner>);
+
+struct WireCompressorInner {
+    enabled: bool,
+    min_bytes: usize,
+    remotes: parking_lot::RwLock<HashMap<Tx2Cert, WireCompression>>,
+}
+
+impl WireCompressor {
+    /// Construct a new compressor from the tuning params.
+    pub fn new(tuning_params: &KitsuneP2pTuningParams) -> Self {
+        Self(Arc::new(WireCompressorInner {
+            enabled: tuning_params.wire_compression,
+            min_bytes: tuning_params.wire_compression_min_bytes as usize,
+            remotes: parking_lot::RwLock::new(HashMap::new()),
+        }))
+    }
+
+    /// The capabilities message to send to a newly connected remote,
+    /// if compression is enabled.
+    pub fn capabilities(&self) -> Option<Wire> {
+        if self.0.enabled {
+            Some(Wire::capabilities(vec![WireCompression::Zstd]))
+        } else {
+            None
+        }
+    }
+
+    /// Record the compression algorithms a remote advertised.
+    pub fn set_remote_capabilities(&self, remote: Tx2Cert, compression: &[WireCompression]) {
+        if self.0.enabled && compression.contains(&WireCompression::Zstd) {
+            self.0.remotes.write().insert(remote, WireCompression::Zstd);
+        } else {
+            self.0.remotes.write().remove(&remote);
+        }
+    }
+
+    /// Forget a remote's capabilities once its connection has closed.
+    pub fn remove_remote(&self, remote: &Tx2Cert) {
+        self.0.remotes.write().remove(remote);
+    }
+
+    /// Compress a message to be sent to a remote, if the remote accepts
+    /// compressed messages and compressing makes the message smaller.
+    /// Otherwise the message is returned unchanged.
+    pub fn compress(&self, remote: &Tx2Cert, msg: Wire) -> Wire {
+        let compression = match self.0.remotes.read().get(remote) {
+            Some(compression) => *compression,
+            None => return msg,
+        };
+        if !matches!(
+            msg,
+            Wire::Call(_)
+                | Wire::CallResp(_)
+                | Wire::DelegateBroadcast(_)
+                | Wire::Broadcast(_)
+                | Wire::Gossip(_)
+        ) {
+            return msg;
+        }
+
+        let encoded = match msg.encode_vec() {
+            Ok(encoded) => encoded,
+            Err(_) => return msg,
+        };
+        if encoded.len() < self.0.min_bytes {
+            return msg;
+        }
+        let compressed = match zstd::bulk::compress(&encoded, ZSTD_LEVEL) {
+            Ok(compressed) => compressed,
+            Err(err) => {
+                tracing::warn!(?err, "failed to compress wire message");
+                return msg;
+            }
+        };
+        if compressed.len() >= encoded.len() {
+            return msg;
+        }
+
+        KitsuneMetrics::count(KitsuneMetrics::CompressionIn, encoded.len());
+        KitsuneMetrics::count(KitsuneMetrics::CompressionOut, compressed.len());
+        tracing::trace!(
+            variant = msg.variant_type(),
+            bytes = encoded.len(),
+            compressed_bytes = compressed.len(),
+            "compressed wire message",
+        );
+
+        Wire::compressed(compression, compressed.into())
+    }
+}
+
+/// The number of bytes a message's payload took up on the wire,
+/// which is less than its decoded size if it was compressed.
+pub fn compressed_len(msg: &Wire) -> Option<usize> {
+    match msg {
+        Wire::Compressed(wire::Compressed { data, .. }) => Some(data.len()),
+        _ => None,
+    }
+}
+
+/// Unwrap a compressed message. Any other message is returned unchanged.
+pub fn decompress(msg: Wire) -> KitsuneResult<Wire> {
+    let data = match msg {
+        Wire::Compressed(wire::Compressed {
+            compression: WireCompression::Zstd,
+            data,
+        }) => data,
+        Wire::Compressed(wire::Compressed { compression, .. }) => {
+            return Err(format!("unsupported wire compression: {:?}", compression).into());
+        }
+        msg => return Ok(msg),
+    };
+
+    let mut decompressed = Vec::new();
+    zstd::stream::read::Decoder::new(&data[..])
+        .map_err(KitsuneError::other)?
+        .take(MAX_DECOMPRESSED_BYTES + 1)
+        .read_to_end(&mut decompressed)
+        .map_err(KitsuneError::other)?;
+    if decompressed.len() as u64 > MAX_DECOMPRESSED_BYTES {
+        return Err("decompressed wire message is too large".into());
+    }
+
+    match Wire::decode_ref(&decompressed).map_err(KitsuneError::other)? {
+        (_, Wire::Compressed(_)) => Err("nested compressed wire message".into()),
+        (_, msg) => Ok(msg),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actor::BroadcastTo;
+    use crate::types::{KitsuneAgent, KitsuneSpace};
+    use kitsune_p2p_types::config::tuning_params_struct;
+
+    fn cert(n: u8) -> Tx2Cert {
+        Tx2Cert::from(vec![n; 32])
+    }
+
+    fn broadcast(data: Vec<u8>) -> Wire {
+        Wire::broadcast(
+            Arc::new(KitsuneSpace(vec![0; 36])),
+            Arc::new(KitsuneAgent(vec![1; 36])),
+            BroadcastTo::Notify,
+            data.into(),
+        )
+    }
+
+    #[test]
+    fn compresses_only_for_capable_remotes() {
+        let compressor = WireCompressor::new(&KitsuneP2pTuningParams::default());
+        let msg = broadcast(b"text heavy op data ".repeat(1000));
+
+        // We haven't heard from this remote yet.
+        assert_eq!(compressor.compress(&cert(1), msg.clone()), msg);
+
+        compressor.set_remote_capabilities(cert(1), &[WireCompression::Unknown]);
+        assert_eq!(compressor.compress(&cert(1), msg.clone()), msg);
+
+        compressor
+            .set_remote_capabilities(cert(1), &[WireCompression::Unknown, WireCompression::Zstd]);
+        let compressed = compressor.compress(&cert(1), msg.clone());
+        assert!(compressed_len(&compressed).unwrap() < msg.encode_vec().unwrap().len());
+        assert_eq!(decompress(compressed).unwrap(), msg);
+
+        // Small messages aren't worth compressing.
+        let small = broadcast(b"small".to_vec());
+        assert_eq!(compressor.compress(&cert(1), small.clone()), small);
+
+        // Nor are messages other than gossip, broadcasts and calls.
+        let failure = Wire::failure("text heavy failure ".repeat(1000));
+        assert_eq!(compressor.compress(&cert(1), failure.clone()), failure);
+
+        compressor.remove_remote(&cert(1));
+        assert_eq!(compressor.compress(&cert(1), msg.clone()), msg);
+    }
+
+    #[test]
+    fn disabled_compressor_does_not_advertise_or_compress() {
+        let tuning_params = tuning_params_struct::KitsuneP2pTuningParams {
+            wire_compression: false,
+            ..Default::default()
+        };
+        let compressor = WireCompressor::new(&Arc::new(tuning_params));
+        assert!(compressor.capabilities().is_none());
+
+        let msg = broadcast(b"text heavy op data ".repeat(1000));
+        compressor.set_remote_capabilities(cert(1), &[WireCompression::Zstd]);
+        assert_eq!(compressor.compress(&cert(1), msg.clone()), msg);
+    }
+
+    #[test]
+    fn capabilities_round_trip() {
+        let compressor = WireCompressor::new(&KitsuneP2pTuningParams::default());
+        let msg = compressor.capabilities().unwrap();
+        let (_, decoded) = Wire::decode_ref(&msg.encode_vec().unwrap()).unwrap();
+        assert_eq!(decoded, Wire::capabilities(vec![WireCompression::Zstd]));
+    }
+
+    #[test]
+    fn rejects_bad_compressed_messages() {
+        assert!(decompress(Wire::compressed(
+            WireCompression::Unknown,
+            vec![0; 16].into()
+        ))
+        .is_err());
+        assert!(decompress(Wire::compressed(WireCompression::Zstd, vec![0; 16].into())).is_err());
+
+        let nested = Wire::compressed(WireCompression::Zstd, vec![0; 16].into());
+        let data = zstd::bulk::compress(&nested.encode_vec().unwrap(), ZSTD_LEVEL).unwrap();
+        assert!(decompress(Wire::compressed(WireCompression::Zstd, data.into())).is_err());
+    }
+}
@@ -21,11 +21,15 @@ pub enum GossipModuleType {
 /// Represents an interchangeable gossip strategy module
 pub trait AsGossipModule: 'static + Send + Sync {
     fn close(&self);
+    /// Handle incoming gossip. `wire_bytes` is the number of bytes
+    /// the gossip took up on the wire, which is less than the length of
+    /// `gossip_data` if it was compressed.
     fn incoming_gossip(
         &self,
         con: Tx2ConHnd<wire::Wire>,
         remote_url: TxUrl,
         gossip_data: Box<[u8]>,
+        wire_bytes: usize,
     ) -> KitsuneResult<()>;
     fn local_agent_join(&self, a: Arc<KitsuneAgent>);
     fn local_agent_leave(&self, a: Arc<KitsuneAgent>);
@@ -45,8 +49,10 @@ impl GossipModule {
         con: Tx2ConHnd<wire::Wire>,
         remote_url: TxUrl,
         gossip_data: Box<[u8]>,
+        wire_bytes: usize,
     ) -> KitsuneResult<()> {
-        self.0.incoming_gossip(con, remote_url, gossip_data)
+        self.0
+            .incoming_gossip(con, remote_url, gossip_data, wire_bytes)
     }
 
     pub fn local_agent_join(&self, a: Arc<KitsuneAgent>) {
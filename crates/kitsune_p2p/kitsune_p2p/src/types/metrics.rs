@@ -11,7 +11,9 @@ observability::metrics!(
     PeerGet,
     PeerGetResp,
     PeerQuery,
-    PeerQueryResp
+    PeerQueryResp,
+    CompressionIn,
+    CompressionOut
 );
 
 /// Print all metrics as tracing events
@@ -48,8 +50,37 @@ pub fn print_all_metrics() {
                     )
                     .expect("Failed to print metrics");
                 }
+                CompressionIn | CompressionOut => {
+                    writeln!(
+                        out,
+                        "metric: {:?} {}Bytes {:.4}MB",
+                        metric,
+                        count,
+                        count as f64 / 1_000_000.0,
+                    )
+                    .expect("Failed to print metrics");
+                }
             }
         }
+        // The ratio of bytes sent to bytes before compression,
+        // for messages which were sent compressed.
+        let mut compression_in = 0;
+        let mut compression_out = 0;
+        for (metric, count) in KitsuneMetrics::iter() {
+            match metric {
+                CompressionIn => compression_in = count,
+                CompressionOut => compression_out = count,
+                _ => (),
+            }
+        }
+        if compression_in > 0 {
+            writeln!(
+                out,
+                "metric: CompressionRatio {:.4}",
+                compression_out as f64 / compression_in as f64,
+            )
+            .expect("Failed to print metrics");
+        }
         tracing::trace!(metric = %out);
     }
 }
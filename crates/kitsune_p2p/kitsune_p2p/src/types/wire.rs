@@ -32,6 +32,19 @@ pub enum MetricExchangeMsg {
     UnknownMessage,
 }
 
+/// A compression algorithm which can be used for wire messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WireCompression {
+    /// Zstandard compression.
+    Zstd,
+
+    /// Future proof by having an unknown algorithm catch-all variant
+    /// that we can ignore for any future algorithms that are added
+    #[serde(other)]
+    Unknown,
+}
+
 kitsune_p2p_types::write_codec_enum! {
     /// KitsuneP2p Wire Protocol Top-Level Enum.
     codec Wire {
@@ -119,5 +132,22 @@ kitsune_p2p_types::write_codec_enum! {
             space.0: Arc<KitsuneSpace>,
             msgs.1: Vec<MetricExchangeMsg>,
         },
+
+        /// Advertise our capabilities to a newly connected remote.
+        /// uses low-level notify, not request
+        Capabilities(0xb0) {
+            /// The compression algorithms we can decompress.
+            compression.0: Vec<WireCompression>,
+        },
+
+        /// Another wire message, encoded then compressed.
+        /// Only sent to remotes which advertised the algorithm.
+        Compressed(0xb1) {
+            /// The algorithm `data` was compressed with.
+            compression.0: WireCompression,
+
+            /// The compressed encoding of the inner wire message.
+            data.1: WireData,
+        },
     }
 }
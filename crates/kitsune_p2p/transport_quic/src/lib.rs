@@ -1,10 +1,11 @@
 #![deny(missing_docs)]
-//! QUIC transport module for kitsune-p2p
+//! QUIC and TCP transport modules for kitsune-p2p
 
 /// Re-exported dependencies.
 pub mod dependencies {
     pub use ::kitsune_p2p_types;
     pub use ::quinn;
+    pub use ::tokio_rustls;
 }
 
 use kitsune_p2p_types::dependencies::url2::*;
@@ -12,6 +13,7 @@ use kitsune_p2p_types::*;
 use std::net::SocketAddr;
 
 const SCHEME: &str = "kitsune-quic";
+const TCP_SCHEME: &str = "kitsune-tcp";
 
 /// internal helper convert urls to socket addrs for binding / connection
 pub(crate) async fn url_to_addr(url: &Url2, scheme: &str) -> KitsuneResult<SocketAddr> {
@@ -48,4 +50,28 @@ pub(crate) async fn url_to_addr(url: &Url2, scheme: &str) -> KitsuneResult<Socke
     Err(format!("could not parse '{}', as 'host:port'", rendered).into())
 }
 
+/// internal helper convert a bound socket addr to a url peers can reach
+pub(crate) fn addr_to_url(addr: SocketAddr, scheme: &str) -> KitsuneResult<Url2> {
+    let mut url = url2!("{}://{}", scheme, addr);
+
+    if let Some(host) = url.host_str() {
+        if host == "0.0.0.0" {
+            for iface in if_addrs::get_if_addrs().map_err(KitsuneError::other)? {
+                // super naive - just picking the first v4 that is not 127.0.0.1
+                let addr = iface.addr.ip();
+                if let std::net::IpAddr::V4(addr) = addr {
+                    if addr != std::net::Ipv4Addr::from([127, 0, 0, 1]) {
+                        url.set_host(Some(&iface.addr.ip().to_string())).unwrap();
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(url)
+}
+
 pub mod tx2;
+pub mod tx2_fallback;
+pub mod tx2_tcp;
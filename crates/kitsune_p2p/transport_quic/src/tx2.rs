@@ -305,28 +305,10 @@ impl EndpointAdapt for QuicEndpointAdapt {
             .0
             .share_mut(|i, _| i.ep.local_addr().map_err(KitsuneError::other))?;
 
-        use kitsune_p2p_types::dependencies::url2;
-        let mut url = url2::url2!("{}://{}", crate::SCHEME, addr);
-
         // MAYBE - not sure how slow `get_if_addrs` is
         //         might be better to do this once on bind
         //         and just cache the bound address
-        if let Some(host) = url.host_str() {
-            if host == "0.0.0.0" {
-                for iface in if_addrs::get_if_addrs().map_err(KitsuneError::other)? {
-                    // super naive - just picking the first v4 that is not 127.0.0.1
-                    let addr = iface.addr.ip();
-                    if let std::net::IpAddr::V4(addr) = addr {
-                        if addr != std::net::Ipv4Addr::from([127, 0, 0, 1]) {
-                            url.set_host(Some(&iface.addr.ip().to_string())).unwrap();
-                            break;
-                        }
-                    }
-                }
-            }
-        }
-
-        Ok(url.into())
+        Ok(crate::addr_to_url(addr, crate::SCHEME)?.into())
     }
 
     fn local_cert(&self) -> Tx2Cert {
@@ -0,0 +1,292 @@
+//! kitsune tx2 quic backend with tcp fallback
+//!
+//! Endpoints bound with this backend listen for both quic and tcp
+//! connections on the same port, and advertise their quic address.
+//! If the tcp port can't be bound, the endpoint only uses quic.
+//! Outgoing connections are attempted over quic first, and retried over
+//! tcp if that fails or takes longer than
+//! `tx2_quic_tcp_fallback_timeout_ms`, so nodes on networks which block
+//! UDP can still reach, and be reached by, their peers.
+
+use crate::tx2::*;
+use crate::tx2_tcp::*;
+use futures::future::{BoxFuture, FutureExt};
+use futures::stream::{BoxStream, StreamExt};
+use kitsune_p2p_types::dependencies::{ghost_actor::dependencies::tracing, serde_json};
+use kitsune_p2p_types::tx2::tx2_adapter::*;
+use kitsune_p2p_types::tx2::tx2_utils::*;
+use kitsune_p2p_types::*;
+use std::sync::Arc;
+
+/// Quic endpoint bind adapter for kitsune tx2, falling back to tcp
+pub async fn tx2_quic_tcp_fallback_adapter(config: QuicConfig) -> KitsuneResult<AdapterFactory> {
+    FallbackBackendAdapt::new(config).await
+}
+
+// -- private -- //
+
+struct FallbackConRecvAdapt(BoxStream<'static, ConFut>);
+
+impl futures::stream::Stream for FallbackConRecvAdapt {
+    type Item = ConFut;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        let inner = &mut self.0;
+        tokio::pin!(inner);
+        futures::stream::Stream::poll_next(inner, cx)
+    }
+}
+
+impl ConRecvAdapt for FallbackConRecvAdapt {}
+
+struct FallbackEndpointAdapt {
+    uniq: Uniq,
+    quic: Arc<dyn EndpointAdapt>,
+    tcp: Option<Arc<dyn EndpointAdapt>>,
+    fallback_timeout: std::time::Duration,
+}
+
+impl EndpointAdapt for FallbackEndpointAdapt {
+    fn debug(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "tx2_quic_tcp_fallback",
+            "quic": self.quic.debug(),
+            "tcp": self.tcp.as_ref().map(|tcp| tcp.debug()),
+        })
+    }
+
+    fn uniq(&self) -> Uniq {
+        self.uniq
+    }
+
+    fn local_addr(&self) -> KitsuneResult<TxUrl> {
+        self.quic.local_addr()
+    }
+
+    fn local_cert(&self) -> Tx2Cert {
+        self.quic.local_cert()
+    }
+
+    fn connect(&self, url: TxUrl, timeout: KitsuneTimeout) -> ConFut {
+        let tcp = match &self.tcp {
+            Some(tcp) => tcp.clone(),
+            None => return self.quic.connect(url, timeout),
+        };
+        if url.as_url2().scheme() == crate::TCP_SCHEME {
+            return tcp.connect(url, timeout);
+        }
+
+        let quic_timeout = KitsuneTimeout::new(std::cmp::min(
+            self.fallback_timeout,
+            timeout.time_remaining(),
+        ));
+        let quic_con = self.quic.connect(url.clone(), quic_timeout);
+        async move {
+            let err = match quic_con.await {
+                Ok(con) => return Ok(con),
+                Err(err) => err,
+            };
+            if timeout.is_expired() {
+                return Err(err);
+            }
+
+            let mut tcp_url = url.as_url2().clone();
+            if tcp_url.set_scheme(crate::TCP_SCHEME).is_err() {
+                return Err(err);
+            }
+            tracing::info!(?err, %tcp_url, "quic connection failed, falling back to tcp");
+            tcp.connect(tcp_url.into(), timeout).await
+        }
+        .boxed()
+    }
+
+    fn is_closed(&self) -> bool {
+        self.quic.is_closed()
+            || self
+                .tcp
+                .as_ref()
+                .map(|tcp| tcp.is_closed())
+                .unwrap_or(false)
+    }
+
+    fn close(&self, code: u32, reason: &str) -> BoxFuture<'static, ()> {
+        let tcp_close = self.tcp.as_ref().map(|tcp| tcp.close(code, reason));
+        futures::future::join(self.quic.close(code, reason), async move {
+            if let Some(tcp_close) = tcp_close {
+                tcp_close.await;
+            }
+        })
+        .map(|_| ())
+        .boxed()
+    }
+}
+
+struct FallbackBackendAdapt {
+    quic: AdapterFactory,
+    tcp: AdapterFactory,
+    fallback_timeout: std::time::Duration,
+}
+
+impl FallbackBackendAdapt {
+    async fn new(config: QuicConfig) -> KitsuneResult<AdapterFactory> {
+        let (tls, tuning_params) = config.split().await?;
+
+        let fallback_timeout =
+            std::time::Duration::from_millis(tuning_params.tx2_quic_tcp_fallback_timeout_ms as u64);
+
+        let tcp = tx2_tcp_adapter(TcpConfig {
+            tls: Some(tls.clone()),
+            tuning_params: Some(tuning_params.clone()),
+        })
+        .await?;
+
+        let quic = tx2_quic_adapter(QuicConfig {
+            tls: Some(tls),
+            tuning_params: Some(tuning_params),
+        })
+        .await?;
+
+        let out: AdapterFactory = Arc::new(Self {
+            quic,
+            tcp,
+            fallback_timeout,
+        });
+
+        Ok(out)
+    }
+}
+
+impl BindAdapt for FallbackBackendAdapt {
+    fn bind(&self, url: TxUrl, timeout: KitsuneTimeout) -> EndpointFut {
+        let quic_fut = self.quic.bind(url.clone(), timeout);
+        let tcp = self.tcp.clone();
+        let fallback_timeout = self.fallback_timeout;
+        async move {
+            let (quic, quic_recv) = quic_fut.await?;
+
+            // listen for tcp on the same interface and port quic is bound to
+            let port = quic.local_addr()?.as_url2().port();
+            let mut tcp_url = url.as_url2().clone();
+            if tcp_url.set_scheme(crate::TCP_SCHEME).is_err() || tcp_url.set_port(port).is_err() {
+                quic.close(0, "").await;
+                return Err(format!("invalid bind url: {}", url).into());
+            }
+            let (tcp, con_recv) = match tcp.bind(tcp_url.clone().into(), timeout).await {
+                Ok((tcp, tcp_recv)) => (
+                    Some(tcp),
+                    futures::stream::select(quic_recv, tcp_recv).boxed(),
+                ),
+                Err(err) => {
+                    tracing::warn!(?err, %tcp_url, "failed to bind tcp fallback, only using quic");
+                    (None, quic_recv.boxed())
+                }
+            };
+
+            let ep: Arc<dyn EndpointAdapt> = Arc::new(FallbackEndpointAdapt {
+                uniq: Uniq::default(),
+                quic,
+                tcp,
+                fallback_timeout,
+            });
+            let con_recv: Box<dyn ConRecvAdapt> = Box::new(FallbackConRecvAdapt(con_recv));
+
+            Ok((ep, con_recv))
+        }
+        .boxed()
+    }
+
+    fn local_cert(&self) -> Tx2Cert {
+        self.quic.local_cert()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kitsune_p2p_types::config::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_quic_tcp_fallback() {
+        kitsune_p2p_types::dependencies::observability::test_run().ok();
+
+        let t = KitsuneTimeout::from_millis(5000);
+
+        let tuning_params = tuning_params_struct::KitsuneP2pTuningParams {
+            tx2_quic_tcp_fallback_timeout_ms: 500,
+            ..Default::default()
+        };
+        let config = QuicConfig {
+            tls: None,
+            tuning_params: Some(Arc::new(tuning_params)),
+        };
+        let factory = tx2_quic_tcp_fallback_adapter(config).await.unwrap();
+        let (ep1, _con_recv1) = factory
+            .bind("kitsune-quic://127.0.0.1:0".into(), t)
+            .await
+            .unwrap();
+
+        // a peer which can only be reached over tcp
+        let factory = tx2_tcp_adapter(TcpConfig::default()).await.unwrap();
+        let (ep2, mut con_recv2) = factory
+            .bind("kitsune-tcp://127.0.0.1:0".into(), t)
+            .await
+            .unwrap();
+        let port = ep2.local_addr().unwrap().as_url2().port().unwrap();
+        let addr2: TxUrl = format!("kitsune-quic://127.0.0.1:{}", port).into();
+
+        let rt = tokio::task::spawn(async move {
+            let (_con, mut recv) = con_recv2.next().await.unwrap().await.unwrap();
+            let mut c = recv.next().await.unwrap().await.unwrap();
+            let t = KitsuneTimeout::from_millis(5000);
+            let (_, data) = c.read(t).await.unwrap();
+            data.to_vec()
+        });
+
+        let (c, _recv) = ep1.connect(addr2, t).await.unwrap();
+        assert_eq!(c.peer_cert(), ep2.local_cert());
+        assert_eq!(c.peer_addr().unwrap().as_url2().scheme(), "kitsune-tcp");
+
+        let mut out = c.out_chan(t).await.unwrap();
+        let mut data = PoolBuf::new();
+        data.extend_from_slice(b"hello");
+        out.write(0.into(), data, t).await.unwrap();
+
+        assert_eq!(rt.await.unwrap(), b"hello".to_vec());
+
+        // the fallback endpoint also accepts tcp connections on its quic port
+        let port = ep1.local_addr().unwrap().as_url2().port().unwrap();
+        let addr1: TxUrl = format!("kitsune-tcp://127.0.0.1:{}", port).into();
+        let (c, _recv) = ep2.connect(addr1, t).await.unwrap();
+        assert_eq!(c.peer_cert(), ep1.local_cert());
+
+        ep1.close(0, "").await;
+        ep2.close(0, "").await;
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_quic_tcp_fallback_tcp_port_taken() {
+        kitsune_p2p_types::dependencies::observability::test_run().ok();
+
+        let t = KitsuneTimeout::from_millis(5000);
+
+        let taken = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = taken.local_addr().unwrap().port();
+
+        // the endpoint still binds, and only uses quic
+        let factory = tx2_quic_tcp_fallback_adapter(QuicConfig::default())
+            .await
+            .unwrap();
+        let (ep, _con_recv) = factory
+            .bind(format!("kitsune-quic://127.0.0.1:{}", port).into(), t)
+            .await
+            .unwrap();
+        assert!(!ep.is_closed());
+        assert_eq!(ep.local_addr().unwrap().as_url2().port(), Some(port));
+
+        ep.close(0, "").await;
+        assert!(ep.is_closed());
+    }
+}
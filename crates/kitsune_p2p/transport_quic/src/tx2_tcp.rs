@@ -0,0 +1,803 @@
+#![allow(clippy::new_ret_no_self)]
+//! kitsune tx2 tcp transport backend
+//!
+//! For networks where UDP, and so QUIC, is blocked. Connections use TLS 1.3
+//! with the same certificates as the quic backend, and tx2 channels are
+//! multiplexed over a single tcp connection to each peer. Each message is
+//! sent as one frame, with little-endian header fields:
+//! `[u32 frame length][u32 channel id][u64 msg id][payload]`.
+
+use futures::future::{BoxFuture, FutureExt};
+use futures::stream::{BoxStream, StreamExt};
+use kitsune_p2p_types::config::*;
+use kitsune_p2p_types::dependencies::{ghost_actor::dependencies::tracing, serde_json};
+use kitsune_p2p_types::tls::*;
+use kitsune_p2p_types::tx2::tx2_adapter::*;
+use kitsune_p2p_types::tx2::tx2_utils::*;
+use kitsune_p2p_types::tx2::*;
+use kitsune_p2p_types::*;
+use std::collections::HashMap;
+use std::sync::atomic;
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt, ReadHalf, WriteHalf};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{mpsc, oneshot};
+use tokio_rustls::{rustls, TlsAcceptor, TlsConnector, TlsStream};
+
+/// Configuration for TcpBackendAdapt
+#[non_exhaustive]
+#[derive(Default)]
+pub struct TcpConfig {
+    /// Tls config
+    /// Default: None = ephemeral.
+    pub tls: Option<TlsConfig>,
+
+    /// Tuning Params
+    /// Default: None = default.
+    pub tuning_params: Option<KitsuneP2pTuningParams>,
+}
+
+impl TcpConfig {
+    /// into inner contents with default application
+    pub async fn split(self) -> KitsuneResult<(TlsConfig, KitsuneP2pTuningParams)> {
+        let TcpConfig { tls, tuning_params } = self;
+
+        let tls = match tls {
+            None => TlsConfig::new_ephemeral().await?,
+            Some(tls) => tls,
+        };
+
+        let tuning_params = tuning_params.unwrap_or_default();
+
+        Ok((tls, tuning_params))
+    }
+}
+
+/// Tcp endpoint bind adapter for kitsune tx2
+pub async fn tx2_tcp_adapter(config: TcpConfig) -> KitsuneResult<AdapterFactory> {
+    TcpBackendAdapt::new(config).await
+}
+
+// -- private -- //
+
+/// Tls ALPN identifier for kitsune tcp handshaking
+const ALPN_KITSUNE_TCP_0: &[u8] = b"kitsune-tcp/0";
+
+const FRAME_SIZE_BYTES: usize = 4;
+const CHAN_ID_BYTES: usize = 4;
+const MSG_ID_BYTES: usize = 8;
+const FRAME_HEADER_BYTES: usize = FRAME_SIZE_BYTES + CHAN_ID_BYTES + MSG_ID_BYTES;
+
+/// How many messages can be queued for a single channel, or for the
+/// connection writer, before the other side waits.
+const CHAN_BOUND: usize = 32;
+
+/// How many incoming channels a peer can have open on one connection.
+const MAX_IN_CHANS: usize = 1024;
+
+type Frame = (u32, MsgId, PoolBuf);
+type FrameSend = mpsc::Sender<(Frame, oneshot::Sender<KitsuneResult<()>>)>;
+type FrameRecv = mpsc::Receiver<(Frame, oneshot::Sender<KitsuneResult<()>>)>;
+
+struct TcpOutChan {
+    chan_id: u32,
+    frame_send: FrameSend,
+}
+
+impl AsFramedWriter for TcpOutChan {
+    fn write(
+        &mut self,
+        msg_id: MsgId,
+        data: PoolBuf,
+        timeout: KitsuneTimeout,
+    ) -> BoxFuture<'_, KitsuneResult<()>> {
+        let frame = (self.chan_id, msg_id, data);
+        let frame_send = self.frame_send.clone();
+        timeout
+            .mix(async move {
+                let (s_done, r_done) = oneshot::channel();
+                if frame_send.send((frame, s_done)).await.is_err() {
+                    return Err(KitsuneErrorKind::Closed.into());
+                }
+                r_done
+                    .await
+                    .map_err(|_| KitsuneError::from(KitsuneErrorKind::Closed))?
+            })
+            .boxed()
+    }
+}
+
+struct TcpInChan(mpsc::Receiver<(MsgId, PoolBuf)>);
+
+impl AsFramedReader for TcpInChan {
+    fn read(&mut self, timeout: KitsuneTimeout) -> BoxFuture<'_, KitsuneResult<(MsgId, PoolBuf)>> {
+        timeout
+            .mix(async move {
+                self.0
+                    .recv()
+                    .await
+                    .ok_or_else(|| KitsuneErrorKind::Closed.into())
+            })
+            .boxed()
+    }
+}
+
+struct TcpInChanRecvAdapt(BoxStream<'static, InChanFut>);
+
+impl TcpInChanRecvAdapt {
+    pub fn new(recv: mpsc::Receiver<InChan>, active: Active) -> Self {
+        Self(
+            futures::stream::unfold((recv, active), move |(mut recv, active)| async move {
+                let fut = active.fut(async move {
+                    let item = recv
+                        .recv()
+                        .await
+                        .ok_or_else(|| KitsuneError::from(KitsuneErrorKind::Closed))?;
+                    Ok((item, recv))
+                });
+                match fut.await {
+                    Err(_) => None,
+                    Ok((item, recv)) => Some((async move { Ok(item) }.boxed(), (recv, active))),
+                }
+            })
+            .boxed(),
+        )
+    }
+}
+
+impl futures::stream::Stream for TcpInChanRecvAdapt {
+    type Item = InChanFut;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        let inner = &mut self.0;
+        tokio::pin!(inner);
+        futures::stream::Stream::poll_next(inner, cx)
+    }
+}
+
+impl InChanRecvAdapt for TcpInChanRecvAdapt {}
+
+/// Write queued frames to the connection until it, or every sender, closes.
+async fn write_frames(
+    mut write: WriteHalf<TlsStream<TcpStream>>,
+    mut frame_recv: FrameRecv,
+) -> KitsuneResult<()> {
+    while let Some(((chan_id, msg_id, mut data), s_done)) = frame_recv.recv().await {
+        let total = (data.len() + FRAME_HEADER_BYTES) as u32;
+
+        data.reserve_front(FRAME_HEADER_BYTES);
+        data.prepend_from_slice(&msg_id.inner().to_le_bytes()[..]);
+        data.prepend_from_slice(&chan_id.to_le_bytes()[..]);
+        data.prepend_from_slice(&total.to_le_bytes()[..]);
+
+        let res = async {
+            write.write_all(&data).await?;
+            write.flush().await
+        }
+        .await;
+        match res {
+            Ok(()) => {
+                let _ = s_done.send(Ok(()));
+            }
+            Err(e) => {
+                let e = KitsuneError::other(e);
+                let _ = s_done.send(Err(e.clone()));
+                return Err(e);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Read frames from the connection, routing each to its channel.
+/// A frame for a channel which isn't open opens a new incoming channel.
+/// A channel is closed when it is dropped locally, or when its reader falls
+/// so far behind that its queue fills up, in which case the frame is
+/// dropped, so that one slow channel can't hold up the whole connection.
+async fn read_frames(
+    mut read: ReadHalf<TlsStream<TcpStream>>,
+    in_chan_send: mpsc::Sender<InChan>,
+    max_message_bytes: usize,
+) -> KitsuneResult<()> {
+    let mut chans: HashMap<u32, mpsc::Sender<(MsgId, PoolBuf)>> = HashMap::new();
+    let mut header = [0_u8; FRAME_HEADER_BYTES];
+    let mut local_buf = [0_u8; 4096];
+    loop {
+        read.read_exact(&mut header)
+            .await
+            .map_err(KitsuneError::other)?;
+
+        let mut size = [0_u8; FRAME_SIZE_BYTES];
+        size.copy_from_slice(&header[..FRAME_SIZE_BYTES]);
+        let size = u32::from_le_bytes(size) as usize;
+        let mut chan_id = [0_u8; CHAN_ID_BYTES];
+        chan_id.copy_from_slice(&header[FRAME_SIZE_BYTES..FRAME_SIZE_BYTES + CHAN_ID_BYTES]);
+        let chan_id = u32::from_le_bytes(chan_id);
+        let mut msg_id = [0_u8; MSG_ID_BYTES];
+        msg_id.copy_from_slice(&header[FRAME_SIZE_BYTES + CHAN_ID_BYTES..]);
+        let msg_id = MsgId::from(u64::from_le_bytes(msg_id));
+
+        let want_size = match size.checked_sub(FRAME_HEADER_BYTES) {
+            Some(want_size) if want_size <= max_message_bytes => want_size,
+            _ => return Err(format!("invalid frame size: {}", size).into()),
+        };
+
+        let mut data = PoolBuf::new();
+        data.reserve(want_size);
+        while data.len() < want_size {
+            let to_read = std::cmp::min(local_buf.len(), want_size - data.len());
+            let count = read
+                .read(&mut local_buf[..to_read])
+                .await
+                .map_err(KitsuneError::other)?;
+            if count == 0 {
+                return Err(KitsuneErrorKind::Closed.into());
+            }
+            data.extend_from_slice(&local_buf[..count]);
+        }
+
+        if !chans.contains_key(&chan_id) {
+            chans.retain(|_, chan| !chan.is_closed());
+            if chans.len() >= MAX_IN_CHANS {
+                return Err("too many incoming channels".into());
+            }
+            let (send, recv) = mpsc::channel(CHAN_BOUND);
+            let in_chan: InChan = Box::new(TcpInChan(recv));
+            if in_chan_send.send(in_chan).await.is_err() {
+                return Err(KitsuneErrorKind::Closed.into());
+            }
+            chans.insert(chan_id, send);
+        }
+        let res = chans[&chan_id].try_send((msg_id, data));
+        match res {
+            Ok(()) => (),
+            Err(mpsc::error::TrySendError::Full(_)) => {
+                tracing::warn!(%chan_id, "incoming channel reader fell behind, closing channel (tcp)");
+                chans.remove(&chan_id);
+            }
+            Err(mpsc::error::TrySendError::Closed(_)) => {
+                chans.remove(&chan_id);
+            }
+        }
+    }
+}
+
+struct TcpConAdapt {
+    uniq: Uniq,
+    dir: Tx2ConDir,
+    peer_addr: TxUrl,
+    peer_cert: Tx2Cert,
+    next_chan_id: atomic::AtomicU32,
+    frame_send: FrameSend,
+    con_active: Active,
+    mix_active: Active,
+}
+
+impl ConAdapt for TcpConAdapt {
+    fn uniq(&self) -> Uniq {
+        self.uniq
+    }
+
+    fn dir(&self) -> Tx2ConDir {
+        self.dir
+    }
+
+    fn peer_addr(&self) -> KitsuneResult<TxUrl> {
+        Ok(self.peer_addr.clone())
+    }
+
+    fn peer_cert(&self) -> Tx2Cert {
+        self.peer_cert.clone()
+    }
+
+    fn out_chan(&self, _timeout: KitsuneTimeout) -> OutChanFut {
+        if !self.mix_active.is_active() {
+            return async move { Err(KitsuneErrorKind::Closed.into()) }.boxed();
+        }
+        // Outgoing connections use odd channel ids, and incoming even,
+        // so the two sides never pick the same id.
+        let chan_id = self.next_chan_id.fetch_add(2, atomic::Ordering::Relaxed);
+        let out: OutChan = Box::new(TcpOutChan {
+            chan_id,
+            frame_send: self.frame_send.clone(),
+        });
+        async move { Ok(out) }.boxed()
+    }
+
+    fn is_closed(&self) -> bool {
+        !self.mix_active.is_active()
+    }
+
+    fn close(&self, code: u32, reason: &str) -> BoxFuture<'static, ()> {
+        tracing::info!(
+            peer_cert=?self.peer_cert,
+            %code,
+            %reason,
+            "close connection (tcp)",
+        );
+        self.con_active.kill();
+        async move {}.boxed()
+    }
+}
+
+/// Set up a connection once the tls handshake has completed,
+/// spawning the tasks which read and write its frames.
+fn established(
+    stream: TlsStream<TcpStream>,
+    dir: Tx2ConDir,
+    local_cert: Tx2Cert,
+    ep_active: &Active,
+    max_message_bytes: usize,
+) -> KitsuneResult<Con> {
+    let (tcp, tls) = stream.get_ref();
+    let peer_addr = tcp.peer_addr().map_err(KitsuneError::other)?;
+    let peer_cert: Tx2Cert = match tls.peer_certificates().and_then(|chain| chain.first()) {
+        None => return Err("invalid peer certificate (none)".into()),
+        Some(cert) => crate::tx2::blake2b_32(cert.as_ref()).into(),
+    };
+
+    use kitsune_p2p_types::dependencies::url2;
+    let peer_addr: TxUrl = url2::url2!("{}://{}", crate::TCP_SCHEME, peer_addr).into();
+
+    let (read, write) = tokio::io::split(stream);
+    let (frame_send, frame_recv) = mpsc::channel(CHAN_BOUND);
+    let (in_chan_send, in_chan_recv) = mpsc::channel(CHAN_BOUND);
+
+    let con_active = Active::new();
+    let mix_active = con_active.mix(ep_active);
+
+    {
+        let con_active = con_active.clone();
+        let mix_active = mix_active.clone();
+        let peer_cert = peer_cert.clone();
+        tokio::task::spawn(async move {
+            let res = mix_active
+                .fut(async move {
+                    futures::future::try_join(
+                        read_frames(read, in_chan_send, max_message_bytes),
+                        write_frames(write, frame_recv),
+                    )
+                    .await
+                    .map(|_| ())
+                })
+                .await;
+            if let Err(err) = res {
+                tracing::debug!(?peer_cert, ?err, "connection closed (tcp)");
+            }
+            con_active.kill();
+        });
+    }
+
+    let first_chan_id = match dir {
+        Tx2ConDir::Outgoing => 1,
+        Tx2ConDir::Incoming => 2,
+    };
+
+    match dir {
+        Tx2ConDir::Outgoing => {
+            tracing::info!(?local_cert, ?peer_cert, %peer_addr, "established outgoing connection (tcp)");
+        }
+        Tx2ConDir::Incoming => {
+            tracing::info!(?local_cert, ?peer_cert, %peer_addr, "established incoming connection (tcp)");
+        }
+    }
+
+    let con: Arc<dyn ConAdapt> = Arc::new(TcpConAdapt {
+        uniq: Uniq::default(),
+        dir,
+        peer_addr,
+        peer_cert,
+        next_chan_id: atomic::AtomicU32::new(first_chan_id),
+        frame_send,
+        con_active,
+        mix_active: mix_active.clone(),
+    });
+    let chan_recv: Box<dyn InChanRecvAdapt> =
+        Box::new(TcpInChanRecvAdapt::new(in_chan_recv, mix_active));
+
+    Ok((con, chan_recv))
+}
+
+struct TcpConRecvAdapt(BoxStream<'static, ConFut>);
+
+impl TcpConRecvAdapt {
+    pub fn new(
+        listener: TcpListener,
+        acceptor: TlsAcceptor,
+        local_cert: Tx2Cert,
+        ep_active: Active,
+        tuning_params: KitsuneP2pTuningParams,
+    ) -> Self {
+        struct OnDrop(Active);
+
+        impl Drop for OnDrop {
+            fn drop(&mut self) {
+                self.0.kill();
+            }
+        }
+
+        let on_drop = OnDrop(ep_active);
+
+        Self(
+            futures::stream::unfold((listener, on_drop), move |(listener, on_drop)| {
+                let acceptor = acceptor.clone();
+                let local_cert = local_cert.clone();
+                let timeout = tuning_params.implicit_timeout();
+                let max_message_bytes = tuning_params.tx2_tcp_max_message_bytes as usize;
+                async move {
+                    let ep_active = on_drop.0.clone();
+                    let accepted = ep_active
+                        .fut(async { listener.accept().await.map_err(KitsuneError::other) })
+                        .await;
+                    if !ep_active.is_active() {
+                        return None;
+                    }
+                    let con_fut = async move {
+                        let (tcp, _) = accepted?;
+                        tcp.set_nodelay(true).map_err(KitsuneError::other)?;
+                        let tls =
+                            timeout
+                                .mix(async move {
+                                    acceptor.accept(tcp).await.map_err(KitsuneError::other)
+                                })
+                                .await?;
+                        established(
+                            tls.into(),
+                            Tx2ConDir::Incoming,
+                            local_cert,
+                            &ep_active,
+                            max_message_bytes,
+                        )
+                    }
+                    .boxed();
+                    Some((con_fut, (listener, on_drop)))
+                }
+            })
+            .boxed(),
+        )
+    }
+}
+
+impl futures::stream::Stream for TcpConRecvAdapt {
+    type Item = ConFut;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        let inner = &mut self.0;
+        tokio::pin!(inner);
+        futures::stream::Stream::poll_next(inner, cx)
+    }
+}
+
+impl ConRecvAdapt for TcpConRecvAdapt {}
+
+struct TcpEndpointAdapt {
+    uniq: Uniq,
+    local_addr: TxUrl,
+    local_cert: Tx2Cert,
+    connector: TlsConnector,
+    ep_active: Active,
+    max_message_bytes: usize,
+}
+
+impl EndpointAdapt for TcpEndpointAdapt {
+    fn debug(&self) -> serde_json::Value {
+        if self.ep_active.is_active() {
+            serde_json::json!({
+                "type": "tx2_tcp",
+                "state": "open",
+                "addr": &self.local_addr,
+            })
+        } else {
+            serde_json::json!({
+                "type": "tx2_tcp",
+                "state": "closed",
+            })
+        }
+    }
+
+    fn uniq(&self) -> Uniq {
+        self.uniq
+    }
+
+    fn local_addr(&self) -> KitsuneResult<TxUrl> {
+        if !self.ep_active.is_active() {
+            return Err(KitsuneErrorKind::Closed.into());
+        }
+        Ok(self.local_addr.clone())
+    }
+
+    fn local_cert(&self) -> Tx2Cert {
+        self.local_cert.clone()
+    }
+
+    fn connect(&self, url: TxUrl, timeout: KitsuneTimeout) -> ConFut {
+        if !self.ep_active.is_active() {
+            return async move { Err(KitsuneErrorKind::Closed.into()) }.boxed();
+        }
+        let connector = self.connector.clone();
+        let local_cert = self.local_cert.clone();
+        let ep_active = self.ep_active.clone();
+        let max_message_bytes = self.max_message_bytes;
+        timeout
+            .mix(async move {
+                let res = async {
+                    let addr = crate::url_to_addr(url.as_url2(), crate::TCP_SCHEME).await?;
+                    let tcp = TcpStream::connect(addr)
+                        .await
+                        .map_err(KitsuneError::other)?;
+                    tcp.set_nodelay(true).map_err(KitsuneError::other)?;
+                    let name =
+                        rustls::ServerName::try_from("stub.stub").map_err(KitsuneError::other)?;
+                    let tls = connector
+                        .connect(name, tcp)
+                        .await
+                        .map_err(KitsuneError::other)?;
+                    established(
+                        tls.into(),
+                        Tx2ConDir::Outgoing,
+                        local_cert,
+                        &ep_active,
+                        max_message_bytes,
+                    )
+                }
+                .await;
+                if let Err(err) = &res {
+                    tracing::warn!(?err, "failed to establish outgoing connection (tcp)");
+                }
+                res
+            })
+            .boxed()
+    }
+
+    fn is_closed(&self) -> bool {
+        !self.ep_active.is_active()
+    }
+
+    fn close(&self, _code: u32, _reason: &str) -> BoxFuture<'static, ()> {
+        tracing::warn!(
+            local_cert=?self.local_cert,
+            "CLOSING ENDPOINT"
+        );
+        self.ep_active.kill();
+        async move {}.boxed()
+    }
+}
+
+/// Tcp endpoint backend bind adapter for kitsune tx2
+pub struct TcpBackendAdapt {
+    local_cert: Tx2Cert,
+    tls_srv: Arc<rustls::ServerConfig>,
+    tls_cli: Arc<rustls::ClientConfig>,
+    tuning_params: KitsuneP2pTuningParams,
+}
+
+impl TcpBackendAdapt {
+    /// Construct a new tcp tx2 backend bind adapter
+    pub async fn new(config: TcpConfig) -> KitsuneResult<AdapterFactory> {
+        let (tls, tuning_params) = config.split().await?;
+
+        let local_cert = tls.cert_digest.clone().into();
+
+        let (tls_srv, tls_cli) = gen_tls_configs(ALPN_KITSUNE_TCP_0, &tls, tuning_params.clone())?;
+
+        let out: AdapterFactory = Arc::new(Self {
+            local_cert,
+            tls_srv,
+            tls_cli,
+            tuning_params,
+        });
+
+        Ok(out)
+    }
+}
+
+impl BindAdapt for TcpBackendAdapt {
+    fn bind(&self, url: TxUrl, timeout: KitsuneTimeout) -> EndpointFut {
+        let local_cert = self.local_cert.clone();
+        let acceptor = TlsAcceptor::from(self.tls_srv.clone());
+        let connector = TlsConnector::from(self.tls_cli.clone());
+        let tuning_params = self.tuning_params.clone();
+        timeout
+            .mix(async move {
+                let addr = crate::url_to_addr(url.as_url2(), crate::TCP_SCHEME)
+                    .await
+                    .map_err(KitsuneError::other)?;
+
+                let listener = TcpListener::bind(addr).await.map_err(KitsuneError::other)?;
+                let local_addr = listener.local_addr().map_err(KitsuneError::other)?;
+                let local_addr: TxUrl = crate::addr_to_url(local_addr, crate::TCP_SCHEME)?.into();
+
+                let ep_active = Active::new();
+                let ep: Arc<dyn EndpointAdapt> = Arc::new(TcpEndpointAdapt {
+                    uniq: Uniq::default(),
+                    local_addr: local_addr.clone(),
+                    local_cert: local_cert.clone(),
+                    connector,
+                    ep_active: ep_active.clone(),
+                    max_message_bytes: tuning_params.tx2_tcp_max_message_bytes as usize,
+                });
+                let con_recv: Box<dyn ConRecvAdapt> = Box::new(TcpConRecvAdapt::new(
+                    listener,
+                    acceptor,
+                    local_cert.clone(),
+                    ep_active,
+                    tuning_params,
+                ));
+
+                tracing::info!(?local_cert, url = %local_addr, "bound local endpoint (tcp)");
+
+                Ok((ep, con_recv))
+            })
+            .boxed()
+    }
+
+    fn local_cert(&self) -> Tx2Cert {
+        self.local_cert.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_tcp_tx2() {
+        kitsune_p2p_types::dependencies::observability::test_run().ok();
+
+        let t = KitsuneTimeout::from_millis(5000);
+
+        let factory = TcpBackendAdapt::new(TcpConfig::default()).await.unwrap();
+        let (ep1, mut con_recv1) = factory
+            .bind("kitsune-tcp://127.0.0.1:0".into(), t)
+            .await
+            .unwrap();
+
+        let factory = TcpBackendAdapt::new(TcpConfig::default()).await.unwrap();
+        let (ep2, mut con_recv2) = factory
+            .bind("kitsune-tcp://127.0.0.1:0".into(), t)
+            .await
+            .unwrap();
+
+        let addr2 = ep2.local_addr().unwrap();
+        let cert1 = ep1.local_cert();
+
+        // Echo each message back on a new channel.
+        let rt = tokio::task::spawn(async move {
+            let (con, mut recv) = con_recv2.next().await.unwrap().await.unwrap();
+            assert_eq!(con.peer_cert(), cert1);
+            let t = KitsuneTimeout::from_millis(5000);
+            for _ in 0..2 {
+                let mut c = recv.next().await.unwrap().await.unwrap();
+                let (msg_id, data) = c.read(t).await.unwrap();
+                let mut out = con.out_chan(t).await.unwrap();
+                out.write(msg_id, data, t).await.unwrap();
+            }
+            con
+        });
+
+        let (c, mut recv) = ep1.connect(addr2, t).await.unwrap();
+        assert_eq!(c.peer_cert(), ep2.local_cert());
+
+        let mut out1 = c.out_chan(t).await.unwrap();
+        let mut out2 = c.out_chan(t).await.unwrap();
+        for (out, msg) in [(&mut out1, &b"hello"[..]), (&mut out2, &b"world"[..])] {
+            let mut data = PoolBuf::new();
+            data.extend_from_slice(msg);
+            out.write(MsgId::new(42), data, t).await.unwrap();
+        }
+
+        let mut echoes = Vec::new();
+        for _ in 0..2 {
+            let mut c = recv.next().await.unwrap().await.unwrap();
+            let (msg_id, data) = c.read(t).await.unwrap();
+            assert_eq!(msg_id.inner(), 42);
+            echoes.push(data.to_vec());
+        }
+        echoes.sort();
+        assert_eq!(echoes, vec![b"hello".to_vec(), b"world".to_vec()]);
+
+        let con2 = rt.await.unwrap();
+
+        // Closing one side closes the other.
+        c.close(0, "").await;
+        assert!(c.is_closed());
+        tokio::time::timeout(std::time::Duration::from_secs(5), async {
+            while !con2.is_closed() {
+                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+
+        ep1.close(0, "").await;
+        ep2.close(0, "").await;
+        assert!(ep1.local_addr().is_err());
+        assert!(con_recv1.next().await.is_none());
+    }
+
+    async fn connected_pair(
+        tuning_params: tuning_params_struct::KitsuneP2pTuningParams,
+    ) -> (Con, Con) {
+        let t = KitsuneTimeout::from_millis(5000);
+
+        let factory = TcpBackendAdapt::new(TcpConfig {
+            tls: None,
+            tuning_params: Some(Arc::new(tuning_params)),
+        })
+        .await
+        .unwrap();
+        let (ep1, mut con_recv1) = factory
+            .bind("kitsune-tcp://127.0.0.1:0".into(), t)
+            .await
+            .unwrap();
+
+        let factory = TcpBackendAdapt::new(TcpConfig::default()).await.unwrap();
+        let (ep2, _con_recv2) = factory
+            .bind("kitsune-tcp://127.0.0.1:0".into(), t)
+            .await
+            .unwrap();
+
+        let con2 = ep2.connect(ep1.local_addr().unwrap(), t).await.unwrap();
+        let con1 = con_recv1.next().await.unwrap().await.unwrap();
+        (con1, con2)
+    }
+
+    fn msg(data: &[u8]) -> PoolBuf {
+        let mut buf = PoolBuf::new();
+        buf.extend_from_slice(data);
+        buf
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_tcp_oversized_message_closes_connection() {
+        kitsune_p2p_types::dependencies::observability::test_run().ok();
+
+        let t = KitsuneTimeout::from_millis(5000);
+        let ((con1, _recv1), (con2, _recv2)) =
+            connected_pair(tuning_params_struct::KitsuneP2pTuningParams {
+                tx2_tcp_max_message_bytes: 1024,
+                ..Default::default()
+            })
+            .await;
+
+        let mut out = con2.out_chan(t).await.unwrap();
+        out.write(MsgId::new(1), msg(&[0; 2048]), t).await.unwrap();
+
+        tokio::time::timeout(std::time::Duration::from_secs(5), async {
+            while !con1.is_closed() || !con2.is_closed() {
+                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_tcp_slow_channel_does_not_block_connection() {
+        kitsune_p2p_types::dependencies::observability::test_run().ok();
+
+        let t = KitsuneTimeout::from_millis(5000);
+        let ((_con1, mut recv1), (con2, _recv2)) = connected_pair(Default::default()).await;
+
+        // Overflow one channel's queue without reading from it.
+        let mut slow = con2.out_chan(t).await.unwrap();
+        for i in 0..(CHAN_BOUND as u64 + 1) {
+            slow.write(MsgId::new(i), msg(b"slow"), t).await.unwrap();
+        }
+        let _slow_in = recv1.next().await.unwrap().await.unwrap();
+
+        // Messages on other channels still get through.
+        let mut fast = con2.out_chan(t).await.unwrap();
+        fast.write(MsgId::new(42), msg(b"fast"), t).await.unwrap();
+        let mut fast_in = recv1.next().await.unwrap().await.unwrap();
+        let (msg_id, data) = fast_in.read(t).await.unwrap();
+        assert_eq!(msg_id.inner(), 42);
+        assert_eq!(data.to_vec(), b"fast".to_vec());
+    }
+}
@@ -180,6 +180,23 @@ pub mod tuning_params_struct {
         /// [Default: 30 seconds]
         tx2_quic_max_idle_timeout_ms: u32 = 1000 * 30,
 
+        /// If true, tx2 quic endpoints also listen for tcp connections
+        /// on the same port, and fall back to connecting over tcp
+        /// if a quic connection attempt fails or times out.
+        /// If the tcp port can't be bound, the endpoint only uses quic.
+        /// [Default: true]
+        tx2_quic_tcp_fallback: bool = true,
+
+        /// How long a tx2 quic connection attempt can take before
+        /// falling back to tcp, if `tx2_quic_tcp_fallback` is set.
+        /// [Default: 5 seconds]
+        tx2_quic_tcp_fallback_timeout_ms: u32 = 1000 * 5,
+
+        /// The largest message a tx2 tcp connection will read from a peer.
+        /// A peer which sends a larger message is disconnected.
+        /// [Default: 64 MiB]
+        tx2_tcp_max_message_bytes: u32 = 1024 * 1024 * 64,
+
         /// tx2 pool max connection count
         /// [Default: 4096]
         tx2_pool_max_connection_count: usize = 4096,
@@ -199,6 +216,17 @@ pub mod tuning_params_struct {
         /// [Default: 200 ms]
         tx2_initial_connect_retry_delay_ms: usize = 200,
 
+        /// If true, advertise zstd compression to peers when connecting,
+        /// and compress gossip, broadcast and call payloads sent to peers
+        /// which advertise it in turn.
+        /// [Default: true]
+        wire_compression: bool = true,
+
+        /// Payloads smaller than this are always sent uncompressed,
+        /// as compressing them saves little, if anything.
+        /// [Default: 1024 bytes]
+        wire_compression_min_bytes: u32 = 1024,
+
         /// if you would like to be able to use an external tool
         /// to debug the QUIC messages sent and received by kitsune
         /// you'll need the decryption keys.
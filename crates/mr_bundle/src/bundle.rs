@@ -118,6 +118,19 @@ where
         Self::from_parts(manifest, self.resources, self.root_dir)
     }
 
+    /// Return a new Bundle with an updated manifest and some additional
+    /// bundled resources, subject to the same validation constraints as
+    /// creating a new Bundle from scratch.
+    pub fn update_manifest_with_resources<R: IntoIterator<Item = (PathBuf, ResourceBytes)>>(
+        self,
+        manifest: M,
+        resources: R,
+    ) -> MrBundleResult<Self> {
+        let mut all_resources = self.resources;
+        all_resources.extend(resources);
+        Self::from_parts(manifest, all_resources, self.root_dir)
+    }
+
     /// Load a Bundle into memory from a file
     pub async fn read_from_file(path: &Path) -> MrBundleResult<Self> {
         Self::decode(&ffs::read(path).await?)
@@ -27,6 +27,6 @@ mod packing;
 
 pub use bundle::Bundle;
 pub use encoding::{decode, encode};
-pub use location::Location;
+pub use location::{resolve_remote, Location};
 pub use manifest::Manifest;
 pub use resource::ResourceBytes;
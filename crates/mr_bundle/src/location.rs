@@ -48,9 +48,12 @@ pub(crate) async fn resolve_local(path: &Path) -> MrBundleResult<ResourceBytes>
     Ok(ffs::read(path).await?)
 }
 
-pub(crate) async fn resolve_remote(url: &str) -> MrBundleResult<ResourceBytes> {
+/// Download a resource from a URL.
+/// Responses with an HTTP error status are treated as errors.
+pub async fn resolve_remote(url: &str) -> MrBundleResult<ResourceBytes> {
     Ok(reqwest::get(url)
         .await?
+        .error_for_status()?
         .bytes()
         .await?
         .into_iter()
@@ -27,7 +27,8 @@ pub fn cap_secret(_: ()) -> ExternResult<CapSecret> {
 fn cap_grant_entry(secret: CapSecret) -> ExternResult<CapGrantEntry> {
     let mut functions: GrantedFunctions = BTreeSet::new();
     let this_zome = zome_info()?.name;
-    functions.insert((this_zome, "needs_cap_claim".into()));
+    functions.insert((this_zome.clone(), "needs_cap_claim".into()));
+    functions.insert((this_zome, "capability_info".into()));
     Ok(CapGrantEntry {
         tag: "".into(),
         access: secret.into(),
@@ -84,6 +85,32 @@ fn try_cap_claim(cap_for: crate::CapFor) -> ExternResult<ZomeCallResponse> {
     )
 }
 
+#[hdk_extern]
+fn try_capability_info(cap_for: crate::CapFor) -> ExternResult<ZomeCallResponse> {
+    call_remote(
+        cap_for.1,
+        zome_info()?.name,
+        "capability_info".to_string().into(),
+        Some(cap_for.0),
+        &(),
+    )
+}
+
+#[hdk_extern]
+fn capability_grants(query: CapGrantQuery) -> ExternResult<Vec<Record>> {
+    hdk::prelude::capability_grants(query)
+}
+
+#[hdk_extern]
+fn capability_claims(query: CapClaimQuery) -> ExternResult<Vec<Record>> {
+    hdk::prelude::capability_claims(query)
+}
+
+#[hdk_extern]
+fn capability_info(_: ()) -> ExternResult<CapabilityInfo> {
+    hdk::prelude::capability_info()
+}
+
 #[hdk_extern]
 fn send_assigned_cap_claim(agent: AgentPubKey) -> ExternResult<()> {
     let tag = String::from("has_cap_claim");
@@ -143,6 +143,11 @@ fn get_links_nested(_: ()) -> ExternResult<Vec<Link>> {
     // Include all link types defined in any zome.
 }
 
+#[hdk_extern]
+fn count_links(_: ()) -> ExternResult<usize> {
+    hdk::prelude::count_links(base()?, LinkTypes::SomeLinks, None, LinkFilter::default())
+}
+
 #[hdk_extern]
 fn get_baseless_links(_: ()) -> ExternResult<Vec<Link>> {
     hdk::prelude::get_links(baseless()?, LinkTypes::SomeLinks, None)
@@ -0,0 +1,22 @@
+use crate::integrity::*;
+use hdk::prelude::*;
+
+#[hdk_extern]
+fn create_post(_: ()) -> ExternResult<ActionHash> {
+    create_entry(EntryTypes::Post(Post("hello".into())))
+}
+
+#[hdk_extern]
+fn like(post: ActionHash) -> ExternResult<ActionHash> {
+    create_link(post.clone(), post, LinkTypes::Likes, ())
+}
+
+#[hdk_extern]
+fn delete_post(post: ActionHash) -> ExternResult<ActionHash> {
+    delete_entry(post)
+}
+
+#[hdk_extern]
+fn get_record(hash: ActionHash) -> ExternResult<Option<Record>> {
+    get(hash, GetOptions::default())
+}
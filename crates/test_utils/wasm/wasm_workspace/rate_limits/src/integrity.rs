@@ -0,0 +1,37 @@
+use hdi::prelude::*;
+
+#[hdk_entry_helper]
+pub struct Post(pub String);
+
+#[hdk_entry_defs]
+#[unit_enum(UnitEntryTypes)]
+pub enum EntryTypes {
+    Post(Post),
+}
+
+#[hdk_link_types]
+pub enum LinkTypes {
+    Likes,
+}
+
+/// Posts and likes are weighed into separate buckets.
+#[hdk_extern]
+fn weigh(input: WeighInput) -> ExternResult<EntryRateWeight> {
+    Ok(match input {
+        WeighInput::Link(_) => EntryRateWeight {
+            bucket_id: 0,
+            units: 1,
+            rate_bytes: 0,
+        },
+        WeighInput::Create(_, _) | WeighInput::Update(_, _) => EntryRateWeight {
+            bucket_id: 1,
+            units: 2,
+            rate_bytes: 1,
+        },
+        WeighInput::Delete(_) => EntryRateWeight {
+            bucket_id: 1,
+            units: 1,
+            rate_bytes: 0,
+        },
+    })
+}
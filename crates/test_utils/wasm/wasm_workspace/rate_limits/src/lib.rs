@@ -0,0 +1,7 @@
+pub mod integrity;
+
+#[cfg(not(feature = "integrity"))]
+pub mod coordinator;
+
+#[cfg(not(feature = "integrity"))]
+pub use coordinator::*;
@@ -30,6 +30,9 @@ rec {
     # run all the non-slow cargo tests
     cargo build --features 'build' -p holochain_wasm_test_utils
     cargo test ''${CARGO_TEST_ARGS:-} --workspace --exclude holochain --lib --tests --profile fast-test -- --nocapture
+
+    # run the database tests again with encryption, which is not a default feature
+    cargo test ''${CARGO_TEST_ARGS:-} -p holochain_sqlite --features db-encryption --lib --profile fast-test -- --nocapture
   '';
 
   hcStandardTestsNextest = writeShellScriptBin "hc-test-standard-nextest" ''
@@ -43,6 +46,9 @@ rec {
     # run all the non-slow cargo tests
     cargo build --features 'build' -p holochain_wasm_test_utils
     cargo nextest ''${CARGO_NEXTEST_ARGS:-run --test-threads=2} --workspace --exclude holochain --lib --tests --cargo-profile fast-test
+
+    # run the database tests again with encryption, which is not a default feature
+    cargo nextest ''${CARGO_NEXTEST_ARGS:-run --test-threads=2} -p holochain_sqlite --features db-encryption --lib --cargo-profile fast-test
   '';
 
   hcSlowTests = writeShellScriptBin "hc-test-slow" ''